@@ -219,9 +219,9 @@ async fn handle_event(evt: ParsedEvent, http_client: &reqwest::Client) {
     );
 
     let timestamp: DateTime<Utc> = Utc::now();
-    let a1 = asks.get(0).cloned().unwrap_or(("N/A".into(), "N/A".into()));
+    let a1 = asks.first().cloned().unwrap_or(("N/A".into(), "N/A".into()));
     let a2 = asks.get(1).cloned().unwrap_or(("N/A".into(), "N/A".into()));
-    let b1 = bids.get(0).cloned().unwrap_or(("N/A".into(), "N/A".into()));
+    let b1 = bids.first().cloned().unwrap_or(("N/A".into(), "N/A".into()));
     let b2 = bids.get(1).cloned().unwrap_or(("N/A".into(), "N/A".into()));
 
     let csv_row = CSV_BUF.with(|buf| {
@@ -4,7 +4,7 @@
 //! Checks if all required environment variables are set correctly
 //! and provides helpful error messages for beginners.
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use dotenvy::dotenv;
 use std::env;
 
@@ -67,7 +67,21 @@ fn main() -> Result<()> {
 }
 
 fn check_private_key(errors: &mut Vec<String>) {
-    match env::var("PRIVATE_KEY") {
+    // Key from a file or keystore instead of the env var
+    let key = match pm_whale_follower::key_source::resolve_private_key() {
+        Ok(Some((key, source))) => {
+            if source != pm_whale_follower::key_source::KeySource::Env {
+                println!("  ✅ Key source: {}", source.describe());
+            }
+            Ok(key)
+        }
+        Ok(None) => Err(None),
+        Err(e) => Err(Some(e)),
+    };
+    match key {
+        Err(Some(e)) => {
+            errors.push(format!("Failed to load private key: {:#}", e));
+        }
         Ok(key) => {
             let key = key.trim();
             
@@ -98,7 +112,7 @@ fn check_private_key(errors: &mut Vec<String>) {
             
             println!("  ✅ PRIVATE_KEY: Valid format");
         }
-        Err(_) => {
+        Err(None) => {
             errors.push(
                 "PRIVATE_KEY is required. Add it to your .env file. See .env.example for format.".to_string()
             );
@@ -158,22 +172,24 @@ fn check_target_whale_address(errors: &mut Vec<String>) {
                 return;
             }
             
-            // TARGET_WHALE_ADDRESS should NOT have 0x prefix
-            let addr_clean = addr.strip_prefix("0x").unwrap_or(addr);
-            
-            if addr_clean.len() != 40 {
-                errors.push(format!(
-                    "TARGET_WHALE_ADDRESS must be exactly 40 hex characters (found {} chars). Remove '0x' prefix if present.",
-                    addr_clean.len()
-                ));
-                return;
-            }
-            
-            if !addr_clean.chars().all(|c| c.is_ascii_hexdigit()) {
-                errors.push(
-                    "TARGET_WHALE_ADDRESS contains invalid characters. Must be hexadecimal (0-9, a-f, A-F).".to_string()
-                );
-                return;
+            // TARGET_WHALE_ADDRESS should NOT have 0x prefix (comma-separated to follow several)
+            for whale in addr.split(',').map(str::trim).filter(|w| !w.is_empty()) {
+                let addr_clean = whale.strip_prefix("0x").unwrap_or(whale);
+                
+                if addr_clean.len() != 40 {
+                    errors.push(format!(
+                        "TARGET_WHALE_ADDRESS must be exactly 40 hex characters (found {} chars). Remove '0x' prefix if present.",
+                        addr_clean.len()
+                    ));
+                    return;
+                }
+                
+                if !addr_clean.chars().all(|c| c.is_ascii_hexdigit()) {
+                    errors.push(
+                        "TARGET_WHALE_ADDRESS contains invalid characters. Must be hexadecimal (0-9, a-f, A-F).".to_string()
+                    );
+                    return;
+                }
             }
             
             println!("  ✅ TARGET_WHALE_ADDRESS: Valid format");
@@ -0,0 +1,168 @@
+//! Block-number fallback for providers that omit `blockNumber` on subscribed logs
+//! Resolves it from the tx hash via `eth_getTransactionReceipt` (cached by tx)
+
+use rustc_hash::FxHashMap;
+use serde_json::Value;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::models::ParsedEvent;
+
+/// Max cached tx -> block entries before the cache is cleared
+const CACHE_MAX_ENTRIES: usize = 4096;
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(2);
+
+// ============================================================================
+// Resolver
+// ============================================================================
+
+pub struct BlockResolver {
+    rpc_url: String,
+    cache: Mutex<FxHashMap<String, u64>>,
+}
+
+impl BlockResolver {
+    pub fn new(rpc_url: &str) -> Self {
+        Self {
+            rpc_url: rpc_url.to_string(),
+            cache: Mutex::new(FxHashMap::default()),
+        }
+    }
+
+    /// Fill a missing block number from the tx receipt. No-op if already present.
+    pub async fn fill_block_number(&self, evt: &mut ParsedEvent, http: &reqwest::Client) {
+        self.fill_with(evt, |tx_hash| fetch_receipt_block(http, &self.rpc_url, tx_hash)).await;
+    }
+
+    /// Fill using the given lookup; returns true if the fallback path ran
+    pub async fn fill_with<F, Fut>(&self, evt: &mut ParsedEvent, lookup: F) -> bool
+    where
+        F: FnOnce(String) -> Fut,
+        Fut: Future<Output = Option<u64>>,
+    {
+        if evt.block_number != 0 || evt.tx_hash.is_empty() {
+            return false;
+        }
+
+        let cached = self.cache.lock().ok().and_then(|c| c.get(&evt.tx_hash).copied());
+        if let Some(block) = cached {
+            evt.block_number = block;
+            return true;
+        }
+
+        if let Some(block) = lookup(evt.tx_hash.clone()).await {
+            evt.block_number = block;
+            if let Ok(mut cache) = self.cache.lock() {
+                if cache.len() >= CACHE_MAX_ENTRIES {
+                    cache.clear();
+                }
+                cache.insert(evt.tx_hash.clone(), block);
+            }
+        }
+        true
+    }
+}
+
+async fn fetch_receipt_block(http: &reqwest::Client, rpc_url: &str, tx_hash: String) -> Option<u64> {
+    let req = serde_json::json!({
+        "jsonrpc": "2.0", "id": 1, "method": "eth_getTransactionReceipt", "params": [tx_hash]
+    });
+    let resp = http.post(rpc_url).json(&req).timeout(RECEIPT_TIMEOUT).send().await.ok()?;
+    let val: Value = resp.json().await.ok()?;
+    parse_receipt_block(&val)
+}
+
+/// Extract `result.blockNumber` (hex) from a JSON-RPC receipt response
+pub fn parse_receipt_block(val: &Value) -> Option<u64> {
+    let hex = val.get("result")?.get("blockNumber")?.as_str()?;
+    u64::from_str_radix(hex.trim_start_matches("0x"), 16).ok()
+}
+
+/// Derive an HTTP JSON-RPC URL from the websocket URL (wss://host/path -> https://host/path)
+pub fn http_url_from_wss(wss_url: &str) -> String {
+    if let Some(rest) = wss_url.strip_prefix("wss://") {
+        format!("https://{}", rest)
+    } else if let Some(rest) = wss_url.strip_prefix("ws://") {
+        format!("http://{}", rest)
+    } else {
+        wss_url.to_string()
+    }
+}
+
+// ============================================================================
+// Global Instance
+// ============================================================================
+
+static GLOBAL_RESOLVER: OnceLock<BlockResolver> = OnceLock::new();
+
+/// Enable the fallback (call once at startup)
+pub fn init(rpc_url: &str) {
+    let _ = GLOBAL_RESOLVER.set(BlockResolver::new(rpc_url));
+}
+
+/// Get the global resolver (None = fallback disabled)
+pub fn global() -> Option<&'static BlockResolver> {
+    GLOBAL_RESOLVER.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{FrameTransport, OrderInfo};
+    use std::cell::Cell;
+
+    fn event(block_number: u64) -> ParsedEvent {
+        ParsedEvent {
+            block_number,
+            tx_hash: "0xabc".into(),
+            whale_address: "0xwhale".into(),
+            counterparty: "0xtaker".into(),
+            order: OrderInfo {
+                order_type: "BUY_FILL".into(),
+                clob_token_id: "123".into(),
+                usd_value: 10.0,
+                shares: 20.0,
+                price_per_share: 0.5,
+            },
+            transport: FrameTransport::Text,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_only_when_block_missing() {
+        let resolver = BlockResolver::new("http://unused");
+        let calls = Cell::new(0);
+
+        let mut present = event(55_000_000);
+        let ran = resolver.fill_with(&mut present, |_| async { calls.set(calls.get() + 1); Some(1) }).await;
+        assert!(!ran);
+        assert_eq!(present.block_number, 55_000_000);
+        assert_eq!(calls.get(), 0);
+
+        let mut missing = event(0);
+        let ran = resolver.fill_with(&mut missing, |tx| async move {
+            assert_eq!(tx, "0xabc");
+            Some(55_000_001)
+        }).await;
+        assert!(ran);
+        assert_eq!(missing.block_number, 55_000_001);
+
+        // Second lookup for the same tx is served from cache
+        let mut again = event(0);
+        resolver.fill_with(&mut again, |_| async { calls.set(calls.get() + 1); None }).await;
+        assert_eq!(again.block_number, 55_000_001);
+        assert_eq!(calls.get(), 0);
+    }
+
+    #[test]
+    fn test_parse_receipt_and_url() {
+        let val = serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": {"blockNumber": "0x3472d1c"}});
+        assert_eq!(parse_receipt_block(&val), Some(0x3472d1c));
+        assert_eq!(parse_receipt_block(&serde_json::json!({"result": null})), None);
+        assert_eq!(
+            http_url_from_wss("wss://polygon-mainnet.g.alchemy.com/v2/key"),
+            "https://polygon-mainnet.g.alchemy.com/v2/key"
+        );
+    }
+}
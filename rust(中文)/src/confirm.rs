@@ -0,0 +1,156 @@
+//! Fill confirmation via the data API (CONFIRM_VIA_API)
+//! A decoded event could be spoofed or mis-decoded. With confirmation on, the event's handler looks
+//! the whale's fill up in the data API's /activity feed by tx hash before queueing it for the
+//! order worker, and only copies a trade the API shows on the same token and side. The feed lags
+//! the chain, so it's polled until CONFIRM_TIMEOUT_MS; a fill still missing then (or an API
+//! failure) is copied or skipped per CONFIRM_ON_TIMEOUT
+
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+use crate::models::ParsedEvent;
+use crate::status_code::{Status, StatusCode};
+
+/// Wait between /activity polls while the fill hasn't shown up yet
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Recent activity entries fetched per poll
+pub const ACTIVITY_LIMIT: u32 = 50;
+
+/// What the /activity feed says about an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confirmation {
+    /// A trade in this tx on the same token and side
+    Confirmed,
+    /// The tx is there, but on another token or side
+    Mismatch,
+    /// Nothing for this tx (yet)
+    Missing,
+}
+
+/// What to do when the fill can't be confirmed in time (missing, API error or timeout)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnTimeout {
+    /// Copy anyway
+    Open,
+    /// Skip the copy (SKIPPED_UNCONFIRMED)
+    #[default]
+    Closed,
+}
+
+impl OnTimeout {
+    /// Parse "open" / "closed" (empty = Closed)
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "closed" => Ok(OnTimeout::Closed),
+            "open" => Ok(OnTimeout::Open),
+            other => anyhow::bail!("CONFIRM_ON_TIMEOUT must be 'open' or 'closed' (found '{}')", other),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OnTimeout::Open => "open",
+            OnTimeout::Closed => "closed",
+        }
+    }
+}
+
+/// Look `tx_hash` up in an /activity response: `[{"type": "TRADE", "transactionHash": "0x..",
+/// "asset": "<token id>", "side": "BUY", ...}]`
+pub fn check_activity(body: &Value, tx_hash: &str, token_id: &str, side_is_buy: bool) -> Confirmation {
+    let side = if side_is_buy { "BUY" } else { "SELL" };
+    let mut in_tx = body.as_array().into_iter().flatten().filter(|entry| {
+        entry["transactionHash"].as_str().is_some_and(|h| h.eq_ignore_ascii_case(tx_hash))
+    }).peekable();
+    if in_tx.peek().is_none() {
+        return Confirmation::Missing;
+    }
+    let matches = |entry: &Value| {
+        entry["asset"].as_str() == Some(token_id)
+            && entry["side"].as_str().is_some_and(|s| s.eq_ignore_ascii_case(side))
+    };
+    if in_tx.any(matches) { Confirmation::Confirmed } else { Confirmation::Mismatch }
+}
+
+/// Whether to go on with the copy. `result` is the last lookup, or why none succeeded
+pub fn decide(result: Result<Confirmation, &'static str>, on_timeout: OnTimeout) -> Result<(), Status> {
+    let reason = match result {
+        Ok(Confirmation::Confirmed) => return Ok(()),
+        Ok(Confirmation::Mismatch) => {
+            return Err(Status::with_message(StatusCode::SkippedUnconfirmed, "MISMATCH: tx found on another token or side"));
+        }
+        Ok(Confirmation::Missing) => "NOT_FOUND",
+        Err(reason) => reason,
+    };
+    match on_timeout {
+        OnTimeout::Open => {
+            eprintln!("⚠️ Fill not confirmed ({}); copying anyway (CONFIRM_ON_TIMEOUT=open)", reason);
+            Ok(())
+        }
+        OnTimeout::Closed => Err(Status::with_message(StatusCode::SkippedUnconfirmed, reason)),
+    }
+}
+
+/// Poll the whale's /activity until the event's tx shows up or `timeout` passes. A missing tx
+/// at the deadline is reported as Missing; a failed last request as its error
+pub async fn fetch_confirmation(
+    http: &reqwest::Client,
+    data_api_base: &str,
+    evt: &ParsedEvent,
+    timeout: Duration,
+) -> Result<Confirmation, &'static str> {
+    let url = format!("{}/activity?user={}&type=TRADE&limit={}", data_api_base, evt.whale_address, ACTIVITY_LIMIT);
+    let side_is_buy = evt.order.order_type.starts_with("BUY");
+    let deadline = Instant::now() + timeout;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err("TIMEOUT");
+        }
+        let fetched = match http.get(&url).timeout(remaining).send().await {
+            Err(e) => Err(if e.is_timeout() { "TIMEOUT" } else { "NETWORK" }),
+            Ok(resp) if !resp.status().is_success() => Err("HTTP_ERROR"),
+            Ok(resp) => resp.json::<Value>().await.map_err(|_| "PARSE"),
+        };
+        let result = fetched.map(|body| check_activity(&body, &evt.tx_hash, &evt.order.clob_token_id, side_is_buy));
+        if !matches!(result, Ok(Confirmation::Missing)) || Instant::now() + POLL_INTERVAL >= deadline {
+            return result;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirm_decision() {
+        let body = serde_json::json!([
+            {"type": "TRADE", "transactionHash": "0xOTHER", "asset": "tok", "side": "BUY"},
+            {"type": "TRADE", "transactionHash": "0xabc", "asset": "tok", "side": "BUY", "size": 100},
+        ]);
+        // Matching: same tx (any case), token and side
+        assert_eq!(check_activity(&body, "0xABC", "tok", true), Confirmation::Confirmed);
+        assert!(decide(Ok(Confirmation::Confirmed), OnTimeout::Closed).is_ok());
+
+        // Non-matching: the tx is a buy of another token, or not a sell
+        assert_eq!(check_activity(&body, "0xabc", "other", true), Confirmation::Mismatch);
+        assert_eq!(check_activity(&body, "0xabc", "tok", false), Confirmation::Mismatch);
+        for on_timeout in [OnTimeout::Open, OnTimeout::Closed] {
+            let err = decide(Ok(Confirmation::Mismatch), on_timeout).unwrap_err();
+            assert_eq!(err.code, StatusCode::SkippedUnconfirmed);
+        }
+
+        // Not there (yet) or no answer: per CONFIRM_ON_TIMEOUT
+        assert_eq!(check_activity(&body, "0xdef", "tok", true), Confirmation::Missing);
+        assert_eq!(check_activity(&serde_json::json!({"error": "x"}), "0xabc", "tok", true), Confirmation::Missing);
+        assert!(decide(Ok(Confirmation::Missing), OnTimeout::Open).is_ok());
+        let err = decide(Err("TIMEOUT"), OnTimeout::Closed).unwrap_err();
+        assert_eq!((err.code, err.message.as_str()), (StatusCode::SkippedUnconfirmed, "TIMEOUT"));
+
+        assert_eq!(OnTimeout::parse(" OPEN ").unwrap(), OnTimeout::Open);
+        assert_eq!(OnTimeout::parse("").unwrap(), OnTimeout::Closed);
+        assert!(OnTimeout::parse("skip").is_err());
+    }
+}
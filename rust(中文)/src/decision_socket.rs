@@ -0,0 +1,224 @@
+//! Decision stream over a Unix domain socket (DECISION_SOCKET)
+//! Every order decision is sent to each connected reader as one frame: a 4-byte big-endian
+//! length, then that many bytes of JSON. Meant for a local supervisor that wants decisions
+//! without tailing the CSV. Frames go through a bounded broadcast channel, so a reader that
+//! stops reading (or disconnects) only loses frames itself; the order worker never waits on it
+
+use serde_json::{json, Value};
+use std::sync::{Arc, OnceLock};
+use tokio::sync::broadcast;
+
+use crate::engine::OrderPlan;
+use crate::models::ParsedEvent;
+use crate::status_code::Status;
+
+/// Frames buffered per reader before a slow one starts skipping
+pub const READER_BACKLOG: usize = 1024;
+
+/// Pause after a failed accept (e.g. out of file descriptors) before the next one
+#[cfg(unix)]
+const ACCEPT_BACKOFF: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Sending side of the stream; cheap to clone
+#[derive(Clone)]
+pub struct DecisionSocket {
+    frames: broadcast::Sender<Arc<[u8]>>,
+}
+
+impl DecisionSocket {
+    /// Listen on `path` (a stale socket there is replaced; any other file is left alone and
+    /// fails the bind) and stream frames to every reader. Must be called inside a Tokio runtime
+    #[cfg(unix)]
+    pub fn bind(path: &std::path::Path) -> std::io::Result<Self> {
+        use std::os::unix::fs::FileTypeExt;
+        use tokio::io::AsyncWriteExt;
+
+        if std::fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_socket()) {
+            std::fs::remove_file(path)?;
+        }
+        let listener = tokio::net::UnixListener::bind(path)?;
+        let (frames, _) = broadcast::channel::<Arc<[u8]>>(READER_BACKLOG);
+        let socket = Self { frames };
+        let accept = socket.clone();
+        tokio::spawn(async move {
+            loop {
+                let mut stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        eprintln!("⚠️ Decision socket accept failed: {}", e);
+                        tokio::time::sleep(ACCEPT_BACKOFF).await;
+                        continue;
+                    }
+                };
+                let mut rx = accept.frames.subscribe();
+                tokio::spawn(async move {
+                    loop {
+                        match rx.recv().await {
+                            Ok(frame) => {
+                                if stream.write_all(&frame).await.is_err() {
+                                    return;
+                                }
+                            }
+                            Err(broadcast::error::RecvError::Lagged(n)) => {
+                                eprintln!("⚠️ Decision socket reader fell behind; {} decisions dropped", n);
+                            }
+                            Err(broadcast::error::RecvError::Closed) => return,
+                        }
+                    }
+                });
+            }
+        });
+        Ok(socket)
+    }
+
+    /// Connected readers
+    pub fn readers(&self) -> usize {
+        self.frames.receiver_count()
+    }
+
+    /// Send `msg` to every connected reader (nothing to do without readers)
+    pub fn emit(&self, msg: &Value) {
+        if self.readers() > 0 {
+            let _ = self.frames.send(frame(msg).into());
+        }
+    }
+}
+
+/// Length-prefixed frame for `msg`
+pub fn frame(msg: &Value) -> Vec<u8> {
+    let body = msg.to_string();
+    let mut out = Vec::with_capacity(4 + body.len());
+    out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    out.extend_from_slice(body.as_bytes());
+    out
+}
+
+/// One decision: the event, the final status and the plan behind it (null for skips)
+pub fn decision_message(evt: &ParsedEvent, plan: Option<&OrderPlan>, status: &Status) -> Value {
+    json!({
+        "tx": evt.tx_hash,
+        "block": evt.block_number,
+        "token": evt.order.clob_token_id.to_string(),
+        "direction": evt.order.order_type,
+        "whale_shares": evt.order.shares,
+        "whale_price": evt.order.price_per_share,
+        "status": status.code.as_str(),
+        "detail": status.message,
+        "plan": plan.map(|p| json!({
+            "side": if p.side_is_buy { "BUY" } else { "SELL" },
+            "limit": p.limit_price,
+            "shares": p.shares,
+            "notional": p.notional,
+            "action": p.order_action,
+            "maker": p.maker,
+        })),
+    })
+}
+
+static DECISION_SOCKET: OnceLock<DecisionSocket> = OnceLock::new();
+
+/// Make `socket` the process-wide stream (the first one installed wins)
+pub fn install(socket: DecisionSocket) {
+    let _ = DECISION_SOCKET.set(socket);
+}
+
+/// Emit on the process-wide stream, if one is installed
+#[inline]
+pub fn emit(msg: impl FnOnce() -> Value) {
+    if let Some(socket) = DECISION_SOCKET.get().filter(|s| s.readers() > 0) {
+        socket.emit(&msg());
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::models::{FrameTransport, OrderInfo, SizeType};
+    use crate::status_code::StatusCode;
+    use std::time::Duration;
+    use tokio::io::AsyncReadExt;
+
+    async fn read_frame(reader: &mut tokio::net::UnixStream) -> Value {
+        let mut len = [0u8; 4];
+        reader.read_exact(&mut len).await.unwrap();
+        let mut body = vec![0u8; u32::from_be_bytes(len) as usize];
+        reader.read_exact(&mut body).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reader_gets_framed_decision() {
+        let path = std::env::temp_dir().join(format!("pm_decision_socket_test_{}.sock", std::process::id()));
+        let socket = DecisionSocket::bind(&path).unwrap();
+        // No reader yet: emitting is a no-op
+        socket.emit(&json!({"dropped": true}));
+
+        let mut reader = tokio::net::UnixStream::connect(&path).await.unwrap();
+        for _ in 0..200 {
+            if socket.readers() > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(socket.readers(), 1);
+
+        let evt = ParsedEvent {
+            block_number: 42,
+            tx_hash: "0xabc".into(),
+            whale_address: "0xwhale".into(),
+            counterparty: String::new(),
+            order: OrderInfo {
+                order_type: "BUY_FILL".into(),
+                clob_token_id: "tok".into(),
+                usd_value: 50.0,
+                shares: 100.0,
+                price_per_share: 0.50,
+            },
+            transport: FrameTransport::Text,
+        };
+        let plan = OrderPlan {
+            side_is_buy: true,
+            limit_price: 0.51,
+            shares: 2.0,
+            size_type: SizeType::Scaled,
+            order_action: "FAK",
+            notional: 1.02,
+            maker: false,
+        };
+        let posted = Status::with_message(StatusCode::OrderPosted, "200 OK");
+        socket.emit(&decision_message(&evt, Some(&plan), &posted));
+        socket.emit(&decision_message(&evt, None, &StatusCode::SkippedSmall.into()));
+
+        let first = tokio::time::timeout(Duration::from_secs(5), read_frame(&mut reader)).await.unwrap();
+        assert_eq!((first["tx"].as_str(), first["block"].as_u64()), (Some("0xabc"), Some(42)));
+        assert_eq!(first["status"], "ORDER_POSTED");
+        assert_eq!(first["plan"]["limit"], 0.51);
+        assert_eq!(first["plan"]["action"], "FAK");
+        let second = tokio::time::timeout(Duration::from_secs(5), read_frame(&mut reader)).await.unwrap();
+        assert_eq!(second["status"], "SKIPPED_SMALL");
+        assert!(second["plan"].is_null());
+
+        // A reader that goes away doesn't hold anything up
+        drop(reader);
+        for _ in 0..2000 {
+            socket.emit(&json!({"n": 1}));
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_bind_replaces_only_stale_sockets() {
+        let path = std::env::temp_dir().join(format!("pm_decision_socket_stale_{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        drop(DecisionSocket::bind(&path).unwrap());
+        // The socket file left behind is replaced on the next bind
+        assert!(DecisionSocket::bind(&path).is_ok());
+        std::fs::remove_file(&path).unwrap();
+
+        // A regular file at the path is never deleted
+        std::fs::write(&path, "keep").unwrap();
+        assert!(DecisionSocket::bind(&path).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "keep");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,602 @@
+//! OrdersFilled log decoding
+//! Turns subscribed WebSocket log messages into ParsedEvents, counting decode failures by field
+//! and frames / events by WebSocket transport
+
+use alloy::primitives::U256;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::models::{FrameTransport, OrderInfo, ParsedEvent, WsMessage};
+use crate::market_cache;
+use crate::status;
+use crate::settings::{is_target_topic, target_topics_from, COLLATERAL_DECIMALS, ORDERS_FILLED_EVENT_SIGNATURE, TARGET_TOPICS};
+use crate::u256_to_scaled_f64;
+
+thread_local! {
+    static TOKEN_ID_CACHE: RefCell<HashMap<[u8; 32], Arc<str>>> = RefCell::new(HashMap::with_capacity(256));
+}
+
+// ============================================================================
+// Decode Errors
+// ============================================================================
+
+/// 32-byte words of the OrdersFilled data payload, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeField {
+    MakerAssetId,
+    TakerAssetId,
+    MakerAmount,
+    TakerAmount,
+}
+
+impl DecodeField {
+    pub const ALL: [DecodeField; 4] = [
+        DecodeField::MakerAssetId,
+        DecodeField::TakerAssetId,
+        DecodeField::MakerAmount,
+        DecodeField::TakerAmount,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DecodeField::MakerAssetId => "maker_asset_id",
+            DecodeField::TakerAssetId => "taker_asset_id",
+            DecodeField::MakerAmount => "maker_amount",
+            DecodeField::TakerAmount => "taker_amount",
+        }
+    }
+
+    /// Hex char range of this word in `0x`-prefixed data
+    #[inline]
+    fn range(self) -> (usize, usize) {
+        let start = 2 + 64 * self as usize;
+        (start, start + 64)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// Not a log for a followed target (subscription acks, other counterparties) - not a failure
+    Ignored,
+    /// A data word was missing or not valid hex
+    Field(DecodeField),
+    /// Neither (or both) asset ids are zero, so BUY/SELL can't be inferred
+    AmbiguousSide,
+    /// Share amount decoded to zero
+    ZeroShares,
+    /// USD amount decoded to zero
+    ZeroUsd,
+    /// Shares or USD didn't convert to a finite number
+    NonFinite,
+    /// Implied price (usd / shares) outside the plausible fill range
+    PriceOutOfRange,
+    /// Binary frame that isn't UTF-8 text
+    InvalidUtf8,
+}
+
+impl DecodeError {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DecodeError::Ignored => "ignored",
+            DecodeError::Field(f) => f.as_str(),
+            DecodeError::AmbiguousSide => "ambiguous_side",
+            DecodeError::ZeroShares => "zero_shares",
+            DecodeError::ZeroUsd => "zero_usd",
+            DecodeError::NonFinite => "non_finite",
+            DecodeError::PriceOutOfRange => "price_out_of_range",
+            DecodeError::InvalidUtf8 => "invalid_utf8",
+        }
+    }
+}
+
+// ============================================================================
+// Decode Failure Telemetry
+// ============================================================================
+
+const FAILURE_SLOTS: usize = 10;
+static DECODE_FAILURES: [AtomicU64; FAILURE_SLOTS] = [const { AtomicU64::new(0) }; FAILURE_SLOTS];
+
+fn failure_slot(err: DecodeError) -> Option<usize> {
+    match err {
+        DecodeError::Ignored => None,
+        DecodeError::Field(f) => Some(f as usize),
+        DecodeError::AmbiguousSide => Some(4),
+        DecodeError::ZeroShares => Some(5),
+        DecodeError::ZeroUsd => Some(6),
+        DecodeError::NonFinite => Some(7),
+        DecodeError::PriceOutOfRange => Some(8),
+        DecodeError::InvalidUtf8 => Some(9),
+    }
+}
+
+/// Count a decode failure (Ignored is not counted). The first failure of each kind is logged,
+/// and the counts are published to /status under "decode_failures".
+pub fn record_decode_failure(err: DecodeError) {
+    let Some(slot) = failure_slot(err) else { return };
+    if DECODE_FAILURES[slot].fetch_add(1, Ordering::Relaxed) == 0 {
+        eprintln!("⚠️ Event decode failed: {} (further failures counted in /status)", err.as_str());
+    }
+    let counts: serde_json::Map<String, serde_json::Value> =
+        decode_failure_counts().into_iter().map(|(k, v)| (k.to_string(), v.into())).collect();
+    status::publish("decode_failures", counts.into());
+}
+
+/// Decode failure counts by reason
+pub fn decode_failure_counts() -> Vec<(&'static str, u64)> {
+    DecodeField::ALL
+        .iter()
+        .map(|f| DecodeError::Field(*f))
+        .chain([
+            DecodeError::AmbiguousSide,
+            DecodeError::ZeroShares,
+            DecodeError::ZeroUsd,
+            DecodeError::NonFinite,
+            DecodeError::PriceOutOfRange,
+            DecodeError::InvalidUtf8,
+        ])
+        .filter_map(|e| Some((e.as_str(), DECODE_FAILURES[failure_slot(e)?].load(Ordering::Relaxed))))
+        .collect()
+}
+
+// ============================================================================
+// Decoding
+// ============================================================================
+
+/// Plausible per-share fill price range (Polymarket's smallest tick is 0.001)
+pub const MIN_FILL_PRICE: f64 = 0.001;
+pub const MAX_FILL_PRICE: f64 = 0.999;
+
+/// Decode a WebSocket log message. `is_target` is matched against topics[2]; `decimals` scales
+/// amounts for tokens without an entry in the collateral decimals cache.
+pub fn decode_event<F>(message: &str, is_target: F, decimals: u8) -> Result<ParsedEvent, DecodeError>
+where
+    F: Fn(&str) -> bool,
+{
+    let msg: WsMessage = serde_json::from_str(message).map_err(|_| DecodeError::Ignored)?;
+    let result = msg.params.and_then(|p| p.result).ok_or(DecodeError::Ignored)?;
+
+    // just to double check!
+    let whale_topic = result.topics.get(2).ok_or(DecodeError::Ignored)?;
+    if !is_target(whale_topic) { return Err(DecodeError::Ignored); }
+
+    let hex_data = &result.data;
+    let (maker_id, maker_bytes) = parse_word(hex_data, DecodeField::MakerAssetId)?;
+    let (taker_id, taker_bytes) = parse_word(hex_data, DecodeField::TakerAssetId)?;
+
+    let (clob_id, token_bytes, base_type) = if maker_id.is_zero() && !taker_id.is_zero() {
+        (taker_id, taker_bytes, "BUY")
+    } else if taker_id.is_zero() && !maker_id.is_zero() {
+        (maker_id, maker_bytes, "SELL")
+    } else {
+        return Err(DecodeError::AmbiguousSide);
+    };
+    let (maker_amt, _) = parse_word(hex_data, DecodeField::MakerAmount)?;
+    let (taker_amt, _) = parse_word(hex_data, DecodeField::TakerAmount)?;
+
+    // Amounts are raw on-chain integers scaled by the market's collateral decimals (6 for USDC)
+    let clob_token_id = u256_to_dec_cached(&token_bytes, &clob_id);
+    let decimals = market_cache::collateral_decimals(&clob_token_id, decimals);
+    let (share_amt, usd_amt) = if base_type == "BUY" { (&taker_amt, &maker_amt) } else { (&maker_amt, &taker_amt) };
+    let shares = u256_to_scaled_f64(share_amt, decimals).ok_or(DecodeError::NonFinite)?;
+    let usd = u256_to_scaled_f64(usd_amt, decimals).ok_or(DecodeError::NonFinite)?;
+    if !shares.is_finite() || !usd.is_finite() { return Err(DecodeError::NonFinite); }
+    if shares <= 0.0 { return Err(DecodeError::ZeroShares); }
+    if usd <= 0.0 { return Err(DecodeError::ZeroUsd); }
+
+    // Outcome prices live in (0, 1); anything else is a degenerate fill (dust shares vs. large USD or vice versa)
+    let price = usd / shares;
+    if !(MIN_FILL_PRICE..=MAX_FILL_PRICE).contains(&price) { return Err(DecodeError::PriceOutOfRange); }
+
+    let whale_address = topic_address(whale_topic);
+    let counterparty = result.topics.get(3).map(|t| topic_address(t)).unwrap_or_default();
+
+    let mut order_type = base_type.to_string();
+    if result.topics[0].eq_ignore_ascii_case(ORDERS_FILLED_EVENT_SIGNATURE) {
+        order_type.push_str("_FILL");
+    }
+
+    Ok(ParsedEvent {
+        block_number: result.block_number.as_deref()
+            .and_then(|s| u64::from_str_radix(s.trim_start_matches("0x"), 16).ok())
+            .unwrap_or_default(),
+        tx_hash: result.transaction_hash.unwrap_or_default(),
+        whale_address,
+        counterparty,
+        order: OrderInfo {
+            order_type,
+            clob_token_id,
+            usd_value: usd,
+            shares,
+            price_per_share: price,
+        },
+        transport: FrameTransport::Text,
+    })
+}
+
+/// Lowercase 0x-prefixed address from an indexed address topic (its last 20 bytes)
+pub(crate) fn topic_address(topic: &str) -> String {
+    format!("0x{}", &topic[topic.len().saturating_sub(40)..]).to_ascii_lowercase()
+}
+
+// ============================================================================
+// Transport Telemetry
+// ============================================================================
+
+/// [frames, events] per transport, indexed by FrameTransport
+static TRANSPORT_COUNTS: [[AtomicU64; 2]; 2] = [const { [const { AtomicU64::new(0) }; 2] }; 2];
+
+/// Frames received and events parsed for one transport
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TransportCounts {
+    pub frames: u64,
+    pub events: u64,
+}
+
+pub fn transport_counts(transport: FrameTransport) -> TransportCounts {
+    let [frames, events] = &TRANSPORT_COUNTS[transport as usize];
+    TransportCounts { frames: frames.load(Ordering::Relaxed), events: events.load(Ordering::Relaxed) }
+}
+
+fn publish_transport_counts() {
+    let counts: serde_json::Map<String, serde_json::Value> = [FrameTransport::Text, FrameTransport::Binary]
+        .into_iter()
+        .map(|t| {
+            let c = transport_counts(t);
+            (t.as_str().to_string(), serde_json::json!({ "frames": c.frames, "events": c.events }))
+        })
+        .collect();
+    status::publish("ws_transport", counts.into());
+}
+
+/// Decode a Text or Binary frame's payload and tag the event with its transport.
+/// A binary payload that isn't UTF-8 is an InvalidUtf8 failure
+pub fn decode_ws_frame<F>(transport: FrameTransport, payload: &[u8], is_target: F, decimals: u8) -> Result<ParsedEvent, DecodeError>
+where
+    F: Fn(&str) -> bool,
+{
+    let text = std::str::from_utf8(payload).map_err(|_| DecodeError::InvalidUtf8)?;
+    let mut evt = decode_event(text, is_target, decimals)?;
+    evt.transport = transport;
+    Ok(evt)
+}
+
+/// decode_ws_frame with frame / event counts per transport (published to /status under
+/// "ws_transport") and failures counted in the decode-failure telemetry. Logs when events start arriving
+/// on a second transport, i.e. the provider mixes or switches formats
+pub fn parse_ws_frame_with<F>(transport: FrameTransport, payload: &[u8], is_target: F, decimals: u8) -> Option<ParsedEvent>
+where
+    F: Fn(&str) -> bool,
+{
+    let [frames, events] = &TRANSPORT_COUNTS[transport as usize];
+    frames.fetch_add(1, Ordering::Relaxed);
+    let parsed = match decode_ws_frame(transport, payload, is_target, decimals) {
+        Ok(evt) => {
+            let other = if transport == FrameTransport::Text { FrameTransport::Binary } else { FrameTransport::Text };
+            if events.fetch_add(1, Ordering::Relaxed) == 0 && transport_counts(other).events > 0 {
+                eprintln!("⚠️ Events now also arriving as {} frames (previously {} only)", transport.as_str(), other.as_str());
+            }
+            Some(evt)
+        }
+        Err(err) => {
+            record_decode_failure(err);
+            None
+        }
+    };
+    publish_transport_counts();
+    parsed
+}
+
+/// parse_ws_frame_with using the configured target topics and collateral decimals
+pub fn parse_ws_frame(transport: FrameTransport, payload: &[u8]) -> Option<ParsedEvent> {
+    parse_ws_frame_with(transport, payload, |topic| is_target_topic(topic, &TARGET_TOPICS), *COLLATERAL_DECIMALS)
+}
+
+// ============================================================================
+// Decode-only Mode (--decode)
+// ============================================================================
+
+/// One report line for a decoded message: its fields, or why it was dropped
+pub fn describe_decode(result: &Result<ParsedEvent, DecodeError>) -> String {
+    match result {
+        Ok(evt) => format!(
+            "OK block={} tx={} whale={} type={} token={} shares={:.6} usd={:.6} price={:.6}",
+            evt.block_number,
+            evt.tx_hash,
+            evt.whale_address,
+            evt.order.order_type,
+            evt.order.clob_token_id,
+            evt.order.shares,
+            evt.order.usd_value,
+            evt.order.price_per_share
+        ),
+        Err(DecodeError::Ignored) => "DROPPED ignored (not a log for a followed target)".to_string(),
+        Err(err) => format!("DROPPED {}", err.as_str()),
+    }
+}
+
+/// Decode every JSON message in `input` (one, pretty-printed or not, or many back to back),
+/// through the same path as the live loop. No telemetry is recorded
+pub fn decode_report<F>(input: &str, is_target: F, decimals: u8) -> Vec<String>
+where
+    F: Fn(&str) -> bool,
+{
+    let mut lines = Vec::new();
+    for value in serde_json::Deserializer::from_str(input).into_iter::<serde_json::Value>() {
+        match value {
+            Ok(msg) => lines.push(describe_decode(&decode_event(&msg.to_string(), &is_target, decimals))),
+            Err(e) => {
+                lines.push(format!("DROPPED invalid_json ({})", e));
+                break;
+            }
+        }
+    }
+    lines
+}
+
+/// decode_report with TARGET_WHALE_ADDRESS and COLLATERAL_DECIMALS from the environment.
+/// Without TARGET_WHALE_ADDRESS every counterparty counts as a target
+pub fn decode_report_configured(input: &str) -> Vec<String> {
+    let topics = std::env::var("TARGET_WHALE_ADDRESS").map(|a| target_topics_from(&a)).unwrap_or_default();
+    decode_report(input, |topic| topics.is_empty() || is_target_topic(topic, &topics), *COLLATERAL_DECIMALS)
+}
+
+#[inline]
+fn parse_word(hex_data: &str, field: DecodeField) -> Result<(U256, [u8; 32]), DecodeError> {
+    let (start, end) = field.range();
+    parse_u256_hex_slice_with_bytes(hex_data, start, end).ok_or(DecodeError::Field(field))
+}
+
+// ============================================================================
+// Hex Parsing Helpers
+// ============================================================================
+
+#[inline]
+pub(crate) fn parse_u256_hex_slice_with_bytes(full: &str, start: usize, end: usize) -> Option<(U256, [u8; 32])> {
+    let slice = full.get(start..end)?;
+    let clean = slice.strip_prefix("0x").unwrap_or(slice);
+    if clean.len() > 64 { return None; }
+
+    let mut hex_buf = [b'0'; 64];
+    hex_buf[64 - clean.len()..].copy_from_slice(clean.as_bytes());
+
+    let mut out = [0u8; 32];
+    for i in 0..32 {
+        let hi = hex_nibble(hex_buf[i * 2])?;
+        let lo = hex_nibble(hex_buf[i * 2 + 1])?;
+        out[i] = (hi << 4) | lo;
+    }
+    Some((U256::from_be_slice(&out), out))
+}
+
+pub(crate) fn u256_to_dec_cached(bytes: &[u8; 32], val: &U256) -> Arc<str> {
+    TOKEN_ID_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if let Some(s) = cache.get(bytes) { return Arc::clone(s); }  // Cheap Arc clone
+        let s: Arc<str> = val.to_string().into();
+        cache.insert(*bytes, Arc::clone(&s));
+        s
+    })
+}
+
+// Hex nibble lookup table - 2-3x faster than branching
+const HEX_NIBBLE_LUT: [u8; 256] = {
+    let mut lut = [255u8; 256];
+    let mut i = b'0';
+    while i <= b'9' {
+        lut[i as usize] = i - b'0';
+        i += 1;
+    }
+    let mut i = b'a';
+    while i <= b'f' {
+        lut[i as usize] = i - b'a' + 10;
+        i += 1;
+    }
+    let mut i = b'A';
+    while i <= b'F' {
+        lut[i as usize] = i - b'A' + 10;
+        i += 1;
+    }
+    lut
+};
+
+#[inline(always)]
+fn hex_nibble(b: u8) -> Option<u8> {
+    let val = HEX_NIBBLE_LUT[b as usize];
+    if val == 255 { None } else { Some(val) }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) const WHALE_TOPIC: &str = "0x000000000000000000000000204f72f35326db932158cba6adff0b9a1da95e14";
+
+    fn word(v: u128) -> String {
+        format!("{:064x}", v)
+    }
+
+    /// A BUY of 20 shares for 10 USDC on token 12345
+    pub(crate) fn buy_data() -> String {
+        format!("0x{}{}{}{}{}", word(0), word(12345), word(10_000_000), word(20_000_000), word(0))
+    }
+
+    pub(crate) fn log_message(topic2: &str, data: &str) -> String {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "eth_subscription",
+            "params": { "result": {
+                "topics": [ORDERS_FILLED_EVENT_SIGNATURE, "0x00", topic2],
+                "data": data,
+                "blockNumber": "0x10",
+                "transactionHash": "0xtx"
+            }}
+        })
+        .to_string()
+    }
+
+    fn decode(data: &str) -> Result<ParsedEvent, DecodeError> {
+        decode_event(&log_message(WHALE_TOPIC, data), |t| t.eq_ignore_ascii_case(WHALE_TOPIC), 6)
+    }
+
+    #[test]
+    fn test_decode_report() {
+        let is_whale = |t: &str| t.eq_ignore_ascii_case(WHALE_TOPIC);
+        let other = "0x000000000000000000000000000000000000000000000000000000000000beef";
+        let input = format!("{}\n{}\n", log_message(WHALE_TOPIC, &buy_data()), log_message(other, &buy_data()));
+        let lines = decode_report(&input, is_whale, 6);
+        assert_eq!(
+            lines,
+            vec![
+                "OK block=16 tx=0xtx whale=0x204f72f35326db932158cba6adff0b9a1da95e14 type=BUY_FILL token=12345 \
+                 shares=20.000000 usd=10.000000 price=0.500000",
+                "DROPPED ignored (not a log for a followed target)",
+            ]
+        );
+
+        // Pretty-printed single message works too; a bad payload reports its reason
+        let pretty = serde_json::to_string_pretty(&serde_json::from_str::<serde_json::Value>(&log_message(WHALE_TOPIC, &buy_data()[..100])).unwrap()).unwrap();
+        assert_eq!(decode_report(&pretty, is_whale, 6), vec!["DROPPED taker_asset_id"]);
+        assert!(decode_report("{not json", is_whale, 6)[0].starts_with("DROPPED invalid_json"));
+    }
+
+    #[test]
+    fn test_decode_buy() {
+        let evt = decode(&buy_data()).unwrap();
+        assert_eq!(evt.order.order_type, "BUY_FILL");
+        assert_eq!(&*evt.order.clob_token_id, "12345");
+        assert_eq!(evt.order.shares, 20.0);
+        assert_eq!(evt.order.usd_value, 10.0);
+        assert_eq!(evt.order.price_per_share, 0.5);
+        assert_eq!(evt.block_number, 16);
+        assert_eq!(evt.whale_address, "0x204f72f35326db932158cba6adff0b9a1da95e14");
+        assert_eq!(evt.counterparty, "");
+
+        // The fill's taker comes from topics[3]
+        let mut msg: serde_json::Value = serde_json::from_str(&log_message(WHALE_TOPIC, &buy_data())).unwrap();
+        msg["params"]["result"]["topics"].as_array_mut().unwrap().push("0x000000000000000000000000C0FFEE0000000000000000000000000000000001".into());
+        let evt = decode_event(&msg.to_string(), |t| t.eq_ignore_ascii_case(WHALE_TOPIC), 6).unwrap();
+        assert_eq!(evt.counterparty, "0xc0ffee0000000000000000000000000000000001");
+    }
+
+    #[test]
+    fn test_mixed_collateral_decimals() {
+        // Token 54321 settles in an 18-decimal collateral; 12345 has no entry and stays at 6
+        market_cache::global_caches().set_collateral_decimals("54321".into(), 18);
+        let usd_18 = 10u128 * 10u128.pow(18);
+        let shares_18 = 20u128 * 10u128.pow(18);
+        let data_18 = format!("0x{}{}{}{}{}", word(0), word(54321), word(usd_18), word(shares_18), word(0));
+
+        let evt = decode(&data_18).unwrap();
+        assert_eq!(&*evt.order.clob_token_id, "54321");
+        assert_eq!((evt.order.shares, evt.order.usd_value, evt.order.price_per_share), (20.0, 10.0, 0.5));
+
+        // Same run, USDC market: the 6-decimal default still applies
+        let evt = decode(&buy_data()).unwrap();
+        assert_eq!((evt.order.shares, evt.order.usd_value), (20.0, 10.0));
+    }
+
+    #[test]
+    fn test_truncated_payload_reports_field() {
+        let full = buy_data();
+        // Cut inside each word: the first incomplete word is reported
+        for field in DecodeField::ALL {
+            let (start, _) = field.range();
+            let truncated = &full[..start + 10];
+            assert_eq!(decode(truncated).unwrap_err(), DecodeError::Field(field), "cut in {:?}", field);
+        }
+        // Cutting exactly at a boundary reports the next word
+        assert_eq!(decode(&full[..2 + 64 * 2]).unwrap_err(), DecodeError::Field(DecodeField::MakerAmount));
+        // Trailing fee word is optional
+        assert!(decode(&full[..2 + 64 * 4]).is_ok());
+        // Bad hex is attributed too
+        let bad = full.replacen(&word(10_000_000), &format!("{:0>64}", "zz"), 1);
+        assert_eq!(decode(&bad).unwrap_err(), DecodeError::Field(DecodeField::MakerAmount));
+    }
+
+    #[test]
+    fn test_non_failures_and_side_errors() {
+        assert_eq!(decode_event(r#"{"jsonrpc":"2.0","id":1,"result":"0xsub"}"#, |_| true, 6).unwrap_err(), DecodeError::Ignored);
+        assert_eq!(
+            decode_event(&log_message("0xother", &buy_data()), |t| t == WHALE_TOPIC, 6).unwrap_err(),
+            DecodeError::Ignored
+        );
+        let both_zero = format!("0x{}{}{}{}", word(0), word(0), word(1), word(1));
+        assert_eq!(decode(&both_zero).unwrap_err(), DecodeError::AmbiguousSide);
+        let zero_shares = format!("0x{}{}{}{}", word(0), word(7), word(1), word(0));
+        assert_eq!(decode(&zero_shares).unwrap_err(), DecodeError::ZeroShares);
+    }
+
+    #[test]
+    fn test_price_sanity_bounds() {
+        // BUY: maker amount is USD, taker amount is shares
+        let zero_usd = format!("0x{}{}{}{}", word(0), word(7), word(0), word(20_000_000));
+        assert_eq!(decode(&zero_usd).unwrap_err(), DecodeError::ZeroUsd);
+
+        // $10 for 1 share -> price 10
+        let price_above_one = format!("0x{}{}{}{}", word(0), word(7), word(10_000_000), word(1_000_000));
+        assert_eq!(decode(&price_above_one).unwrap_err(), DecodeError::PriceOutOfRange);
+
+        // Dust USD for many shares -> implausibly cheap
+        let price_dust = format!("0x{}{}{}{}", word(0), word(7), word(1), word(20_000_000));
+        assert_eq!(decode(&price_dust).unwrap_err(), DecodeError::PriceOutOfRange);
+
+        // 0 / 0 would be NaN: rejected before dividing
+        let nan = format!("0x{}{}{}{}", word(0), word(7), word(0), word(0));
+        assert_eq!(decode(&nan).unwrap_err(), DecodeError::ZeroShares);
+
+        // Edges of the plausible range pass
+        let cheap = format!("0x{}{}{}{}", word(0), word(7), word(1_000), word(1_000_000));
+        assert_eq!(decode(&cheap).unwrap().order.price_per_share, MIN_FILL_PRICE);
+    }
+
+    #[test]
+    fn test_multiple_targets() {
+        // Config spellings are normalized once; topics in logs may be any case
+        let targets = target_topics_from("0xABCDEFabcdefABCDEFabcdefABCDEFabcdef0001, 204F72F35326DB932158CBA6ADFF0B9A1DA95E14");
+        assert_eq!(targets.len(), 2);
+        let is_target = |t: &str| is_target_topic(t, &targets);
+
+        let evt = decode_event(&log_message(&WHALE_TOPIC.to_uppercase().replace("0X", "0x"), &buy_data()), is_target, 6).unwrap();
+        assert_eq!(evt.whale_address, "0x204f72f35326db932158cba6adff0b9a1da95e14");
+
+        let other = "0x0000000000000000000000001111111111111111111111111111111111111111";
+        assert_eq!(decode_event(&log_message(other, &buy_data()), is_target, 6).unwrap_err(), DecodeError::Ignored);
+    }
+
+    #[test]
+    fn test_failure_counts() {
+        let before = decode_failure_counts();
+        record_decode_failure(DecodeError::Field(DecodeField::TakerAmount));
+        record_decode_failure(DecodeError::Ignored);
+        let after = decode_failure_counts();
+        let get = |v: &[(&str, u64)], k: &str| v.iter().find(|(n, _)| *n == k).unwrap().1;
+        assert!(get(&after, "taker_amount") > get(&before, "taker_amount"));
+        assert_eq!(after.len(), 10);
+    }
+
+    #[test]
+    fn test_text_and_binary_frames_counted_by_transport() {
+        let is_target = |t: &str| t.eq_ignore_ascii_case(WHALE_TOPIC);
+        let payload = log_message(WHALE_TOPIC, &buy_data());
+        let (text_before, bin_before) = (transport_counts(FrameTransport::Text), transport_counts(FrameTransport::Binary));
+
+        let from_text = parse_ws_frame_with(FrameTransport::Text, payload.as_bytes(), is_target, 6).unwrap();
+        let from_bin = parse_ws_frame_with(FrameTransport::Binary, payload.as_bytes(), is_target, 6).unwrap();
+        assert_eq!((from_text.transport, from_bin.transport), (FrameTransport::Text, FrameTransport::Binary));
+        assert_eq!(from_text.order.clob_token_id, from_bin.order.clob_token_id);
+        assert_eq!(from_text.order.shares, from_bin.order.shares);
+
+        // Non-UTF-8 binary is counted as a frame and a failure, not dropped silently
+        let failures_before = decode_failure_counts();
+        assert!(parse_ws_frame_with(FrameTransport::Binary, &[0xff, 0xfe, b'{'], is_target, 6).is_none());
+        let get = |v: &[(&str, u64)], k: &str| v.iter().find(|(n, _)| *n == k).unwrap().1;
+        assert!(get(&decode_failure_counts(), "invalid_utf8") > get(&failures_before, "invalid_utf8"));
+
+        // Other tests may count frames concurrently, so only look at the increase
+        let (text_after, bin_after) = (transport_counts(FrameTransport::Text), transport_counts(FrameTransport::Binary));
+        assert!(text_after.frames > text_before.frames && text_after.events > text_before.events);
+        assert!(bin_after.frames >= bin_before.frames + 2 && bin_after.events > bin_before.events);
+    }
+}
@@ -0,0 +1,128 @@
+//! Gamma API health and last-known live status
+//! During a Gamma outage handle_event falls back to the last live flag we saw for a token
+//! instead of treating it as non-live
+
+use rustc_hash::FxHashMap;
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+
+/// Max tokens whose last-known live flag is remembered (cleared when full)
+const MAX_LAST_KNOWN: usize = 4096;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GammaStatus {
+    pub healthy: bool,
+    /// Unix seconds of the first failure in the current outage
+    pub failing_since: Option<u64>,
+    pub consecutive_failures: u64,
+    pub last_ok: Option<u64>,
+    /// Lookups answered from last-known values during outages
+    pub stale_served: u64,
+}
+
+#[derive(Default)]
+struct State {
+    status: GammaStatus,
+    last_known_live: FxHashMap<String, bool>,
+}
+
+#[derive(Default)]
+pub struct GammaHealth {
+    state: Mutex<State>,
+}
+
+/// Health change worth a log line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    None,
+    /// First failure after being healthy (or at startup)
+    StartedFailing,
+    Recovered,
+}
+
+impl GammaHealth {
+    pub fn new() -> Self {
+        Self { state: Mutex::new(State { status: GammaStatus { healthy: true, ..Default::default() }, ..Default::default() }) }
+    }
+
+    /// A live-status lookup succeeded
+    pub fn record_success(&self, token_id: &str, is_live: bool, now_unix_secs: u64) -> Transition {
+        let Ok(mut st) = self.state.lock() else { return Transition::None };
+        if st.last_known_live.len() >= MAX_LAST_KNOWN && !st.last_known_live.contains_key(token_id) {
+            st.last_known_live.clear();
+        }
+        st.last_known_live.insert(token_id.to_string(), is_live);
+        let was_failing = !st.status.healthy;
+        st.status.healthy = true;
+        st.status.failing_since = None;
+        st.status.consecutive_failures = 0;
+        st.status.last_ok = Some(now_unix_secs);
+        if was_failing { Transition::Recovered } else { Transition::None }
+    }
+
+    /// A lookup failed (network, HTTP or parse error)
+    pub fn record_failure(&self, now_unix_secs: u64) -> Transition {
+        let Ok(mut st) = self.state.lock() else { return Transition::None };
+        st.status.consecutive_failures += 1;
+        if st.status.healthy {
+            st.status.healthy = false;
+            st.status.failing_since = Some(now_unix_secs);
+            Transition::StartedFailing
+        } else {
+            Transition::None
+        }
+    }
+
+    /// Last live flag seen for this token (counted as a stale serve)
+    pub fn last_known_live(&self, token_id: &str) -> Option<bool> {
+        let mut st = self.state.lock().ok()?;
+        let v = st.last_known_live.get(token_id).copied();
+        if v.is_some() {
+            st.status.stale_served += 1;
+        }
+        v
+    }
+
+    pub fn snapshot(&self) -> GammaStatus {
+        self.state.lock().map(|st| st.status.clone()).unwrap_or_default()
+    }
+}
+
+// ============================================================================
+// Global Instance
+// ============================================================================
+
+static GLOBAL_GAMMA: OnceLock<GammaHealth> = OnceLock::new();
+
+/// Get the global Gamma health tracker
+pub fn global() -> &'static GammaHealth {
+    GLOBAL_GAMMA.get_or_init(GammaHealth::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_known_served_during_outage() {
+        let gamma = GammaHealth::new();
+        assert_eq!(gamma.record_success("tok", true, 100), Transition::None);
+
+        // Outage: warn once, keep serving the last-known value
+        assert_eq!(gamma.record_failure(200), Transition::StartedFailing);
+        assert_eq!(gamma.record_failure(201), Transition::None);
+        assert_eq!(gamma.last_known_live("tok"), Some(true));
+        assert_eq!(gamma.last_known_live("never_seen"), None);
+
+        let st = gamma.snapshot();
+        assert!(!st.healthy);
+        assert_eq!(st.failing_since, Some(200));
+        assert_eq!(st.consecutive_failures, 2);
+        assert_eq!(st.stale_served, 1);
+
+        assert_eq!(gamma.record_success("tok", false, 300), Transition::Recovered);
+        let st = gamma.snapshot();
+        assert!(st.healthy && st.failing_since.is_none());
+        assert_eq!(st.last_ok, Some(300));
+    }
+}
@@ -0,0 +1,242 @@
+//! Order outcome webhook (WEBHOOK_URL)
+//! Messages are built from a user template with {placeholders}, validated at startup
+
+use crate::block_summary::{classify_status, StatusClass};
+use crate::status_code::StatusCode;
+
+/// Values a template can reference
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placeholder {
+    Token,
+    Market,
+    Side,
+    Usd,
+    Status,
+    FillPct,
+    Block,
+    Tx,
+}
+
+impl Placeholder {
+    pub const ALL: [Placeholder; 8] = [
+        Placeholder::Token,
+        Placeholder::Market,
+        Placeholder::Side,
+        Placeholder::Usd,
+        Placeholder::Status,
+        Placeholder::FillPct,
+        Placeholder::Block,
+        Placeholder::Tx,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Placeholder::Token => "token",
+            Placeholder::Market => "market",
+            Placeholder::Side => "side",
+            Placeholder::Usd => "usd",
+            Placeholder::Status => "status",
+            Placeholder::FillPct => "fill_pct",
+            Placeholder::Block => "block",
+            Placeholder::Tx => "tx",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.as_str() == name)
+    }
+}
+
+/// One order outcome, as the template sees it
+#[derive(Debug, Clone, Default)]
+pub struct OutcomeFields {
+    pub token: String,
+    pub market: String,
+    pub side: String,
+    pub usd: f64,
+    pub status: String,
+    /// Filled / requested shares of our first order (None if unknown or not submitted)
+    pub fill_pct: Option<f64>,
+    pub block: u64,
+    pub tx: String,
+}
+
+impl OutcomeFields {
+    fn value(&self, p: Placeholder) -> String {
+        match p {
+            Placeholder::Token => self.token.clone(),
+            Placeholder::Market => self.market.clone(),
+            Placeholder::Side => self.side.clone(),
+            Placeholder::Usd => format!("{:.2}", self.usd),
+            Placeholder::Status => self.status.clone(),
+            Placeholder::FillPct => self.fill_pct.map(|p| format!("{:.0}", p)).unwrap_or_else(|| "-".into()),
+            Placeholder::Block => self.block.to_string(),
+            Placeholder::Tx => self.tx.clone(),
+        }
+    }
+}
+
+// ============================================================================
+// Template
+// ============================================================================
+
+pub const DEFAULT_TEMPLATE: &str = "{side} {market} ${usd} -> {status}";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Field(Placeholder),
+}
+
+/// Parsed WEBHOOK_TEMPLATE. `{{` and `}}` are literal braces
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageTemplate {
+    segments: Vec<Segment>,
+}
+
+impl MessageTemplate {
+    /// Parse a template; unknown or unclosed placeholders are an error (caught at config validation)
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        name.push(c);
+                    }
+                    if !closed {
+                        return Err(format!("unclosed placeholder {{{}", name));
+                    }
+                    let field = Placeholder::from_name(name.trim()).ok_or_else(|| {
+                        let known: Vec<&str> = Placeholder::ALL.iter().map(|p| p.as_str()).collect();
+                        format!("unknown placeholder {{{}}} (known: {})", name, known.join(", "))
+                    })?;
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Field(field));
+                }
+                _ => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        Ok(Self { segments })
+    }
+
+    pub fn render(&self, fields: &OutcomeFields) -> String {
+        self.segments
+            .iter()
+            .map(|s| match s {
+                Segment::Literal(text) => text.clone(),
+                Segment::Field(p) => fields.value(*p),
+            })
+            .collect()
+    }
+}
+
+impl Default for MessageTemplate {
+    fn default() -> Self {
+        Self::parse(DEFAULT_TEMPLATE).expect("default template is valid")
+    }
+}
+
+// ============================================================================
+// Outcome Filter
+// ============================================================================
+
+/// Which outcomes are sent (WEBHOOK_OUTCOMES: comma list of copied, failed, skipped)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutcomeFilter {
+    pub copied: bool,
+    pub failed: bool,
+    pub skipped: bool,
+}
+
+impl OutcomeFilter {
+    /// Parse a comma list; empty means "copied,failed"
+    pub fn parse(value: &str) -> Self {
+        let names: Vec<String> = value.split(',').map(|s| s.trim().to_ascii_lowercase()).filter(|s| !s.is_empty()).collect();
+        if names.is_empty() {
+            return Self { copied: true, failed: true, skipped: false };
+        }
+        let has = |n: &str| names.iter().any(|s| s == n || s == "all");
+        Self { copied: has("copied"), failed: has("failed"), skipped: has("skipped") }
+    }
+
+    pub fn allows(&self, status: StatusCode) -> bool {
+        match classify_status(status) {
+            StatusClass::Copied => self.copied,
+            StatusClass::Failed => self.failed,
+            StatusClass::Skipped(_) => self.skipped,
+        }
+    }
+}
+
+/// JSON body accepted by Discord ("content") and Slack-style ("text") webhooks
+pub fn webhook_body(message: &str) -> serde_json::Value {
+    serde_json::json!({ "content": message, "text": message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields() -> OutcomeFields {
+        OutcomeFields {
+            token: "12345".into(),
+            market: "BTC above 100k? [Yes]".into(),
+            side: "BUY_FILL".into(),
+            usd: 1234.5,
+            status: "200 OK [FULL]".into(),
+            fill_pct: Some(87.5),
+            block: 42,
+            tx: "0xabc".into(),
+        }
+    }
+
+    #[test]
+    fn test_template_substitution() {
+        let t = MessageTemplate::parse("[{block}] {side} {token} ${usd} {status} fill={fill_pct}% {{raw}}").unwrap();
+        assert_eq!(t.render(&fields()), "[42] BUY_FILL 12345 $1234.50 200 OK [FULL] fill=88% {raw}");
+
+        // Unknown fill shows as "-"; default template
+        let skipped = OutcomeFields { fill_pct: None, status: "SKIPPED_SMALL".into(), ..fields() };
+        assert_eq!(MessageTemplate::parse("{fill_pct}").unwrap().render(&skipped), "-");
+        assert_eq!(MessageTemplate::default().render(&fields()), "BUY_FILL BTC above 100k? [Yes] $1234.50 -> 200 OK [FULL]");
+    }
+
+    #[test]
+    fn test_unknown_placeholder_rejected() {
+        let err = MessageTemplate::parse("{side} {price}").unwrap_err();
+        assert!(err.contains("{price}") && err.contains("fill_pct"), "{err}");
+        assert!(MessageTemplate::parse("{side").is_err());
+        assert_eq!(MessageTemplate::parse("no placeholders").unwrap().render(&fields()), "no placeholders");
+    }
+
+    #[test]
+    fn test_outcome_filter() {
+        let default = OutcomeFilter::parse("");
+        assert!(default.allows(StatusCode::OrderPosted) && default.allows(StatusCode::ExecFail) && !default.allows(StatusCode::SkippedSmall));
+        let skips = OutcomeFilter::parse("skipped");
+        assert!(!skips.allows(StatusCode::OrderPosted) && skips.allows(StatusCode::CbBlocked));
+        assert!(OutcomeFilter::parse("all").allows(StatusCode::SkippedSmall));
+    }
+}
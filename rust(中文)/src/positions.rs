@@ -0,0 +1,219 @@
+//! Internally tracked positions and reconciliation against exchange balances
+//! Catches drift (missed fills, resting orders filled later, manual trades, rounding)
+//! before it quietly skews exposure
+
+use rustc_hash::FxHashMap;
+use serde_json::Value;
+use std::sync::{Mutex, OnceLock};
+
+// ============================================================================
+// Tracker
+// ============================================================================
+
+/// Net shares held per token, as seen by our own fills
+#[derive(Default)]
+pub struct Positions {
+    shares: Mutex<FxHashMap<String, f64>>,
+}
+
+impl Positions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a fill we got back from the CLOB (BUY adds, SELL subtracts)
+    pub fn record_fill(&self, token_id: &str, shares: f64, is_buy: bool) {
+        if !shares.is_finite() || shares <= 0.0 {
+            return;
+        }
+        let Ok(mut map) = self.shares.lock() else { return };
+        let held = map.entry(token_id.to_string()).or_insert(0.0);
+        *held += if is_buy { shares } else { -shares };
+    }
+
+    /// Record what the CLOB actually filled for an order (not the requested size).
+    /// Returns an OVERFILL warning when the fill exceeds the request by more than `tolerance` shares
+    pub fn record_order_fill(&self, token_id: &str, requested: f64, filled: f64, is_buy: bool, tolerance: f64) -> Option<String> {
+        self.record_fill(token_id, filled, is_buy);
+        overfill_excess(requested, filled, tolerance).map(|excess| {
+            format!(
+                "OVERFILL: {:.2}/{:.2} shares on {} (+{:.2}), tracking the actual fill",
+                filled, requested, token_id, excess
+            )
+        })
+    }
+
+    /// Tracked shares for `token_id` (0.0 if never traded)
+    pub fn get(&self, token_id: &str) -> f64 {
+        self.shares.lock().ok().and_then(|m| m.get(token_id).copied()).unwrap_or(0.0)
+    }
+
+    /// Overwrite the tracked size
+    pub fn set(&self, token_id: &str, shares: f64) {
+        if let Ok(mut map) = self.shares.lock() {
+            map.insert(token_id.to_string(), shares);
+        }
+    }
+
+    pub fn snapshot(&self) -> FxHashMap<String, f64> {
+        self.shares.lock().map(|m| m.clone()).unwrap_or_default()
+    }
+
+    /// Move each token by its drift, so fills recorded while the exchange balances were being
+    /// fetched aren't overwritten
+    pub fn apply_corrections(&self, discrepancies: &[Discrepancy]) {
+        let Ok(mut map) = self.shares.lock() else { return };
+        for d in discrepancies {
+            *map.entry(d.token_id.clone()).or_insert(0.0) += d.drift();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.shares.lock().map(|m| m.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Shares filled beyond the requested size, if more than `tolerance`
+pub fn overfill_excess(requested: f64, filled: f64, tolerance: f64) -> Option<f64> {
+    let excess = filled - requested;
+    (requested > 0.0 && excess > tolerance).then_some(excess)
+}
+
+// ============================================================================
+// Reconciliation
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Discrepancy {
+    pub token_id: String,
+    pub internal: f64,
+    pub exchange: f64,
+}
+
+impl Discrepancy {
+    /// Exchange minus internal (positive = we hold more than we think)
+    pub fn drift(&self) -> f64 {
+        self.exchange - self.internal
+    }
+}
+
+/// Tokens whose internal and exchange sizes differ by more than `tolerance` shares,
+/// sorted by token id. A token missing on either side counts as 0.
+pub fn diff_positions(
+    internal: &FxHashMap<String, f64>,
+    exchange: &FxHashMap<String, f64>,
+    tolerance: f64,
+) -> Vec<Discrepancy> {
+    let mut out: Vec<Discrepancy> = internal
+        .keys()
+        .chain(exchange.keys().filter(|k| !internal.contains_key(*k)))
+        .filter_map(|token| {
+            let d = Discrepancy {
+                token_id: token.clone(),
+                internal: internal.get(token).copied().unwrap_or(0.0),
+                exchange: exchange.get(token).copied().unwrap_or(0.0),
+            };
+            (d.drift().abs() > tolerance).then_some(d)
+        })
+        .collect();
+    out.sort_by(|a, b| a.token_id.cmp(&b.token_id));
+    out
+}
+
+/// Share balance from a GET /balance-allowance body (`{"balance":"12500000",...}`), in raw
+/// units at the market's collateral `decimals` (6-decimal micro-units on USDC markets)
+pub fn parse_balance(body: &str, decimals: u8) -> Option<f64> {
+    let v: Value = serde_json::from_str(body).ok()?;
+    let raw = match &v["balance"] {
+        Value::String(s) => s.parse::<f64>().ok()?,
+        Value::Number(n) => n.as_f64()?,
+        _ => return None,
+    };
+    Some(raw / 10f64.powi(decimals as i32))
+}
+
+// ============================================================================
+// Global Instance
+// ============================================================================
+
+static GLOBAL_POSITIONS: OnceLock<Positions> = OnceLock::new();
+
+/// Get the global tracker
+pub fn global() -> &'static Positions {
+    GLOBAL_POSITIONS.get_or_init(Positions::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(entries: &[(&str, f64)]) -> FxHashMap<String, f64> {
+        entries.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn test_diff_positions() {
+        let internal = map(&[("a", 100.0), ("b", 50.0), ("c", 20.0)]);
+        let exchange = map(&[("a", 100.4), ("b", 30.0), ("d", 15.0)]);
+
+        let diff = diff_positions(&internal, &exchange, 1.0);
+        assert_eq!(
+            diff,
+            vec![
+                Discrepancy { token_id: "b".into(), internal: 50.0, exchange: 30.0 },
+                Discrepancy { token_id: "c".into(), internal: 20.0, exchange: 0.0 },
+                Discrepancy { token_id: "d".into(), internal: 0.0, exchange: 15.0 },
+            ]
+        );
+        assert_eq!(diff[0].drift(), -20.0);
+
+        // Within tolerance: nothing to report
+        assert!(diff_positions(&internal, &internal, 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_record_fill_and_correct() {
+        let positions = Positions::new();
+        positions.record_fill("a", 10.0, true);
+        positions.record_fill("a", 4.0, false);
+        positions.record_fill("a", 0.0, true);
+        assert_eq!(positions.get("a"), 6.0);
+
+        let diff = diff_positions(&positions.snapshot(), &map(&[("a", 9.0)]), 1.0);
+        positions.apply_corrections(&diff);
+        assert_eq!(positions.get("a"), 9.0);
+
+        // A fill recorded after the snapshot survives the correction
+        let diff = diff_positions(&positions.snapshot(), &map(&[("a", 12.0)]), 1.0);
+        positions.record_fill("a", 5.0, true);
+        positions.apply_corrections(&diff);
+        assert_eq!(positions.get("a"), 17.0);
+    }
+
+    #[test]
+    fn test_overfill_tracks_actual_fill() {
+        let positions = Positions::new();
+        let warning = positions.record_order_fill("a", 100.0, 105.0, true, 0.01).unwrap();
+        assert_eq!(positions.get("a"), 105.0);
+        assert!(warning.starts_with("OVERFILL: 105.00/100.00 shares on a (+5.00)"));
+
+        // Rounding within tolerance and underfills are recorded as filled, without a warning
+        assert_eq!(positions.record_order_fill("b", 10.0, 10.005, true, 0.01), None);
+        assert_eq!(positions.record_order_fill("c", 10.0, 6.0, true, 0.01), None);
+        assert_eq!(positions.get("b"), 10.005);
+        assert_eq!(positions.get("c"), 6.0);
+    }
+
+    #[test]
+    fn test_parse_balance() {
+        assert_eq!(parse_balance(r#"{"balance":"12500000","allowances":{}}"#, 6), Some(12.5));
+        assert_eq!(parse_balance(r#"{"balance":"12500000000000000000"}"#, 18), Some(12.5));
+        assert_eq!(parse_balance(r#"{"balance":0}"#, 6), Some(0.0));
+        assert_eq!(parse_balance(r#"{"error":"bad"}"#, 6), None);
+        assert_eq!(parse_balance("not json", 6), None);
+    }
+}
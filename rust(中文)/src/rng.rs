@@ -0,0 +1,81 @@
+//! Seedable randomness for sizing rolls and submit jitter
+//! Draws come from a thread-local StdRng instead of rand::thread_rng, so a test or a replay can
+//! fix the seed and get the same probability rolls every run. RNG_SEED seeds every thread that
+//! hasn't drawn yet, mixed with the order threads make their first draw in so no two threads
+//! share a sequence; unseeded threads start from OS entropy
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+static SEED: OnceLock<u64> = OnceLock::new();
+
+/// Threads seeded from SEED so far
+static SEEDED_THREADS: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    static RNG: RefCell<StdRng> = RefCell::new(match SEED.get() {
+        Some(seed) => StdRng::seed_from_u64(thread_seed(*seed, SEEDED_THREADS.fetch_add(1, Ordering::Relaxed))),
+        None => StdRng::from_entropy(),
+    });
+}
+
+/// Seed of the `index`th thread to draw (the first keeps `seed` itself)
+fn thread_seed(seed: u64, index: u64) -> u64 {
+    seed ^ index.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+/// Seed for threads that haven't drawn yet (first call wins). False if already set
+pub fn set_seed(seed: u64) -> bool {
+    SEED.set(seed).is_ok()
+}
+
+/// Restart this thread's sequence from `seed`
+pub fn reseed(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+/// Run `f` with this thread's generator
+pub fn with_rng<R>(f: impl FnOnce(&mut StdRng) -> R) -> R {
+    RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
+
+/// Uniform in [0, 1), e.g. a probabilistic sizing roll
+pub fn roll() -> f64 {
+    with_rng(|rng| rng.r#gen::<f64>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::submit_jitter::SubmitJitter;
+
+    #[test]
+    fn test_fixed_seed_reproducible() {
+        let draw = || {
+            let rolls: Vec<f64> = (0..5).map(|_| roll()).collect();
+            let seed = with_rng(|rng| rng.r#gen::<u64>());
+            let mut jitter = SubmitJitter::new(250, seed);
+            (rolls, (0..5).map(|_| jitter.next_delay()).collect::<Vec<_>>())
+        };
+        reseed(7);
+        let first = draw();
+        reseed(7);
+        assert_eq!(draw(), first);
+        assert!(first.0.iter().all(|r| (0.0..1.0).contains(r)));
+
+        reseed(8);
+        assert_ne!(draw().0, first.0);
+    }
+
+    #[test]
+    fn test_threads_get_distinct_seeds() {
+        assert_eq!(thread_seed(7, 0), 7);
+        let seeds: Vec<u64> = (0..4).map(|i| thread_seed(7, i)).collect();
+        assert!(seeds.iter().enumerate().all(|(i, s)| !seeds[..i].contains(s)));
+        let first = |seed| StdRng::seed_from_u64(seed).r#gen::<u64>();
+        assert_ne!(first(seeds[0]), first(seeds[1]));
+    }
+}
@@ -0,0 +1,110 @@
+//! Bounded runs for CI and smoke tests (--max-events N, --max-runtime SECS)
+//! The WS loop stops once either limit is reached and the bot exits with the session summary
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RunLimits {
+    /// Stop after this many parsed events (None = unbounded)
+    pub max_events: Option<u64>,
+    /// Stop after this long (None = unbounded)
+    pub max_runtime: Option<Duration>,
+}
+
+impl RunLimits {
+    /// Parse `--max-events N` / `--max-runtime SECS` (also `--flag=value`); other args are ignored
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut limits = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            if flag != "--max-events" && flag != "--max-runtime" {
+                continue;
+            }
+            let value = inline.or_else(|| args.next()).ok_or_else(|| format!("{} needs a value", flag))?;
+            let n: u64 = value.trim().parse().ok().filter(|n| *n > 0)
+                .ok_or_else(|| format!("{} must be a positive integer, got {:?}", flag, value))?;
+            if flag == "--max-events" {
+                limits.max_events = Some(n);
+            } else {
+                limits.max_runtime = Some(Duration::from_secs(n));
+            }
+        }
+        Ok(limits)
+    }
+
+    pub fn bounded(&self) -> bool {
+        self.max_events.is_some() || self.max_runtime.is_some()
+    }
+}
+
+/// Counts events against --max-events; shared across reconnects
+#[derive(Debug, Default)]
+pub struct EventBudget {
+    max: Option<u64>,
+    seen: AtomicU64,
+}
+
+impl EventBudget {
+    pub fn new(max: Option<u64>) -> Self {
+        Self { max, seen: AtomicU64::new(0) }
+    }
+
+    /// Count one event; true once the budget is used up (the caller stops reading the feed)
+    pub fn record(&self) -> bool {
+        let seen = self.seen.fetch_add(1, Ordering::Relaxed) + 1;
+        self.max.is_some_and(|max| seen >= max)
+    }
+
+    pub fn seen(&self) -> u64 {
+        self.seen.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_limits() {
+        let limits = RunLimits::from_args(args(&["--print-config", "--max-events", "5", "--max-runtime=30"])).unwrap();
+        assert_eq!(limits, RunLimits { max_events: Some(5), max_runtime: Some(Duration::from_secs(30)) });
+        assert!(limits.bounded());
+        assert!(!RunLimits::from_args(args(&["--decode"])).unwrap().bounded());
+
+        assert!(RunLimits::from_args(args(&["--max-events"])).is_err());
+        assert!(RunLimits::from_args(args(&["--max-events", "0"])).is_err());
+        assert!(RunLimits::from_args(args(&["--max-runtime=soon"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_loop_stops_after_max_events() {
+        // Feed that never runs dry, read the way run_ws_loop reads the socket
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<u32>();
+        for i in 0..100 {
+            tx.send(i).unwrap();
+        }
+        let budget = EventBudget::new(Some(7));
+        let mut handled = Vec::new();
+        while let Some(evt) = rx.recv().await {
+            handled.push(evt);
+            if budget.record() {
+                break;
+            }
+        }
+        assert_eq!(handled, (0..7).collect::<Vec<_>>());
+        assert_eq!(budget.seen(), 7);
+
+        // Unbounded never stops on its own
+        let unbounded = EventBudget::new(None);
+        assert!((0..1_000).all(|_| !unbounded.record()));
+    }
+}
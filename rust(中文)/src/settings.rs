@@ -1,11 +1,26 @@
-/// Settings and configuration management
-/// Handles environment variable loading and validation
+//! Settings and configuration management
+//! Handles environment variable loading and validation
 
 use anyhow::{Context, Result};
 use std::env;
 use std::path::Path;
 use std::time::Duration;
 use crate::risk_guard;
+use crate::block_resolver;
+use crate::key_source;
+use crate::csv_log::{CsvDirection, CsvQuoting};
+use crate::engine::{floor_to_tick, CopyMode, FollowSides, OpposingSignalPolicy, PlanParams, PriceBounds, SameTokenPolicy, ThinBookAction, TickRounding, WhalePriceMode};
+use crate::block_summary::{SummaryMode, UsdPrecision};
+use crate::handler_limit::SaturationPolicy;
+use crate::order_queue::QueuePriority;
+use crate::live_flip::LiveFlipAction;
+use crate::confirm::OnTimeout;
+use crate::market_cache;
+use crate::{HttpTimeouts, StaleCredsPolicy};
+use crate::signer::RemoteSignerConfig;
+use crate::resting_orders::RestingCap;
+use crate::notify::{MessageTemplate, OutcomeFilter};
+use crate::watchdog::{parse_active_hours, WatchdogConfig};
 use crate::tennis_markets;
 use crate::soccer_markets;
 
@@ -18,19 +33,42 @@ use once_cell::sync::Lazy;
 pub const ORDERS_FILLED_EVENT_SIGNATURE: &str =
     "0xd0a08e8c493f9c94f29311604c9de1b4e8c8d4c06bd0c789af57f2d65bfec0f6";
 
-/// Target whale address topic - loaded from TARGET_WHALE_ADDRESS env var
-/// Format: 40-char hex address without 0x prefix (e.g., "204f72f35326db932158cba6adff0b9a1da95e14")
-/// Gets zero-padded to 66 chars with 0x prefix for topic matching
+/// Target whale address topics - loaded from TARGET_WHALE_ADDRESS env var (comma-separated to follow several)
+/// Format: 40-char hex addresses without 0x prefix (e.g., "204f72f35326db932158cba6adff0b9a1da95e14")
+/// Each gets zero-padded to 66 chars with 0x prefix and lowercased for topic matching
 /// 
 /// Note: This is validated in Config::from_env() before use, so expect should not panic
 /// in normal operation. If you see this panic, it means Config::from_env() was not called first.
-pub static TARGET_TOPIC_HEX: Lazy<String> = Lazy::new(|| {
-    let addr = env::var("TARGET_WHALE_ADDRESS")
+pub static TARGET_TOPICS: Lazy<Vec<String>> = Lazy::new(|| {
+    let addrs = env::var("TARGET_WHALE_ADDRESS")
         .expect("TARGET_WHALE_ADDRESS should have been validated in Config::from_env(). \
-                If you see this, please ensure you call Config::from_env() before using TARGET_TOPIC_HEX");
-    format!("0x000000000000000000000000{}", addr.trim_start_matches("0x").to_lowercase())
+                If you see this, please ensure you call Config::from_env() before using TARGET_TOPICS");
+    target_topics_from(&addrs)
 });
 
+/// Normalize a comma-separated address list into lowercase, zero-padded topic hex (duplicates dropped)
+pub fn target_topics_from(addrs: &str) -> Vec<String> {
+    let mut topics: Vec<String> = Vec::new();
+    for addr in addrs.split(',').map(str::trim).filter(|a| !a.is_empty()) {
+        let topic = format!("0x000000000000000000000000{}", addr.trim_start_matches("0x").to_lowercase());
+        if !topics.contains(&topic) {
+            topics.push(topic);
+        }
+    }
+    topics
+}
+
+/// True if `topic` (topics[2] of a log) is one of the normalized targets
+#[inline]
+pub fn is_target_topic(topic: &str, targets: &[String]) -> bool {
+    targets.iter().any(|t| t.eq_ignore_ascii_case(topic))
+}
+
+/// Decimals of on-chain collateral (USDC) and outcome share amounts in fill events and orders.
+/// Polymarket uses 6 for both; override with COLLATERAL_DECIMALS for other deployments/tests.
+/// Markets with their own entry in the collateral decimals cache use that instead
+pub static COLLATERAL_DECIMALS: Lazy<u8> = Lazy::new(|| env_parse("COLLATERAL_DECIMALS", 6u8));
+
 pub const MONITORED_ADDRESSES: [&str; 3] = [
     "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E",
     "0x4d97dcd97ec945f40cf65f87097ace5ea0476045",
@@ -41,9 +79,206 @@ pub const MONITORED_ADDRESSES: [&str; 3] = [
 // API & File Constants
 // ============================================================================
 
+/// Mainnet defaults (see Network for the Amoy testnet bundle)
 pub const CLOB_API_BASE: &str = "https://clob.polymarket.com";
+pub const GAMMA_API_BASE: &str = "https://gamma-api.polymarket.com";
+pub const DATA_API_BASE: &str = "https://data-api.polymarket.com";
 pub const CSV_FILE: &str = "matches_optimized.csv";
 
+/// Book levels read for the risk guard depth check (BOOK_DEPTH_LEVELS, default 10, max 100)
+pub static BOOK_DEPTH_LEVELS: Lazy<usize> =
+    Lazy::new(|| env_parse("BOOK_DEPTH_LEVELS", risk_guard::STACK_BOOK_LEVELS).clamp(1, risk_guard::MAX_BOOK_LEVELS));
+
+/// Reject /book snapshots whose timestamp is older than this for the depth check (refetch once,
+/// then fail the check as STALE_BOOK). BOOK_MAX_AGE_MS, default 0 = off; no-op without a timestamp
+pub static BOOK_MAX_AGE_MS: Lazy<u64> = Lazy::new(|| env_parse("BOOK_MAX_AGE_MS", 0));
+
+/// Taker address put on every order we sign (TAKER_ADDRESS; unset = open to any taker).
+/// Validated by Config::from_env, so an invalid value never reaches here
+pub static TAKER_ADDRESS: Lazy<Option<String>> =
+    Lazy::new(|| parse_taker_address(&env::var("TAKER_ADDRESS").unwrap_or_default()).ok().flatten());
+
+/// TAKER_ADDRESS: empty = None, otherwise 40 hex characters (0x optional), normalized to lowercase 0x-prefixed
+pub fn parse_taker_address(raw: &str) -> Result<Option<String>> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Ok(None);
+    }
+    let hex = raw.strip_prefix("0x").or_else(|| raw.strip_prefix("0X")).unwrap_or(raw);
+    if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        anyhow::bail!("TAKER_ADDRESS must be a 40-hex-character address (0x optional), got {:?}", raw);
+    }
+    Ok(Some(format!("0x{}", hex.to_ascii_lowercase())))
+}
+
+/// Fetch the book before the first order and act if it can't fill the plan:
+/// "off" (default), "downsize" or "skip". LIQUIDITY_PRECHECK
+pub static LIQUIDITY_PRECHECK: Lazy<ThinBookAction> =
+    Lazy::new(|| ThinBookAction::parse(&env::var("LIQUIDITY_PRECHECK").unwrap_or_default()).unwrap_or_default());
+
+/// Fraction of the planned size that must be fillable at the limit to pass the precheck.
+/// LIQUIDITY_PRECHECK_MIN_FRACTION, default 0.5
+pub static LIQUIDITY_PRECHECK_MIN_FRACTION: Lazy<f64> = Lazy::new(|| env_parse("LIQUIDITY_PRECHECK_MIN_FRACTION", 0.5));
+
+/// Floor for the buy limit of tiers with cross_spread: best ask + MIN_CROSS_BUFFER from the
+/// pre-submit book (0 = the ask itself, 0.01 = a cent through it). Unset = off; the whale
+/// premium cap still applies
+pub static MIN_CROSS_BUFFER: Lazy<Option<f64>> = Lazy::new(|| {
+    env::var("MIN_CROSS_BUFFER").ok().and_then(|v| v.trim().parse::<f64>().ok()).filter(|b| b.is_finite() && *b >= 0.0)
+});
+
+/// For tiers with walk_second_level: when the copy is bigger than the best level, move the limit
+/// to the second level's price + SECOND_LEVEL_BUFFER (minus, for sells) from the pre-submit
+/// book, so the FAK can take both levels. Unset = off; buys are still capped by WHALE_MAX_PREMIUM(_PCT)
+pub static SECOND_LEVEL_BUFFER: Lazy<Option<f64>> = Lazy::new(|| {
+    env::var("SECOND_LEVEL_BUFFER").ok().and_then(|v| v.trim().parse::<f64>().ok()).filter(|b| b.is_finite() && *b >= 0.0)
+});
+
+/// Subtract our own resting orders (resting-orders registry) from book depth before the
+/// risk guard depth check (EXCLUDE_OWN_RESTING_DEPTH=true). Off by default: adds a registry lookup
+pub static EXCLUDE_OWN_RESTING_DEPTH: Lazy<bool> = Lazy::new(|| {
+    env::var("EXCLUDE_OWN_RESTING_DEPTH").map(|v| v.eq_ignore_ascii_case("true") || v == "1").unwrap_or(false)
+});
+
+/// Cap on concurrent resting (GTD/GTC) buys: MAX_RESTING_ORDERS overall, MAX_RESTING_PER_TOKEN
+/// per token (0 = no cap). At the cap the new resting order is skipped (SKIPPED_MAX_RESTING),
+/// or with RESTING_CANCEL_OLDEST=true the oldest one is cancelled to make room
+pub static RESTING_CAP: Lazy<RestingCap> = Lazy::new(|| RestingCap {
+    max_total: env_parse("MAX_RESTING_ORDERS", 0),
+    max_per_token: env_parse("MAX_RESTING_PER_TOKEN", 0),
+    cancel_oldest: env::var("RESTING_CANCEL_OLDEST").map(|v| v.eq_ignore_ascii_case("true") || v == "1").unwrap_or(false),
+});
+
+/// Warn (and optionally reconnect) when no event has been parsed for EVENT_WATCHDOG_SECS
+/// (0 = off) within EVENT_WATCHDOG_HOURS_UTC ("start-end", empty = always)
+pub static EVENT_WATCHDOG: Lazy<WatchdogConfig> = Lazy::new(|| WatchdogConfig {
+    threshold_ms: env_parse::<u64>("EVENT_WATCHDOG_SECS", 0).saturating_mul(1000),
+    reconnect: env::var("EVENT_WATCHDOG_RECONNECT").map(|v| v.eq_ignore_ascii_case("true") || v == "1").unwrap_or(false),
+    active_hours: parse_active_hours(&env::var("EVENT_WATCHDOG_HOURS_UTC").unwrap_or_default()),
+});
+
+/// Log every order decision as one JSON line (TRACE_DECISIONS=true)
+pub static TRACE_DECISIONS: Lazy<bool> = Lazy::new(|| {
+    env::var("TRACE_DECISIONS").map(|v| v.eq_ignore_ascii_case("true") || v == "1").unwrap_or(false)
+});
+
+/// Log every effective setting at startup, secrets redacted (PRINT_CONFIG=true or --print-config)
+pub static PRINT_CONFIG: Lazy<bool> = Lazy::new(|| {
+    env::var("PRINT_CONFIG").map(|v| v.eq_ignore_ascii_case("true") || v == "1").unwrap_or(false)
+});
+
+/// Only copy executed fills; ignore matched events without the `_FILL` suffix (ACT_ON_FILLS_ONLY=true)
+pub static ACT_ON_FILLS_ONLY: Lazy<bool> = Lazy::new(|| {
+    env::var("ACT_ON_FILLS_ONLY").map(|v| v.eq_ignore_ascii_case("true") || v == "1").unwrap_or(false)
+});
+
+/// Which whale sides are copied: both (default), buy (entries only) or sell (exits only)
+pub static FOLLOW_SIDES: Lazy<FollowSides> =
+    Lazy::new(|| FollowSides::parse(&env::var("FOLLOW_SIDES").unwrap_or_default()).unwrap_or_default());
+
+/// Take liquidity (taker, default) or rest at the whale's price (maker). COPY_MODE
+pub static COPY_MODE: Lazy<CopyMode> = Lazy::new(|| CopyMode::parse(&env::var("COPY_MODE").unwrap_or_default()).unwrap_or_default());
+
+/// Seconds between reprices of an unfilled maker order (0 = leave it at its first price).
+/// MAKER_REPRICE_SECS, default 5
+pub static MAKER_REPRICE_SECS: Lazy<u64> = Lazy::new(|| env_parse("MAKER_REPRICE_SECS", 5));
+
+/// Most reprices per maker order. MAKER_REPRICE_STEPS, default 5
+pub static MAKER_REPRICE_STEPS: Lazy<u32> = Lazy::new(|| env_parse("MAKER_REPRICE_STEPS", 5));
+
+/// Narrowest spread (ticks) a maker copy rests in; tighter books are crossed as a taker
+/// (0 = always rest). MAKER_MIN_SPREAD_TICKS, default 0
+pub static MAKER_MIN_SPREAD_TICKS: Lazy<u32> = Lazy::new(|| env_parse("MAKER_MIN_SPREAD_TICKS", 0));
+
+/// Enforce each tier's min_depth_shares in the pre-submit book check. TIER_MIN_DEPTH, default false
+pub static TIER_MIN_DEPTH: Lazy<bool> = Lazy::new(|| {
+    env::var("TIER_MIN_DEPTH").map(|v| v.eq_ignore_ascii_case("true") || v == "1").unwrap_or(false)
+});
+
+/// Fixed seed for sizing rolls and, unless SUBMIT_JITTER_SEED is set, the submit jitter, so a
+/// run's random draws can be reproduced (unset = OS entropy). RNG_SEED
+pub static RNG_SEED: Lazy<Option<u64>> = Lazy::new(|| env::var("RNG_SEED").ok().and_then(|v| v.trim().parse().ok()));
+
+/// Max random delay (ms) before a copy's first submit, to desynchronize instances (0 = off).
+/// SUBMIT_JITTER_MS, default 0
+pub static SUBMIT_JITTER_MS: Lazy<u64> = Lazy::new(|| env_parse("SUBMIT_JITTER_MS", 0));
+
+/// Fixed seed for the submit jitter (unset = per instance and run). SUBMIT_JITTER_SEED
+pub static SUBMIT_JITTER_SEED: Lazy<Option<u64>> =
+    Lazy::new(|| env::var("SUBMIT_JITTER_SEED").ok().and_then(|v| v.trim().parse().ok()));
+
+/// How the free-text order_status CSV field is sanitized: "replace" (default) or "strict" (RFC 4180)
+pub static CSV_QUOTING: Lazy<CsvQuoting> =
+    Lazy::new(|| CsvQuoting::parse(&env::var("CSV_QUOTING").unwrap_or_default()).unwrap_or_default());
+
+/// CSV order type layout: "combined" (default, one `direction` column like BUY_FILL) or
+/// "split" (`side` + `is_fill` columns). Existing CSV files keep the header they were created with
+pub static CSV_DIRECTION: Lazy<CsvDirection> =
+    Lazy::new(|| CsvDirection::parse(&env::var("CSV_DIRECTION").unwrap_or_default()).unwrap_or_default());
+
+/// Append the tier outputs used for each trade (tier, buffer, multiplier, action, resubmit
+/// limits) to its CSV row (CSV_TIER_COLUMNS=true). Existing CSV files keep their header
+pub static CSV_TIER_COLUMNS: Lazy<bool> = Lazy::new(|| {
+    env::var("CSV_TIER_COLUMNS").map(|v| v.eq_ignore_ascii_case("true") || v == "1").unwrap_or(false)
+});
+
+/// Count CSV row / sanitize buffer reallocations and print them on shutdown (CSV_BUFFER_STATS=true)
+pub static CSV_BUFFER_STATS: Lazy<bool> = Lazy::new(|| {
+    env::var("CSV_BUFFER_STATS").map(|v| v.eq_ignore_ascii_case("true") || v == "1").unwrap_or(false)
+});
+
+/// Reference whale price for buffers/ceilings: "fill" (default, per event) or "vwap" (across the tx's fills)
+pub static WHALE_PRICE_MODE: Lazy<WhalePriceMode> =
+    Lazy::new(|| WhalePriceMode::parse(&env::var("WHALE_PRICE_MODE").unwrap_or_default()).unwrap_or_default());
+
+/// Align the whale price to the market tick before buffer math: "nearest" (default), "off",
+/// "round_toward_whale", "round_aggressive" or "reject" (see TickRounding)
+pub static WHALE_PRICE_ROUNDING: Lazy<TickRounding> =
+    Lazy::new(|| TickRounding::parse(&env::var("WHALE_PRICE_ROUNDING").unwrap_or_default()).unwrap_or_default());
+
+/// Price range for every submission, initial and resubmit (SUBMIT_PRICE_MIN / SUBMIT_PRICE_MAX).
+/// Unset = each market's [tick, 1 - tick]; prices outside are clamped, not rejected
+pub static SUBMIT_PRICE_BOUNDS: Lazy<PriceBounds> = Lazy::new(|| {
+    let bound = |key: &str| env::var(key).ok().and_then(|v| v.trim().parse::<f64>().ok()).filter(|b| *b > 0.0 && *b < 1.0);
+    PriceBounds { min: bound("SUBMIT_PRICE_MIN"), max: bound("SUBMIT_PRICE_MAX") }
+});
+
+/// Different whales on the same token and side in one block: "all" (default), "first" or "blend"
+pub static SAME_TOKEN_PER_BLOCK: Lazy<SameTokenPolicy> =
+    Lazy::new(|| SameTokenPolicy::parse(&env::var("SAME_TOKEN_PER_BLOCK").unwrap_or_default()).unwrap_or_default());
+
+/// Different whales on opposite sides of one token within OPPOSING_SIGNAL_WINDOW_MS:
+/// "both" (default), "first" or "net"
+pub static OPPOSING_SIGNALS: Lazy<OpposingSignalPolicy> =
+    Lazy::new(|| OpposingSignalPolicy::parse(&env::var("OPPOSING_SIGNALS").unwrap_or_default()).unwrap_or_default());
+
+/// How long (ms) a whale's trade counts as a signal an opposing one is checked against. Default 3000
+pub static OPPOSING_SIGNAL_WINDOW_MS: Lazy<u64> = Lazy::new(|| env_parse("OPPOSING_SIGNAL_WINDOW_MS", 3000));
+
+/// Decimals for USD in console lines: "auto" (default: cents below $100) or a fixed count
+pub static USD_DISPLAY_DECIMALS: Lazy<UsdPrecision> =
+    Lazy::new(|| UsdPrecision::parse(&env::var("USD_DISPLAY_DECIMALS").unwrap_or_default()).unwrap_or_default());
+
+/// Outcome webhook (Discord/Slack-compatible JSON POST). WEBHOOK_URL, empty = off
+pub static WEBHOOK_URL: Lazy<String> = Lazy::new(|| env::var("WEBHOOK_URL").unwrap_or_default().trim().to_string());
+
+/// Message template for the webhook (WEBHOOK_TEMPLATE). Validated by Config::from_env
+pub static WEBHOOK_TEMPLATE: Lazy<MessageTemplate> = Lazy::new(|| {
+    env::var("WEBHOOK_TEMPLATE")
+        .ok()
+        .filter(|t| !t.trim().is_empty())
+        .and_then(|t| MessageTemplate::parse(&t).ok())
+        .unwrap_or_default()
+});
+
+/// Which outcomes go to the webhook: comma list of copied, failed, skipped (or all). Default copied,failed
+pub static WEBHOOK_OUTCOMES: Lazy<OutcomeFilter> =
+    Lazy::new(|| OutcomeFilter::parse(&env::var("WEBHOOK_OUTCOMES").unwrap_or_default()));
+
+/// Per-block console summary: "off" (default), "only" (replaces ⚡ lines) or "both"
+pub static SUMMARY_MODE: Lazy<SummaryMode> =
+    Lazy::new(|| SummaryMode::parse(&env::var("SUMMARY_MODE").unwrap_or_default()).unwrap_or_default());
+
 // Debug flag - set to true to print full API error messages (remove after debugging)
 pub const DEBUG_FULL_ERRORS: bool = true;
 
@@ -57,6 +292,105 @@ pub const MIN_CASH_VALUE: f64 = 1.01;
 pub const MIN_SHARE_COUNT: f64 = 0.0;  // Set to 0 to rely purely on MIN_CASH_VALUE for EV scaling
 pub const USE_PROBABILISTIC_SIZING: bool = true;
 
+/// Inputs to calculate_safe_size, so a second (shadow) config can be sized alongside the live one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizingParams {
+    pub scaling_ratio: f64,
+    pub min_cash_value: f64,
+    pub min_share_count: f64,
+    pub probabilistic: bool,
+    /// Copy this fraction of the whale's shares instead of scaling_ratio (None = ratio mode)
+    pub follow_fraction: Option<f64>,
+    /// In fraction mode, skip the tier/override size multiplier (otherwise it's applied after)
+    pub fraction_ignores_multiplier: bool,
+    /// Allocation mode: size copies as this bankroll times the whale's trade / portfolio value
+    /// (None = off; falls back to ratio/fraction sizing while a whale's value is unknown)
+    pub allocation_bankroll_usd: Option<f64>,
+    /// Largest share of the bankroll one allocation-sized copy may use
+    pub allocation_max_fraction: f64,
+}
+
+/// Parse FOLLOW_FRACTION: "0.02" or "2%"; unset, zero, negative or above 1 = None
+pub fn parse_follow_fraction(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let fraction = match value.strip_suffix('%') {
+        Some(pct) => pct.trim().parse::<f64>().ok()? / 100.0,
+        None => value.parse::<f64>().ok()?,
+    };
+    (fraction > 0.0 && fraction <= 1.0).then_some(fraction)
+}
+
+/// The sizing constants above with every sizing env var unset
+pub const DEFAULT_SIZING: SizingParams = SizingParams {
+    scaling_ratio: SCALING_RATIO,
+    min_cash_value: MIN_CASH_VALUE,
+    min_share_count: MIN_SHARE_COUNT,
+    probabilistic: USE_PROBABILISTIC_SIZING,
+    follow_fraction: None,
+    fraction_ignores_multiplier: false,
+    allocation_bankroll_usd: None,
+    allocation_max_fraction: 0.10,
+};
+
+/// The sizing constants above, plus FOLLOW_FRACTION / FOLLOW_FRACTION_MULTIPLIER
+pub static LIVE_SIZING: Lazy<SizingParams> = Lazy::new(|| SizingParams {
+    follow_fraction: env::var("FOLLOW_FRACTION").ok().and_then(|v| parse_follow_fraction(&v)),
+    fraction_ignores_multiplier: env::var("FOLLOW_FRACTION_MULTIPLIER")
+        .map(|v| v.trim().eq_ignore_ascii_case("ignore"))
+        .unwrap_or(false),
+    allocation_bankroll_usd: env::var("ALLOCATION_BANKROLL_USD").ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|b| b.is_finite() && *b > 0.0),
+    allocation_max_fraction: env_parse("ALLOCATION_MAX_FRACTION", DEFAULT_SIZING.allocation_max_fraction).clamp(0.0, 1.0),
+    ..DEFAULT_SIZING
+});
+
+/// Confirm each whale fill in the data API's /activity feed before copying it. CONFIRM_VIA_API, default false
+pub static CONFIRM_VIA_API: Lazy<bool> = Lazy::new(|| {
+    env::var("CONFIRM_VIA_API").map(|v| v.eq_ignore_ascii_case("true") || v == "1").unwrap_or(false)
+});
+
+/// How long (ms) to poll /activity for the fill. CONFIRM_TIMEOUT_MS, default 1500
+pub static CONFIRM_TIMEOUT_MS: Lazy<u64> = Lazy::new(|| env_parse("CONFIRM_TIMEOUT_MS", 1500));
+
+/// Copy ("open") or skip ("closed", default) a fill not confirmed in time. CONFIRM_ON_TIMEOUT
+pub static CONFIRM_ON_TIMEOUT: Lazy<OnTimeout> =
+    Lazy::new(|| OnTimeout::parse(&env::var("CONFIRM_ON_TIMEOUT").unwrap_or_default()).unwrap_or_default());
+
+/// Seconds between whale portfolio value refreshes in allocation mode. WHALE_PORTFOLIO_REFRESH_SECS, default 300
+pub static WHALE_PORTFOLIO_REFRESH_SECS: Lazy<u64> = Lazy::new(|| env_parse("WHALE_PORTFOLIO_REFRESH_SECS", 300u64).max(10));
+
+/// Shadow sizing config: sized and logged for every planned order, never submitted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadowSizing {
+    pub sizing: SizingParams,
+    /// Added to the tier's limit buffer (premium cap still applies)
+    pub buffer_offset: f64,
+}
+
+/// SHADOW_SIZING=true enables it; SHADOW_* vars override the live values
+pub static SHADOW_SIZING: Lazy<Option<ShadowSizing>> = Lazy::new(|| {
+    let enabled = env::var("SHADOW_SIZING").map(|v| v.eq_ignore_ascii_case("true") || v == "1").unwrap_or(false);
+    enabled.then(|| ShadowSizing {
+        sizing: SizingParams {
+            scaling_ratio: env_parse("SHADOW_SCALING_RATIO", SCALING_RATIO),
+            min_cash_value: env_parse("SHADOW_MIN_CASH_VALUE", MIN_CASH_VALUE),
+            min_share_count: env_parse("SHADOW_MIN_SHARE_COUNT", MIN_SHARE_COUNT),
+            probabilistic: env::var("SHADOW_PROBABILISTIC_SIZING")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(USE_PROBABILISTIC_SIZING),
+            follow_fraction: match env::var("SHADOW_FOLLOW_FRACTION") {
+                Ok(v) => parse_follow_fraction(&v),
+                Err(_) => LIVE_SIZING.follow_fraction,
+            },
+            fraction_ignores_multiplier: LIVE_SIZING.fraction_ignores_multiplier,
+            allocation_bankroll_usd: LIVE_SIZING.allocation_bankroll_usd,
+            allocation_max_fraction: LIVE_SIZING.allocation_max_fraction,
+        },
+        buffer_offset: env_parse("SHADOW_BUFFER_OFFSET", 0.0),
+    })
+});
+
 // Minimum whale trade size to copy (skip trades below this)
 pub const MIN_WHALE_SHARES_TO_COPY: f64 = 10.0;
 
@@ -66,35 +400,120 @@ pub fn should_skip_trade(whale_shares: f64) -> bool {
     whale_shares < MIN_WHALE_SHARES_TO_COPY
 }
 
+/// Returns true if this event isn't an executed fill and only fills are copied
+#[inline]
+pub fn should_skip_non_fill(order_type: &str, fills_only: bool) -> bool {
+    fills_only && !order_type.ends_with("_FILL")
+}
+
 // ============================================================================
 // Timeouts
 // ============================================================================
 
 pub const ORDER_REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+/// Events waiting for the order worker before new ones are rejected (or shed, with ORDER_QUEUE_PRIORITY=usd_value)
+pub const ORDER_QUEUE_CAPACITY: usize = 1024;
 
 // ============================================================================
 // Resubmitter Configuration (for FAK failures)
 // ============================================================================
 
+/// Chase step on a cent-tick market; other tick sizes step one of their own ticks (resubmit_increment)
 pub const RESUBMIT_PRICE_INCREMENT: f64 = 0.01;
 
-// Tier-based max resubmit attempts (4000+ gets 5, others get 4)
+/// Chase step for a market's tick: one tick, so a 0.001-tick market chases a tenth of a cent at a time
+#[inline]
+pub fn resubmit_increment(tick: f64) -> f64 {
+    RESUBMIT_PRICE_INCREMENT / market_cache::DEFAULT_TICK_SIZE * tick
+}
+
+/// Next resubmit price: one tick further in the order's direction when chasing (flat otherwise),
+/// on the tick grid and within [tick, 1 - tick]. An off-grid price is rounded per
+/// WHALE_PRICE_ROUNDING (toward_whale / aggressive; nearest for the other policies)
+#[inline]
+pub fn calculate_next_price(failed_price: f64, side_is_buy: bool, chase: bool, tick: f64) -> f64 {
+    calculate_next_price_with(*WHALE_PRICE_ROUNDING, failed_price, side_is_buy, chase, tick)
+}
+
+/// calculate_next_price with an explicit rounding policy
+#[inline]
+pub fn calculate_next_price_with(rounding: TickRounding, failed_price: f64, side_is_buy: bool, chase: bool, tick: f64) -> f64 {
+    let increment = if chase { RESUBMIT_PRICE_INCREMENT } else { 0.0 };
+    stepped_price_with(rounding, failed_price, side_is_buy, increment, tick)
+}
+
+/// Move `failed_price` by a cent-market `increment` scaled to the tick (0.01 = one tick), then round
+#[inline]
+fn stepped_price_with(rounding: TickRounding, failed_price: f64, side_is_buy: bool, increment: f64, tick: f64) -> f64 {
+    let step = increment * resubmit_increment(tick) / RESUBMIT_PRICE_INCREMENT;
+    let target = if side_is_buy { failed_price + step } else { failed_price - step };
+    rounding.round_price(target, tick, side_is_buy)
+}
+
+/// One attempt of a tier's resubmit schedule
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResubmitStep {
+    /// Wait before submitting this attempt (ms)
+    pub delay_ms: u64,
+    /// Price step from the previous attempt on a cent-tick market (0 = flat retry). Other tick
+    /// sizes scale it, so 0.01 is always one tick
+    pub increment: f64,
+}
+
+impl ResubmitStep {
+    pub const FLAT: ResubmitStep = ResubmitStep { delay_ms: 0, increment: 0.0 };
+
+    #[inline]
+    pub fn delay(&self) -> Duration {
+        Duration::from_millis(self.delay_ms)
+    }
+
+    #[inline]
+    pub fn chases(&self) -> bool {
+        self.increment > 0.0
+    }
+
+    /// This attempt's price, stepped from the previous attempt's (rounded per WHALE_PRICE_ROUNDING)
+    #[inline]
+    pub fn next_price(&self, failed_price: f64, side_is_buy: bool, tick: f64) -> f64 {
+        self.next_price_with(*WHALE_PRICE_ROUNDING, failed_price, side_is_buy, tick)
+    }
+
+    #[inline]
+    pub fn next_price_with(&self, rounding: TickRounding, failed_price: f64, side_is_buy: bool, tick: f64) -> f64 {
+        stepped_price_with(rounding, failed_price, side_is_buy, self.increment, tick)
+    }
+}
+
+/// Step for a 1-based attempt in `schedule` (attempts past its end repeat the last step)
+#[inline]
+pub fn schedule_step(schedule: &[ResubmitStep], attempt: u8) -> ResubmitStep {
+    let idx = usize::from(attempt.max(1)) - 1;
+    schedule.get(idx).or(schedule.last()).copied().unwrap_or(ResubmitStep::FLAT)
+}
+
+/// Resubmit schedule for a buy of this size: its tier's, or DEFAULT_RESUBMIT_SCHEDULE below all tiers
+#[inline]
+pub fn resubmit_schedule(whale_shares: f64) -> &'static [ResubmitStep] {
+    find_execution_tier(whale_shares).map_or(DEFAULT_RESUBMIT_SCHEDULE, |t| t.resubmit_schedule)
+}
+
+/// Delay and price step for resubmit `attempt` of a buy of this size
+#[inline]
+pub fn resubmit_step(whale_shares: f64, attempt: u8) -> ResubmitStep {
+    schedule_step(resubmit_schedule(whale_shares), attempt)
+}
+
+/// Resubmit attempts for a buy of this size: the length of its tier's schedule
 #[inline]
 pub fn get_max_resubmit_attempts(whale_shares: f64) -> u8 {
-    if whale_shares >= 4000.0 { 5 }
-    else { 4 }
+    resubmit_schedule(whale_shares).len().min(u8::MAX as usize) as u8
 }
 
-/// Returns true if this attempt should increment price, false for flat retry
-/// >= 4000: chase attempt 1 only
-/// <4000: never chase (buffer=0)
+/// Returns true if this attempt's schedule step chases the price, false for a flat retry
 #[inline]
 pub fn should_increment_price(whale_shares: f64, attempt: u8) -> bool {
-    if whale_shares >= 4000.0 {
-        attempt == 1  
-    } else {
-        false  
-    }
+    resubmit_step(whale_shares, attempt).chases()
 }
 
 #[inline]
@@ -108,12 +527,206 @@ pub fn get_gtd_expiry_secs(is_live: bool) -> u64 {
 // <4000: no chasing (0.00)
 #[inline]
 pub fn get_resubmit_max_buffer(whale_shares: f64) -> f64 {
-    if whale_shares >= 4000.0 { 0.01 }
+    if tier_shares(whale_shares) >= 4000.0 { 0.01 }
     else { 0.00 }
 }
+
+/// Optional percentage chase ceiling (RESUBMIT_MAX_BUFFER_PCT, e.g. 2.0 = 2% of whale price).
+/// 0 = flat mode (tier buffer only)
+pub static RESUBMIT_MAX_BUFFER_PCT: Lazy<f64> = Lazy::new(|| env_parse("RESUBMIT_MAX_BUFFER_PCT", 0.0));
+
+/// Max resubmit buffer for this trade. In percentage mode the buffer scales with
+/// whale price and the flat tier buffer acts as the cap.
+#[inline]
+pub fn resubmit_max_buffer(whale_shares: f64, whale_price: f64, pct: f64) -> f64 {
+    let flat = get_resubmit_max_buffer(whale_shares);
+    if pct <= 0.0 {
+        return flat;
+    }
+    (whale_price * pct / 100.0).min(flat)
+}
+
+/// Wait before queueing the first resubmit so a swept level can refill (ms; tiers as for chasing:
+/// RESUBMIT_INITIAL_DELAY_MS for 4000+ whale shares, RESUBMIT_INITIAL_DELAY_SMALL_MS below). 0 = immediate
+pub static RESUBMIT_INITIAL_DELAY_MS: Lazy<u64> = Lazy::new(|| env_parse("RESUBMIT_INITIAL_DELAY_MS", 0));
+pub static RESUBMIT_INITIAL_DELAY_SMALL_MS: Lazy<u64> = Lazy::new(|| env_parse("RESUBMIT_INITIAL_DELAY_SMALL_MS", 0));
+
+/// Delay before enqueueing resubmit `attempt`. Only the first attempt waits; later attempts
+/// keep the in-chain timing
+#[inline]
+pub fn resubmit_enqueue_delay(whale_shares: f64, attempt: u8, large_ms: u64, small_ms: u64) -> Duration {
+    if attempt != 1 {
+        return Duration::ZERO;
+    }
+    Duration::from_millis(if tier_shares(whale_shares) >= 4000.0 { large_ms } else { small_ms })
+}
+
+/// Wall-clock limit (ms) on a whole resubmit chain, from the original miss; checked before each attempt.
+/// RESUBMIT_DEADLINE_LIVE_MS applies to live markets (falls back to RESUBMIT_DEADLINE_MS when 0). 0 = none
+pub static RESUBMIT_DEADLINE_MS: Lazy<u64> = Lazy::new(|| env_parse("RESUBMIT_DEADLINE_MS", 0));
+pub static RESUBMIT_DEADLINE_LIVE_MS: Lazy<u64> = Lazy::new(|| env_parse("RESUBMIT_DEADLINE_LIVE_MS", 0));
+
+/// Resubmit worker tasks pulling from the shared queue (RESUBMIT_WORKERS, default 1, at least 1)
+pub static RESUBMIT_WORKERS: Lazy<usize> = Lazy::new(|| env_parse("RESUBMIT_WORKERS", 1usize).max(1));
+
+/// When a partially filled FAK resubmit chain counts as done. With neither threshold set, the
+/// chain stops once at most RESUBMIT_DONE_SHARES remain; otherwise it stops when either is met
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ResubmitDoneRule {
+    /// Filled fraction of the original size (0.95 = 95%)
+    pub fill_ratio: Option<f64>,
+    /// USD value of the remaining shares at the last price (same units as MIN_CASH_VALUE)
+    pub remaining_usd: Option<f64>,
+}
+
+/// Remaining shares treated as "filled" when no RESUBMIT_DONE_* threshold is set
+pub const RESUBMIT_DONE_SHARES: f64 = 1.0;
+
+impl ResubmitDoneRule {
+    /// From RESUBMIT_DONE_FILL_PCT (percent) and RESUBMIT_DONE_REMAINING_USD; 0 = unset
+    pub fn new(fill_pct: f64, remaining_usd: f64) -> Self {
+        Self {
+            fill_ratio: (fill_pct > 0.0).then(|| (fill_pct / 100.0).min(1.0)),
+            remaining_usd: (remaining_usd > 0.0).then_some(remaining_usd),
+        }
+    }
+
+    /// True if the chain should stop resubmitting `remaining` shares at `price`
+    pub fn is_done(&self, total_filled: f64, original_size: f64, remaining: f64, price: f64) -> bool {
+        if self.fill_ratio.is_none() && self.remaining_usd.is_none() {
+            return remaining <= RESUBMIT_DONE_SHARES;
+        }
+        // Tolerance for float noise in summed partial fills
+        let ratio_met = self.fill_ratio.is_some_and(|r| original_size > 0.0 && total_filled >= original_size * r - 1e-9);
+        let cash_met = self.remaining_usd.is_some_and(|usd| remaining * price < usd);
+        ratio_met || cash_met || remaining <= 0.0
+    }
+}
+
+pub static RESUBMIT_DONE_RULE: Lazy<ResubmitDoneRule> = Lazy::new(|| {
+    ResubmitDoneRule::new(env_parse("RESUBMIT_DONE_FILL_PCT", 0.0), env_parse("RESUBMIT_DONE_REMAINING_USD", 0.0))
+});
+
+/// Chain deadline for a market (None = unbounded)
+#[inline]
+pub fn resubmit_deadline(is_live: bool, default_ms: u64, live_ms: u64) -> Option<Duration> {
+    let ms = if is_live && live_ms > 0 { live_ms } else { default_ms };
+    (ms > 0).then(|| Duration::from_millis(ms))
+}
+
+/// Price ceiling for the resubmit chain (shared by underfill and FAK-failure paths),
+/// floored to the market's tick so a percentage buffer can use tenth-cent steps on 0.001 markets
+#[inline]
+pub fn get_resubmit_max_price(whale_shares: f64, whale_price: f64, limit_price: f64, tick: f64) -> f64 {
+    let ceiling = limit_price + resubmit_max_buffer(whale_shares, whale_price, *RESUBMIT_MAX_BUFFER_PCT);
+    let ceiling = whale_price_cap(whale_price).map_or(ceiling, |cap| ceiling.min(cap));
+    floor_to_tick(ceiling, tick)
+}
+
+/// Error-body substrings deciding whether a failed resubmit keeps going.
+/// Matching is case-insensitive; terminal wins over retryable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResubmitErrorPolicy {
+    pub retryable: Vec<String>,
+    pub terminal: Vec<String>,
+}
+
+pub const DEFAULT_RETRYABLE_ERRORS: &[&str] = &["FAK"];
+pub const DEFAULT_TERMINAL_ERRORS: &[&str] = &["not enough balance", "invalid signature"];
+
+impl Default for ResubmitErrorPolicy {
+    fn default() -> Self {
+        Self::new(DEFAULT_RETRYABLE_ERRORS.join(","), DEFAULT_TERMINAL_ERRORS.join(","))
+    }
+}
+
+impl ResubmitErrorPolicy {
+    /// Build from comma-separated substring lists
+    pub fn new(retryable: impl AsRef<str>, terminal: impl AsRef<str>) -> Self {
+        let list = |v: &str| {
+            v.split(',').map(|s| s.trim().to_ascii_lowercase()).filter(|s| !s.is_empty()).collect::<Vec<_>>()
+        };
+        Self { retryable: list(retryable.as_ref()), terminal: list(terminal.as_ref()) }
+    }
+
+    pub fn is_terminal(&self, body: &str) -> bool {
+        let body = body.to_ascii_lowercase();
+        self.terminal.iter().any(|t| body.contains(t.as_str()))
+    }
+
+    /// True if the chain should try again after this error body
+    pub fn is_retryable(&self, body: &str) -> bool {
+        if self.is_terminal(body) {
+            return false;
+        }
+        let body = body.to_ascii_lowercase();
+        self.retryable.iter().any(|r| body.contains(r.as_str()))
+    }
+}
+
+/// RESUBMIT_RETRYABLE_ERRORS / RESUBMIT_TERMINAL_ERRORS (comma-separated); unset = compiled defaults
+pub static RESUBMIT_ERROR_POLICY: Lazy<ResubmitErrorPolicy> = Lazy::new(|| {
+    ResubmitErrorPolicy::new(
+        env::var("RESUBMIT_RETRYABLE_ERRORS").unwrap_or_else(|_| DEFAULT_RETRYABLE_ERRORS.join(",")),
+        env::var("RESUBMIT_TERMINAL_ERRORS").unwrap_or_else(|_| DEFAULT_TERMINAL_ERRORS.join(",")),
+    )
+});
+
+/// Never pay more than whale price + this for a BUY, across the initial order and every resubmit
+/// (WHALE_MAX_PREMIUM, in price units, e.g. 0.03 = 3 cents; 0 = off)
+pub static WHALE_MAX_PREMIUM: Lazy<f64> = Lazy::new(|| env_parse("WHALE_MAX_PREMIUM", 0.0));
+
+/// Same rule as a percentage of whale price (WHALE_MAX_PREMIUM_PCT, e.g. 5 = 5%; 0 = off).
+/// If both are set the tighter one applies.
+pub static WHALE_MAX_PREMIUM_PCT: Lazy<f64> = Lazy::new(|| env_parse("WHALE_MAX_PREMIUM_PCT", 0.0));
+
+/// Highest BUY price allowed for this whale price (None = no premium cap configured)
+#[inline]
+pub fn whale_premium_cap(whale_price: f64, premium: f64, pct: f64) -> Option<f64> {
+    let flat = (premium > 0.0).then_some(whale_price + premium);
+    let scaled = (pct > 0.0).then(|| whale_price * (1.0 + pct / 100.0));
+    match (flat, scaled) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// whale_premium_cap using the configured WHALE_MAX_PREMIUM / WHALE_MAX_PREMIUM_PCT
+#[inline]
+pub fn whale_price_cap(whale_price: f64) -> Option<f64> {
+    whale_premium_cap(whale_price, *WHALE_MAX_PREMIUM, *WHALE_MAX_PREMIUM_PCT)
+}
+
+/// Whether a resubmit BUY at `new_price` must be aborted. The chase ceiling is skipped on the
+/// last (resting) attempt; the whale premium cap never is.
+#[inline]
+pub fn resubmit_exceeds_ceiling(side_is_buy: bool, new_price: f64, max_price: f64, premium_cap: Option<f64>, is_last_attempt: bool) -> bool {
+    side_is_buy && ((!is_last_attempt && new_price > max_price) || exceeds_premium_cap(new_price, premium_cap))
+}
+
+/// True if a BUY at `price` would pay more than the premium cap allows
+#[inline]
+pub fn exceeds_premium_cap(price: f64, cap: Option<f64>) -> bool {
+    // Tolerance for float noise in price + increment sums
+    cap.is_some_and(|cap| price > cap + 1e-9)
+}
+
 pub const BOOK_REQ_TIMEOUT: Duration = Duration::from_millis(2500);
 pub const WS_PING_TIMEOUT: Duration = Duration::from_secs(300);
+/// How often the event watchdog is checked while the WS is connected
+pub const WATCHDOG_CHECK_INTERVAL: Duration = Duration::from_secs(5);
 pub const WS_RECONNECT_DELAY: Duration = Duration::from_secs(3);
+/// How long a bounded run (--max-events / --max-runtime) waits for in-flight event handlers
+pub const RUN_LIMIT_DRAIN_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Fills above the requested size by more than this many shares log an OVERFILL warning
+/// (OVERFILL_TOLERANCE_SHARES, default 0.01 - sizes are floored to 2 decimals before submitting)
+pub static OVERFILL_TOLERANCE_SHARES: Lazy<f64> = Lazy::new(|| env_parse("OVERFILL_TOLERANCE_SHARES", 0.01));
+
+/// Extra attempts for the post-trade book fetch before logging N/A (BOOK_LOG_RETRIES, default 2)
+pub static BOOK_LOG_RETRIES: Lazy<u32> = Lazy::new(|| env_parse("BOOK_LOG_RETRIES", 2));
+/// Backoff before the n-th book fetch retry is n times this
+pub const BOOK_LOG_RETRY_BACKOFF: Duration = Duration::from_millis(150);
 
 // ============================================================================
 // Execution Tiers
@@ -123,56 +736,499 @@ pub const WS_RECONNECT_DELAY: Duration = Duration::from_secs(3);
 pub struct ExecutionTier {
     pub min_shares: f64,
     pub price_buffer: f64,
+    /// Order type for the initial submission and early resubmits ("FAK", "GTD", "GTC")
     pub order_action: &'static str,
+    /// Order type for the last resubmit attempt
+    pub final_action: &'static str,
     pub size_multiplier: f64,
+    /// Raise the buy limit to the best ask (+ MIN_CROSS_BUFFER) when it's below it (buys only)
+    pub cross_spread: bool,
+    /// Price through to the second book level (+ SECOND_LEVEL_BUFFER) when the copy is bigger
+    /// than the best level
+    pub walk_second_level: bool,
+    /// Skip (SKIPPED_INSUFFICIENT_DEPTH) unless the book shows at least this many shares at or
+    /// better than the limit (0 = no floor). Only checked when TIER_MIN_DEPTH is on
+    pub min_depth_shares: f64,
+    /// One step per resubmit attempt (delay, chase increment); its length is the max attempts
+    pub resubmit_schedule: &'static [ResubmitStep],
 }
 
+/// 4000+: chase one tick on attempt 1, then flat retries (5 attempts)
+const CHASE_FIRST_SCHEDULE: &[ResubmitStep] = &[
+    ResubmitStep { delay_ms: 0, increment: 0.01 },
+    ResubmitStep::FLAT,
+    ResubmitStep::FLAT,
+    ResubmitStep::FLAT,
+    ResubmitStep::FLAT,
+];
+
+/// Four immediate flat retries
+const FLAT_SCHEDULE: &[ResubmitStep] = &[ResubmitStep::FLAT; 4];
+
+/// Buys below all tiers: flat retries, 50ms apart so the book can refresh
+pub const DEFAULT_RESUBMIT_SCHEDULE: &[ResubmitStep] = &[
+    ResubmitStep::FLAT,
+    ResubmitStep { delay_ms: 50, increment: 0.0 },
+    ResubmitStep { delay_ms: 50, increment: 0.0 },
+    ResubmitStep { delay_ms: 50, increment: 0.0 },
+];
+
 pub const EXECUTION_TIERS: [ExecutionTier; 3] = [
     ExecutionTier {
         min_shares: 4000.0,
         price_buffer: 0.01,
         order_action: "FAK",
+        final_action: "GTD",
         size_multiplier: 1.25,
+        cross_spread: false,
+        walk_second_level: true,
+        min_depth_shares: 1000.0,
+        resubmit_schedule: CHASE_FIRST_SCHEDULE,
     },
     ExecutionTier {
         min_shares: 2000.0,
         price_buffer: 0.01,
         order_action: "FAK",
+        final_action: "GTD",
         size_multiplier: 1.0,
+        cross_spread: false,
+        walk_second_level: true,
+        min_depth_shares: 500.0,
+        resubmit_schedule: FLAT_SCHEDULE,
     },
     ExecutionTier {
         min_shares: 1000.0,
         price_buffer: 0.00,
         order_action: "FAK",
+        final_action: "GTD",
+        size_multiplier: 1.0,
+        cross_spread: true,
+        walk_second_level: false,
+        min_depth_shares: 0.0,
+        resubmit_schedule: FLAT_SCHEDULE,
+    },
+];
+
+/// Sell-side tiers, independent of the buy table. The buffer is subtracted from the whale
+/// price (toward the bid), so raise it here to exit more aggressively.
+/// Default: every sell rests as a GTD at the whale's price.
+pub const SELL_EXECUTION_TIERS: [ExecutionTier; 1] = [
+    ExecutionTier {
+        min_shares: 0.0,
+        price_buffer: 0.00,
+        order_action: "GTD",
+        final_action: "GTD",
         size_multiplier: 1.0,
+        cross_spread: false,
+        walk_second_level: false,
+        min_depth_shares: 0.0,
+        resubmit_schedule: FLAT_SCHEDULE,
     },
 ];
 
+/// Order types for buys below all tiers
+pub const DEFAULT_ORDER_ACTION: &str = "FAK";
+pub const DEFAULT_FINAL_ACTION: &str = "GTD";
+/// Buys below all tiers (PRICE_BUFFER 0.00) cross the spread when MIN_CROSS_BUFFER is set
+pub const DEFAULT_CROSS_SPREAD: bool = true;
+/// Buys below all tiers are small copies; the best level covers them
+pub const DEFAULT_WALK_SECOND_LEVEL: bool = false;
+/// No depth floor for buys below all tiers
+pub const DEFAULT_MIN_DEPTH_SHARES: f64 = 0.0;
+
+/// Tier table for one side
+#[inline]
+pub fn execution_tiers(side_is_buy: bool) -> &'static [ExecutionTier] {
+    if side_is_buy { &EXECUTION_TIERS } else { &SELL_EXECUTION_TIERS }
+}
+
+/// Granularity whale shares are rounded to before picking a tier, so float noise in the decoded
+/// size (3999.9999 vs 4000) can't flip a trade across a boundary. 1 = nearest share; fractions
+/// should divide 1 (0.1, 0.01). TIER_SHARES_ROUNDING, default 0 (off)
+pub static TIER_SHARES_ROUNDING: Lazy<f64> = Lazy::new(|| env_parse("TIER_SHARES_ROUNDING", 0.0f64).max(0.0));
+
+/// Whale shares as tier selection sees them (TIER_SHARES_ROUNDING)
+#[inline]
+pub fn tier_shares(whale_shares: f64) -> f64 {
+    round_tier_shares_with(whale_shares, *TIER_SHARES_ROUNDING)
+}
+
+/// Round `whale_shares` to the nearest multiple of `granularity` (<= 0 = unchanged). Fractional
+/// granularities scale up by their inverse so 0.01 steps land exactly on whole-share boundaries
+#[inline]
+pub fn round_tier_shares_with(whale_shares: f64, granularity: f64) -> f64 {
+    if granularity <= 0.0 || !granularity.is_finite() {
+        return whale_shares;
+    }
+    if granularity >= 1.0 {
+        (whale_shares / granularity).round() * granularity
+    } else {
+        let per_share = (1.0 / granularity).round();
+        (whale_shares * per_share).round() / per_share
+    }
+}
+
+/// Find the execution tier for a buy of this size (None = below all tiers)
+#[inline]
+pub fn find_execution_tier(whale_shares: f64) -> Option<&'static ExecutionTier> {
+    find_side_execution_tier(whale_shares, true)
+}
+
+/// Find the execution tier for a trade of this size on either side (None = below all tiers)
+#[inline]
+pub fn find_side_execution_tier(whale_shares: f64, side_is_buy: bool) -> Option<&'static ExecutionTier> {
+    find_side_execution_tier_with(whale_shares, side_is_buy, *TIER_SHARES_ROUNDING)
+}
+
+/// find_side_execution_tier with an explicit rounding granularity instead of TIER_SHARES_ROUNDING
+#[inline]
+pub fn find_side_execution_tier_with(whale_shares: f64, side_is_buy: bool, rounding: f64) -> Option<&'static ExecutionTier> {
+    let whale_shares = round_tier_shares_with(whale_shares, rounding);
+    execution_tiers(side_is_buy).iter().find(|t| whale_shares >= t.min_shares)
+}
+
 /// Get tier params for a given trade size
 /// Returns (buffer, order_action, size_multiplier)
 #[inline]
 pub fn get_tier_params(whale_shares: f64, side_is_buy: bool, token_id: &str) -> (f64, &'static str, f64) {
-    if !side_is_buy {
-        return (PRICE_BUFFER, "GTD", 1.0);
-    }
+    get_tier_params_with(whale_shares, side_is_buy, token_id, *TIER_SHARES_ROUNDING)
+}
+
+/// get_tier_params with an explicit rounding granularity instead of TIER_SHARES_ROUNDING
+pub fn get_tier_params_with(whale_shares: f64, side_is_buy: bool, token_id: &str, rounding: f64) -> (f64, &'static str, f64) {
+    let (base_buffer, order_action, size_multiplier) = match find_side_execution_tier_with(whale_shares, side_is_buy, rounding) {
+        Some(tier) => (tier.price_buffer, tier.order_action, tier.size_multiplier),
+        None => (PRICE_BUFFER, DEFAULT_ORDER_ACTION, 1.0),  // Small trades use FAK (Fill and Kill)
+    };
 
-    // Get base tier params - direct if-else is faster than iterator for 3 tiers
-    let (base_buffer, order_action, size_multiplier) = if whale_shares >= 4000.0 {
-        (0.01, "FAK", 1.25)
-    } else if whale_shares >= 2000.0 {
-        (0.01, "FAK", 1.0)
-    } else if whale_shares >= 1000.0 {
-        (0.0, "FAK", 1.0)
+    // Apply sport-specific price adjustments (buys only)
+    let total_buffer = if side_is_buy {
+        let tennis_buffer = tennis_markets::get_tennis_token_buffer(token_id);
+        let soccer_buffer = soccer_markets::get_soccer_token_buffer(token_id);
+        base_buffer + tennis_buffer + soccer_buffer
     } else {
-        (PRICE_BUFFER, "FAK", 1.0)  // Small buys use FAK (Fill and Kill)
+        base_buffer
     };
 
-    // Apply sport-specific price adjustments
-    let tennis_buffer = tennis_markets::get_tennis_token_buffer(token_id);
-    let soccer_buffer = soccer_markets::get_soccer_token_buffer(token_id);
-    let total_buffer = base_buffer + tennis_buffer + soccer_buffer;
+    // A MARKET_OVERRIDES entry wins over tier and sport values
+    match market_cache::get_market_override(token_id) {
+        Some(over) => (
+            over.buffer.unwrap_or(total_buffer),
+            order_action,
+            over.size_multiplier.unwrap_or(size_multiplier),
+        ),
+        None => (total_buffer, order_action, size_multiplier),
+    }
+}
 
-    (total_buffer, order_action, size_multiplier)
+/// Every tier output behind one trade, for the CSV_TIER_COLUMNS audit columns
+#[derive(Debug, Clone, PartialEq)]
+pub struct TierReport {
+    /// Tier's min_shares ("4000+"), "base" below all tiers; sells prefixed "sell "
+    pub label: String,
+    /// Price buffer after sport and market overrides (get_tier_params)
+    pub buffer: f64,
+    pub size_multiplier: f64,
+    pub order_action: &'static str,
+    pub max_attempts: u8,
+    /// Chase allowance over the first limit (resubmit_max_buffer)
+    pub resubmit_buffer: f64,
+    /// Whether resubmit attempt 1 chases the price
+    pub chase_first: bool,
+}
+
+/// Tier outputs for a trade of this size, as the order worker and resubmitter compute them
+pub fn tier_report(whale_shares: f64, whale_price: f64, side_is_buy: bool, token_id: &str) -> TierReport {
+    let (buffer, order_action, size_multiplier) = get_tier_params(whale_shares, side_is_buy, token_id);
+    let side = if side_is_buy { "" } else { "sell " };
+    let label = match find_side_execution_tier(whale_shares, side_is_buy) {
+        Some(tier) => format!("{}{}+", side, tier.min_shares),
+        None => format!("{}base", side),
+    };
+    TierReport {
+        label,
+        buffer,
+        size_multiplier,
+        order_action,
+        max_attempts: get_max_resubmit_attempts(whale_shares),
+        resubmit_buffer: resubmit_max_buffer(whale_shares, whale_price, *RESUBMIT_MAX_BUFFER_PCT),
+        chase_first: should_increment_price(whale_shares, 1),
+    }
+}
+
+/// Whether a buy of this size crosses the spread (its tier's cross_spread flag).
+/// Only takes effect when MIN_CROSS_BUFFER is set
+#[inline]
+pub fn tier_crosses_spread(whale_shares: f64) -> bool {
+    find_execution_tier(whale_shares).map_or(DEFAULT_CROSS_SPREAD, |t| t.cross_spread)
+}
+
+/// Whether a trade of this size may price through to the second book level (its tier's
+/// walk_second_level flag). Only takes effect when SECOND_LEVEL_BUFFER is set
+#[inline]
+pub fn tier_walks_second_level(whale_shares: f64, side_is_buy: bool) -> bool {
+    find_side_execution_tier(whale_shares, side_is_buy).map_or(DEFAULT_WALK_SECOND_LEVEL && side_is_buy, |t| t.walk_second_level)
+}
+
+/// Book depth (shares at or better than the limit) a trade of this size needs (its tier's
+/// min_depth_shares). Only takes effect when TIER_MIN_DEPTH is on
+#[inline]
+pub fn tier_min_depth_shares(whale_shares: f64, side_is_buy: bool) -> f64 {
+    find_side_execution_tier(whale_shares, side_is_buy).map_or(DEFAULT_MIN_DEPTH_SHARES, |t| t.min_depth_shares)
+}
+
+/// Order type for a (buy) resubmit attempt: the tier's action, or its final action on the last attempt
+#[inline]
+pub fn get_resubmit_order_action(whale_shares: f64, is_last_attempt: bool) -> &'static str {
+    get_side_resubmit_order_action(whale_shares, true, is_last_attempt)
+}
+
+/// Same as get_resubmit_order_action, using the tier table for `side_is_buy`
+#[inline]
+pub fn get_side_resubmit_order_action(whale_shares: f64, side_is_buy: bool, is_last_attempt: bool) -> &'static str {
+    match (find_side_execution_tier(whale_shares, side_is_buy), is_last_attempt) {
+        (Some(tier), false) => tier.order_action,
+        (Some(tier), true) => tier.final_action,
+        (None, false) => DEFAULT_ORDER_ACTION,
+        (None, true) => DEFAULT_FINAL_ACTION,
+    }
+}
+
+/// True for order types that rest on the book instead of filling immediately
+#[inline]
+pub fn is_resting_order(order_action: &str) -> bool {
+    matches!(order_action, "GTD" | "GTC")
+}
+
+/// Expiration for an order of this type (GTD only; None = no expiry)
+#[inline]
+pub fn order_expiration(order_action: &str, is_live: bool, now_unix_secs: u64) -> Option<String> {
+    (order_action == "GTD").then(|| (now_unix_secs + get_gtd_expiry_secs(is_live)).to_string())
+}
+
+/// What to assume when a market's live status is unknown (cache miss and Gamma failed)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownLive {
+    /// Treat as not live (non-live GTD expiry)
+    #[default]
+    NonLive,
+    /// Treat as live (live GTD expiry) - suits sports-heavy following
+    Live,
+    /// Don't trade (SKIPPED_UNKNOWN_LIVE)
+    Skip,
+}
+
+impl UnknownLive {
+    /// Parse "nonlive" / "live" / "skip" (empty = NonLive)
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().replace(['-', '_'], "").as_str() {
+            "" | "nonlive" => Ok(UnknownLive::NonLive),
+            "live" => Ok(UnknownLive::Live),
+            "skip" => Ok(UnknownLive::Skip),
+            other => anyhow::bail!("UNKNOWN_LIVE_DEFAULT must be 'nonlive', 'live' or 'skip' (found '{}')", other),
+        }
+    }
+
+    /// Liveness to trade with: the known value, or this policy's assumption (None = skip)
+    #[inline]
+    pub fn resolve(self, is_live: Option<bool>) -> Option<bool> {
+        match (is_live, self) {
+            (Some(v), _) => Some(v),
+            (None, UnknownLive::NonLive) => Some(false),
+            (None, UnknownLive::Live) => Some(true),
+            (None, UnknownLive::Skip) => None,
+        }
+    }
+}
+
+/// Treatment of unknown live status (UNKNOWN_LIVE_DEFAULT=nonlive|live|skip, default nonlive)
+pub static UNKNOWN_LIVE_DEFAULT: Lazy<UnknownLive> =
+    Lazy::new(|| UnknownLive::parse(&env::var("UNKNOWN_LIVE_DEFAULT").unwrap_or_default()).unwrap_or_default());
+
+/// Optional expiry stamped on FAK orders as a safety net in case matching is delayed
+/// (FAK_EXPIRATION_SECS; 0 = none, the default)
+pub static FAK_EXPIRATION_SECS: Lazy<u64> = Lazy::new(|| env_parse("FAK_EXPIRATION_SECS", 0));
+
+/// Expiration to sign into a submitted order: the GTD expiry, or `fak_secs` out for FAK when set
+#[inline]
+pub fn submit_expiration(order_action: &str, is_live: bool, now_unix_secs: u64, fak_secs: u64) -> Option<String> {
+    if order_action == "FAK" && fak_secs > 0 {
+        return Some((now_unix_secs + fak_secs).to_string());
+    }
+    order_expiration(order_action, is_live, now_unix_secs)
+}
+
+// ============================================================================
+// Network Selection
+// ============================================================================
+
+const AMOY_CLOB_API_BASE: &str = "https://clob-staging.polymarket.com";
+const AMOY_GAMMA_API_BASE: &str = "https://gamma-api-staging.polymarket.com";
+const AMOY_DATA_API_BASE: &str = "https://data-api-staging.polymarket.com";
+const AMOY_MONITORED_ADDRESSES: [&str; 2] = [
+    "0xdFE02Eb6733538f8Ea35D585af8DE5958AD99E40",
+    "0xd91E80cF2E7be2e162c6513ceD06f1dD0dA35296",
+];
+
+/// Chain + API bundle selected with NETWORK=mainnet|amoy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Network {
+    Mainnet,
+    Amoy,
+}
+
+impl Network {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "mainnet" | "polygon" => Ok(Network::Mainnet),
+            "amoy" | "testnet" => Ok(Network::Amoy),
+            other => anyhow::bail!("NETWORK must be 'mainnet' or 'amoy' (found '{}')", other),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Network::Mainnet => "mainnet",
+            Network::Amoy => "amoy",
+        }
+    }
+
+    /// Chain id used for EIP-712 order and auth signing
+    pub fn chain_id(self) -> u64 {
+        match self {
+            Network::Mainnet => 137,
+            Network::Amoy => 80002,
+        }
+    }
+
+    pub fn clob_api_base(self) -> &'static str {
+        match self {
+            Network::Mainnet => CLOB_API_BASE,
+            Network::Amoy => AMOY_CLOB_API_BASE,
+        }
+    }
+
+    pub fn gamma_api_base(self) -> &'static str {
+        match self {
+            Network::Mainnet => GAMMA_API_BASE,
+            Network::Amoy => AMOY_GAMMA_API_BASE,
+        }
+    }
+
+    /// Data API for whale portfolio values and fill confirmation
+    pub fn data_api_base(self) -> &'static str {
+        match self {
+            Network::Mainnet => DATA_API_BASE,
+            Network::Amoy => AMOY_DATA_API_BASE,
+        }
+    }
+
+    /// Exchange contracts whose OrdersFilled logs are subscribed to
+    pub fn monitored_addresses(self) -> &'static [&'static str] {
+        match self {
+            Network::Mainnet => &MONITORED_ADDRESSES,
+            Network::Amoy => &AMOY_MONITORED_ADDRESSES,
+        }
+    }
+
+    /// API creds are per-deployment, so each network keeps its own cache file
+    pub fn creds_path(self) -> &'static str {
+        match self {
+            Network::Mainnet => ".clob_creds.json",
+            Network::Amoy => ".clob_creds.amoy.json",
+        }
+    }
+
+    fn rpc_subdomain(self) -> &'static str {
+        match self {
+            Network::Mainnet => "polygon-mainnet",
+            Network::Amoy => "polygon-amoy",
+        }
+    }
+
+    fn other(self) -> Self {
+        match self {
+            Network::Mainnet => Network::Amoy,
+            Network::Amoy => Network::Mainnet,
+        }
+    }
+}
+
+/// Parse the choice settings read through Lazy statics, so a typo fails config load instead of
+/// quietly running with the default (the statics themselves fall back to it)
+fn validate_choice_settings() -> Result<()> {
+    let var = |name: &str| env::var(name).unwrap_or_default();
+    ThinBookAction::parse(&var("LIQUIDITY_PRECHECK"))?;
+    FollowSides::parse(&var("FOLLOW_SIDES"))?;
+    CopyMode::parse(&var("COPY_MODE"))?;
+    CsvQuoting::parse(&var("CSV_QUOTING"))?;
+    CsvDirection::parse(&var("CSV_DIRECTION"))?;
+    WhalePriceMode::parse(&var("WHALE_PRICE_MODE"))?;
+    TickRounding::parse(&var("WHALE_PRICE_ROUNDING"))?;
+    SameTokenPolicy::parse(&var("SAME_TOKEN_PER_BLOCK"))?;
+    OpposingSignalPolicy::parse(&var("OPPOSING_SIGNALS"))?;
+    UsdPrecision::parse(&var("USD_DISPLAY_DECIMALS"))?;
+    SummaryMode::parse(&var("SUMMARY_MODE"))?;
+    OnTimeout::parse(&var("CONFIRM_ON_TIMEOUT"))?;
+    UnknownLive::parse(&var("UNKNOWN_LIVE_DEFAULT"))?;
+    Ok(())
+}
+
+/// Reject API URLs that belong to the other network (e.g. testnet config pointed at mainnet CLOB)
+pub fn validate_network_endpoints(network: Network, clob_api_base: &str, gamma_api_base: &str, data_api_base: &str) -> Result<()> {
+    let other = network.other();
+    let clob = clob_api_base.trim_end_matches('/');
+    let gamma = gamma_api_base.trim_end_matches('/');
+    let data = data_api_base.trim_end_matches('/');
+    if clob.eq_ignore_ascii_case(other.clob_api_base())
+        || gamma.eq_ignore_ascii_case(other.gamma_api_base())
+        || data.eq_ignore_ascii_case(other.data_api_base())
+    {
+        anyhow::bail!(
+            "NETWORK={} but API URLs point at {} ({} / {} / {}). Fix CLOB_API_BASE / GAMMA_API_BASE / DATA_API_BASE.",
+            network.as_str(), other.as_str(), clob, gamma, data
+        );
+    }
+    Ok(())
+}
+
+// ============================================================================
+// WebSocket Subscription
+// ============================================================================
+
+/// eth_subscribe request for OrdersFilled logs from `addresses` whose topics[2] is one of `target_topics`
+pub fn build_subscribe_payload(addresses: &[&str], target_topics: &[String]) -> String {
+    serde_json::json!({
+        "jsonrpc": "2.0", "id": 1, "method": "eth_subscribe",
+        "params": ["logs", {
+            "address": addresses,
+            "topics": [[ORDERS_FILLED_EVENT_SIGNATURE], serde_json::Value::Null, target_topics]
+        }]
+    }).to_string()
+}
+
+/// WebSocket URL and subscription payload, built once at startup and reused on every reconnect
+#[derive(Debug, Clone)]
+pub struct WsSubscription {
+    pub url: String,
+    pub payload: String,
+    /// Exchange contracts whose pending txs are decoded (USE_MEMPOOL); None = OrdersFilled logs
+    pub mempool_exchanges: Option<&'static [&'static str]>,
+}
+
+impl WsSubscription {
+    pub fn from_config(cfg: &Config) -> Self {
+        let addresses = cfg.network.monitored_addresses();
+        Self {
+            url: cfg.wss_url.clone(),
+            payload: if cfg.use_mempool {
+                crate::mempool::build_pending_subscribe_payload(&cfg.wss_url, addresses)
+            } else {
+                build_subscribe_payload(addresses, &TARGET_TOPICS)
+            },
+            mempool_exchanges: cfg.use_mempool.then_some(addresses),
+        }
+    }
 }
 
 // ============================================================================
@@ -185,8 +1241,30 @@ pub struct Config {
     pub private_key: String,
     pub funder_address: String,
     
+    // Network
+    pub network: Network,
+    pub chain_id: u64,
+    pub clob_api_base: String,
+    pub gamma_api_base: String,
+    /// Whale portfolio values and fill confirmation (DATA_API_BASE)
+    pub data_api_base: String,
+
     // WebSocket
     pub wss_url: String,
+    /// Follow pending exchange transactions instead of confirmed OrdersFilled logs (unconfirmed)
+    pub use_mempool: bool,
+    /// Resolve missing log block numbers via eth_getTransactionReceipt (extra round-trip)
+    pub block_number_fallback: bool,
+    /// HTTP JSON-RPC endpoint for the fallback (defaults to wss_url over https)
+    pub block_rpc_url: String,
+    /// Max event handlers running at once (book/market fetches per event)
+    pub max_concurrent_handlers: usize,
+    /// Queue or drop events when every handler slot is busy
+    pub handler_saturation: SaturationPolicy,
+    /// Order worker queue: arrival order, or biggest whale trades first (shedding the smallest when full)
+    pub order_queue_priority: QueuePriority,
+    /// Exit after this many consecutive WS failures without a healthy connection (0 = retry forever)
+    pub max_reconnect_attempts: u32,
     
     // Trading flags
     pub enable_trading: bool,
@@ -198,6 +1276,153 @@ pub struct Config {
     pub cb_sequence_window_secs: u64,
     pub cb_min_depth_usd: f64,
     pub cb_trip_duration_secs: u64,
+
+    // Exposure limits
+    pub daily_notional_cap_usd: f64,
+    pub whale_cooldown_ms: u64,
+    /// Size multiplier added per consecutive same-whale/same-token BUY (0 = off)
+    pub conviction_step: f64,
+    pub conviction_max_multiplier: f64,
+    pub conviction_window_secs: u64,
+    /// Orders allowed back to back on one market (0 = no per-market rate limit)
+    pub market_rate_burst: u32,
+    /// Per-market refill rate, orders per minute
+    pub market_rate_per_min: f64,
+    /// Copy only a whale's first BUY on a token per window; adds are skipped (0 = off)
+    pub whale_entry_window_secs: u64,
+
+    // Decision (see plan_params)
+    pub act_on_fills_only: bool,
+    pub follow_sides: FollowSides,
+    pub copy_mode: CopyMode,
+    pub sizing: SizingParams,
+    pub tier_shares_rounding: f64,
+
+    // Position reconciliation
+    /// Compare tracked positions with exchange balances every N seconds (0 = disabled)
+    pub reconcile_interval_secs: u64,
+    /// Drift (shares) below this is ignored
+    pub reconcile_tolerance_shares: f64,
+    /// Overwrite the tracked position with the exchange balance when they disagree
+    pub reconcile_correct: bool,
+    /// Look up resting GTD orders after they expire to record late fills and release unfilled notional
+    pub gtd_expiry_check: bool,
+    /// Wait this long past expiry before the lookup, so late matches have settled
+    pub gtd_expiry_grace_secs: u64,
+    /// Poll live status of held tokens every N seconds to catch pre-match -> live flips (0 = off)
+    pub live_flip_poll_secs: u64,
+    /// Reaction to a flip: hold, tighten or flatten
+    pub live_flip_action: LiveFlipAction,
+
+    // Admin
+    /// Label written to the CSV instance column (distinguishes merged logs)
+    pub instance_label: String,
+    /// Bind address for the GET /status endpoint (empty = disabled)
+    pub status_addr: String,
+    /// Pause new orders while this file exists (empty = no kill switch)
+    pub kill_switch_file: String,
+    /// Unix socket streaming each order decision as length-prefixed JSON (empty = disabled)
+    pub decision_socket: String,
+    /// Hold orders (SKIPPED_WARMUP) until the market caches have loaded live status
+    pub warmup_gate: bool,
+    /// Longest hold; after this, trade on whatever is cached
+    pub warmup_timeout_secs: u64,
+
+    // Clock
+    /// Warn at startup if local clock differs from CLOB server time by more than this
+    pub clock_skew_warn_secs: u64,
+    /// Refuse to start beyond this skew (0 = never refuse)
+    pub clock_skew_max_secs: u64,
+
+    // HTTP
+    /// CLOB client connect timeout, TLS handshake included
+    pub http_connect_timeout_ms: u64,
+    /// CLOB client per-request timeout (calls without their own)
+    pub http_request_timeout_ms: u64,
+    /// Book fetches in front of an order
+    pub book_timeout_ms: u64,
+    /// Gamma live-flag lookups
+    pub gamma_timeout_ms: u64,
+
+    // Shutdown
+    /// Let in-flight resubmit chains finish on shutdown instead of dropping them
+    pub drain_resubmits_on_shutdown: bool,
+    /// Longest wait for those chains
+    pub resubmit_drain_timeout_secs: u64,
+
+    // Credentials
+    /// What to do when the CLOB rejects the cached API creds at startup
+    pub stale_creds: StaleCredsPolicy,
+    /// SIGNER=remote endpoint (None = sign with private_key)
+    pub remote_signer: Option<RemoteSignerConfig>,
+}
+
+impl Default for Config {
+    /// Defaults used by from_env for unset variables; no credentials, trading disabled.
+    /// Handy for offline simulation (see engine::simulate_event).
+    fn default() -> Self {
+        let network = Network::Mainnet;
+        Self {
+            private_key: String::new(),
+            funder_address: String::new(),
+            network,
+            chain_id: network.chain_id(),
+            clob_api_base: network.clob_api_base().to_string(),
+            gamma_api_base: network.gamma_api_base().to_string(),
+            data_api_base: network.data_api_base().to_string(),
+            wss_url: String::new(),
+            use_mempool: false,
+            block_number_fallback: false,
+            block_rpc_url: String::new(),
+            max_concurrent_handlers: 64,
+            handler_saturation: SaturationPolicy::Queue,
+            order_queue_priority: QueuePriority::Fifo,
+            max_reconnect_attempts: 0,
+            enable_trading: false,
+            mock_trading: false,
+            cb_large_trade_shares: 1500.0,
+            cb_consecutive_trigger: 2,
+            cb_sequence_window_secs: 30,
+            cb_min_depth_usd: 200.0,
+            cb_trip_duration_secs: 120,
+            daily_notional_cap_usd: 0.0,
+            whale_cooldown_ms: 0,
+            conviction_step: 0.0,
+            conviction_max_multiplier: 2.0,
+            conviction_window_secs: 300,
+            market_rate_burst: 0,
+            whale_entry_window_secs: 0,
+            act_on_fills_only: false,
+            follow_sides: FollowSides::default(),
+            copy_mode: CopyMode::default(),
+            sizing: DEFAULT_SIZING,
+            tier_shares_rounding: 0.0,
+            market_rate_per_min: 3.0,
+            reconcile_interval_secs: 0,
+            reconcile_tolerance_shares: 1.0,
+            reconcile_correct: false,
+            gtd_expiry_check: true,
+            gtd_expiry_grace_secs: 15,
+            live_flip_poll_secs: 0,
+            live_flip_action: LiveFlipAction::Hold,
+            instance_label: String::new(),
+            status_addr: String::new(),
+            kill_switch_file: String::new(),
+            decision_socket: String::new(),
+            warmup_gate: false,
+            warmup_timeout_secs: 120,
+            clock_skew_warn_secs: 2,
+            clock_skew_max_secs: 0,
+            http_connect_timeout_ms: 2000,
+            http_request_timeout_ms: 5000,
+            book_timeout_ms: 500,
+            gamma_timeout_ms: 2000,
+            drain_resubmits_on_shutdown: false,
+            resubmit_drain_timeout_secs: 10,
+            stale_creds: StaleCredsPolicy::Rederive,
+            remote_signer: None,
+        }
+    }
 }
 
 impl Config {
@@ -223,24 +1448,9 @@ impl Config {
             );
         }
         
-        let private_key = env::var("PRIVATE_KEY")
-            .context("PRIVATE_KEY env var is required. Add it to your .env file.\n\
-                     Format: 64-character hex string (no 0x prefix)\n\
-                     Example: 0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef")?;
-        
-        // Validate private key format
-        let key_clean = private_key.trim().strip_prefix("0x").unwrap_or(private_key.trim());
-        if key_clean.len() != 64 {
-            anyhow::bail!(
-                "PRIVATE_KEY must be exactly 64 hex characters (found {}).\n\
-                Remove any '0x' prefix. Current value starts with: {}",
-                key_clean.len(),
-                if key_clean.len() > 10 { format!("{}...", &key_clean[..10]) } else { key_clean.to_string() }
-            );
-        }
-        if !key_clean.chars().all(|c| c.is_ascii_hexdigit()) {
-            anyhow::bail!("PRIVATE_KEY contains invalid characters. Must be hexadecimal (0-9, a-f, A-F).");
-        }
+        // SIGNER=remote keeps the key off this host entirely; otherwise load it locally
+        let remote_signer = remote_signer_from_env()?;
+        let private_key = if remote_signer.is_some() { String::new() } else { load_private_key()? };
         
         let funder_address = env::var("FUNDER_ADDRESS")
             .context("FUNDER_ADDRESS env var is required. Add it to your .env file.\n\
@@ -261,6 +1471,31 @@ impl Config {
             anyhow::bail!("FUNDER_ADDRESS contains invalid characters. Must be hexadecimal (0-9, a-f, A-F).");
         }
         
+        parse_taker_address(&env::var("TAKER_ADDRESS").unwrap_or_default())?;
+        if let Ok(template) = env::var("WEBHOOK_TEMPLATE") {
+            if !template.trim().is_empty() {
+                MessageTemplate::parse(&template).map_err(|e| anyhow::anyhow!("WEBHOOK_TEMPLATE: {}", e))?;
+            }
+        }
+
+        validate_choice_settings()?;
+
+        // Network bundle (URLs overridable for staging/self-hosted endpoints)
+        let network = Network::parse(&env::var("NETWORK").unwrap_or_default())?;
+        let clob_api_base = env::var("CLOB_API_BASE")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .unwrap_or_else(|| network.clob_api_base().to_string());
+        let gamma_api_base = env::var("GAMMA_API_BASE")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .unwrap_or_else(|| network.gamma_api_base().to_string());
+        let data_api_base = env::var("DATA_API_BASE")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .unwrap_or_else(|| network.data_api_base().to_string());
+        validate_network_endpoints(network, &clob_api_base, &gamma_api_base, &data_api_base)?;
+
         // WebSocket URL from either provider
         let wss_url = if let Ok(key) = env::var("ALCHEMY_API_KEY") {
             let key = key.trim();
@@ -271,7 +1506,7 @@ impl Config {
                     Then add it to your .env file"
                 );
             }
-            format!("wss://polygon-mainnet.g.alchemy.com/v2/{}", key)
+            format!("wss://{}.g.alchemy.com/v2/{}", network.rpc_subdomain(), key)
         } else if let Ok(key) = env::var("CHAINSTACK_API_KEY") {
             let key = key.trim();
             if key.is_empty() || key == "your_chainstack_api_key_here" {
@@ -281,7 +1516,7 @@ impl Config {
                     Or use ALCHEMY_API_KEY instead (recommended for beginners)"
                 );
             }
-            format!("wss://polygon-mainnet.core.chainstack.com/{}", key)
+            format!("wss://{}.core.chainstack.com/{}", network.rpc_subdomain(), key)
         } else {
             anyhow::bail!(
                 "WebSocket API key required!\n\
@@ -302,32 +1537,40 @@ impl Config {
             );
         };
         
-        // Validate TARGET_WHALE_ADDRESS (used by TARGET_TOPIC_HEX lazy static)
+        // Validate TARGET_WHALE_ADDRESS (used by TARGET_TOPICS lazy static)
         let target_whale = env::var("TARGET_WHALE_ADDRESS")
             .context("TARGET_WHALE_ADDRESS env var is required. Add it to your .env file.\n\
-                     Format: 40-character hex address (no 0x prefix)\n\
+                     Format: 40-character hex address (no 0x prefix), comma-separated to follow several\n\
                      This is the whale address you want to copy trades from.\n\
                      Find whale addresses on Polymarket leaderboards")?;
         
-        let whale_clean = target_whale.trim().strip_prefix("0x").unwrap_or(target_whale.trim());
-        if whale_clean.is_empty() || whale_clean == "target_whale_address_here" {
-            anyhow::bail!(
-                "TARGET_WHALE_ADDRESS is set but has placeholder value.\n\
-                Replace 'target_whale_address_here' with the actual whale address you want to copy.\n\
-                Find whale addresses on Polymarket leaderboards or from successful traders."
-            );
-        }
-        if whale_clean.len() != 40 {
-            anyhow::bail!(
-                "TARGET_WHALE_ADDRESS must be exactly 40 hex characters (found {}).\n\
-                Remove '0x' prefix if present. Current value: {}",
-                whale_clean.len(),
-                if whale_clean.len() > 20 { format!("{}...", &whale_clean[..20]) } else { whale_clean.to_string() }
-            );
+        let whales: Vec<&str> = target_whale.split(',').map(str::trim).filter(|w| !w.is_empty()).collect();
+        if whales.is_empty() {
+            anyhow::bail!("TARGET_WHALE_ADDRESS is empty. Set it to the whale address (or comma-separated addresses) you want to copy.");
         }
-        if !whale_clean.chars().all(|c| c.is_ascii_hexdigit()) {
-            anyhow::bail!("TARGET_WHALE_ADDRESS contains invalid characters. Must be hexadecimal (0-9, a-f, A-F).");
+        for whale in whales {
+            let whale_clean = whale.strip_prefix("0x").unwrap_or(whale);
+            if whale_clean.is_empty() || whale_clean == "target_whale_address_here" {
+                anyhow::bail!(
+                    "TARGET_WHALE_ADDRESS is set but has placeholder value.\n\
+                    Replace 'target_whale_address_here' with the actual whale address you want to copy.\n\
+                    Find whale addresses on Polymarket leaderboards or from successful traders."
+                );
+            }
+            if whale_clean.len() != 40 {
+                anyhow::bail!(
+                    "TARGET_WHALE_ADDRESS must be exactly 40 hex characters (found {}).\n\
+                    Remove '0x' prefix if present. Current value: {}",
+                    whale_clean.len(),
+                    if whale_clean.len() > 20 { format!("{}...", &whale_clean[..20]) } else { whale_clean.to_string() }
+                );
+            }
+            if !whale_clean.chars().all(|c| c.is_ascii_hexdigit()) {
+                anyhow::bail!("TARGET_WHALE_ADDRESS contains invalid characters. Must be hexadecimal (0-9, a-f, A-F).");
+            }
         }
+        // Normalize the target topics once, here, rather than on the first event
+        Lazy::force(&TARGET_TOPICS);
         
         let enable_trading = env::var("ENABLE_TRADING")
             .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
@@ -337,20 +1580,89 @@ impl Config {
             .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
             .unwrap_or(false);
         
+        let d = Self::default();
         Ok(Self {
             private_key,
             funder_address,
+            network,
+            chain_id: network.chain_id(),
+            clob_api_base: clob_api_base.trim_end_matches('/').to_string(),
+            gamma_api_base: gamma_api_base.trim_end_matches('/').to_string(),
+            data_api_base: data_api_base.trim_end_matches('/').to_string(),
+            block_number_fallback: env::var("BLOCK_NUMBER_FALLBACK")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(false),
+            block_rpc_url: env::var("BLOCK_RPC_URL")
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or_else(|| block_resolver::http_url_from_wss(&wss_url)),
             wss_url,
+            use_mempool: env::var("USE_MEMPOOL")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(false),
+            max_concurrent_handlers: env_parse("MAX_CONCURRENT_HANDLERS", d.max_concurrent_handlers).max(1),
+            handler_saturation: SaturationPolicy::parse(&env::var("HANDLER_SATURATION").unwrap_or_default())?,
+            order_queue_priority: QueuePriority::parse(&env::var("ORDER_QUEUE_PRIORITY").unwrap_or_default())?,
+            max_reconnect_attempts: env_parse("MAX_RECONNECT_ATTEMPTS", d.max_reconnect_attempts),
             enable_trading,
             mock_trading,
-            cb_large_trade_shares: env_parse("CB_LARGE_TRADE_SHARES", 1500.0),
-            cb_consecutive_trigger: env_parse("CB_CONSECUTIVE_TRIGGER", 2u8),
-            cb_sequence_window_secs: env_parse("CB_SEQUENCE_WINDOW_SECS", 30),
-            cb_min_depth_usd: env_parse("CB_MIN_DEPTH_USD", 200.0),
-            cb_trip_duration_secs: env_parse("CB_TRIP_DURATION_SECS", 120),
+            cb_large_trade_shares: env_parse("CB_LARGE_TRADE_SHARES", d.cb_large_trade_shares),
+            cb_consecutive_trigger: env_parse("CB_CONSECUTIVE_TRIGGER", d.cb_consecutive_trigger),
+            cb_sequence_window_secs: env_parse("CB_SEQUENCE_WINDOW_SECS", d.cb_sequence_window_secs),
+            cb_min_depth_usd: env_parse("CB_MIN_DEPTH_USD", d.cb_min_depth_usd),
+            cb_trip_duration_secs: env_parse("CB_TRIP_DURATION_SECS", d.cb_trip_duration_secs),
+            daily_notional_cap_usd: env_parse("DAILY_NOTIONAL_CAP_USD", d.daily_notional_cap_usd),
+            whale_cooldown_ms: env_parse("WHALE_COOLDOWN_MS", d.whale_cooldown_ms),
+            conviction_step: env_parse("CONVICTION_STEP", d.conviction_step).max(0.0),
+            conviction_max_multiplier: env_parse("CONVICTION_MAX_MULTIPLIER", d.conviction_max_multiplier),
+            conviction_window_secs: env_parse("CONVICTION_WINDOW_SECS", d.conviction_window_secs),
+            market_rate_burst: env_parse("MARKET_RATE_BURST", d.market_rate_burst),
+            whale_entry_window_secs: env_parse("WHALE_ENTRY_WINDOW_SECS", d.whale_entry_window_secs),
+            act_on_fills_only: *ACT_ON_FILLS_ONLY,
+            follow_sides: *FOLLOW_SIDES,
+            copy_mode: *COPY_MODE,
+            sizing: *LIVE_SIZING,
+            tier_shares_rounding: *TIER_SHARES_ROUNDING,
+            market_rate_per_min: env_parse("MARKET_RATE_PER_MIN", d.market_rate_per_min).max(0.0),
+            reconcile_interval_secs: env_parse("RECONCILE_INTERVAL_SECS", d.reconcile_interval_secs),
+            reconcile_tolerance_shares: env_parse("RECONCILE_TOLERANCE_SHARES", d.reconcile_tolerance_shares).max(0.0),
+            reconcile_correct: env::var("RECONCILE_CORRECT")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(false),
+            gtd_expiry_check: env::var("GTD_EXPIRY_CHECK")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(d.gtd_expiry_check),
+            gtd_expiry_grace_secs: env_parse("GTD_EXPIRY_GRACE_SECS", d.gtd_expiry_grace_secs),
+            live_flip_poll_secs: env_parse("LIVE_FLIP_POLL_SECS", d.live_flip_poll_secs),
+            live_flip_action: LiveFlipAction::parse(&env::var("LIVE_FLIP_ACTION").unwrap_or_default())?,
+            instance_label: env::var("INSTANCE_LABEL").unwrap_or_default(),
+            status_addr: env::var("STATUS_ADDR").unwrap_or_default().trim().to_string(),
+            kill_switch_file: env::var("KILL_SWITCH_FILE").unwrap_or_default().trim().to_string(),
+            decision_socket: env::var("DECISION_SOCKET").unwrap_or_default().trim().to_string(),
+            warmup_gate: env::var("WARMUP_GATE")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(false),
+            warmup_timeout_secs: env_parse("WARMUP_TIMEOUT_SECS", d.warmup_timeout_secs),
+            clock_skew_warn_secs: env_parse("CLOCK_SKEW_WARN_SECS", d.clock_skew_warn_secs),
+            clock_skew_max_secs: env_parse("CLOCK_SKEW_MAX_SECS", d.clock_skew_max_secs),
+            http_connect_timeout_ms: env_parse("HTTP_CONNECT_TIMEOUT_MS", d.http_connect_timeout_ms).max(1),
+            http_request_timeout_ms: env_parse("HTTP_REQUEST_TIMEOUT_MS", d.http_request_timeout_ms).max(1),
+            book_timeout_ms: env_parse("BOOK_TIMEOUT_MS", d.book_timeout_ms).max(1),
+            gamma_timeout_ms: env_parse("GAMMA_TIMEOUT_MS", d.gamma_timeout_ms).max(1),
+            drain_resubmits_on_shutdown: env::var("DRAIN_RESUBMITS_ON_SHUTDOWN")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(false),
+            resubmit_drain_timeout_secs: env_parse("RESUBMIT_DRAIN_TIMEOUT_SECS", d.resubmit_drain_timeout_secs),
+            stale_creds: StaleCredsPolicy::parse(&env::var("STALE_CREDS").unwrap_or_default())?,
+            remote_signer,
         })
     }
     
+    /// What shutdown does with resubmit chains still in flight
+    pub fn shutdown_mode(&self) -> crate::shutdown::ShutdownMode {
+        crate::shutdown::ShutdownMode::new(self.drain_resubmits_on_shutdown, Duration::from_secs(self.resubmit_drain_timeout_secs))
+    }
+
     /// Convert to RiskGuardConfig for safety checks
     pub fn risk_guard_config(&self) -> risk_guard::RiskGuardConfig {
         risk_guard::RiskGuardConfig {
@@ -359,11 +1671,267 @@ impl Config {
             sequence_window: Duration::from_secs(self.cb_sequence_window_secs),
             min_depth_beyond_usd: self.cb_min_depth_usd,
             trip_duration: Duration::from_secs(self.cb_trip_duration_secs),
+            daily_notional_cap_usd: self.daily_notional_cap_usd,
+            whale_cooldown: Duration::from_millis(self.whale_cooldown_ms),
+            conviction_step: self.conviction_step,
+            conviction_max_multiplier: self.conviction_max_multiplier,
+            conviction_window: Duration::from_secs(self.conviction_window_secs),
+            market_rate_burst: self.market_rate_burst,
+            market_rate_per_min: self.market_rate_per_min,
+            whale_entry_window: Duration::from_secs(self.whale_entry_window_secs),
+        }
+    }
+
+    /// Settings the order decision reads (engine::plan_order)
+    pub fn plan_params(&self) -> PlanParams {
+        PlanParams {
+            act_on_fills_only: self.act_on_fills_only,
+            follow_sides: self.follow_sides,
+            copy_mode: self.copy_mode,
+            sizing: self.sizing,
+            tier_shares_rounding: self.tier_shares_rounding,
         }
     }
+
+    /// Timeouts for the CLOB client and the other API calls
+    pub fn http_timeouts(&self) -> HttpTimeouts {
+        HttpTimeouts {
+            connect: Duration::from_millis(self.http_connect_timeout_ms),
+            request: Duration::from_millis(self.http_request_timeout_ms),
+            book: Duration::from_millis(self.book_timeout_ms),
+            gamma: Duration::from_millis(self.gamma_timeout_ms),
+        }
+    }
+
+    /// Every effective setting, one per line, with secrets redacted and whether
+    /// each came from the environment or a built-in default
+    pub fn describe(&self) -> String {
+        self.describe_with(|key| env::var(key).is_ok_and(|v| !v.trim().is_empty()))
+    }
+
+    /// describe() with an injectable "is this env var set" check (for tests)
+    pub fn describe_with(&self, is_set: impl Fn(&str) -> bool) -> String {
+        let secret = |v: &str| if v.is_empty() { "<unset>".to_string() } else { "<redacted>".to_string() };
+        let any_set = |keys: &[&str]| keys.iter().any(|k| is_set(k));
+        let rows: Vec<(&str, String, bool)> = vec![
+            ("PRIVATE_KEY", secret(&self.private_key), any_set(&["PRIVATE_KEY", "PRIVATE_KEY_FILE", "KEYSTORE_PATH"])),
+            ("FUNDER_ADDRESS", self.funder_address.clone(), is_set("FUNDER_ADDRESS")),
+            ("TAKER_ADDRESS", TAKER_ADDRESS.clone().unwrap_or_default(), is_set("TAKER_ADDRESS")),
+            ("TARGET_WHALE_ADDRESS", env::var("TARGET_WHALE_ADDRESS").unwrap_or_default(), is_set("TARGET_WHALE_ADDRESS")),
+            ("NETWORK", self.network.as_str().to_string(), is_set("NETWORK")),
+            ("CHAIN_ID", self.chain_id.to_string(), is_set("NETWORK")),
+            ("CLOB_API_BASE", self.clob_api_base.clone(), is_set("CLOB_API_BASE")),
+            ("GAMMA_API_BASE", self.gamma_api_base.clone(), is_set("GAMMA_API_BASE")),
+            ("DATA_API_BASE", self.data_api_base.clone(), is_set("DATA_API_BASE")),
+            ("WSS_URL", redact_url(&self.wss_url), any_set(&["ALCHEMY_API_KEY", "CHAINSTACK_API_KEY"])),
+            ("USE_MEMPOOL", self.use_mempool.to_string(), is_set("USE_MEMPOOL")),
+            ("BLOCK_NUMBER_FALLBACK", self.block_number_fallback.to_string(), is_set("BLOCK_NUMBER_FALLBACK")),
+            ("BLOCK_RPC_URL", redact_url(&self.block_rpc_url), is_set("BLOCK_RPC_URL")),
+            ("MAX_CONCURRENT_HANDLERS", self.max_concurrent_handlers.to_string(), is_set("MAX_CONCURRENT_HANDLERS")),
+            ("HANDLER_SATURATION", self.handler_saturation.as_str().to_string(), is_set("HANDLER_SATURATION")),
+            ("ORDER_QUEUE_PRIORITY", self.order_queue_priority.as_str().to_string(), is_set("ORDER_QUEUE_PRIORITY")),
+            ("MAX_RECONNECT_ATTEMPTS", self.max_reconnect_attempts.to_string(), is_set("MAX_RECONNECT_ATTEMPTS")),
+            ("ENABLE_TRADING", self.enable_trading.to_string(), is_set("ENABLE_TRADING")),
+            ("MOCK_TRADING", self.mock_trading.to_string(), is_set("MOCK_TRADING")),
+            ("CB_LARGE_TRADE_SHARES", self.cb_large_trade_shares.to_string(), is_set("CB_LARGE_TRADE_SHARES")),
+            ("CB_CONSECUTIVE_TRIGGER", self.cb_consecutive_trigger.to_string(), is_set("CB_CONSECUTIVE_TRIGGER")),
+            ("CB_SEQUENCE_WINDOW_SECS", self.cb_sequence_window_secs.to_string(), is_set("CB_SEQUENCE_WINDOW_SECS")),
+            ("CB_MIN_DEPTH_USD", self.cb_min_depth_usd.to_string(), is_set("CB_MIN_DEPTH_USD")),
+            ("CB_TRIP_DURATION_SECS", self.cb_trip_duration_secs.to_string(), is_set("CB_TRIP_DURATION_SECS")),
+            ("DAILY_NOTIONAL_CAP_USD", self.daily_notional_cap_usd.to_string(), is_set("DAILY_NOTIONAL_CAP_USD")),
+            ("WHALE_COOLDOWN_MS", self.whale_cooldown_ms.to_string(), is_set("WHALE_COOLDOWN_MS")),
+            ("MARKET_RATE_BURST", self.market_rate_burst.to_string(), is_set("MARKET_RATE_BURST")),
+            ("WHALE_ENTRY_WINDOW_SECS", self.whale_entry_window_secs.to_string(), is_set("WHALE_ENTRY_WINDOW_SECS")),
+            ("MARKET_RATE_PER_MIN", self.market_rate_per_min.to_string(), is_set("MARKET_RATE_PER_MIN")),
+            ("CONVICTION_STEP", self.conviction_step.to_string(), is_set("CONVICTION_STEP")),
+            ("CONVICTION_MAX_MULTIPLIER", self.conviction_max_multiplier.to_string(), is_set("CONVICTION_MAX_MULTIPLIER")),
+            ("CONVICTION_WINDOW_SECS", self.conviction_window_secs.to_string(), is_set("CONVICTION_WINDOW_SECS")),
+            ("RECONCILE_INTERVAL_SECS", self.reconcile_interval_secs.to_string(), is_set("RECONCILE_INTERVAL_SECS")),
+            ("RECONCILE_TOLERANCE_SHARES", self.reconcile_tolerance_shares.to_string(), is_set("RECONCILE_TOLERANCE_SHARES")),
+            ("RECONCILE_CORRECT", self.reconcile_correct.to_string(), is_set("RECONCILE_CORRECT")),
+            ("GTD_EXPIRY_CHECK", self.gtd_expiry_check.to_string(), is_set("GTD_EXPIRY_CHECK")),
+            ("GTD_EXPIRY_GRACE_SECS", self.gtd_expiry_grace_secs.to_string(), is_set("GTD_EXPIRY_GRACE_SECS")),
+            ("LIVE_FLIP_POLL_SECS", self.live_flip_poll_secs.to_string(), is_set("LIVE_FLIP_POLL_SECS")),
+            ("LIVE_FLIP_ACTION", self.live_flip_action.as_str().to_string(), is_set("LIVE_FLIP_ACTION")),
+            ("INSTANCE_LABEL", self.instance_label.clone(), is_set("INSTANCE_LABEL")),
+            ("STATUS_ADDR", self.status_addr.clone(), is_set("STATUS_ADDR")),
+            ("KILL_SWITCH_FILE", self.kill_switch_file.clone(), is_set("KILL_SWITCH_FILE")),
+            ("DECISION_SOCKET", self.decision_socket.clone(), is_set("DECISION_SOCKET")),
+            ("WARMUP_GATE", self.warmup_gate.to_string(), is_set("WARMUP_GATE")),
+            ("WARMUP_TIMEOUT_SECS", self.warmup_timeout_secs.to_string(), is_set("WARMUP_TIMEOUT_SECS")),
+            ("CLOCK_SKEW_WARN_SECS", self.clock_skew_warn_secs.to_string(), is_set("CLOCK_SKEW_WARN_SECS")),
+            ("CLOCK_SKEW_MAX_SECS", self.clock_skew_max_secs.to_string(), is_set("CLOCK_SKEW_MAX_SECS")),
+            ("HTTP_CONNECT_TIMEOUT_MS", self.http_connect_timeout_ms.to_string(), is_set("HTTP_CONNECT_TIMEOUT_MS")),
+            ("HTTP_REQUEST_TIMEOUT_MS", self.http_request_timeout_ms.to_string(), is_set("HTTP_REQUEST_TIMEOUT_MS")),
+            ("BOOK_TIMEOUT_MS", self.book_timeout_ms.to_string(), is_set("BOOK_TIMEOUT_MS")),
+            ("GAMMA_TIMEOUT_MS", self.gamma_timeout_ms.to_string(), is_set("GAMMA_TIMEOUT_MS")),
+            ("DRAIN_RESUBMITS_ON_SHUTDOWN", self.drain_resubmits_on_shutdown.to_string(), is_set("DRAIN_RESUBMITS_ON_SHUTDOWN")),
+            ("RESUBMIT_DRAIN_TIMEOUT_SECS", self.resubmit_drain_timeout_secs.to_string(), is_set("RESUBMIT_DRAIN_TIMEOUT_SECS")),
+            ("STALE_CREDS", format!("{:?}", self.stale_creds), is_set("STALE_CREDS")),
+            ("SIGNER", if self.remote_signer.is_some() { "remote" } else { "local" }.to_string(), is_set("SIGNER")),
+            (
+                "REMOTE_SIGNER_URL",
+                self.remote_signer.as_ref().map(|r| redact_url(&r.url)).unwrap_or_default(),
+                is_set("REMOTE_SIGNER_URL"),
+            ),
+            (
+                "REMOTE_SIGNER_ADDRESS",
+                self.remote_signer.as_ref().map(|r| r.address.clone()).unwrap_or_default(),
+                is_set("REMOTE_SIGNER_ADDRESS"),
+            ),
+            (
+                "REMOTE_SIGNER_TOKEN",
+                secret(self.remote_signer.as_ref().map_or("", |r| r.token.as_str())),
+                is_set("REMOTE_SIGNER_TOKEN"),
+            ),
+            // Read once at first use rather than stored on Config
+            ("COLLATERAL_DECIMALS", COLLATERAL_DECIMALS.to_string(), is_set("COLLATERAL_DECIMALS")),
+            ("BOOK_DEPTH_LEVELS", BOOK_DEPTH_LEVELS.to_string(), is_set("BOOK_DEPTH_LEVELS")),
+            ("EXCLUDE_OWN_RESTING_DEPTH", EXCLUDE_OWN_RESTING_DEPTH.to_string(), is_set("EXCLUDE_OWN_RESTING_DEPTH")),
+            ("MAX_RESTING_ORDERS", RESTING_CAP.max_total.to_string(), is_set("MAX_RESTING_ORDERS")),
+            ("MAX_RESTING_PER_TOKEN", RESTING_CAP.max_per_token.to_string(), is_set("MAX_RESTING_PER_TOKEN")),
+            ("RESTING_CANCEL_OLDEST", RESTING_CAP.cancel_oldest.to_string(), is_set("RESTING_CANCEL_OLDEST")),
+            ("MARKET_OVERRIDES", env::var("MARKET_OVERRIDES").unwrap_or_default(), is_set("MARKET_OVERRIDES")),
+            ("IGNORED_MAKERS", env::var("IGNORED_MAKERS").unwrap_or_default(), is_set("IGNORED_MAKERS")),
+            ("BOOK_MAX_AGE_MS", BOOK_MAX_AGE_MS.to_string(), is_set("BOOK_MAX_AGE_MS")),
+            ("LIQUIDITY_PRECHECK", format!("{:?}", *LIQUIDITY_PRECHECK), is_set("LIQUIDITY_PRECHECK")),
+            ("LIQUIDITY_PRECHECK_MIN_FRACTION", LIQUIDITY_PRECHECK_MIN_FRACTION.to_string(), is_set("LIQUIDITY_PRECHECK_MIN_FRACTION")),
+            ("MIN_CROSS_BUFFER", MIN_CROSS_BUFFER.map_or("off".into(), |b| b.to_string()), is_set("MIN_CROSS_BUFFER")),
+            ("SECOND_LEVEL_BUFFER", SECOND_LEVEL_BUFFER.map_or("off".into(), |b| b.to_string()), is_set("SECOND_LEVEL_BUFFER")),
+            ("TIER_MIN_DEPTH", TIER_MIN_DEPTH.to_string(), is_set("TIER_MIN_DEPTH")),
+            ("BOOK_LOG_RETRIES", BOOK_LOG_RETRIES.to_string(), is_set("BOOK_LOG_RETRIES")),
+            ("OVERFILL_TOLERANCE_SHARES", OVERFILL_TOLERANCE_SHARES.to_string(), is_set("OVERFILL_TOLERANCE_SHARES")),
+            ("TRACE_DECISIONS", TRACE_DECISIONS.to_string(), is_set("TRACE_DECISIONS")),
+            ("ACT_ON_FILLS_ONLY", ACT_ON_FILLS_ONLY.to_string(), is_set("ACT_ON_FILLS_ONLY")),
+            ("FOLLOW_SIDES", format!("{:?}", *FOLLOW_SIDES), is_set("FOLLOW_SIDES")),
+            ("COPY_MODE", format!("{:?}", *COPY_MODE), is_set("COPY_MODE")),
+            ("MAKER_REPRICE_SECS", MAKER_REPRICE_SECS.to_string(), is_set("MAKER_REPRICE_SECS")),
+            ("MAKER_REPRICE_STEPS", MAKER_REPRICE_STEPS.to_string(), is_set("MAKER_REPRICE_STEPS")),
+            ("MAKER_MIN_SPREAD_TICKS", MAKER_MIN_SPREAD_TICKS.to_string(), is_set("MAKER_MIN_SPREAD_TICKS")),
+            ("RNG_SEED", RNG_SEED.map_or("entropy".into(), |s| s.to_string()), is_set("RNG_SEED")),
+            ("SUBMIT_JITTER_MS", SUBMIT_JITTER_MS.to_string(), is_set("SUBMIT_JITTER_MS")),
+            ("SUBMIT_JITTER_SEED", SUBMIT_JITTER_SEED.map_or("per instance".into(), |s| s.to_string()), is_set("SUBMIT_JITTER_SEED")),
+            ("CSV_QUOTING", format!("{:?}", *CSV_QUOTING), is_set("CSV_QUOTING")),
+            ("CSV_DIRECTION", format!("{:?}", *CSV_DIRECTION), is_set("CSV_DIRECTION")),
+            ("CSV_TIER_COLUMNS", CSV_TIER_COLUMNS.to_string(), is_set("CSV_TIER_COLUMNS")),
+            ("CSV_BUFFER_STATS", CSV_BUFFER_STATS.to_string(), is_set("CSV_BUFFER_STATS")),
+            ("WHALE_PRICE_MODE", format!("{:?}", *WHALE_PRICE_MODE), is_set("WHALE_PRICE_MODE")),
+            ("WHALE_PRICE_ROUNDING", format!("{:?}", *WHALE_PRICE_ROUNDING), is_set("WHALE_PRICE_ROUNDING")),
+            ("SUBMIT_PRICE_MIN", SUBMIT_PRICE_BOUNDS.min.map_or("tick".into(), |b| b.to_string()), is_set("SUBMIT_PRICE_MIN")),
+            ("SUBMIT_PRICE_MAX", SUBMIT_PRICE_BOUNDS.max.map_or("1 - tick".into(), |b| b.to_string()), is_set("SUBMIT_PRICE_MAX")),
+            ("SAME_TOKEN_PER_BLOCK", format!("{:?}", *SAME_TOKEN_PER_BLOCK), is_set("SAME_TOKEN_PER_BLOCK")),
+            ("OPPOSING_SIGNALS", format!("{:?}", *OPPOSING_SIGNALS), is_set("OPPOSING_SIGNALS")),
+            ("OPPOSING_SIGNAL_WINDOW_MS", OPPOSING_SIGNAL_WINDOW_MS.to_string(), is_set("OPPOSING_SIGNAL_WINDOW_MS")),
+            ("SUMMARY_MODE", format!("{:?}", *SUMMARY_MODE), is_set("SUMMARY_MODE")),
+            ("EVENT_WATCHDOG_SECS", (EVENT_WATCHDOG.threshold_ms / 1000).to_string(), is_set("EVENT_WATCHDOG_SECS")),
+            ("EVENT_WATCHDOG_RECONNECT", EVENT_WATCHDOG.reconnect.to_string(), is_set("EVENT_WATCHDOG_RECONNECT")),
+            ("EVENT_WATCHDOG_HOURS_UTC", format!("{:?}", EVENT_WATCHDOG.active_hours), is_set("EVENT_WATCHDOG_HOURS_UTC")),
+            ("WEBHOOK_URL", redact_url(&WEBHOOK_URL), is_set("WEBHOOK_URL")),
+            ("WEBHOOK_TEMPLATE", env::var("WEBHOOK_TEMPLATE").unwrap_or_else(|_| crate::notify::DEFAULT_TEMPLATE.into()), is_set("WEBHOOK_TEMPLATE")),
+            ("WEBHOOK_OUTCOMES", format!("{:?}", *WEBHOOK_OUTCOMES), is_set("WEBHOOK_OUTCOMES")),
+            ("USD_DISPLAY_DECIMALS", format!("{:?}", *USD_DISPLAY_DECIMALS), is_set("USD_DISPLAY_DECIMALS")),
+            ("RESUBMIT_MAX_BUFFER_PCT", RESUBMIT_MAX_BUFFER_PCT.to_string(), is_set("RESUBMIT_MAX_BUFFER_PCT")),
+            ("TIER_SHARES_ROUNDING", TIER_SHARES_ROUNDING.to_string(), is_set("TIER_SHARES_ROUNDING")),
+            ("WHALE_MAX_PREMIUM", WHALE_MAX_PREMIUM.to_string(), is_set("WHALE_MAX_PREMIUM")),
+            ("WHALE_MAX_PREMIUM_PCT", WHALE_MAX_PREMIUM_PCT.to_string(), is_set("WHALE_MAX_PREMIUM_PCT")),
+            ("FAK_EXPIRATION_SECS", FAK_EXPIRATION_SECS.to_string(), is_set("FAK_EXPIRATION_SECS")),
+            ("UNKNOWN_LIVE_DEFAULT", format!("{:?}", *UNKNOWN_LIVE_DEFAULT), is_set("UNKNOWN_LIVE_DEFAULT")),
+            ("FOLLOW_FRACTION", format!("{:?}", LIVE_SIZING.follow_fraction), is_set("FOLLOW_FRACTION")),
+            (
+                "FOLLOW_FRACTION_MULTIPLIER",
+                if LIVE_SIZING.fraction_ignores_multiplier { "ignore" } else { "apply" }.to_string(),
+                is_set("FOLLOW_FRACTION_MULTIPLIER"),
+            ),
+            ("ALLOCATION_BANKROLL_USD", LIVE_SIZING.allocation_bankroll_usd.map_or("off".into(), |b| b.to_string()), is_set("ALLOCATION_BANKROLL_USD")),
+            ("ALLOCATION_MAX_FRACTION", LIVE_SIZING.allocation_max_fraction.to_string(), is_set("ALLOCATION_MAX_FRACTION")),
+            ("WHALE_PORTFOLIO_REFRESH_SECS", WHALE_PORTFOLIO_REFRESH_SECS.to_string(), is_set("WHALE_PORTFOLIO_REFRESH_SECS")),
+            ("CONFIRM_VIA_API", CONFIRM_VIA_API.to_string(), is_set("CONFIRM_VIA_API")),
+            ("CONFIRM_TIMEOUT_MS", CONFIRM_TIMEOUT_MS.to_string(), is_set("CONFIRM_TIMEOUT_MS")),
+            ("CONFIRM_ON_TIMEOUT", CONFIRM_ON_TIMEOUT.as_str().to_string(), is_set("CONFIRM_ON_TIMEOUT")),
+            ("SHADOW_SIZING", format!("{:?}", *SHADOW_SIZING), is_set("SHADOW_SIZING")),
+            ("RESUBMIT_INITIAL_DELAY_MS", RESUBMIT_INITIAL_DELAY_MS.to_string(), is_set("RESUBMIT_INITIAL_DELAY_MS")),
+            ("RESUBMIT_INITIAL_DELAY_SMALL_MS", RESUBMIT_INITIAL_DELAY_SMALL_MS.to_string(), is_set("RESUBMIT_INITIAL_DELAY_SMALL_MS")),
+            ("RESUBMIT_DEADLINE_MS", RESUBMIT_DEADLINE_MS.to_string(), is_set("RESUBMIT_DEADLINE_MS")),
+            ("RESUBMIT_DEADLINE_LIVE_MS", RESUBMIT_DEADLINE_LIVE_MS.to_string(), is_set("RESUBMIT_DEADLINE_LIVE_MS")),
+            ("RESUBMIT_WORKERS", RESUBMIT_WORKERS.to_string(), is_set("RESUBMIT_WORKERS")),
+            ("RESUBMIT_DONE_FILL_PCT", RESUBMIT_DONE_RULE.fill_ratio.map_or("-".into(), |r| format!("{}", r * 100.0)), is_set("RESUBMIT_DONE_FILL_PCT")),
+            ("RESUBMIT_DONE_REMAINING_USD", RESUBMIT_DONE_RULE.remaining_usd.map_or("-".into(), |u| u.to_string()), is_set("RESUBMIT_DONE_REMAINING_USD")),
+            ("RESUBMIT_RETRYABLE_ERRORS", RESUBMIT_ERROR_POLICY.retryable.join(","), is_set("RESUBMIT_RETRYABLE_ERRORS")),
+            ("RESUBMIT_TERMINAL_ERRORS", RESUBMIT_ERROR_POLICY.terminal.join(","), is_set("RESUBMIT_TERMINAL_ERRORS")),
+        ];
+
+        let mut out = String::from("⚙️ Effective configuration:\n");
+        for (key, value, from_env) in rows {
+            out.push_str(&format!("  {:<28} = {:<40} [{}]\n", key, value, if from_env { "env" } else { "default" }));
+        }
+        out
+    }
+}
+
+/// scheme://host with the path (where RPC providers put the API key) redacted
+fn redact_url(url: &str) -> String {
+    if url.is_empty() {
+        return "<unset>".to_string();
+    }
+    let Some((scheme, rest)) = url.split_once("://") else { return "<redacted>".to_string() };
+    match rest.split_once('/') {
+        Some((host, path)) if !path.is_empty() => format!("{}://{}/<redacted>", scheme, host),
+        _ => url.to_string(),
+    }
 }
 
 /// Parse env var with default fallback
+/// Resolve and validate the local signing key (KEYSTORE_PATH, PRIVATE_KEY_FILE or PRIVATE_KEY)
+fn load_private_key() -> Result<String> {
+    // PRIVATE_KEY_FILE / KEYSTORE_PATH keep the key out of the environment and shell history
+    let (private_key, key_source) = key_source::resolve_private_key()?
+        .context("PRIVATE_KEY env var is required. Add it to your .env file.\n\
+                 Format: 64-character hex string (no 0x prefix)\n\
+                 Example: 0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef\n\
+                 Or set PRIVATE_KEY_FILE (file with the key, chmod 600) or KEYSTORE_PATH + KEYSTORE_PASSPHRASE")?;
+    if key_source != key_source::KeySource::Env {
+        println!("🔑 Signing key loaded from {}", key_source.describe());
+    }
+    
+    // Validate private key format
+    let key_clean = private_key.trim().strip_prefix("0x").unwrap_or(private_key.trim());
+    if key_clean.len() != 64 {
+        anyhow::bail!(
+            "PRIVATE_KEY must be exactly 64 hex characters (found {}).\n\
+            Remove any '0x' prefix. Current value starts with: {}",
+            key_clean.len(),
+            if key_clean.len() > 10 { format!("{}...", &key_clean[..10]) } else { key_clean.to_string() }
+        );
+    }
+    if !key_clean.chars().all(|c| c.is_ascii_hexdigit()) {
+        anyhow::bail!("PRIVATE_KEY contains invalid characters. Must be hexadecimal (0-9, a-f, A-F).");
+    }
+    Ok(private_key)
+}
+
+/// SIGNER=remote: REMOTE_SIGNER_URL and REMOTE_SIGNER_ADDRESS are required. Unset or "local" = None
+pub fn remote_signer_from_env() -> Result<Option<RemoteSignerConfig>> {
+    let mode = env::var("SIGNER").unwrap_or_default();
+    match mode.trim().to_ascii_lowercase().as_str() {
+        "" | "local" => return Ok(None),
+        "remote" => {}
+        other => anyhow::bail!("SIGNER must be \"local\" or \"remote\", got {:?}", other),
+    }
+    let url = env::var("REMOTE_SIGNER_URL").unwrap_or_default().trim().to_string();
+    if url.is_empty() {
+        anyhow::bail!("SIGNER=remote requires REMOTE_SIGNER_URL");
+    }
+    let address = parse_taker_address(&env::var("REMOTE_SIGNER_ADDRESS").unwrap_or_default())
+        .map_err(|_| anyhow::anyhow!("REMOTE_SIGNER_ADDRESS must be a 40-hex-character address"))?
+        .context("SIGNER=remote requires REMOTE_SIGNER_ADDRESS (the address the endpoint signs for)")?;
+    Ok(Some(RemoteSignerConfig {
+        url,
+        address,
+        token: env::var("REMOTE_SIGNER_TOKEN").unwrap_or_default().trim().to_string(),
+        timeout_ms: env_parse("REMOTE_SIGNER_TIMEOUT_MS", 2000),
+    }))
+}
+
 fn env_parse<T: std::str::FromStr>(key: &str, default: T) -> T {
     env::var(key)
         .ok()
@@ -549,6 +2117,185 @@ mod tests {
         assert_eq!(mult, 1.0);
     }
 
+    // -------------------------------------------------------------------------
+    // Test: buy and sell tiers come from separate tables
+    // -------------------------------------------------------------------------
+    #[test]
+    fn test_sell_tiers_independent_of_buy() {
+        let token_id = "fake_token";
+
+        // Where the buy table has a buffer/FAK tier, sells use their own table
+        let buy = get_tier_params(5000.0, true, token_id);
+        let sell = get_tier_params(5000.0, false, token_id);
+        assert_eq!(buy, (0.01, "FAK", 1.25));
+        assert_eq!(sell, (SELL_EXECUTION_TIERS[0].price_buffer, SELL_EXECUTION_TIERS[0].order_action, 1.0));
+        assert_ne!(buy, sell);
+
+        // Every sell-table tier is honored for sells, not the buy table
+        for tier in SELL_EXECUTION_TIERS.iter() {
+            assert_eq!(find_side_execution_tier(tier.min_shares, false).map(|t| t.min_shares), Some(tier.min_shares));
+            assert_eq!(get_side_resubmit_order_action(tier.min_shares, false, false), tier.order_action);
+            assert_eq!(get_side_resubmit_order_action(tier.min_shares, false, true), tier.final_action);
+        }
+        assert_eq!(execution_tiers(true).len(), EXECUTION_TIERS.len());
+        assert_eq!(execution_tiers(false).len(), SELL_EXECUTION_TIERS.len());
+    }
+
+    // -------------------------------------------------------------------------
+    // Test: Tier order actions drive the order type sent
+    // -------------------------------------------------------------------------
+    #[test]
+    fn test_tier_actions_drive_order_type() {
+        for tier in EXECUTION_TIERS.iter() {
+            let (_, action, _) = get_tier_params(tier.min_shares, true, "fake_token");
+            assert_eq!(action, tier.order_action);
+            assert_eq!(get_resubmit_order_action(tier.min_shares, false), tier.order_action);
+            assert_eq!(get_resubmit_order_action(tier.min_shares, true), tier.final_action);
+        }
+
+        // Below all tiers: defaults
+        assert_eq!(get_resubmit_order_action(100.0, false), DEFAULT_ORDER_ACTION);
+        assert_eq!(get_resubmit_order_action(100.0, true), DEFAULT_FINAL_ACTION);
+
+        // Only GTD carries an expiry; GTD/GTC rest on the book
+        let now = 1_700_000_000;
+        assert_eq!(order_expiration("GTD", true, now), Some((now + 61).to_string()));
+        assert_eq!(order_expiration("GTD", false, now), Some((now + 1800).to_string()));
+        assert_eq!(order_expiration("FAK", true, now), None);
+        assert_eq!(order_expiration("GTC", true, now), None);
+        assert!(is_resting_order("GTC") && is_resting_order("GTD"));
+        assert!(!is_resting_order("FAK"));
+    }
+
+    // -------------------------------------------------------------------------
+    // Test: TAKER_ADDRESS is only set when configured, and normalized
+    // -------------------------------------------------------------------------
+    #[test]
+    fn test_taker_address() {
+        assert_eq!(parse_taker_address("").unwrap(), None);
+        assert_eq!(parse_taker_address("   ").unwrap(), None);
+        let expected = Some("0xdfe02eb6733538f8ea35d585af8de5958ad99e40".to_string());
+        assert_eq!(parse_taker_address(" 0xdFE02Eb6733538f8Ea35D585af8DE5958AD99E40 ").unwrap(), expected);
+        assert_eq!(parse_taker_address("DFE02EB6733538F8EA35D585AF8DE5958AD99E40").unwrap(), expected);
+        // Wrong length / not hex
+        assert!(parse_taker_address("0xdfe02eb6").is_err());
+        assert!(parse_taker_address("0xzfe02eb6733538f8ea35d585af8de5958ad99e40").is_err());
+    }
+
+    // -------------------------------------------------------------------------
+    // Test: describe() redacts secrets and reports where values came from
+    // -------------------------------------------------------------------------
+    #[test]
+    fn test_describe_redacts_secrets() {
+        let key = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+        let cfg = Config {
+            private_key: key.to_string(),
+            wss_url: "wss://polygon-mainnet.g.alchemy.com/v2/sekretApiKey".to_string(),
+            block_rpc_url: "https://polygon-mainnet.g.alchemy.com/v2/sekretApiKey".to_string(),
+            daily_notional_cap_usd: 250.0,
+            ..Default::default()
+        };
+        let out = cfg.describe_with(|k| k == "PRIVATE_KEY" || k == "DAILY_NOTIONAL_CAP_USD");
+
+        assert!(!out.contains(key));
+        assert!(!out.contains("sekretApiKey"));
+        assert!(out.contains("wss://polygon-mainnet.g.alchemy.com/<redacted>"));
+        let line = |name: &str| out.lines().find(|l| l.trim_start().starts_with(name)).unwrap().to_string();
+        assert!(line("PRIVATE_KEY ").contains("<redacted>") && line("PRIVATE_KEY ").ends_with("[env]"));
+        assert!(line("DAILY_NOTIONAL_CAP_USD").contains("250") && line("DAILY_NOTIONAL_CAP_USD").ends_with("[env]"));
+        assert!(line("CB_MIN_DEPTH_USD").ends_with("[default]"));
+    }
+
+    // -------------------------------------------------------------------------
+    // Test: configured error substrings decide whether the resubmit chain continues
+    // -------------------------------------------------------------------------
+    #[test]
+    fn test_resubmit_error_policy() {
+        let d = ResubmitErrorPolicy::default();
+        assert!(d.is_retryable("no orders found to match with FAK order"));
+        assert!(!d.is_retryable("not enough balance / allowance"));
+        assert!(!d.is_retryable("some new error"));
+
+        let policy = ResubmitErrorPolicy::new("no match, Try Again", "market closed");
+        assert!(policy.is_retryable("order had NO MATCH at this price"));
+        assert!(policy.is_retryable("please try again"));
+        // Terminal wins even when a retryable substring is present too
+        assert!(policy.is_terminal("no match: Market Closed"));
+        assert!(!policy.is_retryable("no match: Market Closed"));
+        // Configured lists replace the defaults
+        assert!(!policy.is_retryable("FAK order not filled"));
+    }
+
+    // -------------------------------------------------------------------------
+    // Test: unknown live status follows UNKNOWN_LIVE_DEFAULT; known values pass through
+    // -------------------------------------------------------------------------
+    #[test]
+    fn test_unknown_live_policies() {
+        assert_eq!(UnknownLive::parse("").unwrap(), UnknownLive::NonLive);
+        assert_eq!(UnknownLive::parse("non-live").unwrap(), UnknownLive::NonLive);
+        assert_eq!(UnknownLive::parse("LIVE").unwrap(), UnknownLive::Live);
+        assert_eq!(UnknownLive::parse("skip").unwrap(), UnknownLive::Skip);
+        assert!(UnknownLive::parse("maybe").is_err());
+
+        // nonlive: today's behavior
+        assert_eq!(UnknownLive::NonLive.resolve(None), Some(false));
+        // live: live GTD expiry
+        assert_eq!(UnknownLive::Live.resolve(None), Some(true));
+        assert_eq!(
+            order_expiration("GTD", UnknownLive::Live.resolve(None).unwrap(), 1000),
+            Some((1000 + get_gtd_expiry_secs(true)).to_string())
+        );
+        // skip: no trade
+        assert_eq!(UnknownLive::Skip.resolve(None), None);
+
+        for policy in [UnknownLive::NonLive, UnknownLive::Live, UnknownLive::Skip] {
+            assert_eq!(policy.resolve(Some(true)), Some(true));
+            assert_eq!(policy.resolve(Some(false)), Some(false));
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // Test: FAK expiry only when FAK_EXPIRATION_SECS is configured
+    // -------------------------------------------------------------------------
+    #[test]
+    fn test_fak_expiration_only_when_configured() {
+        let now = 1_700_000_000;
+        assert_eq!(submit_expiration("FAK", false, now, 0), None);
+        assert_eq!(submit_expiration("FAK", false, now, 90), Some((now + 90).to_string()));
+        // Other order types are unaffected by the setting
+        assert_eq!(submit_expiration("GTD", true, now, 90), Some((now + 61).to_string()));
+        assert_eq!(submit_expiration("GTC", true, now, 90), None);
+    }
+
+    // -------------------------------------------------------------------------
+    // Test: NETWORK switch selects URLs, chain id and creds file
+    // -------------------------------------------------------------------------
+    #[test]
+    fn test_network_selection() {
+        let mainnet = Network::parse("mainnet").unwrap();
+        assert_eq!(Network::parse("").unwrap(), Network::Mainnet);
+        assert_eq!(mainnet.chain_id(), 137);
+        assert_eq!(mainnet.clob_api_base(), "https://clob.polymarket.com");
+        assert_eq!(mainnet.gamma_api_base(), "https://gamma-api.polymarket.com");
+        assert_eq!(mainnet.data_api_base(), "https://data-api.polymarket.com");
+        assert_eq!(mainnet.monitored_addresses().len(), 3);
+
+        let amoy = Network::parse(" AMOY ").unwrap();
+        assert_eq!(amoy, Network::Amoy);
+        assert_eq!(amoy.chain_id(), 80002);
+        assert_ne!(amoy.clob_api_base(), mainnet.clob_api_base());
+        assert_ne!(amoy.creds_path(), mainnet.creds_path());
+        assert!(Network::parse("goerli").is_err());
+
+        // Testnet can't be pointed at mainnet endpoints (and vice versa)
+        let (amoy_data, mainnet_data) = (amoy.data_api_base(), mainnet.data_api_base());
+        assert!(validate_network_endpoints(amoy, amoy.clob_api_base(), amoy.gamma_api_base(), amoy_data).is_ok());
+        assert!(validate_network_endpoints(amoy, "https://clob.polymarket.com/", amoy.gamma_api_base(), amoy_data).is_err());
+        assert!(validate_network_endpoints(mainnet, mainnet.clob_api_base(), amoy.gamma_api_base(), mainnet_data).is_err());
+        assert!(validate_network_endpoints(amoy, amoy.clob_api_base(), amoy.gamma_api_base(), "https://data-api.polymarket.com/").is_err());
+        assert!(validate_network_endpoints(mainnet, "http://localhost:8080", mainnet.gamma_api_base(), mainnet_data).is_ok());
+    }
+
     // -------------------------------------------------------------------------
     // Test: Resubmit params for different sizes
     // Current config:
@@ -655,4 +2402,74 @@ mod tests {
         assert_eq!(buf, PRICE_BUFFER);
         assert_eq!(mult, 1.0);
     }
+
+    // -------------------------------------------------------------------------
+    // Test: TIER_SHARES_ROUNDING makes boundary bucketing deterministic
+    // -------------------------------------------------------------------------
+    #[test]
+    fn test_tier_shares_rounding() {
+        let tier = |raw: f64, granularity: f64| {
+            find_execution_tier(round_tier_shares_with(raw, granularity)).map(|t| t.min_shares)
+        };
+
+        // Off: float noise just under a boundary drops a tier
+        assert_eq!(tier(3999.9999, 0.0), Some(2000.0));
+        // Nearest share: noise either side of 4000 lands in the 4000+ tier ...
+        assert_eq!(tier(3999.9999, 1.0), Some(4000.0));
+        assert_eq!(tier(4000.0001, 1.0), Some(4000.0));
+        // ... while a real share short stays below it
+        assert_eq!(tier(3999.4, 1.0), Some(2000.0));
+        assert_eq!(tier(3999.5, 1.0), Some(4000.0));
+        // Finer and coarser granularities
+        assert_eq!(round_tier_shares_with(1999.996, 0.01), 2000.0);
+        assert_eq!(tier(1999.996, 0.01), Some(2000.0));
+        assert_eq!(tier(1999.98, 0.01), Some(1000.0));
+        assert_eq!(round_tier_shares_with(3976.0, 50.0), 4000.0);
+        assert_eq!(tier(3974.0, 50.0), Some(2000.0));
+        assert_eq!(tier(999.9999, 1.0), Some(1000.0));
+        assert_eq!(tier(999.4, 1.0), None);
+    }
+
+    // -------------------------------------------------------------------------
+    // Test: ACT_ON_FILLS_ONLY skips placements but not fills
+    // -------------------------------------------------------------------------
+    #[test]
+    fn test_act_on_fills_only() {
+        // Flag set: only _FILL events go through
+        assert!(should_skip_non_fill("BUY", true));
+        assert!(should_skip_non_fill("SELL", true));
+        assert!(!should_skip_non_fill("BUY_FILL", true));
+        assert!(!should_skip_non_fill("SELL_FILL", true));
+
+        // Flag off (default): everything goes through
+        assert!(!should_skip_non_fill("SELL", false));
+        assert!(!should_skip_non_fill("BUY_FILL", false));
+    }
+
+    // -------------------------------------------------------------------------
+    // Test: precomputed subscription matches the per-connect json! build
+    // -------------------------------------------------------------------------
+    #[test]
+    fn test_subscription_payload_precomputed() {
+        let targets = target_topics_from("204f72f35326db932158cba6adff0b9a1da95e14");
+        let cfg = Config { wss_url: "wss://example/ws".into(), ..Default::default() };
+        let sub = WsSubscription {
+            url: cfg.wss_url.clone(),
+            payload: build_subscribe_payload(cfg.network.monitored_addresses(), &targets),
+            mempool_exchanges: None,
+        };
+
+        let dynamic = serde_json::json!({
+            "jsonrpc": "2.0", "id": 1, "method": "eth_subscribe",
+            "params": ["logs", {
+                "address": Network::Mainnet.monitored_addresses(),
+                "topics": [[ORDERS_FILLED_EVENT_SIGNATURE], serde_json::Value::Null, targets.as_slice()]
+            }]
+        }).to_string();
+        assert_eq!(sub.payload, dynamic);
+        assert_eq!(sub.url, "wss://example/ws");
+
+        let parsed: serde_json::Value = serde_json::from_str(&sub.payload).unwrap();
+        assert_eq!(parsed["params"][1]["topics"][2][0], targets[0].as_str());
+    }
 }
@@ -1,5 +1,5 @@
-/// Soccer market detection and price buffer adjustments
-/// Uses market cache for efficient token lookups
+//! Soccer market detection and price buffer adjustments
+//! Uses market cache for efficient token lookups
 
 use crate::market_cache;
 
@@ -0,0 +1,67 @@
+//! Random delay before a copy's first submit (SUBMIT_JITTER_MS)
+//! Instances following the same whale all react to the same block at once and collide on the
+//! same thin liquidity. A per-instance seeded delay of 0..=N ms spreads them out. The delay is
+//! drawn when an event is queued and the order worker waits it out once the event has planned
+//! into an order, so skipped events aren't held back and events keep their queue order (at the
+//! cost of the wait also holding the events behind it). Resubmits aren't delayed
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+pub struct SubmitJitter {
+    max_ms: u64,
+    rng: StdRng,
+}
+
+impl SubmitJitter {
+    pub fn new(max_ms: u64, seed: u64) -> Self {
+        Self { max_ms, rng: StdRng::seed_from_u64(seed) }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.max_ms > 0
+    }
+
+    /// Delay for the next copy, uniform in 0..=max_ms
+    pub fn next_delay(&mut self) -> Duration {
+        if self.max_ms == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_millis(self.rng.gen_range(0..=self.max_ms))
+    }
+}
+
+/// Seed for this instance: SUBMIT_JITTER_SEED when set, else derived from the instance label
+/// and run id so two instances (or two runs of one) don't draw the same delays
+pub fn instance_seed(configured: Option<u64>, instance_label: &str, run_id: &str) -> u64 {
+    configured.unwrap_or_else(|| {
+        let mut h = rustc_hash::FxHasher::default();
+        (instance_label, run_id).hash(&mut h);
+        h.finish()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jitter_in_range_and_seeded() {
+        let mut a = SubmitJitter::new(50, 42);
+        let mut b = SubmitJitter::new(50, 42);
+        let draws: Vec<_> = (0..200).map(|_| a.next_delay()).collect();
+        assert!(draws.iter().all(|d| *d <= Duration::from_millis(50)));
+        assert!(draws.iter().any(|d| *d != draws[0]));
+        // Same seed, same delays
+        assert_eq!(draws, (0..200).map(|_| b.next_delay()).collect::<Vec<_>>());
+
+        let mut off = SubmitJitter::new(0, 42);
+        assert!(!off.enabled());
+        assert_eq!(off.next_delay(), Duration::ZERO);
+
+        assert_eq!(instance_seed(Some(7), "a", "b"), 7);
+        assert_ne!(instance_seed(None, "bot", "0000aaaa"), instance_seed(None, "bot", "0000bbbb"));
+    }
+}
@@ -1,5 +1,5 @@
-/// Tennis market detection and price buffer adjustments
-/// Uses market cache for efficient token lookups
+//! Tennis market detection and price buffer adjustments
+//! Uses market cache for efficient token lookups
 
 use crate::market_cache;
 
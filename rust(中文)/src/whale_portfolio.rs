@@ -0,0 +1,85 @@
+//! Whale portfolio values for allocation sizing (ALLOCATION_BANKROLL_USD)
+//! The data API's /value endpoint gives each followed whale's total position value. It's
+//! refreshed in the background every WHALE_PORTFOLIO_REFRESH_SECS; sizing reads the last value
+//! and falls back to ratio sizing until one has been fetched
+
+use rustc_hash::FxHashMap;
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+
+#[derive(Default)]
+pub struct PortfolioValues {
+    by_whale: RwLock<FxHashMap<String, f64>>,
+}
+
+impl PortfolioValues {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Last fetched value (USD) of a whale's portfolio, keyed by lowercase 0x address
+    pub fn get(&self, whale: &str) -> Option<f64> {
+        self.by_whale.read().ok()?.get(&whale.to_ascii_lowercase()).copied()
+    }
+
+    pub fn set(&self, whale: &str, value_usd: f64) {
+        if let Ok(mut map) = self.by_whale.write() {
+            map.insert(whale.to_ascii_lowercase(), value_usd);
+        }
+    }
+}
+
+static PORTFOLIO_VALUES: OnceLock<PortfolioValues> = OnceLock::new();
+
+/// Process-wide values (refresh task and order engine)
+pub fn global() -> &'static PortfolioValues {
+    PORTFOLIO_VALUES.get_or_init(PortfolioValues::new)
+}
+
+/// Portfolio value from a data API /value response: `[{"user": "0x..", "value": 1234.5}]`
+pub fn parse_value(body: &str) -> Option<f64> {
+    let v: serde_json::Value = serde_json::from_str(body).ok()?;
+    let entry = v.as_array().and_then(|a| a.first()).unwrap_or(&v);
+    entry["value"].as_f64().filter(|value| value.is_finite() && *value >= 0.0)
+}
+
+/// Fetch every whale's value now, then again every `interval`. Failures keep the last value
+pub fn spawn_refresh(client: reqwest::Client, data_api_base: String, whales: Vec<String>, interval: Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        println!("💼 Whale portfolio values refreshed every {}s ({} whales)", interval.as_secs(), whales.len());
+        let mut tick = tokio::time::interval(interval);
+        loop {
+            tick.tick().await;
+            for whale in &whales {
+                match fetch_value(&client, &data_api_base, whale).await {
+                    Ok(value) => global().set(whale, value),
+                    Err(e) => eprintln!("⚠️ Portfolio value for {} not refreshed: {}", whale, e),
+                }
+            }
+        }
+    })
+}
+
+async fn fetch_value(client: &reqwest::Client, data_api_base: &str, whale: &str) -> anyhow::Result<f64> {
+    let url = format!("{}/value?user={}", data_api_base, whale);
+    let body = client.get(url).timeout(Duration::from_secs(10)).send().await?.error_for_status()?.text().await?;
+    parse_value(&body).ok_or_else(|| anyhow::anyhow!("unexpected response: {}", body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value(r#"[{"user":"0xabc","value":1234.5}]"#), Some(1234.5));
+        assert_eq!(parse_value(r#"{"user":"0xabc","value":10}"#), Some(10.0));
+        assert_eq!(parse_value("[]"), None);
+        assert_eq!(parse_value(r#"[{"value":-1}]"#), None);
+
+        let values = PortfolioValues::new();
+        values.set("0xABC", 500.0);
+        assert_eq!(values.get("0xabc"), Some(500.0));
+        assert_eq!(values.get("0xdef"), None);
+    }
+}
@@ -0,0 +1,268 @@
+//! Per-block console summary (SUMMARY_MODE)
+//! Folds a busy block's ⚡ lines into one line: events, copied, skipped by reason, failed, whale notional
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::status_code::StatusCode;
+
+/// A block is summarized once no event for it has arrived for this long
+pub const SUMMARY_FLUSH_DELAY: Duration = Duration::from_millis(1500);
+
+/// Whether ⚡ lines, block summaries, or both are printed (SUMMARY_MODE env var)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SummaryMode {
+    /// Per-event lines only
+    #[default]
+    Off,
+    /// One summary line per block instead of per-event lines
+    Only,
+    /// Per-event lines plus the block summary
+    Both,
+}
+
+impl SummaryMode {
+    /// Parse "off" / "only" / "both" ("true" / "1" mean only; empty = Off)
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "off" | "false" | "0" => Ok(SummaryMode::Off),
+            "only" | "true" | "1" => Ok(SummaryMode::Only),
+            "both" => Ok(SummaryMode::Both),
+            other => anyhow::bail!("SUMMARY_MODE must be 'off', 'only' or 'both' (found '{}')", other),
+        }
+    }
+
+    #[inline]
+    pub fn per_event_lines(self) -> bool {
+        self != SummaryMode::Only
+    }
+
+    #[inline]
+    pub fn enabled(self) -> bool {
+        self != SummaryMode::Off
+    }
+}
+
+/// Decimals for USD amounts in console lines (USD_DISPLAY_DECIMALS env var)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UsdPrecision {
+    /// Whole dollars from $100 up, cents below (so a $0.37 trade isn't shown as $0)
+    #[default]
+    Auto,
+    /// Always this many decimals (capped at 6)
+    Fixed(u8),
+}
+
+impl UsdPrecision {
+    /// Parse "auto" or a decimal count (empty = Auto)
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        let value = value.trim();
+        if value.is_empty() || value.eq_ignore_ascii_case("auto") {
+            return Ok(UsdPrecision::Auto);
+        }
+        match value.parse::<u8>() {
+            Ok(d) => Ok(UsdPrecision::Fixed(d.min(6))),
+            Err(_) => anyhow::bail!("USD_DISPLAY_DECIMALS must be 'auto' or a number of decimals (found '{}')", value),
+        }
+    }
+
+    pub fn decimals(self, usd: f64) -> usize {
+        match self {
+            UsdPrecision::Auto if usd.abs() >= 100.0 => 0,
+            UsdPrecision::Auto => 2,
+            UsdPrecision::Fixed(d) => d as usize,
+        }
+    }
+
+    /// "$1234" / "$0.37"
+    pub fn format(self, usd: f64) -> String {
+        format!("${:.*}", self.decimals(usd), usd)
+    }
+}
+
+// ============================================================================
+// Aggregation
+// ============================================================================
+
+/// How an order status code counts in the summary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatusClass {
+    /// Submitted and accepted (2xx)
+    Copied,
+    /// Deliberately not copied (SKIPPED_*, CB_*, MOCK_ONLY); carries the reason code
+    Skipped(String),
+    /// Rejected or errored
+    Failed,
+}
+
+pub fn classify_status(code: StatusCode) -> StatusClass {
+    if code == StatusCode::OrderPosted {
+        StatusClass::Copied
+    } else if code.is_skip() {
+        StatusClass::Skipped(code.as_str().to_string())
+    } else {
+        StatusClass::Failed
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BlockSummary {
+    pub block: u64,
+    pub events: u32,
+    /// Events that were executed fills (`*_FILL` order types)
+    pub fills: u32,
+    pub copied: u32,
+    pub failed: u32,
+    /// Reason code -> count
+    pub skipped: BTreeMap<String, u32>,
+    /// Sum of whale USD across the block's events
+    pub whale_usd: f64,
+}
+
+impl BlockSummary {
+    fn record(&mut self, order_type: &str, usd_value: f64, status: StatusCode) {
+        self.events += 1;
+        if order_type.ends_with("_FILL") {
+            self.fills += 1;
+        }
+        self.whale_usd += usd_value;
+        match classify_status(status) {
+            StatusClass::Copied => self.copied += 1,
+            StatusClass::Skipped(reason) => *self.skipped.entry(reason).or_insert(0) += 1,
+            StatusClass::Failed => self.failed += 1,
+        }
+    }
+
+    pub fn skipped_total(&self) -> u32 {
+        self.skipped.values().sum()
+    }
+
+    pub fn render(&self, usd: UsdPrecision) -> String {
+        let mut line = format!(
+            "📦 [B:{}] {} events ({} fills) | copied {} | failed {} | skipped {}",
+            self.block, self.events, self.fills, self.copied, self.failed, self.skipped_total()
+        );
+        if !self.skipped.is_empty() {
+            let reasons: Vec<String> = self.skipped.iter().map(|(r, n)| format!("{}×{}", r, n)).collect();
+            line.push_str(&format!(" ({})", reasons.join(", ")));
+        }
+        line.push_str(&format!(" | {} whale", usd.format(self.whale_usd)));
+        line
+    }
+}
+
+/// Open blocks waiting for their flush delay
+#[derive(Default)]
+pub struct BlockAggregator {
+    open: BTreeMap<u64, (BlockSummary, Instant)>,
+}
+
+impl BlockAggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, block: u64, order_type: &str, usd_value: f64, status: StatusCode, now: Instant) {
+        let (summary, last_seen) = self
+            .open
+            .entry(block)
+            .or_insert_with(|| (BlockSummary { block, ..Default::default() }, now));
+        summary.record(order_type, usd_value, status);
+        *last_seen = now;
+    }
+
+    /// Remove and return blocks quiet for at least `delay`, oldest first
+    pub fn flush(&mut self, now: Instant, delay: Duration) -> Vec<BlockSummary> {
+        let ready: Vec<u64> = self
+            .open
+            .iter()
+            .filter(|(_, (_, last_seen))| now.saturating_duration_since(*last_seen) >= delay)
+            .map(|(block, _)| *block)
+            .collect();
+        ready.into_iter().filter_map(|b| self.open.remove(&b).map(|(s, _)| s)).collect()
+    }
+
+    pub fn pending(&self) -> usize {
+        self.open.len()
+    }
+}
+
+// ============================================================================
+// Global Instance
+// ============================================================================
+
+static GLOBAL_AGGREGATOR: OnceLock<Mutex<BlockAggregator>> = OnceLock::new();
+
+/// Get the global aggregator
+pub fn global() -> &'static Mutex<BlockAggregator> {
+    GLOBAL_AGGREGATOR.get_or_init(|| Mutex::new(BlockAggregator::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_status() {
+        assert_eq!(classify_status(StatusCode::OrderPosted), StatusClass::Copied);
+        assert_eq!(classify_status(StatusCode::SkippedSmall), StatusClass::Skipped("SKIPPED_SMALL".into()));
+        assert_eq!(classify_status(StatusCode::CbBlocked), StatusClass::Skipped("CB_BLOCKED".into()));
+        assert_eq!(classify_status(StatusCode::MockOnly), StatusClass::Skipped("MOCK_ONLY".into()));
+        assert_eq!(classify_status(StatusCode::OrderRejected), StatusClass::Failed);
+        assert_eq!(classify_status(StatusCode::ExecFail), StatusClass::Failed);
+        assert_eq!(classify_status(StatusCode::WorkerTimeout), StatusClass::Failed);
+    }
+
+    #[test]
+    fn test_per_block_aggregation() {
+        let t0 = Instant::now();
+        let mut agg = BlockAggregator::new();
+        agg.record(100, "BUY_FILL", 1000.0, StatusCode::OrderPosted, t0);
+        agg.record(100, "BUY_FILL", 50.0, StatusCode::SkippedSmall, t0);
+        agg.record(100, "SELL", 200.0, StatusCode::SkippedNotFill, t0);
+        agg.record(100, "BUY_FILL", 300.0, StatusCode::SkippedSmall, t0);
+        agg.record(100, "SELL_FILL", 400.0, StatusCode::ExecFail, t0);
+        agg.record(101, "BUY_FILL", 10.0, StatusCode::OrderPosted, t0 + Duration::from_secs(1));
+
+        // Nothing is quiet long enough yet
+        assert!(agg.flush(t0 + Duration::from_millis(500), SUMMARY_FLUSH_DELAY).is_empty());
+
+        // Block 100 is flushed, 101 still open
+        let flushed = agg.flush(t0 + Duration::from_secs(2), SUMMARY_FLUSH_DELAY);
+        assert_eq!(flushed.len(), 1);
+        let s = &flushed[0];
+        assert_eq!((s.block, s.events, s.fills, s.copied, s.failed), (100, 5, 4, 1, 1));
+        assert_eq!(s.skipped.get("SKIPPED_SMALL"), Some(&2));
+        assert_eq!(s.skipped.get("SKIPPED_NOT_FILL"), Some(&1));
+        assert_eq!(s.whale_usd, 1950.0);
+        assert_eq!(
+            s.render(UsdPrecision::Auto),
+            "📦 [B:100] 5 events (4 fills) | copied 1 | failed 1 | skipped 3 (SKIPPED_NOT_FILL×1, SKIPPED_SMALL×2) | $1950 whale"
+        );
+        assert_eq!(agg.pending(), 1);
+    }
+
+    #[test]
+    fn test_usd_precision() {
+        // Small trades stay visible
+        assert_eq!(UsdPrecision::Auto.format(0.37), "$0.37");
+        assert_eq!(UsdPrecision::Auto.format(42.5), "$42.50");
+        assert_eq!(UsdPrecision::Auto.format(1950.4), "$1950");
+        assert_eq!(UsdPrecision::Fixed(2).format(1950.4), "$1950.40");
+        assert_eq!(UsdPrecision::Fixed(0).format(0.37), "$0");
+        assert_eq!(UsdPrecision::parse("3").unwrap(), UsdPrecision::Fixed(3));
+        assert_eq!(UsdPrecision::parse("99").unwrap(), UsdPrecision::Fixed(6));
+        assert_eq!(UsdPrecision::parse("auto").unwrap(), UsdPrecision::Auto);
+        assert!(UsdPrecision::parse("two").is_err());
+    }
+
+    #[test]
+    fn test_summary_mode_parse() {
+        assert_eq!(SummaryMode::parse("").unwrap(), SummaryMode::Off);
+        assert_eq!(SummaryMode::parse("ONLY").unwrap(), SummaryMode::Only);
+        assert_eq!(SummaryMode::parse("both").unwrap(), SummaryMode::Both);
+        assert!(SummaryMode::parse("all").is_err());
+        assert!(!SummaryMode::Only.per_event_lines() && SummaryMode::Both.per_event_lines());
+    }
+}
@@ -0,0 +1,585 @@
+//! CSV trade log formatting
+//! One audit row per handled event (skips included), field sanitizing for the free-text
+//! status_detail column, run identification and shadow sizing columns, and optional
+//! growth counters for the reused row buffers
+
+use rand::Rng;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+use crate::models::{ParsedEvent, ShadowPlan};
+use crate::settings::TierReport;
+use crate::status_code::Status;
+
+pub const CSV_HEADER: &str = "timestamp,block,clob_asset_id,usd_value,shares,price_per_share,direction,order_status,status_detail,best_price,best_size,second_price,second_size,tx_hash,is_live,instance,run_id,shadow_size,shadow_limit,order_id,order_tx,market,price_delta";
+/// CSV_HEADER with `direction` split into `side,is_fill` (CSV_DIRECTION=split)
+pub const CSV_HEADER_SPLIT: &str = "timestamp,block,clob_asset_id,usd_value,shares,price_per_share,side,is_fill,order_status,status_detail,best_price,best_size,second_price,second_size,tx_hash,is_live,instance,run_id,shadow_size,shadow_limit,order_id,order_tx,market,price_delta";
+
+/// Trailing columns appended with CSV_TIER_COLUMNS=true (see push_tier_columns)
+pub const CSV_TIER_HEADER: &str = ",tier,tier_buffer,tier_size_multiplier,tier_action,tier_max_attempts,tier_resubmit_buffer,tier_chase_first";
+
+/// Header for the configured direction layout
+pub fn csv_header(direction: CsvDirection) -> &'static str {
+    match direction {
+        CsvDirection::Combined => CSV_HEADER,
+        CsvDirection::Split => CSV_HEADER_SPLIT,
+    }
+}
+
+// ============================================================================
+// Run Identification
+// ============================================================================
+
+/// Identifies which process produced a row when logs from several instances are merged
+#[derive(Debug, Clone)]
+pub struct RunInfo {
+    /// INSTANCE_LABEL (sanitized; empty if unset)
+    pub instance_label: String,
+    /// Random per-process id, fixed for the lifetime of the run (repeats under RNG_SEED)
+    pub run_id: String,
+}
+
+impl RunInfo {
+    pub fn new(instance_label: &str) -> Self {
+        let mut label = String::new();
+        sanitize_csv(instance_label.trim(), CsvQuoting::Replace, &mut label);
+        Self {
+            instance_label: label,
+            run_id: format!("{:08x}", crate::rng::with_rng(|rng| rng.r#gen::<u32>())),
+        }
+    }
+}
+
+static RUN_INFO: OnceLock<RunInfo> = OnceLock::new();
+
+/// Set the instance label (call once at startup, before the first row is written)
+pub fn init_run_info(instance_label: &str) -> &'static RunInfo {
+    RUN_INFO.get_or_init(|| RunInfo::new(instance_label))
+}
+
+/// Get this process's run info (unlabelled if init_run_info was never called)
+#[inline]
+pub fn run_info() -> &'static RunInfo {
+    RUN_INFO.get_or_init(|| RunInfo::new(""))
+}
+
+/// Append the trailing `,instance,run_id` columns to a row
+#[inline]
+pub fn push_run_columns(row: &mut String) {
+    let info = run_info();
+    let _ = write!(row, ",{},{}", info.instance_label, info.run_id);
+}
+
+/// Append the trailing `,shadow_size,shadow_limit` columns (empty when shadow sizing is off)
+#[inline]
+pub fn push_shadow_columns(row: &mut String, shadow: Option<&ShadowPlan>) {
+    match shadow {
+        Some(s) => {
+            let _ = write!(row, ",{:.6},{:.4}", s.shares, s.limit_price);
+        }
+        None => row.push_str(",,"),
+    }
+}
+
+/// Append the CSV_TIER_HEADER columns for `tier`
+#[inline]
+pub fn push_tier_columns(row: &mut String, tier: &TierReport) {
+    let _ = write!(
+        row,
+        ",{},{:.4},{},{},{},{:.4},{}",
+        tier.label, tier.buffer, tier.size_multiplier, tier.order_action, tier.max_attempts, tier.resubmit_buffer, tier.chase_first,
+    );
+}
+
+// ============================================================================
+// Audit Row
+// ============================================================================
+
+/// Everything logged for one handled event. Every event gets a row, whatever its outcome,
+/// so skips carry their reason: the code in order_status, any detail in status_detail
+#[derive(Debug, Clone, Copy)]
+pub struct AuditRow<'a> {
+    pub evt: &'a ParsedEvent,
+    pub status: &'a Status,
+    /// best_price, best_size, second_price, second_size ("N/A" if the book fetch failed)
+    pub book: [&'a str; 4],
+    pub is_live: bool,
+    pub shadow: Option<&'a ShadowPlan>,
+    pub order_id: &'a str,
+    pub order_tx: &'a str,
+    /// Short market label (None for unknown markets; it would just repeat clob_asset_id)
+    pub market: Option<&'a str>,
+    /// Our fill price vs the whale's (positive = better; None if nothing filled)
+    pub price_delta: Option<f64>,
+    /// Tier outputs, appended as the CSV_TIER_HEADER columns (None = lean row)
+    pub tier: Option<&'a TierReport>,
+}
+
+/// Format `rec` as a csv_header(direction) row (plus CSV_TIER_HEADER if it has a tier) into `row`. `scratch` is reused for sanitizing.
+/// With buffer stats on, a write that reallocates either buffer is counted
+pub fn write_audit_row(
+    row: &mut String,
+    scratch: &mut String,
+    timestamp: &str,
+    rec: &AuditRow,
+    quoting: CsvQuoting,
+    direction: CsvDirection,
+) {
+    let evt = rec.evt;
+    let [bp, bs, sp, ss] = rec.book;
+    let capacities = (row.capacity(), scratch.capacity());
+    sanitize_csv(&rec.status.message, quoting, scratch);
+    row.clear();
+    let _ = write!(row,
+        "{},{},{},{:.4},{:.6},{:.4},",
+        timestamp,
+        evt.block_number, evt.order.clob_token_id, evt.order.usd_value,
+        evt.order.shares, evt.order.price_per_share,
+    );
+    push_direction_columns(row, &evt.order.order_type, direction);
+    let _ = write!(row, ",{},{},{},{},{},{},{},{}", rec.status.code, scratch, bp, bs, sp, ss, evt.tx_hash, rec.is_live);
+    push_run_columns(row);
+    push_shadow_columns(row, rec.shadow);
+    let _ = write!(row, ",{},{},", rec.order_id, rec.order_tx);
+    if let Some(market) = rec.market {
+        sanitize_csv(market, quoting, scratch);
+        row.push_str(scratch);
+    }
+    row.push(',');
+    if let Some(delta) = rec.price_delta {
+        let _ = write!(row, "{:+.4}", delta);
+    }
+    if let Some(tier) = rec.tier {
+        push_tier_columns(row, tier);
+    }
+    if BUFFER_STATS_ENABLED.load(Ordering::Relaxed) {
+        record_buffer_growth(capacities, (row.capacity(), scratch.capacity()));
+    }
+}
+
+// ============================================================================
+// Buffer Growth
+// ============================================================================
+
+/// The per-thread row / sanitize buffers are sized with with_capacity and reused; these count
+/// the writes that had to grow them, for tuning the initial sizes (CSV_BUFFER_STATS=true)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BufferGrowth {
+    pub row_growths: u64,
+    pub scratch_growths: u64,
+    /// Largest capacity seen after a growth
+    pub row_capacity: usize,
+    pub scratch_capacity: usize,
+}
+
+static BUFFER_STATS_ENABLED: AtomicBool = AtomicBool::new(false);
+static ROW_GROWTHS: AtomicU64 = AtomicU64::new(0);
+static SCRATCH_GROWTHS: AtomicU64 = AtomicU64::new(0);
+static ROW_CAPACITY: AtomicU64 = AtomicU64::new(0);
+static SCRATCH_CAPACITY: AtomicU64 = AtomicU64::new(0);
+
+/// Start counting buffer growth (off by default: two capacity reads per row)
+pub fn enable_buffer_stats() {
+    BUFFER_STATS_ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn buffer_growth() -> BufferGrowth {
+    BufferGrowth {
+        row_growths: ROW_GROWTHS.load(Ordering::Relaxed),
+        scratch_growths: SCRATCH_GROWTHS.load(Ordering::Relaxed),
+        row_capacity: ROW_CAPACITY.load(Ordering::Relaxed) as usize,
+        scratch_capacity: SCRATCH_CAPACITY.load(Ordering::Relaxed) as usize,
+    }
+}
+
+fn record_buffer_growth(before: (usize, usize), after: (usize, usize)) {
+    if after.0 > before.0 {
+        ROW_GROWTHS.fetch_add(1, Ordering::Relaxed);
+        ROW_CAPACITY.fetch_max(after.0 as u64, Ordering::Relaxed);
+    }
+    if after.1 > before.1 {
+        SCRATCH_GROWTHS.fetch_add(1, Ordering::Relaxed);
+        SCRATCH_CAPACITY.fetch_max(after.1 as u64, Ordering::Relaxed);
+    }
+}
+
+// ============================================================================
+// Direction Columns
+// ============================================================================
+
+/// How the order type is logged (CSV_DIRECTION env var)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CsvDirection {
+    /// One `direction` column: BUY_FILL, SELL, ... (compat: the original layout)
+    #[default]
+    Combined,
+    /// `side` (BUY/SELL) and `is_fill` (true/false) columns
+    Split,
+}
+
+impl CsvDirection {
+    /// Parse "split" / "combined" (empty = Combined)
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "combined" => Ok(CsvDirection::Combined),
+            "split" => Ok(CsvDirection::Split),
+            other => anyhow::bail!("CSV_DIRECTION must be 'combined' or 'split' (found '{}')", other),
+        }
+    }
+}
+
+/// Append the direction column(s) for an order type like "BUY_FILL"
+#[inline]
+pub fn push_direction_columns(row: &mut String, order_type: &str, direction: CsvDirection) {
+    match direction {
+        CsvDirection::Combined => row.push_str(order_type),
+        CsvDirection::Split => {
+            let (side, is_fill) = match order_type.strip_suffix("_FILL") {
+                Some(side) => (side, true),
+                None => (order_type, false),
+            };
+            let _ = write!(row, "{},{}", side, is_fill);
+        }
+    }
+}
+
+// ============================================================================
+// Quoting Mode
+// ============================================================================
+
+/// How free-text fields are made CSV-safe (CSV_QUOTING env var)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CsvQuoting {
+    /// Replace commas with ';' and newlines with ' ' (fast, lossy)
+    #[default]
+    Replace,
+    /// RFC 4180: wrap in double quotes and double internal quotes
+    Strict,
+}
+
+impl CsvQuoting {
+    /// Parse "strict" / "replace" (empty = Replace)
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "replace" => Ok(CsvQuoting::Replace),
+            "strict" => Ok(CsvQuoting::Strict),
+            other => anyhow::bail!("CSV_QUOTING must be 'replace' or 'strict' (found '{}')", other),
+        }
+    }
+}
+
+// ============================================================================
+// Sanitizing
+// ============================================================================
+
+/// Write a CSV-safe copy of `value` into `out` using the given mode
+#[inline]
+pub fn sanitize_csv(value: &str, mode: CsvQuoting, out: &mut String) {
+    out.clear();
+    match mode {
+        CsvQuoting::Replace => {
+            if !value.bytes().any(|b| b == b',' || b == b'\n' || b == b'\r') {
+                out.push_str(value);
+                return;
+            }
+            out.reserve(value.len());
+            for c in value.chars() {
+                out.push(match c { ',' => ';', '\n' | '\r' => ' ', _ => c });
+            }
+        }
+        CsvQuoting::Strict => {
+            if !value.bytes().any(|b| matches!(b, b',' | b'"' | b'\n' | b'\r')) {
+                out.push_str(value);
+                return;
+            }
+            out.reserve(value.len() + 2);
+            out.push('"');
+            for c in value.chars() {
+                if c == '"' {
+                    out.push('"');
+                }
+                out.push(c);
+            }
+            out.push('"');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::status_code::StatusCode;
+
+    const NASTY: &str = "400 Bad Request | {\"error\":\"not enough balance, allowance\"}\r\nretry";
+
+    #[test]
+    fn test_replace_mode() {
+        let mut out = String::new();
+        sanitize_csv(NASTY, CsvQuoting::Replace, &mut out);
+        assert_eq!(out, "400 Bad Request | {\"error\":\"not enough balance; allowance\"}  retry");
+        assert!(!out.contains(',') && !out.contains('\n'));
+
+        sanitize_csv("200 OK [SCALED]", CsvQuoting::Replace, &mut out);
+        assert_eq!(out, "200 OK [SCALED]");
+    }
+
+    #[test]
+    fn test_strict_mode() {
+        let mut out = String::new();
+        sanitize_csv(NASTY, CsvQuoting::Strict, &mut out);
+        assert_eq!(
+            out,
+            "\"400 Bad Request | {\"\"error\"\":\"\"not enough balance, allowance\"\"}\r\nretry\""
+        );
+
+        // Clean values are left unquoted
+        sanitize_csv("200 OK [SCALED]", CsvQuoting::Strict, &mut out);
+        assert_eq!(out, "200 OK [SCALED]");
+
+        // A lone quote forces quoting
+        sanitize_csv("say \"hi\"", CsvQuoting::Strict, &mut out);
+        assert_eq!(out, "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_run_id_stable_and_in_rows() {
+        let info = run_info();
+        assert_eq!(info.run_id.len(), 8);
+        assert_eq!(run_info().run_id, info.run_id);
+        assert_eq!(init_run_info("ignored-after-first").run_id, info.run_id);
+
+        let mut row = String::from("2024-01-01 00:00:00.000,1,tok");
+        push_run_columns(&mut row);
+        assert!(row.ends_with(&format!(",{},{}", info.instance_label, info.run_id)));
+        assert_eq!(row.split(',').count(), 5);
+        assert_eq!(CSV_HEADER.split(',').count(), 23);
+
+        // Labels can't break the row
+        assert_eq!(RunInfo::new(" box-a,eu\n").instance_label, "box-a;eu");
+    }
+
+    #[test]
+    fn test_guard_skip_still_audited() {
+        use crate::engine::{plan_order, DecisionTrace};
+        use crate::models::{FrameTransport, OrderInfo};
+        use crate::risk_guard::{RiskGuard, RiskGuardConfig};
+        use crate::settings::Config;
+
+        // A trade the min-whale-shares guard rejects before any order is built
+        let evt = ParsedEvent {
+            block_number: 77,
+            tx_hash: "0xskip".into(),
+            whale_address: "0xwhale".into(),
+            counterparty: String::new(),
+            order: OrderInfo {
+                order_type: "BUY_FILL".into(),
+                clob_token_id: "tok".into(),
+                usd_value: 2.5,
+                shares: 5.0,
+                price_per_share: 0.50,
+            },
+            transport: FrameTransport::Text,
+        };
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+        let status = plan_order(&evt, &mut guard, &Config::default().plan_params(), |_, _| panic!("no book"), &mut DecisionTrace::disabled()).unwrap_err();
+        assert_eq!(status.code, StatusCode::SkippedSmall);
+
+        let rec = AuditRow {
+            evt: &evt,
+            status: &status,
+            book: ["N/A"; 4],
+            is_live: false,
+            shadow: None,
+            order_id: "",
+            order_tx: "",
+            market: Some("Will it rain, today? [Yes]"),
+            price_delta: None,
+            tier: None,
+        };
+        let (mut row, mut scratch) = (String::new(), String::new());
+        write_audit_row(&mut row, &mut scratch, "2024-01-01 00:00:00.000", &rec, CsvQuoting::Replace, CsvDirection::Combined);
+
+        let cols: Vec<&str> = row.split(',').collect();
+        let header: Vec<&str> = CSV_HEADER.split(',').collect();
+        assert_eq!(cols.len(), header.len());
+        let col = |name: &str| cols[header.iter().position(|h| *h == name).unwrap()];
+        // The reason code, its detail, and no order id
+        assert_eq!(col("order_status"), "SKIPPED_SMALL");
+        assert_eq!(col("status_detail"), status.message);
+        assert_eq!(col("block"), "77");
+        assert_eq!(col("tx_hash"), "0xskip");
+        assert_eq!(col("order_id"), "");
+        assert_eq!(col("market"), "Will it rain; today? [Yes]");
+        assert_eq!(col("price_delta"), "");
+    }
+
+    #[test]
+    fn test_long_status_counts_buffer_growth() {
+        use crate::models::{FrameTransport, OrderInfo};
+
+        enable_buffer_stats();
+        let evt = ParsedEvent {
+            block_number: 1,
+            tx_hash: "0xtx".into(),
+            whale_address: "0xwhale".into(),
+            counterparty: String::new(),
+            order: OrderInfo {
+                order_type: "BUY_FILL".into(),
+                clob_token_id: "tok".into(),
+                usd_value: 2.5,
+                shares: 5.0,
+                price_per_share: 0.50,
+            },
+            transport: FrameTransport::Text,
+        };
+        // Sized like the main binary's thread-local buffers
+        let (mut row, mut scratch) = (String::with_capacity(512), String::with_capacity(128));
+        let rec = |status| AuditRow {
+            evt: &evt,
+            status,
+            book: ["N/A"; 4],
+            is_live: false,
+            shadow: None,
+            order_id: "",
+            order_tx: "",
+            market: None,
+            price_delta: None,
+            tier: None,
+        };
+
+        // Other tests write rows concurrently, so only look at the increase
+        let before = buffer_growth();
+        let long_status = Status::with_message(StatusCode::OrderRejected, format!("400 Bad Request | {}", "x".repeat(4096)));
+        write_audit_row(&mut row, &mut scratch, "2024-01-01 00:00:00.000", &rec(&long_status), CsvQuoting::Replace, CsvDirection::Combined);
+        let after = buffer_growth();
+        assert!(after.row_growths > before.row_growths);
+        assert!(after.scratch_growths > before.scratch_growths);
+        assert!(after.row_capacity >= row.len() && after.scratch_capacity >= long_status.message.len());
+    }
+
+    #[test]
+    fn test_split_direction_columns() {
+        use crate::models::{FrameTransport, OrderInfo};
+
+        let evt = ParsedEvent {
+            block_number: 9,
+            tx_hash: "0xtx".into(),
+            whale_address: "0xwhale".into(),
+            counterparty: String::new(),
+            order: OrderInfo {
+                order_type: "SELL_FILL".into(),
+                clob_token_id: "tok".into(),
+                usd_value: 5.0,
+                shares: 10.0,
+                price_per_share: 0.50,
+            },
+            transport: FrameTransport::Text,
+        };
+        let posted = Status::with_message(StatusCode::OrderPosted, "200 OK");
+        let rec = AuditRow {
+            evt: &evt,
+            status: &posted,
+            book: ["N/A"; 4],
+            is_live: true,
+            shadow: None,
+            order_id: "0xorder",
+            order_tx: "",
+            market: None,
+            price_delta: Some(-0.005),
+            tier: None,
+        };
+        let (mut row, mut scratch) = (String::new(), String::new());
+        write_audit_row(&mut row, &mut scratch, "2024-01-01 00:00:00.000", &rec, CsvQuoting::Replace, CsvDirection::Split);
+
+        let cols: Vec<&str> = row.split(',').collect();
+        let header: Vec<&str> = csv_header(CsvDirection::Split).split(',').collect();
+        assert_eq!(cols.len(), header.len());
+        let col = |name: &str| cols[header.iter().position(|h| *h == name).unwrap()];
+        assert_eq!((col("side"), col("is_fill")), ("SELL", "true"));
+        assert!(!header.contains(&"direction"));
+        assert_eq!((col("order_status"), col("status_detail")), ("ORDER_POSTED", "200 OK"));
+        assert_eq!(col("order_id"), "0xorder");
+        assert_eq!(col("price_delta"), "-0.0050");
+
+        // Placements aren't fills; combined keeps the original column
+        let mut cell = String::new();
+        push_direction_columns(&mut cell, "BUY", CsvDirection::Split);
+        assert_eq!(cell, "BUY,false");
+        write_audit_row(&mut row, &mut scratch, "2024-01-01 00:00:00.000", &rec, CsvQuoting::Replace, CsvDirection::Combined);
+        assert_eq!(row.split(',').nth(6), Some("SELL_FILL"));
+        assert_eq!(row.split(',').count(), CSV_HEADER.split(',').count());
+        assert_eq!(CsvDirection::parse("SPLIT").unwrap(), CsvDirection::Split);
+        assert_eq!(CsvDirection::parse("").unwrap(), CsvDirection::Combined);
+        assert!(CsvDirection::parse("sides").is_err());
+    }
+
+    #[test]
+    fn test_tier_columns() {
+        use crate::models::{FrameTransport, OrderInfo};
+        use crate::settings::{get_tier_params, tier_report};
+
+        let evt = ParsedEvent {
+            block_number: 5,
+            tx_hash: "0xtier".into(),
+            whale_address: "0xwhale".into(),
+            counterparty: String::new(),
+            order: OrderInfo {
+                order_type: "BUY_FILL".into(),
+                clob_token_id: "tok".into(),
+                usd_value: 2500.0,
+                shares: 5000.0,
+                price_per_share: 0.50,
+            },
+            transport: FrameTransport::Text,
+        };
+        let tier = tier_report(5000.0, 0.50, true, "tok");
+        let (buffer, action, multiplier) = get_tier_params(5000.0, true, "tok");
+        assert_eq!((tier.label.as_str(), tier.buffer, tier.order_action, tier.size_multiplier), ("4000+", buffer, action, multiplier));
+
+        let posted = Status::with_message(StatusCode::OrderPosted, "200 OK");
+        let mut rec = AuditRow {
+            evt: &evt,
+            status: &posted,
+            book: ["N/A"; 4],
+            is_live: false,
+            shadow: None,
+            order_id: "",
+            order_tx: "",
+            market: None,
+            price_delta: None,
+            tier: Some(&tier),
+        };
+        let (mut row, mut scratch) = (String::new(), String::new());
+        write_audit_row(&mut row, &mut scratch, "2024-01-01 00:00:00.000", &rec, CsvQuoting::Replace, CsvDirection::Combined);
+
+        let header = format!("{}{}", CSV_HEADER, CSV_TIER_HEADER);
+        let header: Vec<&str> = header.split(',').collect();
+        let cols: Vec<&str> = row.split(',').collect();
+        assert_eq!(cols.len(), header.len());
+        let col = |name: &str| cols[header.iter().position(|h| *h == name).unwrap()];
+        assert_eq!(col("tier"), "4000+");
+        assert_eq!(col("tier_buffer"), format!("{:.4}", buffer));
+        assert_eq!(col("tier_size_multiplier"), multiplier.to_string());
+        assert_eq!(col("tier_action"), action);
+        assert_eq!(col("tier_max_attempts"), "5");
+        assert_eq!(col("tier_resubmit_buffer"), "0.0100");
+        assert_eq!(col("tier_chase_first"), "true");
+
+        // Lean by default; small trades and sells are labelled too
+        rec.tier = None;
+        write_audit_row(&mut row, &mut scratch, "2024-01-01 00:00:00.000", &rec, CsvQuoting::Replace, CsvDirection::Combined);
+        assert_eq!(row.split(',').count(), CSV_HEADER.split(',').count());
+        assert_eq!(tier_report(10.0, 0.50, true, "tok").label, "base");
+        assert!(tier_report(10.0, 0.50, false, "tok").label.starts_with("sell "));
+    }
+
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!(CsvQuoting::parse("strict").unwrap(), CsvQuoting::Strict);
+        assert_eq!(CsvQuoting::parse(" STRICT ").unwrap(), CsvQuoting::Strict);
+        assert_eq!(CsvQuoting::parse("replace").unwrap(), CsvQuoting::Replace);
+        assert_eq!(CsvQuoting::parse("").unwrap(), CsvQuoting::Replace);
+        assert!(CsvQuoting::parse("rfc4180").is_err());
+    }
+}
@@ -0,0 +1,2186 @@
+//! Order decision engine
+//! Tier selection, risk guard checks and sizing for a parsed whale event, with an opt-in decision trace
+//! and an offline simulation entry point (simulate_event)
+
+use rustc_hash::FxHashMap;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::models::{ParsedEvent, ShadowPlan, SizeType};
+use crate::risk_guard::{calc_liquidity_depth, BookLevel, RiskGuard, SafetyDecision, TradeSide};
+use crate::settings::*;
+use crate::status_code::{Status, StatusCode};
+
+// ============================================================================
+// Order Plan
+// ============================================================================
+
+/// What would be submitted for an event that passed every check
+#[derive(Debug, Clone)]
+pub struct OrderPlan {
+    pub side_is_buy: bool,
+    pub limit_price: f64,
+    /// Unrounded size in shares
+    pub shares: f64,
+    pub size_type: SizeType,
+    pub order_action: &'static str,
+    /// shares * limit_price
+    pub notional: f64,
+    /// Rests at the whale's price (COPY_MODE=maker) instead of taking
+    pub maker: bool,
+}
+
+/// Settings plan_order decides with, so a simulation can run under a config other than the
+/// process environment (see Config::plan_params)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlanParams {
+    /// ACT_ON_FILLS_ONLY
+    pub act_on_fills_only: bool,
+    /// FOLLOW_SIDES
+    pub follow_sides: FollowSides,
+    /// COPY_MODE
+    pub copy_mode: CopyMode,
+    /// LIVE_SIZING
+    pub sizing: SizingParams,
+    /// TIER_SHARES_ROUNDING, applied before the tier lookup
+    pub tier_shares_rounding: f64,
+}
+
+/// Run fill, skip, tier, risk guard and sizing checks for an event.
+/// `fetch_depth(side, limit_price)` is only called when the guard asks for the book.
+/// Returns the plan, or the status explaining why the trade was skipped.
+pub fn plan_order<F>(
+    evt: &ParsedEvent,
+    guard: &mut RiskGuard,
+    params: &PlanParams,
+    fetch_depth: F,
+    trace: &mut DecisionTrace,
+) -> Result<OrderPlan, Status>
+where
+    F: FnOnce(TradeSide, f64) -> Result<f64, &'static str>,
+{
+    plan_order_with_shadow(evt, guard, params, fetch_depth, trace, None).0
+}
+
+/// plan_order, also sizing the event under a shadow config once it passes the risk guard.
+/// The shadow shares the live tier, conviction and probability roll; it ignores the daily cap
+/// and is returned even when the live size rounds to a skip
+pub fn plan_order_with_shadow<F>(
+    evt: &ParsedEvent,
+    guard: &mut RiskGuard,
+    params: &PlanParams,
+    fetch_depth: F,
+    trace: &mut DecisionTrace,
+    shadow: Option<&ShadowSizing>,
+) -> (Result<OrderPlan, Status>, Option<ShadowPlan>)
+where
+    F: FnOnce(TradeSide, f64) -> Result<f64, &'static str>,
+{
+    let mut shadow_plan = None;
+    let result = plan_inner(evt, guard, params, fetch_depth, trace, shadow, &mut shadow_plan);
+    (result, shadow_plan)
+}
+
+fn plan_inner<F>(
+    evt: &ParsedEvent,
+    guard: &mut RiskGuard,
+    params: &PlanParams,
+    fetch_depth: F,
+    trace: &mut DecisionTrace,
+    shadow: Option<&ShadowSizing>,
+    shadow_plan: &mut Option<ShadowPlan>,
+) -> Result<OrderPlan, Status>
+where
+    F: FnOnce(TradeSide, f64) -> Result<f64, &'static str>,
+{
+    let info = &evt.order;
+    let side_is_buy = info.order_type.starts_with("BUY");
+    let side = if side_is_buy { TradeSide::Buy } else { TradeSide::Sell };
+    let whale_shares = info.shares;
+    let whale_price = info.price_per_share;
+
+    // Order placements aren't executed trades - copying them chases phantom liquidity
+    if should_skip_non_fill(&info.order_type, params.act_on_fills_only) {
+        return Err(trace.reject("fills_only", StatusCode::SkippedNotFill.into()));
+    }
+    trace.pass("fills_only");
+
+    // Entries-only / exits-only strategies
+    if !params.follow_sides.allows(side_is_buy) {
+        return Err(trace.reject("side_filter", StatusCode::SkippedSideFilter.into()));
+    }
+    trace.pass("side_filter");
+
+    // Wash trades (IGNORED_MAKERS): not whale activity, so they don't count as an entry either
+    if crate::market_cache::is_ignored_maker(&evt.whale_address) || crate::market_cache::is_ignored_maker(&evt.counterparty) {
+        return Err(trace.reject("ignored_maker", StatusCode::SkippedIgnoredMaker.into()));
+    }
+    trace.pass("ignored_maker");
+
+    // Conviction: every whale fill extends or ends its (whale, token) buy streak,
+    // even if this one is skipped below. Sizing leans in; the daily cap still applies
+    let conviction = guard.observe_conviction(&evt.whale_address, &info.clob_token_id, side_is_buy);
+
+    // Entries only (WHALE_ENTRY_WINDOW_SECS): the whale's later BUYs on the token are adds
+    if guard.observe_whale_entry(&evt.whale_address, &info.clob_token_id, side_is_buy) {
+        return Err(trace.reject("whale_entry", StatusCode::SkippedWhaleAdd.into()));
+    }
+    trace.pass("whale_entry");
+
+    // Markets excluded in MARKET_OVERRIDES
+    let market_override = crate::market_cache::get_market_override(&info.clob_token_id);
+    if market_override.is_some_and(|o| o.skip) {
+        return Err(trace.reject("market_override", StatusCode::SkippedMarketOverride.into()));
+    }
+    trace.pass("market_override");
+
+    // Skip small trades - negative expected value after costs
+    if should_skip_trade(whale_shares) {
+        return Err(trace.reject("min_whale_shares", Status::with_message(StatusCode::SkippedSmall, format!("<{:.0} shares", MIN_WHALE_SHARES_TO_COPY))));
+    }
+    trace.pass("min_whale_shares");
+
+    if guard.whale_on_cooldown(&evt.whale_address) {
+        return Err(trace.reject("whale_cooldown", StatusCode::SkippedWhaleCooldown.into()));
+    }
+    trace.pass("whale_cooldown");
+
+    // Per-market token bucket (MARKET_RATE_BURST / MARKET_RATE_PER_MIN)
+    if !guard.market_rate_allows(&info.clob_token_id) {
+        return Err(trace.reject("market_rate", StatusCode::SkippedRateLimit.into()));
+    }
+    trace.pass("market_rate");
+
+    let (buffer, order_action, size_multiplier) =
+        get_tier_params_with(whale_shares, side_is_buy, &info.clob_token_id, params.tier_shares_rounding);
+    // Maker mode rests at the whale's price; the book check keeps it from crossing
+    let (buffer, order_action) = match params.copy_mode {
+        CopyMode::Taker => (buffer, order_action),
+        CopyMode::Maker => (0.0, MAKER_ORDER_ACTION),
+    };
+
+    // Never pay more than the whale + the configured premium
+    let premium_cap = if side_is_buy { whale_price_cap(whale_price) } else { None };
+    let tick = crate::market_cache::tick_size(&info.clob_token_id);
+    let (limit_price, capped) = limit_for(whale_price, buffer, side_is_buy, premium_cap, tick);
+    if capped {
+        trace.verdict("whale_premium", "CAPPED");
+    } else {
+        trace.pass("whale_premium");
+    }
+    let tier_min_shares = find_side_execution_tier_with(whale_shares, side_is_buy, params.tier_shares_rounding)
+        .filter(|_| side_is_buy)
+        .map(|t| t.min_shares);
+    trace.tier(tier_min_shares, buffer, order_action, size_multiplier, limit_price);
+
+    // Risk guard check
+    let eval = guard.check_fast(&info.clob_token_id, whale_shares);
+    match eval.decision {
+        SafetyDecision::Block => {
+            return Err(trace.reject("risk_guard", Status::with_message(StatusCode::CbBlocked, eval.reason.as_str())));
+        }
+        SafetyDecision::FetchBook => match fetch_depth(side, limit_price) {
+            Ok(depth) => {
+                trace.book_depth_usd = Some(depth);
+                let final_eval = guard.check_with_book(&info.clob_token_id, eval.consecutive_large, depth);
+                if final_eval.decision == SafetyDecision::Block {
+                    return Err(trace.reject("risk_guard", Status::with_message(StatusCode::CbBlocked, final_eval.reason.as_str())));
+                }
+                trace.verdict("risk_guard", final_eval.reason.as_str());
+            }
+            Err(e) => {
+                guard.trip(&info.clob_token_id);
+                return Err(trace.reject("risk_guard", Status::with_message(StatusCode::CbBookFail, e)));
+            }
+        },
+        SafetyDecision::Allow => trace.verdict("risk_guard", eval.reason.as_str()),
+    }
+
+    // One roll for both configs so probabilistic sizing compares like for like
+    let roll = crate::rng::roll();
+    let portfolio = crate::whale_portfolio::global().get(&evt.whale_address);
+    if let Some(sh) = shadow {
+        let (shadow_limit, _) = limit_for(whale_price, buffer + sh.buffer_offset, side_is_buy, premium_cap, tick);
+        let (shares, _) = size_copy(&sh.sizing, whale_shares, whale_price, portfolio, shadow_limit, size_multiplier * conviction, roll);
+        *shadow_plan = Some(ShadowPlan { shares, limit_price: shadow_limit });
+    }
+
+    let market_min = market_override.and_then(|o| o.min_shares);
+    let sizing = market_sizing(&params.sizing, market_min);
+    let (my_shares, size_type) = size_copy(&sizing, whale_shares, whale_price, portfolio, limit_price, size_multiplier * conviction, roll);
+    trace.size(my_shares, &size_type);
+    if my_shares == 0.0 {
+        return Err(trace.reject("sizing", Status::with_message(StatusCode::SkippedProbability, size_type.to_string())));
+    }
+
+    // Dust in a market that needs a bigger size to be worth it
+    if market_min.is_some_and(|min| my_shares < min) {
+        return Err(trace.reject(
+            "market_min",
+            Status::with_message(StatusCode::SkippedBelowMarketMin, format!("<{} shares", market_min.unwrap_or_default())),
+        ));
+    }
+    trace.pass("market_min");
+
+    let notional = my_shares * limit_price;
+    check_daily_notional(guard, side, notional, "daily_notional", trace)?;
+
+    Ok(OrderPlan { side_is_buy, limit_price, shares: my_shares, size_type, order_action, notional, maker: params.copy_mode == CopyMode::Maker })
+}
+
+/// Which whale sides are copied (FOLLOW_SIDES env var)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FollowSides {
+    #[default]
+    Both,
+    /// Entries only
+    Buy,
+    /// Exits only
+    Sell,
+}
+
+impl FollowSides {
+    /// Parse "both" / "buy" / "sell" (empty = Both)
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "both" => Ok(FollowSides::Both),
+            "buy" => Ok(FollowSides::Buy),
+            "sell" => Ok(FollowSides::Sell),
+            other => anyhow::bail!("FOLLOW_SIDES must be 'both', 'buy' or 'sell' (found '{}')", other),
+        }
+    }
+
+    pub fn allows(self, side_is_buy: bool) -> bool {
+        match self {
+            FollowSides::Both => true,
+            FollowSides::Buy => side_is_buy,
+            FollowSides::Sell => !side_is_buy,
+        }
+    }
+}
+
+/// How copies are placed (COPY_MODE env var)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CopyMode {
+    /// Take liquidity: tier buffer and order type (FAK by default), resubmits chase the price
+    #[default]
+    Taker,
+    /// Provide liquidity: rest a GTD at the whale's price without crossing, repriced toward
+    /// the touch while it sits unfilled
+    Maker,
+}
+
+impl CopyMode {
+    /// Parse "taker" / "maker" (empty = Taker)
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "taker" => Ok(CopyMode::Taker),
+            "maker" => Ok(CopyMode::Maker),
+            other => anyhow::bail!("COPY_MODE must be 'taker' or 'maker' (found '{}')", other),
+        }
+    }
+}
+
+/// Order type every maker-mode copy rests as
+pub const MAKER_ORDER_ACTION: &str = "GTD";
+
+/// Maker-mode limit: the whale's price, pulled back to one tick inside the opposite touch
+/// when it would cross (a buy at or above the best ask, a sell at or below the best bid)
+pub fn maker_limit(whale_price: f64, best_bid: Option<f64>, best_ask: Option<f64>, side_is_buy: bool, tick: f64) -> f64 {
+    let price = snap_to_tick(whale_price, tick);
+    if side_is_buy {
+        best_ask.map_or(price, |ask| price.min(floor_to_tick(ask - tick, tick)))
+    } else {
+        best_bid.map_or(price, |bid| price.max(ceil_to_tick(bid + tick, tick)))
+    }
+}
+
+/// Maker mode pre-submit check: move the plan's limit to maker_limit (shares kept, notional
+/// follows). The limit only ever gets less aggressive, so the premium cap still holds
+pub fn maker_touch(plan: &mut OrderPlan, best_bid: Option<f64>, best_ask: Option<f64>, tick: f64, trace: &mut DecisionTrace) {
+    let limit = maker_limit(plan.limit_price, best_bid, best_ask, plan.side_is_buy, tick);
+    if (limit - plan.limit_price).abs() > 1e-9 {
+        plan.limit_price = limit;
+        plan.notional = plan.shares * limit;
+        trace.verdict("maker_touch", "PULLED_BACK");
+    } else {
+        trace.pass("maker_touch");
+    }
+}
+
+/// Spread between the best bid and ask in ticks (None when either side is empty)
+pub fn spread_ticks(best_bid: Option<f64>, best_ask: Option<f64>, tick: f64) -> Option<f64> {
+    Some(((best_ask? - best_bid?) / tick).round())
+}
+
+/// Maker mode pre-submit check: keep resting only when the spread is at least `min_ticks`
+/// wide (MAKER_MIN_SPREAD_TICKS, 0 = always). Otherwise there's no spread to capture, so the
+/// plan switches to the tier's taker buffer and order type (shares kept, notional follows).
+/// A one-sided book counts as wide. Returns whether the plan still rests
+#[allow(clippy::too_many_arguments)]
+pub fn maker_spread_check(
+    plan: &mut OrderPlan,
+    evt: &ParsedEvent,
+    params: &PlanParams,
+    best_bid: Option<f64>,
+    best_ask: Option<f64>,
+    tick: f64,
+    min_ticks: u32,
+    trace: &mut DecisionTrace,
+) -> bool {
+    if min_ticks == 0 || spread_ticks(best_bid, best_ask, tick).is_none_or(|t| t >= min_ticks as f64) {
+        trace.pass("maker_spread");
+        return true;
+    }
+    let info = &evt.order;
+    let (buffer, order_action, _) =
+        get_tier_params_with(info.shares, plan.side_is_buy, &info.clob_token_id, params.tier_shares_rounding);
+    let premium_cap = if plan.side_is_buy { whale_price_cap(info.price_per_share) } else { None };
+    let (limit_price, _) = limit_for(info.price_per_share, buffer, plan.side_is_buy, premium_cap, tick);
+    plan.limit_price = limit_price;
+    plan.order_action = order_action;
+    plan.notional = plan.shares * limit_price;
+    plan.maker = false;
+    trace.verdict("maker_spread", "TAKER");
+    false
+}
+
+/// Next price for an unfilled maker order: one tick toward the touch (one tick inside the
+/// opposite side, the most aggressive price that still rests), never past `bound` (the buy
+/// ceiling / sell floor). None when it's already there or the opposite side is empty
+pub fn maker_reprice(current: f64, best_bid: Option<f64>, best_ask: Option<f64>, side_is_buy: bool, tick: f64, bound: f64) -> Option<f64> {
+    if side_is_buy {
+        let touch = floor_to_tick(best_ask? - tick, tick).min(bound);
+        let next = snap_to_tick(current + tick, tick).min(touch);
+        (next > current + 1e-9).then_some(next)
+    } else {
+        let touch = ceil_to_tick(best_bid? + tick, tick).max(bound);
+        let next = snap_to_tick(current - tick, tick).max(touch);
+        (next < current - 1e-9).then_some(next)
+    }
+}
+
+/// What to do when the book can't fill most of a planned order (LIQUIDITY_PRECHECK env var)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThinBookAction {
+    /// No pre-submit book fetch; thin books show up as FAK misses
+    #[default]
+    Off,
+    /// Shrink the order to the shares available at the limit
+    Downsize,
+    /// Don't submit (SKIPPED_THIN_PRECHECK)
+    Skip,
+}
+
+impl ThinBookAction {
+    /// Parse "off" / "downsize" / "skip" (empty = Off)
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "off" => Ok(ThinBookAction::Off),
+            "downsize" => Ok(ThinBookAction::Downsize),
+            "skip" => Ok(ThinBookAction::Skip),
+            other => anyhow::bail!("LIQUIDITY_PRECHECK must be 'off', 'downsize' or 'skip' (found '{}')", other),
+        }
+    }
+}
+
+/// Pre-submit liquidity check. `available` is the shares fillable at the plan's limit; below
+/// `min_fraction` of the plan, Skip rejects and Downsize shrinks the order to what's there
+/// (rejecting if that falls under `min_shares`)
+pub fn precheck_liquidity(
+    plan: &mut OrderPlan,
+    available: f64,
+    action: ThinBookAction,
+    min_fraction: f64,
+    min_shares: f64,
+    trace: &mut DecisionTrace,
+) -> Result<(), Status> {
+    if action == ThinBookAction::Off || available >= plan.shares * min_fraction {
+        trace.pass("liquidity_precheck");
+        return Ok(());
+    }
+    let status = Status::with_message(
+        StatusCode::SkippedThinPrecheck,
+        format!("{:.2}/{:.2} shares at {:.2}", available, plan.shares, plan.limit_price),
+    );
+    if action == ThinBookAction::Skip || available < min_shares {
+        return Err(trace.reject("liquidity_precheck", status));
+    }
+    plan.shares = available.min(plan.shares);
+    plan.notional = plan.shares * plan.limit_price;
+    trace.verdict("liquidity_precheck", "DOWNSIZED");
+    Ok(())
+}
+
+/// DAILY_NOTIONAL_CAP_USD for `notional` on `side`, recorded in the trace as `step`
+fn check_daily_notional(guard: &mut RiskGuard, side: TradeSide, notional: f64, step: &'static str, trace: &mut DecisionTrace) -> Result<(), Status> {
+    if !guard.daily_notional_allows(side, notional) {
+        return Err(trace.reject(
+            step,
+            Status::with_message(StatusCode::SkippedDailyNotionalCap, format!("${:.0} today", guard.daily_notional())),
+        ));
+    }
+    trace.pass(step);
+    Ok(())
+}
+
+/// Daily notional cap again after the pre-submit book checks: MIN_CROSS_BUFFER and
+/// SECOND_LEVEL_BUFFER raise the limit with the shares kept, so a plan that fit under the cap
+/// when it was sized can go over it
+pub fn recheck_daily_notional(plan: &OrderPlan, guard: &mut RiskGuard, trace: &mut DecisionTrace) -> Result<(), Status> {
+    let side = if plan.side_is_buy { TradeSide::Buy } else { TradeSide::Sell };
+    check_daily_notional(guard, side, plan.notional, "daily_notional_recheck", trace)
+}
+
+/// TIER_MIN_DEPTH: reject a plan when `available` (shares at or better than its limit) is under
+/// the tier's `min_depth` floor. Unlike the liquidity precheck this is absolute, not a fraction
+/// of the copy: a big whale's trade is only copied into a book that deep
+pub fn check_min_depth(plan: &OrderPlan, available: f64, min_depth: f64, trace: &mut DecisionTrace) -> Result<(), Status> {
+    if min_depth <= 0.0 || available >= min_depth {
+        trace.pass("min_depth");
+        return Ok(());
+    }
+    Err(trace.reject(
+        "min_depth",
+        Status::with_message(
+            StatusCode::SkippedInsufficientDepth,
+            format!("{:.0}/{:.0} shares at {:.2}", available, min_depth, plan.limit_price),
+        ),
+    ))
+}
+
+/// MIN_CROSS_BUFFER: raise a buy limit that sits under the best ask to `best_ask + min_cross`,
+/// so a zero-buffer FAK doesn't miss by construction. Never above the premium cap; shares are
+/// kept and the notional follows the new limit
+pub fn cross_spread_floor(
+    plan: &mut OrderPlan,
+    best_ask: Option<f64>,
+    min_cross: f64,
+    tick: f64,
+    premium_cap: Option<f64>,
+    trace: &mut DecisionTrace,
+) {
+    let Some(ask) = best_ask.filter(|_| plan.side_is_buy) else { return };
+    let floor = snap_to_tick(ask + min_cross, tick);
+    let target = match premium_cap {
+        Some(cap) => floor.min(floor_to_tick(cap, tick)),
+        None => floor,
+    };
+    if plan.limit_price >= floor - 1e-9 {
+        trace.pass("cross_spread");
+    } else if target > plan.limit_price + 1e-9 {
+        plan.limit_price = target;
+        plan.notional = plan.shares * target;
+        trace.verdict("cross_spread", if target < floor - 1e-9 { "RAISED_TO_CAP" } else { "RAISED" });
+    } else {
+        trace.verdict("cross_spread", "CAPPED");
+    }
+}
+
+/// SECOND_LEVEL_BUFFER: when the plan is bigger than the best level (`best` / `second` are
+/// (price, size) on the side the order takes from), move the limit through to the second
+/// level's price +/- `buffer` so a FAK doesn't miss against a thin top of book. Only ever makes
+/// the limit more aggressive; buys never above the premium cap. Shares are kept
+pub fn second_level_limit(
+    plan: &mut OrderPlan,
+    best: Option<BookLevel>,
+    second: Option<BookLevel>,
+    buffer: f64,
+    tick: f64,
+    premium_cap: Option<f64>,
+    trace: &mut DecisionTrace,
+) {
+    let (Some((_, best_size)), Some((second_price, _))) = (best, second) else {
+        trace.pass("second_level");
+        return;
+    };
+    if plan.shares <= best_size {
+        trace.pass("second_level");
+        return;
+    }
+    let (lo, hi) = (tick, 1.0 - tick);
+    let target = if plan.side_is_buy {
+        let walk = snap_to_tick(second_price + buffer, tick).clamp(lo, hi);
+        match premium_cap {
+            Some(cap) => walk.min(floor_to_tick(cap, tick)),
+            None => walk,
+        }
+    } else {
+        snap_to_tick(second_price - buffer, tick).clamp(lo, hi)
+    };
+    let improves = if plan.side_is_buy { target > plan.limit_price + 1e-9 } else { target < plan.limit_price - 1e-9 };
+    if improves {
+        plan.limit_price = target;
+        plan.notional = plan.shares * target;
+        trace.verdict("second_level", "WALKED");
+    } else {
+        trace.pass("second_level");
+    }
+}
+
+/// Limit price for a buffer, on the market's tick grid, clamped to [tick, 1 - tick] and the premium cap.
+/// Returns (limit, whether the cap lowered it)
+fn limit_for(whale_price: f64, buffer: f64, side_is_buy: bool, premium_cap: Option<f64>, tick: f64) -> (f64, bool) {
+    let limit_price = if side_is_buy {
+        snap_to_tick(whale_price + buffer, tick)
+    } else {
+        snap_to_tick(whale_price - buffer, tick)
+    };
+    match premium_cap {
+        Some(cap) if exceeds_premium_cap(limit_price, premium_cap) => (floor_to_tick(cap, tick), true),
+        _ => (limit_price, false),
+    }
+}
+
+/// Sizing for a market: its MARKET_OVERRIDES min_shares replaces the global share floor, and is
+/// enforced as a skip after sizing rather than by rounding up (the cash floor still applies)
+pub fn market_sizing(params: &SizingParams, market_min: Option<f64>) -> SizingParams {
+    match market_min {
+        Some(_) => SizingParams { min_share_count: 0.0, ..*params },
+        None => *params,
+    }
+}
+
+pub fn calculate_safe_size(whale_shares: f64, price: f64, size_multiplier: f64) -> (f64, SizeType) {
+    calculate_size_with(&LIVE_SIZING, whale_shares, price, size_multiplier, crate::rng::roll())
+}
+
+/// calculate_safe_size with explicit params and probability roll (uniform in [0, 1)).
+/// Target is whale_shares * scaling_ratio * size_multiplier, or in fraction mode
+/// whale_shares * follow_fraction (times size_multiplier unless it's ignored)
+pub fn calculate_size_with(params: &SizingParams, whale_shares: f64, price: f64, size_multiplier: f64, roll: f64) -> (f64, SizeType) {
+    let target_scaled = match params.follow_fraction {
+        Some(fraction) if params.fraction_ignores_multiplier => whale_shares * fraction,
+        Some(fraction) => whale_shares * fraction * size_multiplier,
+        None => whale_shares * params.scaling_ratio * size_multiplier,
+    };
+    size_from_target(params, target_scaled, price, roll)
+}
+
+/// Allocation-mode target in shares at `price`: allocation_bankroll_usd times the whale's
+/// trade as a fraction of their portfolio (times size_multiplier), that fraction capped at
+/// allocation_max_fraction. None when allocation sizing is off or the portfolio value is unknown
+pub fn allocation_target(params: &SizingParams, whale_usd: f64, portfolio_usd: Option<f64>, price: f64, size_multiplier: f64) -> Option<f64> {
+    let bankroll = params.allocation_bankroll_usd?;
+    let portfolio = portfolio_usd.filter(|p| *p > 0.0)?;
+    let fraction = (whale_usd / portfolio * size_multiplier).min(params.allocation_max_fraction);
+    Some(bankroll * fraction / price.max(0.0001))
+}
+
+/// Size a copy: by allocation when the whale's portfolio value is known, otherwise as
+/// calculate_size_with. The cash / share floors apply either way
+pub fn size_copy(
+    params: &SizingParams,
+    whale_shares: f64,
+    whale_price: f64,
+    portfolio_usd: Option<f64>,
+    price: f64,
+    size_multiplier: f64,
+    roll: f64,
+) -> (f64, SizeType) {
+    match allocation_target(params, whale_shares * whale_price, portfolio_usd, price, size_multiplier) {
+        Some(target) => size_from_target(params, target, price, roll),
+        None => calculate_size_with(params, whale_shares, price, size_multiplier, roll),
+    }
+}
+
+/// Apply the MIN_CASH_VALUE / min_share_count floor to a target size: round up (or, with
+/// probabilistic sizing, take the floor with probability target / floor)
+fn size_from_target(params: &SizingParams, target_scaled: f64, price: f64, roll: f64) -> (f64, SizeType) {
+    let safe_price = price.max(0.0001);
+    let required_floor = (params.min_cash_value / safe_price).max(params.min_share_count);
+
+    if target_scaled >= required_floor {
+        return (target_scaled, SizeType::Scaled);
+    }
+
+    if !params.probabilistic {
+        return (required_floor, SizeType::Scaled);
+    }
+
+    let probability = target_scaled / required_floor;
+    let pct = (probability * 100.0) as u8;
+    if roll < probability {
+        (required_floor, SizeType::ProbHit(pct))
+    } else {
+        (0.0, SizeType::ProbSkip(pct))
+    }
+}
+
+// ============================================================================
+// Whale Reference Price
+// ============================================================================
+
+/// Max tracked fill groups before the table is cleared
+const PRICE_GROUPS_MAX_ENTRIES: usize = 4096;
+
+/// Which price the limit buffer and resubmit ceiling are anchored to (WHALE_PRICE_MODE env var)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WhalePriceMode {
+    /// This event's own usd / shares
+    #[default]
+    Fill,
+    /// Volume-weighted average over the whale's fills in the same tx, token and side seen so far
+    Vwap,
+}
+
+impl WhalePriceMode {
+    /// Parse "fill" / "vwap" (empty = Fill)
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "fill" => Ok(WhalePriceMode::Fill),
+            "vwap" => Ok(WhalePriceMode::Vwap),
+            other => anyhow::bail!("WHALE_PRICE_MODE must be 'fill' or 'vwap' (found '{}')", other),
+        }
+    }
+}
+
+/// How the whale reference price is aligned to the market tick (WHALE_PRICE_ROUNDING env var).
+/// usd/shares often lands between ticks (0.4999999), and buffers added to it then round a tick off.
+/// Float noise within OFF_TICK_TOLERANCE of a tick always snaps to it; the policy decides what
+/// a genuinely off-tick price (0.505 on a cent market) becomes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TickRounding {
+    /// Round to the nearest tick and clamp to [tick, 1 - tick]
+    #[default]
+    Nearest,
+    /// Use the derived price as-is
+    Off,
+    /// The tick on the whale's side: never worse than the whale's price (buys down, sells up)
+    TowardWhale,
+    /// The tick that fills more easily (buys up, sells down)
+    Aggressive,
+    /// Don't trade it (SKIPPED_OFF_TICK)
+    Reject,
+}
+
+/// Fraction of a tick that still counts as float noise rather than an off-tick price
+pub const OFF_TICK_TOLERANCE: f64 = 1e-3;
+
+impl TickRounding {
+    /// Parse "nearest" / "off" / "round_toward_whale" / "round_aggressive" / "reject"
+    /// (the round_ prefix is optional; empty = Nearest)
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        let value = value.trim().to_ascii_lowercase();
+        match value.strip_prefix("round_").unwrap_or(&value) {
+            "" | "nearest" => Ok(TickRounding::Nearest),
+            "off" => Ok(TickRounding::Off),
+            "toward_whale" => Ok(TickRounding::TowardWhale),
+            "aggressive" => Ok(TickRounding::Aggressive),
+            "reject" => Ok(TickRounding::Reject),
+            _ => anyhow::bail!(
+                "WHALE_PRICE_ROUNDING must be 'nearest', 'off', 'round_toward_whale', 'round_aggressive' or 'reject' (found '{}')",
+                value
+            ),
+        }
+    }
+
+    /// Align a whale price. None = off-tick under Reject
+    pub fn apply(self, price: f64, tick: f64, side_is_buy: bool) -> Option<f64> {
+        match self {
+            TickRounding::Off => Some(price),
+            TickRounding::Reject if !on_tick(price, tick) => None,
+            _ => Some(self.round_price(price, tick, side_is_buy)),
+        }
+    }
+
+    /// Put a price we derived ourselves on the grid (resubmit chases). Reject can't skip
+    /// mid-chain and Off can't submit off-grid, so both round to nearest here
+    pub fn round_price(self, price: f64, tick: f64, side_is_buy: bool) -> f64 {
+        if on_tick(price, tick) {
+            return snap_to_tick(price, tick);
+        }
+        match (self, side_is_buy) {
+            (TickRounding::TowardWhale, true) | (TickRounding::Aggressive, false) => floor_to_tick(price, tick),
+            (TickRounding::TowardWhale, false) | (TickRounding::Aggressive, true) => ceil_to_tick(price, tick),
+            _ => snap_to_tick(price, tick),
+        }
+    }
+}
+
+/// Within OFF_TICK_TOLERANCE of a tick (always true for an invalid tick)
+fn on_tick(price: f64, tick: f64) -> bool {
+    if !valid_tick(tick) || !price.is_finite() {
+        return true;
+    }
+    let steps = price / tick;
+    (steps - steps.round()).abs() < OFF_TICK_TOLERANCE
+}
+
+fn valid_tick(tick: f64) -> bool {
+    tick > 0.0 && tick < 1.0
+}
+
+/// Round a price to the nearest tick and clamp to [tick, 1 - tick]
+/// (an invalid tick or non-finite price is returned as-is)
+pub fn snap_to_tick(price: f64, tick: f64) -> f64 {
+    if !valid_tick(tick) || !price.is_finite() {
+        return price;
+    }
+    let snapped = (price / tick).round() * tick;
+    // Strip float noise (0.51 * 100 steps back to 0.51, not 0.51000000000001)
+    let snapped = (snapped * 1e6).round() / 1e6;
+    snapped.clamp(tick, 1.0 - tick)
+}
+
+/// Highest tick at or below `price`, clamped to [tick, 1 - tick]. For ceilings, which
+/// must not round up past the limit they came from (0.5175 -> 0.51 at 0.01, 0.517 at 0.001)
+pub fn floor_to_tick(price: f64, tick: f64) -> f64 {
+    if !valid_tick(tick) || !price.is_finite() {
+        return price;
+    }
+    // Nudge so 0.52 / 0.01 = 51.9999999 still floors to 52
+    let floored = ((price / tick) + 1e-9).floor() * tick;
+    let floored = (floored * 1e6).round() / 1e6;
+    floored.clamp(tick, 1.0 - tick)
+}
+
+/// Lowest tick at or above `price`, clamped to [tick, 1 - tick]
+pub fn ceil_to_tick(price: f64, tick: f64) -> f64 {
+    if !valid_tick(tick) || !price.is_finite() {
+        return price;
+    }
+    let ceiled = ((price / tick) - 1e-9).ceil() * tick;
+    let ceiled = (ceiled * 1e6).round() / 1e6;
+    ceiled.clamp(tick, 1.0 - tick)
+}
+
+/// Submit price range (SUBMIT_PRICE_MIN / SUBMIT_PRICE_MAX). An unset side is the market's own
+/// limit (tick or 1 - tick); a set bound can only narrow it, the CLOB rejects anything outside
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PriceBounds {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl PriceBounds {
+    /// Effective [lo, hi] on a tick's grid: bounds rounded inward, within [tick, 1 - tick].
+    /// Bounds that cross on this grid fall back to the market range
+    pub fn range(&self, tick: f64) -> (f64, f64) {
+        let market = (tick, snap_to_tick(1.0 - tick, tick));
+        let lo = self.min.map_or(market.0, |min| ceil_to_tick(min, tick));
+        let hi = self.max.map_or(market.1, |max| floor_to_tick(max, tick));
+        if lo > hi { market } else { (lo, hi) }
+    }
+}
+
+/// Final price for every submission, first order and resubmits alike: nearest tick, then
+/// clamped into `bounds` (an invalid tick or non-finite price is returned as-is)
+pub fn submit_price(price: f64, tick: f64, bounds: &PriceBounds) -> f64 {
+    if !valid_tick(tick) || !price.is_finite() {
+        return price;
+    }
+    let (lo, hi) = bounds.range(tick);
+    snap_to_tick(price, tick).clamp(lo, hi)
+}
+
+/// Align the event's whale price to its market's tick (from the tick cache) under `mode`.
+/// Runs first thing in the order worker. Err is the SKIPPED_OFF_TICK status (Reject)
+pub fn tick_align_whale_price(mut evt: ParsedEvent, mode: TickRounding) -> Result<ParsedEvent, Status> {
+    let tick = crate::market_cache::tick_size(&evt.order.clob_token_id);
+    let price = evt.order.price_per_share;
+    let side_is_buy = evt.order.order_type.starts_with("BUY");
+    match mode.apply(price, tick, side_is_buy) {
+        Some(aligned) => {
+            evt.order.price_per_share = aligned;
+            Ok(evt)
+        }
+        None => Err(Status::with_message(StatusCode::SkippedOffTick, format!("{:.6} on {} tick", price, tick))),
+    }
+}
+
+/// Volume-weighted average price of (usd, shares) fills (None if no shares)
+pub fn vwap<I: IntoIterator<Item = (f64, f64)>>(fills: I) -> Option<f64> {
+    let (usd, shares) = fills.into_iter().fold((0.0, 0.0), |(u, s), (fu, fs)| (u + fu, s + fs));
+    (shares > 0.0).then(|| usd / shares)
+}
+
+/// Groups a multi-fill whale order by (tx, token, side) and rewrites each event's
+/// price_per_share to the group's running VWAP. A no-op in Fill mode.
+pub struct WhalePriceTracker {
+    mode: WhalePriceMode,
+    groups: FxHashMap<(String, Arc<str>, bool), (f64, f64)>,
+}
+
+impl WhalePriceTracker {
+    pub fn new(mode: WhalePriceMode) -> Self {
+        Self { mode, groups: FxHashMap::default() }
+    }
+
+    /// Return the event with its reference whale price applied
+    pub fn apply(&mut self, mut evt: ParsedEvent) -> ParsedEvent {
+        if self.mode == WhalePriceMode::Fill || evt.tx_hash.is_empty() {
+            return evt;
+        }
+        let key = (evt.tx_hash.clone(), evt.order.clob_token_id.clone(), evt.order.order_type.starts_with("BUY"));
+        if !self.groups.contains_key(&key) && self.groups.len() >= PRICE_GROUPS_MAX_ENTRIES {
+            self.groups.clear();
+        }
+        let group = self.groups.entry(key).or_insert((0.0, 0.0));
+        group.0 += evt.order.usd_value;
+        group.1 += evt.order.shares;
+        if let Some(price) = vwap([*group]) {
+            evt.order.price_per_share = price;
+        }
+        evt
+    }
+}
+
+// ============================================================================
+// Same Token Per Block
+// ============================================================================
+
+/// Blocks behind the newest one whose token groups are still kept (events can arrive slightly out of order)
+const SAME_TOKEN_BLOCK_WINDOW: u64 = 8;
+
+/// What to do when different followed whales trade the same token and side in one block
+/// (SAME_TOKEN_PER_BLOCK env var)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SameTokenPolicy {
+    /// Copy every event
+    #[default]
+    All,
+    /// Copy only the first whale's events; later whales are skipped
+    First,
+    /// Copy every event at its own size, priced off the running VWAP of all whales' trades on
+    /// the token in the block. Sizes are not summed into one copy
+    Blend,
+}
+
+impl SameTokenPolicy {
+    /// Parse "all" / "first" / "blend" ("sum" is accepted for Blend; empty = All)
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "all" => Ok(SameTokenPolicy::All),
+            "first" => Ok(SameTokenPolicy::First),
+            "blend" | "sum" => Ok(SameTokenPolicy::Blend),
+            other => anyhow::bail!("SAME_TOKEN_PER_BLOCK must be 'all', 'first' or 'blend' (found '{}')", other),
+        }
+    }
+}
+
+/// (block, token, is_buy)
+type BlockTokenKey = (u64, Arc<str>, bool);
+
+/// Tracks which whale was first copied on each (block, token, side) and the copied whales'
+/// combined fills. Events without a block number (mempool, logs missing blockNumber) can't be
+/// grouped and always pass
+pub struct SameTokenTracker {
+    policy: SameTokenPolicy,
+    newest_block: u64,
+    /// Key -> (first whale, usd, shares)
+    groups: FxHashMap<BlockTokenKey, (String, f64, f64)>,
+}
+
+impl SameTokenTracker {
+    pub fn new(policy: SameTokenPolicy) -> Self {
+        Self { policy, newest_block: 0, groups: FxHashMap::default() }
+    }
+
+    #[inline]
+    fn key(evt: &ParsedEvent) -> BlockTokenKey {
+        (evt.block_number, evt.order.clob_token_id.clone(), evt.order.order_type.starts_with("BUY"))
+    }
+
+    /// Return the event to copy (price blended under Blend), or None if First drops it.
+    /// The first whale's own follow-up fills in the block are always kept. Nothing is recorded
+    /// until the copy goes through (see record)
+    pub fn admit(&mut self, mut evt: ParsedEvent) -> Option<ParsedEvent> {
+        if self.policy == SameTokenPolicy::All || evt.block_number == 0 {
+            return Some(evt);
+        }
+        if evt.block_number > self.newest_block {
+            self.newest_block = evt.block_number;
+            let oldest = self.newest_block.saturating_sub(SAME_TOKEN_BLOCK_WINDOW);
+            self.groups.retain(|(block, _, _), _| *block >= oldest);
+        }
+        let Some((first, usd, shares)) = self.groups.get(&Self::key(&evt)) else { return Some(evt) };
+        match self.policy {
+            SameTokenPolicy::First if *first != evt.whale_address => None,
+            SameTokenPolicy::Blend => {
+                if let Some(price) = vwap([(usd + evt.order.usd_value, shares + evt.order.shares)]) {
+                    evt.order.price_per_share = price;
+                }
+                Some(evt)
+            }
+            _ => Some(evt),
+        }
+    }
+
+    /// Add a copied event (as admitted) to its block group
+    pub fn record(&mut self, evt: &ParsedEvent) {
+        if self.policy == SameTokenPolicy::All || evt.block_number == 0 {
+            return;
+        }
+        let group = self.groups.entry(Self::key(evt)).or_insert_with(|| (evt.whale_address.clone(), 0.0, 0.0));
+        group.1 += evt.order.usd_value;
+        group.2 += evt.order.shares;
+    }
+}
+
+// ============================================================================
+// Opposing Signals
+// ============================================================================
+
+/// Shares left below this after netting count as fully offset
+const NET_EPSILON_SHARES: f64 = 1e-6;
+
+/// What to do when different followed whales take opposite sides of one token within
+/// OPPOSING_SIGNAL_WINDOW_MS (OPPOSING_SIGNALS env var). A whale's own flips always pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OpposingSignalPolicy {
+    /// Copy each side
+    #[default]
+    Both,
+    /// Copy the earlier side; opposing trades in the window are skipped
+    First,
+    /// Offset an opposing trade against the opposite volume copied in the window and copy only
+    /// the residual. Copies already placed stay as they are, so only the later trade shrinks
+    Net,
+}
+
+impl OpposingSignalPolicy {
+    /// Parse "both" / "first" / "net" (empty = Both)
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "both" => Ok(OpposingSignalPolicy::Both),
+            "first" => Ok(OpposingSignalPolicy::First),
+            "net" => Ok(OpposingSignalPolicy::Net),
+            other => anyhow::bail!("OPPOSING_SIGNALS must be 'both', 'first' or 'net' (found '{}')", other),
+        }
+    }
+}
+
+/// A copied trade still inside the window
+struct Signal {
+    whale: String,
+    is_buy: bool,
+    /// Copied whale shares not yet offset by an opposing trade (Net)
+    open_shares: f64,
+    at: Instant,
+}
+
+impl Signal {
+    /// Open volume from another whale on the other side of a trade
+    fn opposes(&self, whale: &str, is_buy: bool) -> bool {
+        self.is_buy != is_buy && self.whale != whale && self.open_shares > 0.0
+    }
+}
+
+/// Recent copied trades per token, checked against each new trade on the other side
+pub struct OpposingSignalTracker {
+    policy: OpposingSignalPolicy,
+    window: Duration,
+    by_token: FxHashMap<Arc<str>, Vec<Signal>>,
+}
+
+impl OpposingSignalTracker {
+    pub fn new(policy: OpposingSignalPolicy, window: Duration) -> Self {
+        Self { policy, window, by_token: FxHashMap::default() }
+    }
+
+    fn prune(&mut self, now: Instant) {
+        let window = self.window;
+        self.by_token.retain(|_, signals| {
+            signals.retain(|s| now.duration_since(s.at) <= window);
+            !signals.is_empty()
+        });
+    }
+
+    /// Return the event to copy (shares and USD cut to the residual under Net), or None if it's
+    /// skipped: First drops any opposing trade, Net one fully offset by open opposite volume.
+    /// Nothing is recorded until the copy goes through (see record)
+    pub fn admit(&mut self, mut evt: ParsedEvent, now: Instant) -> Option<ParsedEvent> {
+        if self.policy == OpposingSignalPolicy::Both {
+            return Some(evt);
+        }
+        self.prune(now);
+        let is_buy = evt.order.order_type.starts_with("BUY");
+        let open: f64 = self.by_token.get(&evt.order.clob_token_id)
+            .map(|signals| signals.iter().filter(|s| s.opposes(&evt.whale_address, is_buy)).map(|s| s.open_shares).sum())
+            .unwrap_or(0.0);
+        match self.policy {
+            OpposingSignalPolicy::First if open > 0.0 => None,
+            OpposingSignalPolicy::Net if open > 0.0 => {
+                let shares = evt.order.shares - open;
+                if shares < NET_EPSILON_SHARES {
+                    return None;
+                }
+                evt.order.usd_value *= shares / evt.order.shares;
+                evt.order.shares = shares;
+                Some(evt)
+            }
+            _ => Some(evt),
+        }
+    }
+
+    /// Add a copied event (as admitted, at `now` from its admit) to the window. Under Net a
+    /// copied residual has used up all the opposite volume that was open against it
+    pub fn record(&mut self, evt: &ParsedEvent, now: Instant) {
+        if self.policy == OpposingSignalPolicy::Both {
+            return;
+        }
+        self.prune(now);
+        let is_buy = evt.order.order_type.starts_with("BUY");
+        let signals = self.by_token.entry(evt.order.clob_token_id.clone()).or_default();
+        if self.policy == OpposingSignalPolicy::Net {
+            signals.iter_mut()
+                .filter(|s| s.opposes(&evt.whale_address, is_buy))
+                .for_each(|s| s.open_shares = 0.0);
+        }
+        signals.push(Signal { whale: evt.whale_address.clone(), is_buy, open_shares: evt.order.shares, at: now });
+    }
+}
+
+// ============================================================================
+// Simulation
+// ============================================================================
+
+/// Synthetic order book: (price, size) levels, best first
+#[derive(Debug, Clone, Default)]
+pub struct Book {
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+}
+
+impl Book {
+    /// USD depth beyond `threshold` on the side a trade would take, reading at most `max_levels` levels
+    pub fn depth_beyond(&self, side: TradeSide, threshold: f64, max_levels: usize) -> f64 {
+        let levels = if side == TradeSide::Buy { &self.asks } else { &self.bids };
+        calc_liquidity_depth(side, &levels[..levels.len().min(max_levels)], threshold)
+    }
+}
+
+/// Result of a simulated decision
+#[derive(Debug, Clone)]
+pub enum OrderOutcome {
+    /// Order that would be submitted
+    Submit(OrderPlan),
+    /// Status explaining why the trade was skipped (its code is the CSV order_status)
+    Skip(Status),
+}
+
+impl OrderOutcome {
+    pub fn plan(&self) -> Option<&OrderPlan> {
+        match self {
+            OrderOutcome::Submit(plan) => Some(plan),
+            OrderOutcome::Skip(_) => None,
+        }
+    }
+}
+
+impl From<Result<OrderPlan, Status>> for OrderOutcome {
+    fn from(result: Result<OrderPlan, Status>) -> Self {
+        match result {
+            Ok(plan) => OrderOutcome::Submit(plan),
+            Err(status) => OrderOutcome::Skip(status),
+        }
+    }
+}
+
+/// Run the full decision (tiers, risk guard, sizing) for one event against a synthetic book,
+/// with a fresh risk guard and the decision settings from `cfg`. No network access.
+pub fn simulate_event(event: ParsedEvent, cfg: &Config, book: &Book) -> OrderOutcome {
+    let mut guard = RiskGuard::new(cfg.risk_guard_config());
+    simulate_with_guard(&event, &mut guard, &cfg.plan_params(), book)
+}
+
+/// Like simulate_event, but reuses a guard so sequences of events see each other's state
+pub fn simulate_with_guard(event: &ParsedEvent, guard: &mut RiskGuard, params: &PlanParams, book: &Book) -> OrderOutcome {
+    plan_order(
+        event,
+        guard,
+        params,
+        |side, limit_price| Ok(book.depth_beyond(side, limit_price, *BOOK_DEPTH_LEVELS)),
+        &mut DecisionTrace::disabled(),
+    )
+    .into()
+}
+
+// ============================================================================
+// Decision Trace
+// ============================================================================
+
+/// One guard's verdict in a decision trace
+#[derive(Debug, Clone, Serialize)]
+pub struct GuardVerdict {
+    pub guard: &'static str,
+    pub verdict: String,
+}
+
+/// Every step of one order decision, logged as a single JSON line (TRACE_DECISIONS=true).
+/// Recording is a no-op when disabled so the hot path stays allocation-free.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DecisionTrace {
+    #[serde(skip)]
+    enabled: bool,
+    pub tx_hash: String,
+    pub token_id: String,
+    pub whale: String,
+    pub whale_side: String,
+    pub whale_shares: f64,
+    pub whale_price: f64,
+    /// WebSocket frame type the event arrived in
+    pub transport: &'static str,
+    pub tier_min_shares: Option<f64>,
+    pub buffer: Option<f64>,
+    pub order_action: Option<&'static str>,
+    pub size_multiplier: Option<f64>,
+    pub limit_price: Option<f64>,
+    pub book_depth_usd: Option<f64>,
+    pub size: Option<f64>,
+    pub size_type: Option<String>,
+    pub guards: Vec<GuardVerdict>,
+    /// Guard that rejected the trade (None = not rejected before submission)
+    pub rejected_by: Option<&'static str>,
+    pub status: Option<String>,
+}
+
+impl DecisionTrace {
+    pub fn new(evt: &ParsedEvent) -> Self {
+        Self {
+            enabled: true,
+            tx_hash: evt.tx_hash.clone(),
+            token_id: evt.order.clob_token_id.to_string(),
+            whale: evt.whale_address.clone(),
+            whale_side: evt.order.order_type.clone(),
+            whale_shares: evt.order.shares,
+            whale_price: evt.order.price_per_share,
+            transport: evt.transport.as_str(),
+            ..Default::default()
+        }
+    }
+
+    /// Trace that records nothing
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn tier(&mut self, tier_min_shares: Option<f64>, buffer: f64, order_action: &'static str, size_multiplier: f64, limit_price: f64) {
+        if !self.enabled { return; }
+        self.tier_min_shares = tier_min_shares;
+        self.buffer = Some(buffer);
+        self.order_action = Some(order_action);
+        self.size_multiplier = Some(size_multiplier);
+        self.limit_price = Some(limit_price);
+    }
+
+    fn size(&mut self, shares: f64, size_type: &SizeType) {
+        if !self.enabled { return; }
+        self.size = Some(shares);
+        self.size_type = Some(size_type.to_string());
+    }
+
+    #[inline]
+    fn verdict(&mut self, guard: &'static str, verdict: &str) {
+        if !self.enabled { return; }
+        self.guards.push(GuardVerdict { guard, verdict: verdict.to_string() });
+    }
+
+    #[inline]
+    fn pass(&mut self, guard: &'static str) {
+        self.verdict(guard, "PASS");
+    }
+
+    /// Record the rejecting guard and pass its status through
+    #[inline]
+    fn reject(&mut self, guard: &'static str, status: Status) -> Status {
+        if self.enabled {
+            self.guards.push(GuardVerdict { guard, verdict: status.to_string() });
+            self.rejected_by = Some(guard);
+        }
+        status
+    }
+
+    /// Record the final status and return the trace as one JSON line (None when disabled)
+    pub fn finish(&mut self, status: &Status) -> Option<String> {
+        if !self.enabled { return None; }
+        self.status = Some(status.to_string());
+        serde_json::to_string(self).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{FrameTransport, OrderInfo};
+    use crate::risk_guard::{best_ask_from_json, fillable_shares_from_json, top_two_from_json, RiskGuardConfig};
+    use std::time::Duration;
+
+    fn event(order_type: &str, shares: f64, price: f64) -> ParsedEvent {
+        ParsedEvent {
+            block_number: 1,
+            tx_hash: "0xtx".into(),
+            whale_address: "0xwhale".into(),
+            counterparty: "0xtaker".into(),
+            order: OrderInfo {
+                order_type: order_type.into(),
+                clob_token_id: "tok".into(),
+                usd_value: shares * price,
+                shares,
+                price_per_share: price,
+            },
+            transport: FrameTransport::Text,
+        }
+    }
+
+    fn params() -> PlanParams {
+        Config::default().plan_params()
+    }
+
+    fn no_book(_: TradeSide, _: f64) -> Result<f64, &'static str> {
+        panic!("book should not be fetched");
+    }
+
+    #[test]
+    fn test_plan_for_large_buy() {
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+        let mut trace = DecisionTrace::disabled();
+        let plan = plan_order(&event("BUY_FILL", 5000.0, 0.50), &mut guard, &params(), no_book, &mut trace).unwrap();
+        assert!(plan.side_is_buy);
+        assert!((plan.limit_price - 0.51).abs() < 1e-9);
+        assert!((plan.shares - 5000.0 * SCALING_RATIO * 1.25).abs() < 1e-9);
+        assert_eq!(plan.order_action, "FAK");
+
+        // Disabled trace stays empty
+        assert!(trace.guards.is_empty());
+        assert_eq!(trace.finish(&Status::new(StatusCode::OrderPosted)), None);
+    }
+
+    #[test]
+    fn test_rejected_trade_still_traced() {
+        let mut guard = RiskGuard::new(RiskGuardConfig {
+            large_trade_shares: 1000.0,
+            consecutive_trigger: 1,
+            min_depth_beyond_usd: 500.0,
+            trip_duration: Duration::from_secs(60),
+            ..Default::default()
+        });
+        let evt = event("BUY_FILL", 5000.0, 0.50);
+        let mut trace = DecisionTrace::new(&evt);
+
+        // Thin book behind the limit trips the guard
+        let status = plan_order(&evt, &mut guard, &params(), |_, _| Ok(50.0), &mut trace).unwrap_err();
+        assert_eq!(status, Status::with_message(StatusCode::CbBlocked, "TRAP"));
+
+        // Everything up to the rejection is captured
+        assert_eq!(trace.tier_min_shares, Some(4000.0));
+        assert_eq!(trace.order_action, Some("FAK"));
+        assert!((trace.limit_price.unwrap() - 0.51).abs() < 1e-9);
+        assert_eq!(trace.book_depth_usd, Some(50.0));
+        assert_eq!(trace.rejected_by, Some("risk_guard"));
+        let names: Vec<_> = trace.guards.iter().map(|g| g.guard).collect();
+        assert_eq!(names, vec!["fills_only", "side_filter", "ignored_maker", "whale_entry", "market_override", "min_whale_shares", "whale_cooldown", "market_rate", "whale_premium", "risk_guard"]);
+        assert_eq!(trace.size, None);
+
+        let line = trace.finish(&status).unwrap();
+        assert!(!line.contains('\n'));
+        let json: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(json["status"], "CB_BLOCKED: TRAP");
+        assert_eq!(json["tx_hash"], "0xtx");
+        assert_eq!(json["transport"], "text");
+    }
+
+    #[test]
+    fn test_shadow_sized_and_logged() {
+        let shadow = ShadowSizing {
+            sizing: SizingParams { scaling_ratio: SCALING_RATIO * 2.0, ..*LIVE_SIZING },
+            buffer_offset: 0.02,
+        };
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+        let evt = event("BUY_FILL", 5000.0, 0.50);
+        let (plan, shadow_plan) =
+            plan_order_with_shadow(&evt, &mut guard, &params(), no_book, &mut DecisionTrace::disabled(), Some(&shadow));
+        let plan = plan.unwrap();
+        let shadow_plan = shadow_plan.unwrap();
+
+        // Same tier (1.25x, 0.01 buffer); the shadow doubles the ratio and adds 0.02 to the buffer
+        assert!((plan.shares - 5000.0 * SCALING_RATIO * 1.25).abs() < 1e-9);
+        assert!((shadow_plan.shares - plan.shares * 2.0).abs() < 1e-9);
+        assert!((plan.limit_price - 0.51).abs() < 1e-9);
+        assert!((shadow_plan.limit_price - 0.53).abs() < 1e-9);
+
+        let mut row = String::from("row");
+        crate::csv_log::push_shadow_columns(&mut row, Some(&shadow_plan));
+        assert_eq!(row, "row,250.000000,0.5300");
+
+        // Shadow off: no shadow plan, empty columns
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+        let (_, none) = plan_order_with_shadow(&evt, &mut guard, &params(), no_book, &mut DecisionTrace::disabled(), None);
+        assert_eq!(none, None);
+        let mut row = String::from("row");
+        crate::csv_log::push_shadow_columns(&mut row, none.as_ref());
+        assert_eq!(row, "row,,");
+    }
+
+    fn event_on(token: &str, order_type: &str, shares: f64, price: f64) -> ParsedEvent {
+        let mut evt = event(order_type, shares, price);
+        evt.order.clob_token_id = Arc::from(token);
+        evt
+    }
+
+    #[test]
+    fn test_market_override_changes_buffer_and_size() {
+        use crate::market_cache::{global_caches, MarketOverride};
+        global_caches().set_override(
+            "override_tok".into(),
+            MarketOverride { buffer: Some(0.0), size_multiplier: Some(3.0), ..Default::default() },
+        );
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+        let evt = event_on("override_tok", "BUY_FILL", 5000.0, 0.50);
+        let plan = plan_order(&evt, &mut guard, &params(), no_book, &mut DecisionTrace::disabled()).unwrap();
+
+        // Tier would give 0.01 buffer and 1.25x; the override wins. Order type still from the tier
+        assert!((plan.limit_price - 0.50).abs() < 1e-9);
+        assert!((plan.shares - 5000.0 * SCALING_RATIO * 3.0).abs() < 1e-9);
+        assert_eq!(plan.order_action, "FAK");
+    }
+
+    #[test]
+    fn test_market_override_skips_market() {
+        use crate::market_cache::{global_caches, MarketOverride};
+        global_caches().set_override("skipped_tok".into(), MarketOverride { skip: true, ..Default::default() });
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+        let evt = event_on("skipped_tok", "BUY_FILL", 5000.0, 0.50);
+        let mut trace = DecisionTrace::new(&evt);
+        let status = plan_order(&evt, &mut guard, &params(), no_book, &mut trace).unwrap_err();
+        assert_eq!(status.code, StatusCode::SkippedMarketOverride);
+        assert_eq!(trace.rejected_by, Some("market_override"));
+    }
+
+    #[test]
+    fn test_ignored_maker_skipped_others_pass() {
+        use crate::market_cache::global_caches;
+        global_caches().ignore_maker("0xWashDesk");
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+
+        let mut wash = event_on("wash_tok", "BUY_FILL", 5000.0, 0.50);
+        wash.counterparty = "0xwashdesk".into();
+        let mut trace = DecisionTrace::new(&wash);
+        let status = plan_order(&wash, &mut guard, &params(), no_book, &mut trace).unwrap_err();
+        assert_eq!(status.code, StatusCode::SkippedIgnoredMaker);
+        assert_eq!(trace.rejected_by, Some("ignored_maker"));
+
+        // Listed as the subscribed whale itself: skipped too
+        let mut own = event_on("wash_tok", "BUY_FILL", 5000.0, 0.50);
+        own.whale_address = "0xwashdesk".into();
+        assert_eq!(plan_order(&own, &mut guard, &params(), no_book, &mut DecisionTrace::disabled()).unwrap_err().code, StatusCode::SkippedIgnoredMaker);
+
+        // Any other counterparty is copied
+        let fair = event_on("wash_tok", "BUY_FILL", 5000.0, 0.50);
+        assert!(plan_order(&fair, &mut guard, &params(), no_book, &mut DecisionTrace::disabled()).is_ok());
+    }
+
+    #[test]
+    fn test_market_min_shares_replaces_global_floor() {
+        // Global floor of 50 shares; a 20-share target at 0.50 is $10, above the cash floor
+        let global = SizingParams { min_share_count: 50.0, probabilistic: false, ..*LIVE_SIZING };
+        let whale_for_20 = 20.0 / global.scaling_ratio;
+        let sized = |params: &SizingParams| calculate_size_with(params, whale_for_20, 0.50, 1.0, 0.0).0;
+
+        // Unlisted market: the global floor rounds the copy up to 50
+        assert!((sized(&market_sizing(&global, None)) - 50.0).abs() < 1e-9);
+        // Listed with a 10-share floor: the copy keeps its own size
+        assert!((sized(&market_sizing(&global, Some(10.0))) - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_market_min_shares_skips_dust() {
+        use crate::market_cache::{global_caches, MarketOverride};
+        global_caches().set_override("min_tok_high".into(), MarketOverride { min_shares: Some(500.0), ..Default::default() });
+        global_caches().set_override("min_tok_low".into(), MarketOverride { min_shares: Some(100.0), ..Default::default() });
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+
+        // 5000 whale shares copy to 125 (tier multiplier 1.25)
+        let evt = event_on("min_tok_high", "BUY_FILL", 5000.0, 0.50);
+        let mut trace = DecisionTrace::new(&evt);
+        let status = plan_order(&evt, &mut guard, &params(), no_book, &mut trace).unwrap_err();
+        assert_eq!(status, Status::with_message(StatusCode::SkippedBelowMarketMin, "<500 shares"));
+        assert_eq!(trace.rejected_by, Some("market_min"));
+
+        let plan = plan_order(&event_on("min_tok_low", "BUY_FILL", 5000.0, 0.50), &mut guard, &params(), no_book, &mut DecisionTrace::disabled()).unwrap();
+        assert!((plan.shares - 125.0).abs() < 1e-9);
+        // Unlisted markets only have the global floor
+        assert!(plan_order(&event_on("min_tok_none", "BUY_FILL", 5000.0, 0.50), &mut guard, &params(), no_book, &mut DecisionTrace::disabled()).is_ok());
+    }
+
+    fn ladder(start: f64, step: f64, size: f64, n: usize) -> Vec<(f64, f64)> {
+        (0..n).map(|i| (start + step * i as f64, size)).collect()
+    }
+
+    fn cfg_trigger_on_first_large() -> Config {
+        Config { cb_large_trade_shares: 1000.0, cb_consecutive_trigger: 1, cb_min_depth_usd: 500.0, ..Default::default() }
+    }
+
+    #[test]
+    fn test_simulate_default_config() {
+        // Default config never fetches the book for a single trade, so an empty book is fine
+        let outcome = simulate_event(event("BUY_FILL", 2500.0, 0.40), &Config::default(), &Book::default());
+        let plan = outcome.plan().expect("should submit");
+        assert!((plan.limit_price - 0.41).abs() < 1e-9);
+        assert!((plan.shares - 2500.0 * SCALING_RATIO).abs() < 1e-9);
+        assert!(matches!(plan.size_type, SizeType::Scaled));
+
+        match simulate_event(event("BUY_FILL", 1.0, 0.40), &Config::default(), &Book::default()) {
+            OrderOutcome::Skip(status) => assert_eq!(status.code, StatusCode::SkippedSmall),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_simulate_book_depth_decides() {
+        let cfg = cfg_trigger_on_first_large();
+        let evt = event("BUY_FILL", 5000.0, 0.50);
+
+        // Thin asks behind the 0.51 limit: blocked
+        let thin = Book { asks: ladder(0.52, 0.01, 10.0, 5), ..Default::default() };
+        match simulate_event(evt.clone(), &cfg, &thin) {
+            OrderOutcome::Skip(status) => assert_eq!(status.code, StatusCode::CbBlocked, "{status}"),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // Deep asks: submitted at the tier's limit
+        let deep = Book { asks: ladder(0.52, 0.01, 1000.0, 5), ..Default::default() };
+        let outcome = simulate_event(evt, &cfg, &deep);
+        let plan = outcome.plan().expect("deep book should submit");
+        assert_eq!(plan.order_action, "FAK");
+        assert!((plan.limit_price - 0.51).abs() < 1e-9);
+
+        // Sells read the bid side
+        let sell = event("SELL_FILL", 5000.0, 0.50);
+        let bids_only = Book { bids: ladder(0.48, -0.01, 1000.0, 5), ..Default::default() };
+        assert!(simulate_event(sell.clone(), &cfg, &bids_only).plan().is_some());
+        assert!(simulate_event(sell, &cfg, &deep).plan().is_none());
+    }
+
+    #[test]
+    fn test_simulate_uses_config_decision_settings() {
+        let buy = event("BUY_FILL", 2500.0, 0.40);
+        let base = simulate_event(buy.clone(), &Config::default(), &Book::default());
+        let base = base.plan().expect("default config copies it");
+        assert!(!base.maker);
+
+        let exits_only = Config { follow_sides: FollowSides::Sell, ..Default::default() };
+        match simulate_event(buy.clone(), &exits_only, &Book::default()) {
+            OrderOutcome::Skip(status) => assert_eq!(status.code, StatusCode::SkippedSideFilter),
+            other => panic!("unexpected {:?}", other),
+        }
+        let fills_only = Config { act_on_fills_only: true, ..Default::default() };
+        match simulate_event(event("BUY", 2500.0, 0.40), &fills_only, &Book::default()) {
+            OrderOutcome::Skip(status) => assert_eq!(status.code, StatusCode::SkippedNotFill),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let maker = Config { copy_mode: CopyMode::Maker, ..Default::default() };
+        let outcome = simulate_event(buy.clone(), &maker, &Book::default());
+        let plan = outcome.plan().expect("maker copies it too");
+        assert!(plan.maker);
+        assert_eq!(plan.order_action, MAKER_ORDER_ACTION);
+
+        let doubled = Config { sizing: SizingParams { scaling_ratio: SCALING_RATIO * 2.0, ..DEFAULT_SIZING }, ..Default::default() };
+        let outcome = simulate_event(buy, &doubled, &Book::default());
+        assert!(outcome.plan().expect("doubled size").shares > base.shares);
+    }
+
+    #[test]
+    fn test_simulate_sequence_shares_guard() {
+        let cfg = Config { daily_notional_cap_usd: 20.0, ..Default::default() };
+        let mut guard = RiskGuard::new(cfg.risk_guard_config());
+        let book = Book::default();
+
+        // Each 24 share buy at 0.50 is $12: the second one exceeds the cap
+        let evt = event("BUY_FILL", 1200.0, 0.50);
+        let first = simulate_with_guard(&evt, &mut guard, &cfg.plan_params(), &book);
+        let plan = first.plan().expect("first fits under the cap");
+        guard.record_notional(TradeSide::Buy, plan.notional);
+        match simulate_with_guard(&evt, &mut guard, &cfg.plan_params(), &book) {
+            OrderOutcome::Skip(status) => assert_eq!(status.code, StatusCode::SkippedDailyNotionalCap),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_vwap_over_fills() {
+        // 100 @ 0.50, 300 @ 0.60, 100 @ 0.40 -> (50 + 180 + 40) / 500
+        let fills = [(50.0, 100.0), (180.0, 300.0), (40.0, 100.0)];
+        assert!((vwap(fills).unwrap() - 0.54).abs() < 1e-12);
+        assert_eq!(vwap([]), None);
+        assert_eq!(vwap([(1.0, 0.0)]), None);
+
+        // Tracker applies the running VWAP per tx; an outlier fill is pulled toward the group
+        let mut tracker = WhalePriceTracker::new(WhalePriceMode::Vwap);
+        let p1 = tracker.apply(event("BUY_FILL", 100.0, 0.50)).order.price_per_share;
+        let p2 = tracker.apply(event("BUY_FILL", 300.0, 0.60)).order.price_per_share;
+        assert!((p1 - 0.50).abs() < 1e-12);
+        assert!((p2 - 0.575).abs() < 1e-12);
+
+        // Other side of the same tx is its own group
+        let sell = tracker.apply(event("SELL_FILL", 100.0, 0.40)).order.price_per_share;
+        assert!((sell - 0.40).abs() < 1e-12);
+
+        // Fill mode leaves prices alone
+        let mut fill = WhalePriceTracker::new(WhalePriceMode::Fill);
+        fill.apply(event("BUY_FILL", 100.0, 0.50));
+        assert_eq!(fill.apply(event("BUY_FILL", 300.0, 0.60)).order.price_per_share, 0.60);
+        assert_eq!(WhalePriceMode::parse(" VWAP").unwrap(), WhalePriceMode::Vwap);
+        assert_eq!(WhalePriceMode::parse("").unwrap(), WhalePriceMode::Fill);
+        assert!(WhalePriceMode::parse("mean").is_err());
+    }
+
+    #[test]
+    fn test_liquidity_precheck() {
+        // Buy 100 @ limit 0.51: thin book has 30 at or under the limit, deep book has 250
+        let thin = serde_json::json!({"asks": [{"price": "0.60", "size": "500"}, {"price": "0.51", "size": "20"}, {"price": "0.50", "size": "10"}]});
+        let deep = serde_json::json!({"asks": [{"price": "0.51", "size": "200"}, {"price": "0.49", "size": "50"}]});
+        let thin_avail = fillable_shares_from_json(&thin, TradeSide::Buy, 0.51);
+        let deep_avail = fillable_shares_from_json(&deep, TradeSide::Buy, 0.51);
+        assert_eq!((thin_avail, deep_avail), (30.0, 250.0));
+        let plan = OrderPlan {
+            side_is_buy: true,
+            limit_price: 0.51,
+            shares: 100.0,
+            size_type: SizeType::Scaled,
+            order_action: "FAK",
+            notional: 51.0,
+            maker: false,
+        };
+        let check = |avail: f64, action: ThinBookAction, min_shares: f64| {
+            let mut p = plan.clone();
+            precheck_liquidity(&mut p, avail, action, 0.5, min_shares, &mut DecisionTrace::disabled()).map(|_| p)
+        };
+
+        // Deep book: untouched either way
+        for action in [ThinBookAction::Downsize, ThinBookAction::Skip] {
+            assert_eq!(check(deep_avail, action, 5.0).unwrap().shares, 100.0);
+        }
+        // Thin book: skip rejects, downsize trims to what's there
+        let err = check(thin_avail, ThinBookAction::Skip, 5.0).unwrap_err();
+        assert_eq!(err.code, StatusCode::SkippedThinPrecheck, "{err}");
+        let downsized = check(thin_avail, ThinBookAction::Downsize, 5.0).unwrap();
+        assert_eq!(downsized.shares, 30.0);
+        assert!((downsized.notional - 15.3).abs() < 1e-9);
+        // ... unless the remainder is below the minimum order
+        assert!(check(thin_avail, ThinBookAction::Downsize, 40.0).is_err());
+        // Off never rejects
+        assert_eq!(check(0.0, ThinBookAction::Off, 5.0).unwrap().shares, 100.0);
+
+        // Sells read bids at or above the limit
+        let bids = serde_json::json!({"bids": [{"price": "0.45", "size": "10"}, {"price": "0.48", "size": "15"}]});
+        assert_eq!(fillable_shares_from_json(&bids, TradeSide::Sell, 0.46), 15.0);
+        assert_eq!(ThinBookAction::parse("Downsize").unwrap(), ThinBookAction::Downsize);
+        assert_eq!(ThinBookAction::parse("").unwrap(), ThinBookAction::Off);
+        assert!(ThinBookAction::parse("shrink").is_err());
+    }
+
+    #[test]
+    fn test_tier_min_depth() {
+        // 4000 tier wants 1000 shares at or under the limit, 2000 tier 500, smaller buys none
+        let (big, mid) = (tier_min_depth_shares(5000.0, true), tier_min_depth_shares(2500.0, true));
+        assert_eq!((big, mid), (1000.0, 500.0));
+        assert_eq!(tier_min_depth_shares(1500.0, true), 0.0);
+        assert_eq!(tier_min_depth_shares(500.0, true), DEFAULT_MIN_DEPTH_SHARES);
+
+        let deep = serde_json::json!({"asks": [{"price": "0.52", "size": "900"}, {"price": "0.51", "size": "1200"}]});
+        let shallow = serde_json::json!({"asks": [{"price": "0.60", "size": "5000"}, {"price": "0.51", "size": "700"}]});
+        let deep_avail = fillable_shares_from_json(&deep, TradeSide::Buy, 0.51);
+        let shallow_avail = fillable_shares_from_json(&shallow, TradeSide::Buy, 0.51);
+        assert_eq!((deep_avail, shallow_avail), (1200.0, 700.0));
+        let plan = OrderPlan {
+            side_is_buy: true,
+            limit_price: 0.51,
+            shares: 100.0,
+            size_type: SizeType::Scaled,
+            order_action: "FAK",
+            notional: 51.0,
+            maker: false,
+        };
+        let check = |avail: f64, min_depth: f64| check_min_depth(&plan, avail, min_depth, &mut DecisionTrace::disabled());
+
+        // Deep book clears both tiers
+        assert!(check(deep_avail, big).is_ok());
+        assert!(check(deep_avail, mid).is_ok());
+        // Shallow book clears the mid tier but not the big one; depth past the limit doesn't count
+        assert!(check(shallow_avail, mid).is_ok());
+        let err = check(shallow_avail, big).unwrap_err();
+        assert_eq!(err.code, StatusCode::SkippedInsufficientDepth, "{err}");
+        // No floor never rejects
+        assert!(check(0.0, 0.0).is_ok());
+    }
+
+    #[test]
+    fn test_maker_limit_rests_at_touch() {
+        assert_eq!(CopyMode::parse(" Maker ").unwrap(), CopyMode::Maker);
+        assert_eq!(CopyMode::parse("taker").unwrap(), CopyMode::Taker);
+        assert_eq!(CopyMode::parse("").unwrap(), CopyMode::Taker);
+        assert!(CopyMode::parse("makers").is_err());
+
+        // Whale bought at 0.50 inside a 0.48 / 0.52 book: rest right there
+        assert_eq!(maker_limit(0.50, Some(0.48), Some(0.52), true, 0.01), 0.50);
+        // Whale lifted the ask (0.52): join one tick under it instead of crossing
+        assert_eq!(maker_limit(0.52, Some(0.48), Some(0.52), true, 0.01), 0.51);
+        assert_eq!(maker_limit(0.55, Some(0.48), Some(0.52), true, 0.01), 0.51);
+        // Sells mirror it against the bid
+        assert_eq!(maker_limit(0.48, Some(0.48), Some(0.52), false, 0.01), 0.49);
+        assert_eq!(maker_limit(0.50, Some(0.48), Some(0.52), false, 0.01), 0.50);
+        // Empty opposite side: nothing to cross
+        assert_eq!(maker_limit(0.50, None, None, true, 0.01), 0.50);
+
+        // Applied to a plan: the limit comes back, shares stay and the notional follows
+        let mut plan = OrderPlan {
+            side_is_buy: true,
+            limit_price: 0.53,
+            shares: 100.0,
+            size_type: SizeType::Scaled,
+            order_action: MAKER_ORDER_ACTION,
+            notional: 53.0,
+            maker: true,
+        };
+        maker_touch(&mut plan, Some(0.48), Some(0.52), 0.01, &mut DecisionTrace::disabled());
+        assert_eq!((plan.limit_price, plan.shares), (0.51, 100.0));
+        assert!((plan.notional - 51.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_maker_spread_picks_maker_or_taker() {
+        let evt = event("BUY_FILL", 100.0, 0.50);
+        let maker_plan = OrderPlan {
+            side_is_buy: true,
+            limit_price: 0.50,
+            shares: 20.0,
+            size_type: SizeType::Scaled,
+            order_action: MAKER_ORDER_ACTION,
+            notional: 10.0,
+            maker: true,
+        };
+        let check = |bid: Option<f64>, ask: Option<f64>, min_ticks: u32| {
+            let mut plan = maker_plan.clone();
+            let rests = maker_spread_check(&mut plan, &evt, &params(), bid, ask, 0.01, min_ticks, &mut DecisionTrace::disabled());
+            (rests, plan)
+        };
+
+        // 0.46 / 0.54 = 8 ticks: wide enough to rest
+        let (rests, plan) = check(Some(0.46), Some(0.54), 3);
+        assert!(rests && plan.maker);
+        assert_eq!((plan.limit_price, plan.order_action), (0.50, MAKER_ORDER_ACTION));
+        // Exactly at the minimum still rests
+        assert!(check(Some(0.49), Some(0.52), 3).0);
+
+        // One-tick spread: cross as a taker with the tier's buffer and order type
+        let (rests, plan) = check(Some(0.50), Some(0.51), 3);
+        let (buffer, action, _) = get_tier_params(100.0, true, "tok");
+        assert!(!rests && !plan.maker);
+        assert_eq!(plan.order_action, action);
+        assert!((plan.limit_price - (0.50 + buffer)).abs() < 1e-9);
+        assert_eq!(plan.shares, 20.0);
+        assert!((plan.notional - 20.0 * plan.limit_price).abs() < 1e-9);
+
+        // Off, or a one-sided book: rest
+        assert!(check(Some(0.50), Some(0.51), 0).0);
+        assert!(check(None, Some(0.51), 3).0);
+        assert_eq!(spread_ticks(Some(0.48), Some(0.52), 0.01), Some(4.0));
+
+        // The taker fallback looks the tier up with the plan's TIER_SHARES_ROUNDING
+        let evt = event("BUY_FILL", 1999.9999, 0.50);
+        let rounded = PlanParams { tier_shares_rounding: 1.0, ..params() };
+        let mut plan = maker_plan.clone();
+        assert!(!maker_spread_check(&mut plan, &evt, &rounded, Some(0.50), Some(0.51), 0.01, 3, &mut DecisionTrace::disabled()));
+        let (buffer, _, _) = get_tier_params_with(1999.9999, true, "tok", 1.0);
+        assert!(buffer > get_tier_params_with(1999.9999, true, "tok", 0.0).0);
+        assert!((plan.limit_price - (0.50 + buffer)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_maker_reprice_walks_toward_touch() {
+        let (bid, ask) = (Some(0.47), Some(0.52));
+        // Buy resting at 0.48: one tick per step up to 0.51, never onto the ask
+        assert_eq!(maker_reprice(0.48, bid, ask, true, 0.01, 0.60), Some(0.49));
+        assert_eq!(maker_reprice(0.50, bid, ask, true, 0.01, 0.60), Some(0.51));
+        assert_eq!(maker_reprice(0.51, bid, ask, true, 0.01, 0.60), None);
+        // The ceiling stops it first
+        assert_eq!(maker_reprice(0.49, bid, ask, true, 0.01, 0.49), None);
+        // Sell resting at 0.51 steps down to 0.48, one tick over the bid
+        assert_eq!(maker_reprice(0.51, bid, ask, false, 0.01, 0.0), Some(0.50));
+        assert_eq!(maker_reprice(0.48, bid, ask, false, 0.01, 0.0), None);
+        assert_eq!(maker_reprice(0.51, bid, ask, false, 0.01, 0.51), None);
+        // No opposite side to measure the touch against
+        assert_eq!(maker_reprice(0.48, bid, None, true, 0.01, 0.60), None);
+    }
+
+    #[test]
+    fn test_cross_spread_floor() {
+        // Whale filled at 0.50 with a 0.00 buffer, but the ask has moved to 0.52
+        let book = serde_json::json!({"asks": [{"price": "0.55", "size": "100"}, {"price": "0.52", "size": "40"}]});
+        let ask = best_ask_from_json(&book);
+        assert_eq!(ask, Some(0.52));
+        let plan = OrderPlan {
+            side_is_buy: true,
+            limit_price: 0.50,
+            shares: 20.0,
+            size_type: SizeType::Scaled,
+            order_action: "FAK",
+            notional: 10.0,
+            maker: false,
+        };
+        let cross = |plan: &OrderPlan, ask: Option<f64>, min_cross: f64, tick: f64, cap: Option<f64>| {
+            let mut p = plan.clone();
+            cross_spread_floor(&mut p, ask, min_cross, tick, cap, &mut DecisionTrace::disabled());
+            p
+        };
+
+        // Bumped to the ask, or a tick through it; shares kept, notional follows
+        let bumped = cross(&plan, ask, 0.0, 0.01, None);
+        assert_eq!((bumped.limit_price, bumped.shares), (0.52, 20.0));
+        assert!((bumped.notional - 10.4).abs() < 1e-9);
+        assert_eq!(cross(&plan, ask, 0.01, 0.01, None).limit_price, 0.53);
+        assert_eq!(cross(&plan, Some(0.512), 0.001, 0.001, None).limit_price, 0.513);
+        // Premium cap still wins
+        assert_eq!(cross(&plan, ask, 0.01, 0.01, Some(0.515)).limit_price, 0.51);
+        // Already crossing, no asks, or a sell: untouched
+        assert_eq!(cross(&OrderPlan { limit_price: 0.53, ..plan.clone() }, ask, 0.0, 0.01, None).limit_price, 0.53);
+        assert_eq!(cross(&plan, best_ask_from_json(&serde_json::json!({"asks": []})), 0.0, 0.01, None).limit_price, 0.50);
+        assert_eq!(cross(&OrderPlan { side_is_buy: false, ..plan.clone() }, ask, 0.0, 0.01, None).limit_price, 0.50);
+
+        // Opt-in per tier: the 0.00-buffer tiers by default
+        assert!(tier_crosses_spread(500.0) && tier_crosses_spread(1500.0));
+        assert!(!tier_crosses_spread(2500.0));
+    }
+
+    #[test]
+    fn test_daily_notional_recheck_after_cross() {
+        let mut guard = RiskGuard::new(RiskGuardConfig { daily_notional_cap_usd: 20.0, ..Default::default() });
+        guard.record_notional(TradeSide::Buy, 10.0);
+        // $10 at the whale's price fits; crossing to a 0.52 ask makes it $10.40
+        let mut plan = OrderPlan {
+            side_is_buy: true,
+            limit_price: 0.50,
+            shares: 20.0,
+            size_type: SizeType::Scaled,
+            order_action: "FAK",
+            notional: 10.0,
+            maker: false,
+        };
+        assert!(recheck_daily_notional(&plan, &mut guard, &mut DecisionTrace::disabled()).is_ok());
+        cross_spread_floor(&mut plan, Some(0.52), 0.0, 0.01, None, &mut DecisionTrace::disabled());
+        let status = recheck_daily_notional(&plan, &mut guard, &mut DecisionTrace::disabled()).unwrap_err();
+        assert_eq!(status.code, StatusCode::SkippedDailyNotionalCap);
+
+        // Sells aren't capped
+        let sell = OrderPlan { side_is_buy: false, ..plan };
+        assert!(recheck_daily_notional(&sell, &mut guard, &mut DecisionTrace::disabled()).is_ok());
+    }
+
+    #[test]
+    fn test_second_level_limit() {
+        // 30 shares at 0.52 on top, 200 behind it at 0.53; the copy wants 100
+        let book = serde_json::json!({"asks": [
+            {"price": "0.55", "size": "500"}, {"price": "0.52", "size": "30"}, {"price": "0.53", "size": "200"}
+        ]});
+        let (best, second) = top_two_from_json(&book, TradeSide::Buy);
+        assert_eq!((best, second), (Some((0.52, 30.0)), Some((0.53, 200.0))));
+        let plan = OrderPlan {
+            side_is_buy: true,
+            limit_price: 0.52,
+            shares: 100.0,
+            size_type: SizeType::Scaled,
+            order_action: "FAK",
+            notional: 52.0,
+            maker: false,
+        };
+        let walk = |plan: &OrderPlan, buffer: f64, cap: Option<f64>| {
+            let mut p = plan.clone();
+            second_level_limit(&mut p, best, second, buffer, 0.01, cap, &mut DecisionTrace::disabled());
+            p
+        };
+
+        // Bigger than the top level: limit moves to the second level (+ buffer), shares kept
+        let walked = walk(&plan, 0.0, None);
+        assert_eq!((walked.limit_price, walked.shares), (0.53, 100.0));
+        assert!((walked.notional - 53.0).abs() < 1e-9);
+        assert_eq!(walk(&plan, 0.01, None).limit_price, 0.54);
+        // Premium cap still wins
+        assert_eq!(walk(&plan, 0.01, Some(0.535)).limit_price, 0.53);
+        // Fits in the top level, or already priced through: untouched
+        assert_eq!(walk(&OrderPlan { shares: 30.0, ..plan.clone() }, 0.0, None).limit_price, 0.52);
+        assert_eq!(walk(&OrderPlan { limit_price: 0.55, ..plan.clone() }, 0.0, None).limit_price, 0.55);
+
+        // Sells walk down the bids
+        let bids = serde_json::json!({"bids": [{"price": "0.48", "size": "10"}, {"price": "0.47", "size": "100"}]});
+        let (best, second) = top_two_from_json(&bids, TradeSide::Sell);
+        let mut sell = OrderPlan { side_is_buy: false, limit_price: 0.48, ..plan.clone() };
+        second_level_limit(&mut sell, best, second, 0.0, 0.01, None, &mut DecisionTrace::disabled());
+        assert_eq!(sell.limit_price, 0.47);
+
+        // Opt-in per tier: the two largest buy tiers by default
+        assert!(tier_walks_second_level(5000.0, true) && tier_walks_second_level(2500.0, true));
+        assert!(!tier_walks_second_level(1500.0, true) && !tier_walks_second_level(500.0, true));
+        assert!(!tier_walks_second_level(5000.0, false));
+    }
+
+    fn whale_event(whale: &str, shares: f64, price: f64) -> ParsedEvent {
+        let mut evt = event("BUY_FILL", shares, price);
+        evt.whale_address = whale.into();
+        evt
+    }
+
+    #[test]
+    fn test_same_token_per_block_policies() {
+        // Two whales buy the same token in block 1; a third event is whale A topping up
+        let events = || [whale_event("0xa", 100.0, 0.50), whale_event("0xb", 300.0, 0.60), whale_event("0xa", 100.0, 0.40)];
+
+        // Every admitted event is copied
+        let copy = |tracker: &mut SameTokenTracker, e: ParsedEvent| {
+            let e = tracker.admit(e)?;
+            tracker.record(&e);
+            Some(e)
+        };
+
+        // all: every event passes untouched
+        let mut all = SameTokenTracker::new(SameTokenPolicy::All);
+        let prices: Vec<f64> = events().into_iter().filter_map(|e| copy(&mut all, e)).map(|e| e.order.price_per_share).collect();
+        assert_eq!(prices, vec![0.50, 0.60, 0.40]);
+
+        // first: whale B is dropped, A's own follow-up is kept
+        let mut first = SameTokenTracker::new(SameTokenPolicy::First);
+        let kept: Vec<String> = events().into_iter().filter_map(|e| copy(&mut first, e)).map(|e| e.whale_address).collect();
+        assert_eq!(kept, vec!["0xa", "0xa"]);
+        // Next block, or the other side, starts a fresh group
+        let mut next = whale_event("0xb", 10.0, 0.5);
+        next.block_number = 2;
+        assert!(first.admit(next).is_some());
+        let mut sell = whale_event("0xb", 10.0, 0.5);
+        sell.order.order_type = "SELL_FILL".into();
+        assert!(first.admit(sell).is_some());
+
+        // first: an admitted event that isn't copied (a later guard skips it) doesn't claim the group
+        let mut uncopied = SameTokenTracker::new(SameTokenPolicy::First);
+        assert!(uncopied.admit(whale_event("0xa", 100.0, 0.50)).is_some());
+        assert!(copy(&mut uncopied, whale_event("0xb", 300.0, 0.60)).is_some());
+        assert!(uncopied.admit(whale_event("0xa", 100.0, 0.50)).is_none());
+
+        // No block number (mempool): never grouped, so whale B isn't deduped against A
+        let mut unblocked = SameTokenTracker::new(SameTokenPolicy::First);
+        let mempool = |whale| ParsedEvent { block_number: 0, ..whale_event(whale, 100.0, 0.50) };
+        assert!(copy(&mut unblocked, mempool("0xa")).is_some());
+        assert!(copy(&mut unblocked, mempool("0xb")).is_some());
+        assert!(unblocked.groups.is_empty());
+
+        // blend: every event copied at its own size, priced at the running cross-whale VWAP
+        let mut blend = SameTokenTracker::new(SameTokenPolicy::Blend);
+        let out: Vec<ParsedEvent> = events().into_iter().filter_map(|e| copy(&mut blend, e)).collect();
+        assert_eq!(out.len(), 3);
+        assert!((out[0].order.price_per_share - 0.50).abs() < 1e-12);
+        assert!((out[1].order.price_per_share - 0.575).abs() < 1e-12);
+        assert!((out[2].order.price_per_share - 0.54).abs() < 1e-12);
+        let shares: Vec<f64> = out.iter().map(|e| e.order.shares).collect();
+        assert_eq!(shares, vec![100.0, 300.0, 100.0]);
+
+        assert_eq!(SameTokenPolicy::parse("FIRST").unwrap(), SameTokenPolicy::First);
+        assert_eq!(SameTokenPolicy::parse("blend").unwrap(), SameTokenPolicy::Blend);
+        assert_eq!(SameTokenPolicy::parse("sum").unwrap(), SameTokenPolicy::Blend);
+        assert_eq!(SameTokenPolicy::parse("").unwrap(), SameTokenPolicy::All);
+        assert!(SameTokenPolicy::parse("x").is_err());
+    }
+
+    #[test]
+    fn test_opposing_signal_policies() {
+        // Whale A buys 100, whale B sells 60 of the same token right after
+        let pair = || {
+            let mut sell = whale_event("0xb", 60.0, 0.50);
+            sell.order.order_type = "SELL_FILL".into();
+            [whale_event("0xa", 100.0, 0.50), sell]
+        };
+        let window = Duration::from_secs(3);
+        let t0 = Instant::now();
+        // Every admitted event is copied
+        let copy = |tracker: &mut OpposingSignalTracker, e: ParsedEvent, now: Instant| {
+            let e = tracker.admit(e, now)?;
+            tracker.record(&e, now);
+            Some(e)
+        };
+        let run = |policy| {
+            let mut tracker = OpposingSignalTracker::new(policy, window);
+            pair().into_iter().filter_map(|e| copy(&mut tracker, e, t0)).map(|e| (e.order.order_type, e.order.shares)).collect::<Vec<_>>()
+        };
+
+        // both: each side copied as is
+        assert_eq!(run(OpposingSignalPolicy::Both), vec![("BUY_FILL".into(), 100.0), ("SELL_FILL".into(), 60.0)]);
+        // first: the earlier buy wins, the sell is dropped
+        assert_eq!(run(OpposingSignalPolicy::First), vec![("BUY_FILL".into(), 100.0)]);
+        // net: the sell is fully offset by the open buy
+        assert_eq!(run(OpposingSignalPolicy::Net), vec![("BUY_FILL".into(), 100.0)]);
+
+        // net: a larger opposing sell copies only its residual; the buy's volume is then used up
+        let mut net = OpposingSignalTracker::new(OpposingSignalPolicy::Net, window);
+        assert!(copy(&mut net, whale_event("0xa", 100.0, 0.50), t0).is_some());
+        let mut sell = whale_event("0xb", 150.0, 0.50);
+        sell.order.order_type = "SELL_FILL".into();
+        let residual = copy(&mut net, sell.clone(), t0).unwrap();
+        assert_eq!(residual.order.shares, 50.0);
+        assert!((residual.order.usd_value - 25.0).abs() < 1e-9);
+        // The residual sell (not the whale's 150) is what's open against later buys
+        let mut buy = whale_event("0xc", 80.0, 0.50);
+        assert_eq!(net.admit(buy.clone(), t0).unwrap().order.shares, 30.0);
+        buy.whale_address = "0xb".into();
+        assert_eq!(net.admit(buy, t0).unwrap().order.shares, 80.0);
+        sell.whale_address = "0xc".into();
+        sell.order.shares = 30.0;
+        assert_eq!(net.admit(sell.clone(), t0).unwrap().order.shares, 30.0);
+
+        // Only copies count: a buy that was admitted but not copied opposes nothing
+        let mut uncopied = OpposingSignalTracker::new(OpposingSignalPolicy::First, window);
+        assert!(uncopied.admit(whale_event("0xa", 100.0, 0.50), t0).is_some());
+        assert!(uncopied.admit(sell.clone(), t0).is_some());
+        let mut uncopied_net = OpposingSignalTracker::new(OpposingSignalPolicy::Net, window);
+        assert!(uncopied_net.admit(whale_event("0xa", 100.0, 0.50), t0).is_some());
+        assert_eq!(uncopied_net.admit(sell.clone(), t0).unwrap().order.shares, 30.0);
+
+        // A whale's own flip and trades outside the window aren't opposing signals
+        let mut first = OpposingSignalTracker::new(OpposingSignalPolicy::First, window);
+        assert!(copy(&mut first, whale_event("0xa", 100.0, 0.50), t0).is_some());
+        let mut own = sell.clone();
+        own.whale_address = "0xa".into();
+        assert!(first.admit(own, t0).is_some());
+        assert!(first.admit(sell.clone(), t0).is_none());
+        assert!(first.admit(sell, t0 + Duration::from_secs(4)).is_some());
+
+        assert_eq!(OpposingSignalPolicy::parse(" NET ").unwrap(), OpposingSignalPolicy::Net);
+        assert_eq!(OpposingSignalPolicy::parse("first").unwrap(), OpposingSignalPolicy::First);
+        assert_eq!(OpposingSignalPolicy::parse("").unwrap(), OpposingSignalPolicy::Both);
+        assert!(OpposingSignalPolicy::parse("last").is_err());
+    }
+
+    #[test]
+    fn test_whale_price_tick_aligned() {
+        // usd/shares noise lands on the tick below/above
+        assert_eq!(TickRounding::Nearest.apply(0.4999999, 0.01, true), Some(0.50));
+        assert_eq!(TickRounding::Nearest.apply(0.5049, 0.01, true), Some(0.50));
+        assert_eq!(TickRounding::Nearest.apply(0.1234567, 0.001, true), Some(0.123));
+        // Clamped into the valid range
+        assert_eq!(TickRounding::Nearest.apply(0.003, 0.01, true), Some(0.01));
+        assert_eq!(TickRounding::Nearest.apply(0.998, 0.01, true), Some(0.99));
+        // Off: untouched
+        assert_eq!(TickRounding::Off.apply(0.4999999, 0.01, true), Some(0.4999999));
+        assert_eq!(TickRounding::parse("OFF").unwrap(), TickRounding::Off);
+        assert_eq!(TickRounding::parse("").unwrap(), TickRounding::Nearest);
+
+        // Tick comes from the cache; a 0.001 market keeps its third decimal
+        crate::market_cache::global_caches().set_tick_size("fine_tick_tok".into(), 0.001);
+        let mut evt = event("BUY_FILL", 1000.0, 0.4567891);
+        evt.order.clob_token_id = Arc::from("fine_tick_tok");
+        assert_eq!(tick_align_whale_price(evt, TickRounding::Nearest).unwrap().order.price_per_share, 0.457);
+        let uncached = tick_align_whale_price(event("BUY_FILL", 1000.0, 0.4567891), TickRounding::Nearest).unwrap();
+        assert_eq!(uncached.order.price_per_share, 0.46);
+
+        // Buffer math then starts from a clean tick: 0.50 + 0.01
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+        let evt = tick_align_whale_price(event("BUY_FILL", 5000.0, 0.4999999), TickRounding::Nearest).unwrap();
+        let plan = plan_order(&evt, &mut guard, &params(), no_book, &mut DecisionTrace::disabled()).unwrap();
+        assert_eq!(plan.limit_price, 0.51);
+    }
+
+    #[test]
+    fn test_off_tick_whale_price_policies() {
+        // $50.60 for 100 shares: 0.506, between cent ticks
+        let buy = event("BUY_FILL", 100.0, 0.506);
+        let sell = event("SELL_FILL", 100.0, 0.506);
+        let price = |mode, evt: &ParsedEvent| tick_align_whale_price(evt.clone(), mode).map(|e| e.order.price_per_share);
+
+        assert_eq!(TickRounding::parse("round_toward_whale").unwrap(), TickRounding::TowardWhale);
+        assert_eq!(TickRounding::parse("Aggressive").unwrap(), TickRounding::Aggressive);
+        assert_eq!(TickRounding::parse("reject").unwrap(), TickRounding::Reject);
+        assert!(TickRounding::parse("round_up").is_err());
+
+        // Toward the whale: never pay more / sell for less than it did
+        assert_eq!(price(TickRounding::TowardWhale, &buy), Ok(0.50));
+        assert_eq!(price(TickRounding::TowardWhale, &sell), Ok(0.51));
+        // Aggressive: the side that fills
+        assert_eq!(price(TickRounding::Aggressive, &buy), Ok(0.51));
+        assert_eq!(price(TickRounding::Aggressive, &sell), Ok(0.50));
+        // Reject skips the trade
+        let err = price(TickRounding::Reject, &buy).unwrap_err();
+        assert_eq!(err.code, StatusCode::SkippedOffTick, "{err}");
+        assert_eq!(price(TickRounding::Nearest, &buy), Ok(0.51));
+
+        // Float noise is not off-tick: every policy snaps it, none rejects
+        let noisy = event("BUY_FILL", 100.0, 0.4999999);
+        for mode in [TickRounding::TowardWhale, TickRounding::Aggressive, TickRounding::Reject] {
+            assert_eq!(price(mode, &noisy), Ok(0.50));
+        }
+
+        // Resubmit chases round the same way (Reject falls back to nearest mid-chain)
+        assert_eq!(TickRounding::TowardWhale.round_price(0.5151, 0.01, true), 0.51);
+        assert_eq!(TickRounding::Aggressive.round_price(0.5151, 0.01, true), 0.52);
+        assert_eq!(TickRounding::Reject.round_price(0.5151, 0.01, true), 0.52);
+    }
+
+    #[test]
+    fn test_tenth_cent_tick_market_pricing() {
+        crate::market_cache::global_caches().set_tick_size("tenth_cent_tok".into(), 0.001);
+        let tenth_cent = |shares: f64, price: f64| {
+            let mut evt = event("BUY_FILL", shares, price);
+            evt.order.clob_token_id = Arc::from("tenth_cent_tok");
+            evt
+        };
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+
+        // 4000+ tier: 0.457 + 0.01 buffer stays on the 0.001 grid
+        let plan = plan_order(&tenth_cent(5000.0, 0.457), &mut guard, &params(), no_book, &mut DecisionTrace::disabled()).unwrap();
+        assert_eq!(plan.limit_price, 0.467);
+        // A cent market would snap the same whale price to 0.46 first
+        let plan = plan_order(&event("BUY_FILL", 5000.0, 0.46), &mut guard, &params(), no_book, &mut DecisionTrace::disabled()).unwrap();
+        assert_eq!(plan.limit_price, 0.47);
+
+        // Limits near 1 clamp to 0.999 rather than 0.99
+        let (limit, capped) = limit_for(0.995, 0.01, true, None, 0.001);
+        assert_eq!((limit, capped), (0.999, false));
+        assert_eq!(limit_for(0.995, 0.01, true, None, 0.01).0, 0.99);
+        // Premium cap lands on the tick below it
+        assert_eq!(limit_for(0.457, 0.01, true, Some(0.4625), 0.001), (0.462, true));
+        assert_eq!(limit_for(0.45, 0.02, true, Some(0.4625), 0.01), (0.46, true));
+
+        assert_eq!(snap_to_tick(0.4567891, 0.001), 0.457);
+        assert_eq!(floor_to_tick(0.52, 0.01), 0.52);
+    }
+
+    #[test]
+    fn test_submit_price_clamped_on_both_paths() {
+        use crate::settings::calculate_next_price_with;
+        let market = PriceBounds::default();
+        let narrow = PriceBounds { min: Some(0.05), max: Some(0.95) };
+        let rounding = TickRounding::Nearest;
+
+        // Initial order: a buffer past the top of the book, and resubmit chasing past it
+        let (initial, _) = limit_for(0.99, 0.02, true, None, 0.01);
+        let chased = calculate_next_price_with(rounding, 0.99, true, true, 0.01);
+        for price in [initial, chased, 1.2] {
+            assert_eq!(submit_price(price, 0.01, &market), 0.99);
+            assert_eq!(submit_price(price, 0.01, &narrow), 0.95);
+        }
+        // Sells at the bottom clamp the same way
+        let (initial, _) = limit_for(0.01, 0.02, false, None, 0.01);
+        let chased = calculate_next_price_with(rounding, 0.01, false, true, 0.01);
+        for price in [initial, chased, -0.3] {
+            assert_eq!(submit_price(price, 0.01, &market), 0.01);
+            assert_eq!(submit_price(price, 0.01, &narrow), 0.05);
+        }
+
+        // 0.001 markets keep their wider range unless bounds narrow it
+        assert_eq!(submit_price(0.9996, 0.001, &market), 0.999);
+        assert_eq!(submit_price(0.0004, 0.001, &market), 0.001);
+        assert_eq!(submit_price(0.9996, 0.001, &narrow), 0.95);
+        // Bounds round inward onto the grid and never widen past [tick, 1 - tick]
+        let odd = PriceBounds { min: Some(0.0005), max: Some(0.9555) };
+        assert_eq!(odd.range(0.01), (0.01, 0.95));
+        assert_eq!(odd.range(0.001), (0.001, 0.955));
+        // Crossed bounds fall back to the market range
+        assert_eq!(PriceBounds { min: Some(0.6), max: Some(0.4) }.range(0.01), (0.01, 0.99));
+        // In-range prices only snap
+        assert_eq!(submit_price(0.4999999, 0.01, &narrow), 0.50);
+    }
+
+    #[test]
+    fn test_follow_sides_filter() {
+        let (buy, sell) = (true, false);
+
+        assert!(FollowSides::Both.allows(buy) && FollowSides::Both.allows(sell));
+        assert!(FollowSides::Buy.allows(buy) && !FollowSides::Buy.allows(sell));
+        assert!(!FollowSides::Sell.allows(buy) && FollowSides::Sell.allows(sell));
+
+        assert_eq!(FollowSides::parse(" SELL ").unwrap(), FollowSides::Sell);
+        assert_eq!(FollowSides::parse("buy").unwrap(), FollowSides::Buy);
+        assert_eq!(FollowSides::parse("").unwrap(), FollowSides::Both);
+        assert!(FollowSides::parse("exits").is_err());
+
+        // Default (env unset): both sides reach sizing, and the step is traced
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+        for order_type in ["BUY_FILL", "SELL_FILL"] {
+            let evt = event(order_type, 5000.0, 0.50);
+            let mut trace = DecisionTrace::new(&evt);
+            assert!(plan_order(&evt, &mut guard, &params(), no_book, &mut trace).is_ok(), "{order_type}");
+            assert!(trace.finish(&StatusCode::OrderPosted.into()).unwrap().contains("side_filter"));
+        }
+    }
+
+    #[test]
+    fn test_follow_fraction_sizing() {
+        let fraction = SizingParams { follow_fraction: Some(0.02), fraction_ignores_multiplier: false, ..*LIVE_SIZING };
+        let size = |params: &SizingParams, whale: f64, mult: f64| calculate_size_with(params, whale, 0.50, mult, 0.0).0;
+
+        // 2% of the whale's shares, tier multiplier applied after
+        assert!((size(&fraction, 1000.0, 1.0) - 20.0).abs() < 1e-9);
+        assert!((size(&fraction, 5000.0, 1.25) - 125.0).abs() < 1e-9);
+        assert!((size(&fraction, 250.0, 1.0) - 5.0).abs() < 1e-9);
+        // ... or ignored
+        let replace = SizingParams { fraction_ignores_multiplier: true, ..fraction };
+        assert!((size(&replace, 5000.0, 1.25) - 100.0).abs() < 1e-9);
+        // Still floored by MIN_CASH_VALUE: 50 * 0.02 = 1 share < 2.02 -> probabilistic floor
+        let (shares, size_type) = calculate_size_with(&fraction, 50.0, 0.50, 1.0, 0.0);
+        assert!((shares - MIN_CASH_VALUE / 0.50).abs() < 1e-9);
+        assert!(matches!(size_type, SizeType::ProbHit(_)));
+        // Ratio mode untouched
+        let ratio = SizingParams { follow_fraction: None, scaling_ratio: 0.05, ..fraction };
+        assert!((size(&ratio, 1000.0, 2.0) - 100.0).abs() < 1e-9);
+
+        assert_eq!(parse_follow_fraction("0.02"), Some(0.02));
+        assert_eq!(parse_follow_fraction(" 2.5% "), Some(0.025));
+        assert_eq!(parse_follow_fraction("0"), None);
+        assert_eq!(parse_follow_fraction("150%"), None);
+        assert_eq!(parse_follow_fraction(""), None);
+    }
+
+    #[test]
+    fn test_allocation_sizing() {
+        let params = SizingParams {
+            allocation_bankroll_usd: Some(1_000.0),
+            allocation_max_fraction: 0.10,
+            probabilistic: true,
+            ..*LIVE_SIZING
+        };
+        let size = |whale_shares: f64, portfolio: Option<f64>, mult: f64, roll: f64| {
+            size_copy(&params, whale_shares, 0.50, portfolio, 0.50, mult, roll)
+        };
+
+        // Whale puts $500 of a $10k portfolio in (5%): 5% of our $1000 = $50 = 100 shares
+        let (shares, size_type) = size(1_000.0, Some(10_000.0), 1.0, 0.0);
+        assert!((shares - 100.0).abs() < 1e-9);
+        assert!(matches!(size_type, SizeType::Scaled));
+        // Tier / conviction multiplier scales the fraction
+        assert!((size(1_000.0, Some(10_000.0), 1.5, 0.0).0 - 150.0).abs() < 1e-9);
+        // "All in" is capped at ALLOCATION_MAX_FRACTION of the bankroll ($100 = 200 shares)
+        assert!((size(1_000.0, Some(500.0), 1.0, 0.0).0 - 200.0).abs() < 1e-9);
+        assert!((size(1_000.0, Some(10_000.0), 10.0, 0.0).0 - 200.0).abs() < 1e-9);
+
+        // A nibble below MIN_CASH_VALUE hits the probabilistic floor like ratio sizing
+        // ($50 of $1M = $0.05 -> 0.1 shares of a 2.02 floor)
+        let (shares, size_type) = size(100.0, Some(1_000_000.0), 1.0, 0.99);
+        assert_eq!(shares, 0.0);
+        assert!(matches!(size_type, SizeType::ProbSkip(_)));
+        let (shares, _) = size(100.0, Some(1_000_000.0), 1.0, 0.0);
+        assert!((shares - MIN_CASH_VALUE / 0.50).abs() < 1e-9);
+
+        // Portfolio unknown (or empty): ratio sizing
+        let ratio = calculate_size_with(&params, 1_000.0, 0.50, 1.0, 0.0).0;
+        assert_eq!(size(1_000.0, None, 1.0, 0.0).0, ratio);
+        assert_eq!(size(1_000.0, Some(0.0), 1.0, 0.0).0, ratio);
+        // Allocation off
+        let off = SizingParams { allocation_bankroll_usd: None, ..params };
+        assert_eq!(allocation_target(&off, 500.0, Some(10_000.0), 0.50, 1.0), None);
+    }
+
+    #[test]
+    fn test_early_skip_traced() {
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+        let evt = event("BUY_FILL", 1.0, 0.50);
+        let mut trace = DecisionTrace::new(&evt);
+        let status = plan_order(&evt, &mut guard, &params(), no_book, &mut trace).unwrap_err();
+        assert_eq!(status, Status::with_message(StatusCode::SkippedSmall, "<10 shares"));
+        assert_eq!(trace.rejected_by, Some("min_whale_shares"));
+        assert_eq!(trace.limit_price, None);
+    }
+}
@@ -0,0 +1,190 @@
+//! Concurrency limit for spawned WebSocket event handlers
+//! Bounds in-flight handle_event tasks (each does book/market fetches) independently of the order channel
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+use crate::status;
+
+/// What to do with an event when every handler permit is taken (HANDLER_SATURATION env var)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SaturationPolicy {
+    /// Spawn anyway and wait for a permit before running
+    #[default]
+    Queue,
+    /// Drop the event and count it
+    Drop,
+}
+
+impl SaturationPolicy {
+    /// Parse "queue" / "drop" (empty = Queue)
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "queue" => Ok(SaturationPolicy::Queue),
+            "drop" => Ok(SaturationPolicy::Drop),
+            other => anyhow::bail!("HANDLER_SATURATION must be 'queue' or 'drop' (found '{}')", other),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SaturationPolicy::Queue => "queue",
+            SaturationPolicy::Drop => "drop",
+        }
+    }
+}
+
+// ============================================================================
+// Limiter
+// ============================================================================
+
+#[derive(Clone)]
+pub struct HandlerLimiter {
+    permits: Arc<Semaphore>,
+    limit: usize,
+    policy: SaturationPolicy,
+    queued: Arc<AtomicU64>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl HandlerLimiter {
+    /// `limit` concurrent handlers (clamped to at least 1)
+    pub fn new(limit: usize, policy: SaturationPolicy) -> Self {
+        let limit = limit.max(1);
+        Self {
+            permits: Arc::new(Semaphore::new(limit)),
+            limit,
+            policy,
+            queued: Arc::new(AtomicU64::new(0)),
+            dropped: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Spawn `handler` once a permit is available. Returns false if it was dropped (Drop policy, saturated).
+    pub fn spawn<F>(&self, handler: F) -> bool
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        match Arc::clone(&self.permits).try_acquire_owned() {
+            Ok(permit) => {
+                tokio::spawn(async move {
+                    handler.await;
+                    drop(permit);
+                });
+                true
+            }
+            Err(_) if self.policy == SaturationPolicy::Drop => {
+                if self.dropped.fetch_add(1, Ordering::Relaxed) == 0 {
+                    eprintln!("⚠️ Event handlers saturated ({}): dropping events (count in /status)", self.limit);
+                }
+                self.publish();
+                false
+            }
+            Err(_) => {
+                self.queued.fetch_add(1, Ordering::Relaxed);
+                self.publish();
+                let permits = Arc::clone(&self.permits);
+                tokio::spawn(async move {
+                    let Ok(_permit) = permits.acquire_owned().await else { return };
+                    handler.await;
+                });
+                true
+            }
+        }
+    }
+
+    /// Wait until every spawned handler (running or queued for a permit) has finished
+    pub async fn wait_idle(&self) {
+        // The semaphore is fair, so this queues behind handlers already waiting for a permit
+        let _ = self.permits.acquire_many(self.limit as u32).await;
+    }
+
+    /// Handlers currently holding a permit
+    pub fn in_flight(&self) -> usize {
+        self.limit - self.permits.available_permits()
+    }
+
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Events that had to wait for a permit
+    pub fn queued(&self) -> u64 {
+        self.queued.load(Ordering::Relaxed)
+    }
+
+    fn publish(&self) {
+        status::publish("event_handlers", serde_json::json!({
+            "limit": self.limit,
+            "policy": self.policy.as_str(),
+            "in_flight": self.in_flight(),
+            "queued_total": self.queued(),
+            "dropped_total": self.dropped(),
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_caps_concurrent_handlers() {
+        let limiter = HandlerLimiter::new(3, SaturationPolicy::Queue);
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let done = Arc::new(AtomicUsize::new(0));
+
+        for _ in 0..20 {
+            let (running, peak, done) = (running.clone(), peak.clone(), done.clone());
+            assert!(limiter.spawn(async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                done.fetch_add(1, Ordering::SeqCst);
+            }));
+        }
+
+        while done.load(Ordering::SeqCst) < 20 {
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+        assert_eq!(limiter.queued(), 17);
+        assert_eq!(limiter.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_drop_policy_when_saturated() {
+        let limiter = HandlerLimiter::new(2, SaturationPolicy::Drop);
+        let (tx, rx) = tokio::sync::watch::channel(false);
+
+        let accepted = (0..5)
+            .filter(|_| {
+                let mut rx = rx.clone();
+                limiter.spawn(async move { let _ = rx.wait_for(|v| *v).await; })
+            })
+            .count();
+        assert_eq!(accepted, 2);
+        assert_eq!(limiter.dropped(), 3);
+        assert_eq!(limiter.in_flight(), 2);
+
+        tx.send(true).unwrap();
+        while limiter.in_flight() > 0 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert!(limiter.spawn(async {}));
+    }
+
+    #[test]
+    fn test_parse_policy() {
+        assert_eq!(SaturationPolicy::parse("DROP").unwrap(), SaturationPolicy::Drop);
+        assert_eq!(SaturationPolicy::parse("queue").unwrap(), SaturationPolicy::Queue);
+        assert_eq!(SaturationPolicy::parse("").unwrap(), SaturationPolicy::Queue);
+        assert!(SaturationPolicy::parse("block").is_err());
+    }
+}
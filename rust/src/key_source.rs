@@ -0,0 +1,132 @@
+//! Signing key resolution
+//! PRIVATE_KEY_FILE (0600 key file) or KEYSTORE_PATH (encrypted JSON keystore) instead of a plain PRIVATE_KEY env var
+
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{Context, Result};
+use std::env;
+use std::path::Path;
+
+/// Where the key was loaded from (for the startup log; never the key itself)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeySource {
+    Env,
+    File(String),
+    Keystore(String),
+}
+
+impl KeySource {
+    pub fn describe(&self) -> String {
+        match self {
+            KeySource::Env => "PRIVATE_KEY".to_string(),
+            KeySource::File(path) => format!("PRIVATE_KEY_FILE ({})", path),
+            KeySource::Keystore(path) => format!("KEYSTORE_PATH ({})", path),
+        }
+    }
+}
+
+/// Resolve the hex private key: KEYSTORE_PATH, then PRIVATE_KEY_FILE, then PRIVATE_KEY.
+/// Returns Ok(None) if none are set.
+pub fn resolve_private_key() -> Result<Option<(String, KeySource)>> {
+    if let Some(path) = non_empty_var("KEYSTORE_PATH") {
+        let passphrase = match non_empty_var("KEYSTORE_PASSPHRASE_FILE") {
+            Some(pass_path) => read_secret_file(&pass_path)?,
+            None => env::var("KEYSTORE_PASSPHRASE")
+                .context("KEYSTORE_PATH is set but neither KEYSTORE_PASSPHRASE nor KEYSTORE_PASSPHRASE_FILE is")?,
+        };
+        let key = read_keystore(&path, &passphrase)?;
+        return Ok(Some((key, KeySource::Keystore(path))));
+    }
+    if let Some(path) = non_empty_var("PRIVATE_KEY_FILE") {
+        let key = read_secret_file(&path)?;
+        return Ok(Some((key, KeySource::File(path))));
+    }
+    Ok(env::var("PRIVATE_KEY").ok().map(|key| (key, KeySource::Env)))
+}
+
+fn non_empty_var(key: &str) -> Option<String> {
+    env::var(key).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Read a secret (key or passphrase) from a file, trimmed. Warns if group/other can read it.
+pub fn read_secret_file(path: &str) -> Result<String> {
+    if let Some(warning) = loose_permissions_warning(Path::new(path)) {
+        eprintln!("⚠️ {}", warning);
+    }
+    let contents = std::fs::read_to_string(path).with_context(|| format!("Failed to read secret file {}", path))?;
+    Ok(contents.trim().to_string())
+}
+
+/// Decrypt a JSON keystore (Web3 Secret Storage) and return the key as hex (no 0x)
+pub fn read_keystore(path: &str, passphrase: &str) -> Result<String> {
+    let signer = PrivateKeySigner::decrypt_keystore(path, passphrase)
+        .map_err(|e| anyhow::anyhow!("Failed to decrypt keystore {}: {}", path, e))?;
+    Ok(alloy::hex::encode(signer.credential().to_bytes()))
+}
+
+/// Warning text if a secret file is readable by group/other (Unix only; None elsewhere)
+pub fn loose_permissions_warning(path: &Path) -> Option<String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(path).ok()?.permissions().mode() & 0o777;
+        (mode & 0o077 != 0).then(|| {
+            format!("{} has permissions {:o}; restrict it with `chmod 600 {}`", path.display(), mode, path.display())
+        })
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        env::temp_dir().join(format!("pm_key_source_{}_{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_read_key_file() {
+        let path = temp_path("key");
+        std::fs::write(&path, format!("{}\n", KEY)).unwrap();
+        assert_eq!(read_secret_file(path.to_str().unwrap()).unwrap(), KEY);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(read_secret_file(path.to_str().unwrap()).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_permission_warning() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = temp_path("perms");
+        std::fs::write(&path, KEY).unwrap();
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        assert_eq!(loose_permissions_warning(&path), None);
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        let warning = loose_permissions_warning(&path).unwrap();
+        assert!(warning.contains("644") && warning.contains("chmod 600"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_keystore_roundtrip() {
+        let dir = temp_path("keystore");
+        std::fs::create_dir_all(&dir).unwrap();
+        let key_bytes = alloy::hex::decode(KEY).unwrap();
+        PrivateKeySigner::encrypt_keystore(&dir, &mut rand::thread_rng(), &key_bytes, "hunter2", Some("ks.json")).unwrap();
+
+        let path = dir.join("ks.json");
+        assert_eq!(read_keystore(path.to_str().unwrap(), "hunter2").unwrap(), KEY);
+        assert!(read_keystore(path.to_str().unwrap(), "wrong").is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
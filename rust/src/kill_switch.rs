@@ -0,0 +1,98 @@
+//! File-based kill switch (KILL_SWITCH_FILE)
+//! While the file exists the order worker refuses new orders (SKIPPED_PAUSED); removing it
+//! resumes trading. Meant for scripted control: `touch STOP` / `rm STOP`.
+//! Resubmit chains already in flight and resting GTD orders are left alone
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// How often the watcher checks for the file
+pub const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Pause state change worth a log line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    None,
+    Paused,
+    Resumed,
+}
+
+#[derive(Debug, Default)]
+pub struct KillSwitch {
+    paused: AtomicBool,
+}
+
+impl KillSwitch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Pause while `path` exists, resume once it's gone
+    pub fn poll(&self, path: &Path) -> Transition {
+        let stop = path.exists();
+        match (self.paused.swap(stop, Ordering::Relaxed), stop) {
+            (false, true) => Transition::Paused,
+            (true, false) => Transition::Resumed,
+            _ => Transition::None,
+        }
+    }
+}
+
+static KILL_SWITCH: OnceLock<KillSwitch> = OnceLock::new();
+
+/// Process-wide pause flag (watcher and order worker)
+pub fn global() -> &'static KillSwitch {
+    KILL_SWITCH.get_or_init(KillSwitch::new)
+}
+
+/// Poll `path` every POLL_INTERVAL, logging each pause / resume
+pub fn spawn_watcher(path: PathBuf) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        println!("🛑 Kill switch: trading pauses while {} exists", path.display());
+        let mut tick = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            tick.tick().await;
+            match global().poll(&path) {
+                Transition::Paused => eprintln!("🛑 Trading PAUSED: kill switch file {} present", path.display()),
+                Transition::Resumed => println!("▶️ Trading resumed: kill switch file {} removed", path.display()),
+                Transition::None => continue,
+            }
+            crate::status::publish("kill_switch", serde_json::json!({
+                "file": path.display().to_string(),
+                "paused": global().is_paused(),
+            }));
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stop_file_toggles_paused() {
+        let path = std::env::temp_dir().join(format!("pm_kill_switch_test_{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let switch = KillSwitch::new();
+
+        assert_eq!(switch.poll(&path), Transition::None);
+        assert!(!switch.is_paused());
+
+        std::fs::write(&path, "").unwrap();
+        assert_eq!(switch.poll(&path), Transition::Paused);
+        assert!(switch.is_paused());
+        // Still there: stays paused, no repeat log
+        assert_eq!(switch.poll(&path), Transition::None);
+        assert!(switch.is_paused());
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(switch.poll(&path), Transition::Resumed);
+        assert!(!switch.is_paused());
+    }
+}
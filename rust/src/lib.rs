@@ -1,12 +1,10 @@
-/// PM Whale Follower - Polymarket trading bot library
-/// Core library for interacting with Polymarket CLOB API
+//! PM Whale Follower - Polymarket trading bot library
+//! Core library for interacting with Polymarket CLOB API
 use std::time::{SystemTime, UNIX_EPOCH, Duration};
 
 use anyhow::{Result, anyhow};
 use base64::Engine;
 use base64::engine::general_purpose::URL_SAFE;
-use alloy::signers::local::PrivateKeySigner;
-use alloy::signers::SignerSync;
 use alloy::primitives::{B256, U256};
 use alloy::dyn_abi::eip712::TypedData;
 use hmac::{Hmac, Mac};
@@ -18,6 +16,7 @@ use std::collections::HashMap;
 use std::fs;
 use itoa::Buffer as ItoaBuffer;
 use std::path::Path;
+use std::sync::Arc;
 
 pub mod profiler;
 pub use profiler::{ops, PROFILER};
@@ -27,6 +26,38 @@ pub mod tennis_markets;
 pub mod soccer_markets;
 pub mod settings;
 pub mod models;
+pub mod status;
+pub mod block_resolver;
+pub mod csv_log;
+pub mod engine;
+pub mod decode;
+pub mod resting_orders;
+pub mod handler_limit;
+pub mod key_source;
+pub mod signer;
+pub mod reconnect;
+pub mod positions;
+pub mod block_summary;
+pub mod session_summary;
+pub mod worker_pool;
+pub mod notify;
+pub mod watchdog;
+pub mod retry;
+pub mod gamma_health;
+pub mod run_limits;
+pub mod self_test;
+pub mod order_queue;
+pub mod status_code;
+pub mod replay;
+pub mod shutdown;
+pub mod mempool;
+pub mod kill_switch;
+pub mod submit_jitter;
+pub mod whale_portfolio;
+pub mod rng;
+pub mod live_flip;
+pub mod confirm;
+pub mod decision_socket;
 
 #[cfg(test)]
 mod resubmit_tests;
@@ -118,6 +149,56 @@ pub struct OrderResponse {
     pub making_amount: String,
 }
 
+impl OrderResponse {
+    /// Parse a POST /order response body
+    pub fn parse(body: &str) -> Option<Self> {
+        serde_json::from_str(body).ok()
+    }
+
+    /// Settlement tx hashes joined with ';' (CSV-safe; empty until matched)
+    pub fn tx_hashes(&self) -> String {
+        self.transactions_hashes.join(";")
+    }
+
+    /// Average price of the immediate fill, USDC per share (a buy makes USDC and takes shares,
+    /// a sell the reverse). None if nothing filled
+    pub fn avg_fill_price(&self, side_is_buy: bool) -> Option<f64> {
+        let (usdc, shares) = if side_is_buy {
+            (&self.making_amount, &self.taking_amount)
+        } else {
+            (&self.taking_amount, &self.making_amount)
+        };
+        let usdc: f64 = usdc.parse().ok()?;
+        let shares: f64 = shares.parse().ok()?;
+        (usdc > 0.0 && shares > 0.0).then(|| usdc / shares)
+    }
+}
+
+/// Whether a DELETE /order response lists `order_id` as cancelled. An order that already
+/// filled (or expired) comes back under not_canceled instead
+pub fn cancel_confirmed(body: &str, order_id: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| v["canceled"].as_array().map(|ids| ids.iter().any(|id| id.as_str() == Some(order_id))))
+        .unwrap_or(false)
+}
+
+/// Shares matched so far from a GET /data/order response (`size_matched`, a decimal string)
+pub fn parse_size_matched(body: &str) -> Option<f64> {
+    let v: serde_json::Value = serde_json::from_str(body).ok()?;
+    match &v["size_matched"] {
+        serde_json::Value::String(s) => s.parse().ok(),
+        other => other.as_f64(),
+    }
+}
+
+/// How much better our fill price was than the whale's, per share: positive when we bought
+/// cheaper or sold dearer, negative for slippage
+#[inline]
+pub fn copy_price_delta(whale_price: f64, fill_price: f64, side_is_buy: bool) -> f64 {
+    if side_is_buy { whale_price - fill_price } else { fill_price - whale_price }
+}
+
 // ============================================================================
 // PREPARED CREDENTIALS 
 // ============================================================================
@@ -169,12 +250,37 @@ impl PreparedCreds {
     }
 }
 
+/// HTTP timeouts for the CLOB client and the bot's other API calls (Config::http_timeouts)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HttpTimeouts {
+    /// TCP connect plus TLS handshake (reqwest has no separate handshake timeout)
+    pub connect: Duration,
+    /// Whole request, connect to last body byte, unless a call sets its own
+    pub request: Duration,
+    /// Book fetches in front of an order (risk guard depth, pre-submit checks)
+    pub book: Duration,
+    /// Each Gamma lookup for a token's live flag
+    pub gamma: Duration,
+}
+
+impl Default for HttpTimeouts {
+    fn default() -> Self {
+        Self {
+            connect: Duration::from_secs(2),
+            request: Duration::from_secs(5),
+            book: Duration::from_millis(500),
+            gamma: Duration::from_secs(2),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct RustClobClient {
     host: String,
     chain_id: u64,
-    wallet: PrivateKeySigner,
+    signer: Arc<dyn signer::Signer>,
     http: Client,
+    timeouts: HttpTimeouts,
     funder: String,
     signature_type: i32,
     neg_risk_cache: HashMap<String, bool>,
@@ -184,8 +290,22 @@ pub struct RustClobClient {
 
 impl RustClobClient {
     pub fn new(host: &str, chain_id: u64, private_key: &str, funder: &str) -> Result<Self> {
-        let wallet: PrivateKeySigner = private_key.parse()
-            .map_err(|e| anyhow!("Failed to parse private key: {}", e))?;
+        Self::with_signer(host, chain_id, Arc::new(signer::LocalSigner::from_hex(private_key)?), funder)
+    }
+
+    /// Client that signs orders and auth headers through `signer` (local key, remote endpoint, ...)
+    pub fn with_signer(host: &str, chain_id: u64, signer: Arc<dyn signer::Signer>, funder: &str) -> Result<Self> {
+        Self::with_signer_and_timeouts(host, chain_id, signer, funder, HttpTimeouts::default())
+    }
+
+    /// with_signer, with the HTTP client built from `timeouts`
+    pub fn with_signer_and_timeouts(
+        host: &str,
+        chain_id: u64,
+        signer: Arc<dyn signer::Signer>,
+        funder: &str,
+        timeouts: HttpTimeouts,
+    ) -> Result<Self> {
         let http = Client::builder()
             // Connection pooling 
             .pool_max_idle_per_host(8)
@@ -196,21 +316,22 @@ impl RustClobClient {
             .tcp_nodelay(true)  
 
             // Timeouts
-            .timeout(Duration::from_secs(5))
-            .connect_timeout(Duration::from_secs(2))
+            .timeout(timeouts.request)
+            .connect_timeout(timeouts.connect)
 
             .connection_verbose(false)
             .no_proxy()
             .user_agent(USER_AGENT)
 
             .build()?;
-        let wallet_address_str = format!("{}", wallet.address());
+        let wallet_address_str = format!("{}", signer.address());
 
         Ok(Self {
             host: host.trim_end_matches('/').to_string(),
             chain_id,
-            wallet,
+            signer,
             http,
+            timeouts,
             funder: funder.to_string(),
             signature_type: 1,
             neg_risk_cache: HashMap::with_capacity(256),
@@ -226,11 +347,12 @@ impl RustClobClient {
 
     pub fn load_cache(&mut self) -> Result<()> {
         profile!(ops::CACHE_LOAD);
-        if let Some(ref p) = self.cache_path
-            && Path::new(p).exists() {
+        if let Some(ref p) = self.cache_path {
+            if Path::new(p).exists() {
                 let data = fs::read_to_string(p)?;
                 self.neg_risk_cache = serde_json::from_str(&data)?;
             }
+        }
         Ok(())
     }
 
@@ -250,6 +372,12 @@ impl RustClobClient {
         Ok(resp.text()?)
     }
 
+    /// Local clock minus CLOB server clock, in seconds (positive = local is ahead)
+    pub fn measure_clock_skew(&self) -> Result<i64> {
+        let body = self.get_time()?;
+        clock_skew_secs(&body, current_unix_ts())
+    }
+
     pub fn derive_api_key(&self, nonce: u64) -> Result<ApiCreds> {
         let url = build_url_1(&self.host, "/auth/derive-api-key");
         let resp = self.http.get(url).headers(self.l1_headers(nonce)?).send()?;
@@ -262,8 +390,7 @@ impl RustClobClient {
     pub fn l1_headers(&self, nonce: u64) -> Result<HeaderMap> {
         let timestamp = current_unix_ts();
         let digest = clob_auth_digest(self.chain_id, &self.wallet_address_str, timestamp, nonce)?;
-        let sig = self.wallet.sign_hash_sync(&digest)
-            .map_err(|e| anyhow!("Failed to sign: {}", e))?;
+        let sig = self.signer.sign_digest(&digest.0)?;
 
         let mut itoa_buf = ItoaBuffer::new();
 
@@ -308,10 +435,47 @@ impl RustClobClient {
         Ok(self.http.post(url).headers(headers).body(body).send()?)
     }
 
+    /// List our API keys (GET /auth/api-keys): a cheap authenticated call to check creds
+    pub fn get_api_keys(&self, creds: &PreparedCreds) -> Result<reqwest::blocking::Response> {
+        let path = "/auth/api-keys";
+        let url = build_url_1(&self.host, path);
+        let headers = self.l2_headers_fast("GET", path, None, creds)?;
+        Ok(self.http.get(url).headers(headers).send()?)
+    }
+
+    /// Cancel one open order by id (DELETE /order)
+    pub fn cancel_order(&self, order_id: &str, creds: &PreparedCreds) -> Result<reqwest::blocking::Response> {
+        let path = "/order";
+        let url = build_url_1(&self.host, path);
+        let body = serde_json::json!({ "orderID": order_id }).to_string();
+        let headers = self.l2_headers_fast("DELETE", path, Some(&body), creds)?;
+        Ok(self.http.delete(url).headers(headers).body(body).send()?)
+    }
+
+    /// One order by id, including how much of it matched (GET /data/order/{id})
+    pub fn get_order(&self, order_id: &str, creds: &PreparedCreds) -> Result<reqwest::blocking::Response> {
+        let path = format!("/data/order/{}", order_id);
+        let url = build_url_1(&self.host, &path);
+        let headers = self.l2_headers_fast("GET", &path, None, creds)?;
+        Ok(self.http.get(url).headers(headers).send()?)
+    }
+
+    /// Our balance of one outcome token (GET /balance-allowance, asset_type=CONDITIONAL)
+    pub fn get_token_balance(&self, token_id: &str, creds: &PreparedCreds) -> Result<reqwest::blocking::Response> {
+        let path = "/balance-allowance";
+        // Query string is not part of the signed path
+        let url = format!(
+            "{}{}?asset_type=CONDITIONAL&token_id={}&signature_type={}",
+            self.host, path, token_id, self.signature_type
+        );
+        let headers = self.l2_headers_fast("GET", path, None, creds)?;
+        Ok(self.http.get(url).headers(headers).send()?)
+    }
+
     pub fn create_order(&mut self, args: OrderArgs) -> Result<SignedOrder> {
         profile!(ops::CREATE_ORDER);
 
-        let tick = "0.01";
+        let tick = tick_str(market_cache::tick_size(&args.token_id));
 
         // Check global market cache first (periodically refreshed from disk)
         let neg_risk = if let Some(n) = market_cache::is_neg_risk(&args.token_id) {
@@ -338,13 +502,13 @@ impl RustClobClient {
             return Err(anyhow!("price {} outside allowed range", args.price));
         }
 
-        let is_fak = args.order_type.as_ref().map_or(true, |t| t.eq_ignore_ascii_case("FAK"));
+        let is_fak = args.order_type.as_ref().is_none_or(|t| t.eq_ignore_ascii_case("FAK"));
 
-        let round_cfg = round_config(tick)?;
+        let decimals = market_cache::collateral_decimals(&args.token_id, *settings::COLLATERAL_DECIMALS);
         let (side_code, maker_amt, taker_amt) = if args.side.eq_ignore_ascii_case("BUY") {
-            get_order_amounts_buy(args.size, args.price, &round_cfg, is_fak)?
+            get_order_amounts_buy(args.size, args.price, is_fak, decimals)?
         } else if args.side.eq_ignore_ascii_case("SELL") {
-            get_order_amounts_sell(args.size, args.price, &round_cfg, is_fak)?
+            get_order_amounts_sell(args.size, args.price, is_fak, decimals)?
         } else {
             return Err(anyhow!("side must be BUY or SELL"));
         };
@@ -395,7 +559,7 @@ impl RustClobClient {
         profile!(ops::CREATE_ORDER_SIGN);
         let digest = typed.eip712_signing_hash()
             .map_err(|e| anyhow!("EIP-712 encoding failed: {}", e))?;
-        let sig = self.wallet.sign_hash_sync(&digest)
+        let sig = self.signer.sign_digest(&digest.0)
             .map_err(|e| anyhow!("Failed to sign order: {}", e))?;
 
         let order = SignedOrder {
@@ -406,8 +570,51 @@ impl RustClobClient {
         Ok(order)
     }
 
+    /// Recover the address that signed `order` from its EIP-712 digest (local check, no API call)
+    pub fn recover_order_signer(&self, order: &SignedOrder, neg_risk: bool) -> Result<alloy::primitives::Address> {
+        let o = &order.order;
+        let parse = |name: &str, v: &str| v.parse::<U256>().map_err(|e| anyhow!("bad {} {}: {}", name, v, e));
+        let data = OrderData {
+            maker: o.maker.clone(),
+            taker: o.taker.clone(),
+            token_id: o.token_id.clone(),
+            token_id_u256: parse("tokenId", &o.token_id)?,
+            maker_amount: o.maker_amount.clone(),
+            maker_amount_u256: parse("makerAmount", &o.maker_amount)?,
+            taker_amount: o.taker_amount.clone(),
+            taker_amount_u256: parse("takerAmount", &o.taker_amount)?,
+            side: o.side,
+            fee_rate_bps: o.fee_rate_bps.clone(),
+            nonce: o.nonce.clone(),
+            nonce_u256: parse("nonce", &o.nonce)?,
+            signer: o.signer.clone(),
+            expiration: o.expiration.clone(),
+            expiration_u256: parse("expiration", &o.expiration)?,
+            signature_type: o.signature_type,
+            salt: o.salt,
+        };
+        let exchange = get_exchange_address(self.chain_id, neg_risk)
+            .ok_or_else(|| anyhow!("unsupported chain"))?;
+        let digest = order_typed_data(self.chain_id, exchange, &data)?
+            .eip712_signing_hash()
+            .map_err(|e| anyhow!("EIP-712 encoding failed: {}", e))?;
+        let sig: alloy::primitives::Signature = order.signature.parse()
+            .map_err(|e| anyhow!("bad order signature: {}", e))?;
+        sig.recover_address_from_prehash(&digest)
+            .map_err(|e| anyhow!("order signature doesn't recover: {}", e))
+    }
+
+    /// Address orders are signed with
+    pub fn signer_address(&self) -> alloy::primitives::Address { self.signer.address() }
+
     pub fn http_client(&self) -> &reqwest::blocking::Client { &self.http }
 
+    /// Timeouts the HTTP client was built with
+    pub fn timeouts(&self) -> HttpTimeouts { self.timeouts }
+
+    /// CLOB API base URL this client talks to
+    pub fn host(&self) -> &str { &self.host }
+
     pub fn set_neg_risk(&mut self, token_id: &str, neg_risk: bool) {
         self.neg_risk_cache.insert(token_id.to_string(), neg_risk);
     }
@@ -446,6 +653,102 @@ fn current_unix_ts() -> u64 {
     SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
 }
 
+/// Skew of the local clock against a `/time` response body (unix seconds)
+pub fn clock_skew_secs(server_time_body: &str, local_unix_secs: u64) -> Result<i64> {
+    let server: u64 = server_time_body
+        .trim()
+        .trim_matches('"')
+        .parse()
+        .map_err(|_| anyhow!("unexpected /time response: {}", server_time_body))?;
+    Ok(local_unix_secs as i64 - server as i64)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockSkewCheck {
+    Ok,
+    Warn,
+    Refuse,
+}
+
+/// Classify skew against thresholds (max_secs = 0 never refuses)
+pub fn classify_clock_skew(skew_secs: i64, warn_secs: u64, max_secs: u64) -> ClockSkewCheck {
+    let abs = skew_secs.unsigned_abs();
+    if max_secs > 0 && abs > max_secs {
+        ClockSkewCheck::Refuse
+    } else if abs > warn_secs {
+        ClockSkewCheck::Warn
+    } else {
+        ClockSkewCheck::Ok
+    }
+}
+
+/// What to do at startup when the CLOB rejects the cached API creds (STALE_CREDS env var)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StaleCredsPolicy {
+    /// Derive fresh creds and overwrite the creds file; exit if that fails too
+    #[default]
+    Rederive,
+    /// Exit with an error
+    Exit,
+    /// Don't check the cached creds
+    Off,
+}
+
+impl StaleCredsPolicy {
+    /// Parse "rederive" / "exit" / "off" (empty = Rederive)
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "rederive" => Ok(StaleCredsPolicy::Rederive),
+            "exit" => Ok(StaleCredsPolicy::Exit),
+            "off" => Ok(StaleCredsPolicy::Off),
+            other => Err(anyhow!("STALE_CREDS must be 'rederive', 'exit' or 'off' (found '{}')", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredsCheck {
+    Valid,
+    /// 401/403: revoked or rotated server-side
+    Rejected,
+    /// Network error or other status: can't tell, so the creds are kept
+    Unknown,
+}
+
+/// Classify the HTTP status of an authenticated no-op (None = request failed)
+pub fn classify_creds_check(status: Option<u16>) -> CredsCheck {
+    match status {
+        Some(200..=299) => CredsCheck::Valid,
+        Some(401 | 403) => CredsCheck::Rejected,
+        _ => CredsCheck::Unknown,
+    }
+}
+
+/// Check cached creds; on rejection apply `policy`. Re-derived creds are checked too.
+/// Returns the creds to use and whether they were re-derived (and should be saved)
+pub fn validate_or_rederive<V, D>(
+    cached: ApiCreds,
+    policy: StaleCredsPolicy,
+    mut validate: V,
+    derive: D,
+) -> Result<(ApiCreds, bool)>
+where
+    V: FnMut(&ApiCreds) -> CredsCheck,
+    D: FnOnce() -> Result<ApiCreds>,
+{
+    if policy == StaleCredsPolicy::Off || validate(&cached) != CredsCheck::Rejected {
+        return Ok((cached, false));
+    }
+    if policy == StaleCredsPolicy::Exit {
+        return Err(anyhow!("cached API creds were rejected by the CLOB (STALE_CREDS=exit); delete the creds file or set STALE_CREDS=rederive"));
+    }
+    let fresh = derive().map_err(|e| anyhow!("cached API creds were rejected and re-deriving failed: {e}"))?;
+    if validate(&fresh) == CredsCheck::Rejected {
+        return Err(anyhow!("cached API creds were rejected and freshly derived creds were rejected too; check PRIVATE_KEY / FUNDER_ADDRESS"));
+    }
+    Ok((fresh, true))
+}
+
 fn clob_auth_digest(chain_id: u64, address_str: &str, timestamp: u64, nonce: u64) -> Result<B256> {
     let json_str = format!(
         r#"{{"types":{{"EIP712Domain":[{{"name":"name","type":"string"}},{{"name":"version","type":"string"}},{{"name":"chainId","type":"uint256"}}],"ClobAuth":[{{"name":"address","type":"address"}},{{"name":"timestamp","type":"string"}},{{"name":"nonce","type":"uint256"}},{{"name":"message","type":"string"}}]}},"primaryType":"ClobAuth","domain":{{"name":"ClobAuthDomain","version":"1","chainId":{}}},"message":{{"address":"{}","timestamp":"{}","nonce":{},"message":"{}"}}}}"#,
@@ -560,25 +863,22 @@ impl OrderData {
     }
 }
 
-fn round_config(tick: &str) -> Result<RoundConfig> {
-    match tick {
-        "0.1" => Ok(RoundConfig { price: 1, size: 2, amount: 3 }),
-        "0.01" => Ok(RoundConfig { price: 2, size: 2, amount: 4 }),
-        "0.001" => Ok(RoundConfig { price: 3, size: 2, amount: 5 }),
-        "0.0001" => Ok(RoundConfig { price: 4, size: 2, amount: 6 }),
-        _ => Err(anyhow!("unsupported tick size {}", tick)),
+/// Tick size as price_valid expects it (unsupported sizes fall back to 0.01)
+fn tick_str(tick: f64) -> &'static str {
+    match (tick * 10_000.0).round() as u32 {
+        1000 => "0.1",
+        10 => "0.001",
+        1 => "0.0001",
+        _ => "0.01",
     }
 }
 
-#[derive(Debug, Clone)]
-struct RoundConfig { price: u32, size: u32, amount: u32 }
-
 fn price_valid(price: f64, tick: &str) -> bool {
     let t: f64 = tick.parse().unwrap_or(0.0);
     price >= t && price <= 1.0 - t
 }
 
-fn get_order_amounts_buy(size: f64, price: f64, _cfg: &RoundConfig, is_fak: bool) -> Result<(i32, u128, u128)> {
+fn get_order_amounts_buy(size: f64, price: f64, is_fak: bool, decimals: u8) -> Result<(i32, u128, u128)> {
     // For BUY: taker = shares we receive, maker = USDC we pay
     // FAK (market orders): USDC max 2 decimals, shares max 4 decimals
     // GTD/GTC (limit orders): USDC max 4 decimals, shares max 2 decimals
@@ -587,10 +887,10 @@ fn get_order_amounts_buy(size: f64, price: f64, _cfg: &RoundConfig, is_fak: bool
     let raw_taker = round_down(size, shares_decimals);
     let raw_maker = round_down(raw_taker * price, usdc_decimals);
 
-    Ok((0, to_token_decimals(raw_maker)?, to_token_decimals(raw_taker)?))
+    Ok((0, to_token_decimals(raw_maker, decimals)?, to_token_decimals(raw_taker, decimals)?))
 }
 
-fn get_order_amounts_sell(size: f64, price: f64, _cfg: &RoundConfig, is_fak: bool) -> Result<(i32, u128, u128)> {
+fn get_order_amounts_sell(size: f64, price: f64, is_fak: bool, decimals: u8) -> Result<(i32, u128, u128)> {
     // For SELL: maker = shares we sell, taker = USDC we receive
     // FAK (market orders): USDC max 2 decimals, shares max 4 decimals
     // GTD/GTC (limit orders): USDC max 4 decimals, shares max 2 decimals
@@ -599,7 +899,7 @@ fn get_order_amounts_sell(size: f64, price: f64, _cfg: &RoundConfig, is_fak: boo
     let raw_maker = round_down(size, shares_decimals);
     let raw_taker = round_down(raw_maker * price, usdc_decimals);
 
-    Ok((1, to_token_decimals(raw_maker)?, to_token_decimals(raw_taker)?))
+    Ok((1, to_token_decimals(raw_maker, decimals)?, to_token_decimals(raw_taker, decimals)?))
 }
 
 #[inline(always)] fn round_down(x: f64, d: u32) -> f64 { let f = 10f64.powi(d as i32); (x * f).floor() / f }
@@ -616,8 +916,24 @@ fn decimal_places_fast(x: f64) -> u32 {
     10
 }
 
-fn to_token_decimals(x: f64) -> Result<u128> {
-    let scaled = x * 1_000_000f64;
+/// Convert a raw on-chain amount to human units at the given decimals
+/// (Polymarket USDC collateral and outcome shares both use 6)
+#[inline]
+pub fn u256_to_scaled_f64(v: &U256, decimals: u8) -> Option<f64> {
+    let raw = if v.bit_len() <= 64 { v.as_limbs()[0] as f64 } else { v.to_string().parse().ok()? };
+    Some(raw / 10f64.powi(decimals as i32))
+}
+
+/// Round an amount to the smallest unit representable at `decimals` (micro-units for USDC),
+/// so float noise like 40.7999999 doesn't truncate a unit away when encoded
+#[inline]
+pub fn round_to_units(x: f64, decimals: u8) -> f64 {
+    let scale = 10f64.powi(decimals as i32);
+    (x * scale).round() / scale
+}
+
+fn to_token_decimals(x: f64, decimals: u8) -> Result<u128> {
+    let scaled = x * 10f64.powi(decimals as i32);
     let val = if decimal_places_fast(scaled) > 0 { round_normal(scaled, 0) } else { scaled };
     if val < 0.0 { return Err(anyhow!("negative amount")); }
     Ok(val as u128)
@@ -626,15 +942,275 @@ fn to_token_decimals(x: f64) -> Result<u128> {
 #[inline(always)]
 fn generate_seed() -> u128 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos() % u128::from(u32::MAX) }
 
+fn order_typed_data(chain_id: u64, exchange: &str, data: &OrderData) -> Result<TypedData> {
+    let json_str = format!(
+        concat!(
+            r#"{{"types":{{"EIP712Domain":["#,
+            r#"{{"name":"name","type":"string"}},"#,
+            r#"{{"name":"version","type":"string"}},"#,
+            r#"{{"name":"chainId","type":"uint256"}},"#,
+            r#"{{"name":"verifyingContract","type":"address"}}"#,
+            r#"],"Order":["#,
+            r#"{{"name":"salt","type":"uint256"}},"#,
+            r#"{{"name":"maker","type":"address"}},"#,
+            r#"{{"name":"signer","type":"address"}},"#,
+            r#"{{"name":"taker","type":"address"}},"#,
+            r#"{{"name":"tokenId","type":"uint256"}},"#,
+            r#"{{"name":"makerAmount","type":"uint256"}},"#,
+            r#"{{"name":"takerAmount","type":"uint256"}},"#,
+            r#"{{"name":"expiration","type":"uint256"}},"#,
+            r#"{{"name":"nonce","type":"uint256"}},"#,
+            r#"{{"name":"feeRateBps","type":"uint256"}},"#,
+            r#"{{"name":"side","type":"uint8"}},"#,
+            r#"{{"name":"signatureType","type":"uint8"}}"#,
+            r#"]}},"primaryType":"Order","#,
+            r#""domain":{{"name":"Polymarket CTF Exchange","version":"1","chainId":{},"verifyingContract":"{}"}},"#,
+            r#""message":{{"salt":"{}","maker":"{}","signer":"{}","taker":"{}","tokenId":"{}","makerAmount":"{}","takerAmount":"{}","expiration":"{}","nonce":"{}","feeRateBps":"0","side":{},"signatureType":{}}}}}"#
+        ),
+        chain_id,
+        exchange,
+        data.salt,
+        data.maker,
+        data.signer,
+        data.taker,
+        data.token_id_u256,
+        data.maker_amount_u256,
+        data.taker_amount_u256,
+        data.expiration_u256,
+        data.nonce_u256,
+        data.side,
+        data.signature_type,
+    );
+    Ok(serde_json::from_str(&json_str)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_u256_scaling_by_decimals() {
+        // 12.5 shares for 6.25 USDC encoded at 6 and at 18 decimals
+        let shares_6 = U256::from(12_500_000u64);
+        let usd_6 = U256::from(6_250_000u64);
+        let shares_18 = U256::from(12_500_000_000_000_000_000u128);
+        let usd_18 = U256::from(6_250_000_000_000_000_000u128);
+
+        assert_eq!(u256_to_scaled_f64(&shares_6, 6), Some(12.5));
+        assert_eq!(u256_to_scaled_f64(&usd_6, 6), Some(6.25));
+        assert!((u256_to_scaled_f64(&shares_18, 18).unwrap() - 12.5).abs() < 1e-9);
+        assert!((u256_to_scaled_f64(&usd_18, 18).unwrap() - 6.25).abs() < 1e-9);
+
+        // Same raw value read at the wrong scale is off by 10^12
+        assert!((u256_to_scaled_f64(&shares_6, 18).unwrap() - 12.5e-12).abs() < 1e-18);
+    }
+
+    /// Test signer: a throwaway key that records every digest it signs
+    struct MockSigner {
+        key: alloy::signers::local::PrivateKeySigner,
+        signed: std::sync::Mutex<Vec<[u8; 32]>>,
+    }
+
+    impl signer::Signer for MockSigner {
+        fn address(&self) -> alloy::primitives::Address {
+            self.key.address()
+        }
+
+        fn sign_digest(&self, digest: &[u8; 32]) -> Result<alloy::primitives::Signature> {
+            use alloy::signers::SignerSync;
+            self.signed.lock().unwrap().push(*digest);
+            Ok(self.key.sign_hash_sync(&B256::from(*digest))?)
+        }
+    }
+
+    #[test]
+    fn test_order_signed_by_configured_signer() {
+        let mock = Arc::new(MockSigner { key: alloy::signers::local::PrivateKeySigner::random(), signed: Default::default() });
+        let funder = "0x1111111111111111111111111111111111111111";
+        let mut client = RustClobClient::with_signer("http://127.0.0.1:9", 137, mock.clone(), funder).unwrap();
+        client.set_neg_risk("424242", false);
+
+        let args = OrderArgs {
+            token_id: "424242".into(),
+            price: 0.50,
+            size: 10.0,
+            side: "BUY".into(),
+            fee_rate_bps: None,
+            nonce: Some(0),
+            expiration: None,
+            taker: None,
+            order_type: Some("FAK".into()),
+        };
+        let order = client.create_order(args).unwrap();
+
+        // One digest went through the signer, and the order carries its signature and address
+        let signed = mock.signed.lock().unwrap().clone();
+        assert_eq!(signed.len(), 1);
+        let sig: alloy::primitives::Signature = order.signature.parse().unwrap();
+        assert_eq!(sig.recover_address_from_prehash(&B256::from(signed[0])).unwrap(), mock.key.address());
+        assert_eq!(order.order.signer, mock.key.address().to_string());
+        assert_eq!(order.order.maker, funder);
+
+        // The digest can be rebuilt from the order alone; a tampered order recovers elsewhere
+        assert_eq!(client.recover_order_signer(&order, false).unwrap(), mock.key.address());
+        let mut tampered = order.clone();
+        tampered.order.maker_amount = "1".into();
+        assert_ne!(client.recover_order_signer(&tampered, false).unwrap(), mock.key.address());
+    }
+
+    #[test]
+    fn test_copy_price_delta_from_fill() {
+        // Bought 200 shares for $101 (0.505) after the whale paid 0.50: half a cent worse
+        let buy = OrderResponse::parse(r#"{"success":true,"orderID":"0x1","makingAmount":"101","takingAmount":"200"}"#).unwrap();
+        let fill = buy.avg_fill_price(true).unwrap();
+        assert!((fill - 0.505).abs() < 1e-12);
+        assert!((copy_price_delta(0.50, fill, true) + 0.005).abs() < 1e-12);
+
+        // Sold 100 shares for $62 (0.62) against the whale's 0.60: two cents better
+        let sell = OrderResponse::parse(r#"{"success":true,"orderID":"0x2","makingAmount":"100","takingAmount":"62"}"#).unwrap();
+        assert!((copy_price_delta(0.60, sell.avg_fill_price(false).unwrap(), false) - 0.02).abs() < 1e-12);
+
+        // A resting order with nothing matched has no fill price
+        let resting = OrderResponse::parse(r#"{"success":true,"orderID":"0x3","makingAmount":"","takingAmount":""}"#).unwrap();
+        assert_eq!(resting.avg_fill_price(true), None);
+    }
+
+    #[test]
+    fn test_cancel_confirmed() {
+        assert!(cancel_confirmed(r#"{"canceled":["0xabc"],"not_canceled":{}}"#, "0xabc"));
+        assert!(!cancel_confirmed(r#"{"canceled":[],"not_canceled":{"0xabc":"order already matched"}}"#, "0xabc"));
+        assert!(!cancel_confirmed(r#"{"canceled":["0xdef"]}"#, "0xabc"));
+        assert!(!cancel_confirmed("not json", "0xabc"));
+    }
+
+    #[test]
+    fn test_parse_size_matched() {
+        assert_eq!(parse_size_matched(r#"{"id":"0xabc","status":"MATCHED","size_matched":"12.5"}"#), Some(12.5));
+        assert_eq!(parse_size_matched(r#"{"size_matched":0}"#), Some(0.0));
+        assert_eq!(parse_size_matched(r#"{"id":"0xabc"}"#), None);
+        assert_eq!(parse_size_matched("null"), None);
+    }
+
+    #[test]
+    fn test_configured_timeouts_applied() {
+        use std::io::Read;
+        use std::net::TcpListener;
+
+        let signer = Arc::new(signer::LocalSigner::from_hex(&format!("{:064x}", 1)).unwrap());
+        let funder = "0x1111111111111111111111111111111111111111";
+        let timeouts = HttpTimeouts { request: Duration::from_millis(200), ..HttpTimeouts::default() };
+        let client = RustClobClient::with_signer_and_timeouts("http://127.0.0.1:9", 137, signer.clone(), funder, timeouts).unwrap();
+        assert_eq!(client.timeouts(), timeouts);
+        assert_eq!(RustClobClient::with_signer("http://127.0.0.1:9", 137, signer, funder).unwrap().timeouts(), HttpTimeouts::default());
+
+        // A server that accepts and never answers: the request timeout ends the call
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut conn, _) = listener.accept().unwrap();
+            let _ = conn.read(&mut [0u8; 1024]);
+            std::thread::sleep(Duration::from_secs(1));
+        });
+        let started = std::time::Instant::now();
+        let err = client.http_client().get(format!("http://{}/book", addr)).send().unwrap_err();
+        assert!(err.is_timeout(), "{err}");
+        assert!(started.elapsed() < Duration::from_millis(900));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_order_signed_with_cached_tick() {
+        assert_eq!(tick_str(0.01), "0.01");
+        assert_eq!(tick_str(0.001), "0.001");
+        assert_eq!(tick_str(0.0001), "0.0001");
+        assert_eq!(tick_str(0.05), "0.01");
+        // 0.999 is only valid on a 0.001 market
+        assert!(price_valid(0.999, tick_str(0.001)));
+        assert!(!price_valid(0.999, tick_str(0.01)));
+    }
+
+    #[test]
+    fn test_clock_skew_against_mocked_server_time() {
+        let server = "1700000000";
+        assert_eq!(clock_skew_secs(server, 1_700_000_000).unwrap(), 0);
+        assert_eq!(clock_skew_secs(server, 1_700_000_007).unwrap(), 7);   // local ahead
+        assert_eq!(clock_skew_secs("1700000030\n", 1_700_000_000).unwrap(), -30); // local behind
+        assert!(clock_skew_secs("<html>", 1_700_000_000).is_err());
+
+        assert_eq!(classify_clock_skew(1, 2, 30), ClockSkewCheck::Ok);
+        assert_eq!(classify_clock_skew(-5, 2, 30), ClockSkewCheck::Warn);
+        assert_eq!(classify_clock_skew(-31, 2, 30), ClockSkewCheck::Refuse);
+        assert_eq!(classify_clock_skew(600, 2, 0), ClockSkewCheck::Warn);
+    }
+
+    #[test]
+    fn test_stale_creds_rederived() {
+        let creds = |key: &str| ApiCreds { api_key: key.into(), api_secret: "c2VjcmV0".into(), api_passphrase: "p".into() };
+        let only_new_valid = |c: &ApiCreds| if c.api_key == "new" { CredsCheck::Valid } else { CredsCheck::Rejected };
+
+        // Valid or unverifiable creds are kept without deriving
+        let (c, rederived) = validate_or_rederive(creds("old"), StaleCredsPolicy::Rederive, |_| CredsCheck::Valid, || panic!("no derive")).unwrap();
+        assert_eq!((c.api_key.as_str(), rederived), ("old", false));
+        let (_, rederived) = validate_or_rederive(creds("old"), StaleCredsPolicy::Rederive, |_| classify_creds_check(None), || panic!("no derive")).unwrap();
+        assert!(!rederived);
+
+        // Simulated auth failure: re-derive, check, and report for saving
+        assert_eq!(classify_creds_check(Some(401)), CredsCheck::Rejected);
+        let (c, rederived) = validate_or_rederive(creds("old"), StaleCredsPolicy::Rederive, only_new_valid, || Ok(creds("new"))).unwrap();
+        assert_eq!((c.api_key.as_str(), rederived), ("new", true));
+
+        // Derive fails, or the new creds are rejected as well: clear error
+        let err = validate_or_rederive(creds("old"), StaleCredsPolicy::Rederive, only_new_valid, || Err(anyhow!("403"))).unwrap_err();
+        assert!(err.to_string().contains("re-deriving failed"), "{err}");
+        assert!(validate_or_rederive(creds("old"), StaleCredsPolicy::Rederive, |_| CredsCheck::Rejected, || Ok(creds("new"))).is_err());
+
+        // exit refuses; off never checks
+        assert!(validate_or_rederive(creds("old"), StaleCredsPolicy::Exit, only_new_valid, || Ok(creds("new"))).is_err());
+        let (c, _) = validate_or_rederive(creds("old"), StaleCredsPolicy::Off, |_| panic!("no check"), || panic!("no derive")).unwrap();
+        assert_eq!(c.api_key, "old");
+        assert_eq!(StaleCredsPolicy::parse("EXIT").unwrap(), StaleCredsPolicy::Exit);
+        assert!(StaleCredsPolicy::parse("retry").is_err());
+    }
+
+    #[test]
+    fn test_order_response_ids() {
+        let body = r#"{"errorMsg":"","orderID":"0x5a3f1c9e","takingAmount":"20","makingAmount":"10.2",
+            "status":"matched","transactionsHashes":["0xaaa","0xbbb"],"success":true}"#;
+        let resp = OrderResponse::parse(body).unwrap();
+        assert_eq!(resp.order_id, "0x5a3f1c9e");
+        assert_eq!(resp.tx_hashes(), "0xaaa;0xbbb");
+
+        // Resting order: id but no settlement yet
+        let resting = OrderResponse::parse(r#"{"success":true,"orderID":"0x77","status":"live"}"#).unwrap();
+        assert_eq!((resting.order_id.as_str(), resting.tx_hashes().as_str()), ("0x77", ""));
+
+        assert!(OrderResponse::parse("<html>").is_none());
+    }
+
+    #[test]
+    fn test_order_amounts_at_collateral_decimals() {
+        // One run, two collaterals: 77.03 shares @ 0.41 encoded at 6 and at 18 decimals
+        let (_, maker_6, taker_6) = get_order_amounts_buy(77.03, 0.41, false, 6).unwrap();
+        let (_, maker_18, taker_18) = get_order_amounts_buy(77.03, 0.41, false, 18).unwrap();
+        assert_eq!((maker_6, taker_6), (31_582_300, 77_030_000));
+        assert_eq!(taker_18, 77_030_000 * 10u128.pow(12));
+
+        // And back: both decode to the same human amounts
+        for (amount, decimals) in [(maker_6, 6), (maker_18, 18)] {
+            assert!((u256_to_scaled_f64(&U256::from(amount), decimals).unwrap() - 31.5823).abs() < 1e-9);
+        }
+        let (_, shares_18, _) = get_order_amounts_sell(116.88, 0.45, false, 18).unwrap();
+        assert!((u256_to_scaled_f64(&U256::from(shares_18), 18).unwrap() - 116.88).abs() < 1e-9);
+
+        // Size rounding follows the decimals too
+        assert_eq!(round_to_units(40.7999999999, 6), 40.8);
+        assert_eq!(round_to_units(0.123456789, 2), 0.12);
+    }
+
     #[test]
     fn test_order_amounts_buy_fak() {
         // Test FAK order: 108.68 shares @ 0.14
-        let cfg = RoundConfig { price: 2, size: 2, amount: 4 };
-        let (side, maker_amt, taker_amt) = get_order_amounts_buy(108.68, 0.14, &cfg, true).unwrap();
+        let (side, maker_amt, taker_amt) = get_order_amounts_buy(108.68, 0.14, true, 6).unwrap();
 
         // For a FAK buy order:
         // - taker amount = shares we receive = 108.68 (max 4 decimals)
@@ -648,8 +1224,7 @@ mod tests {
     #[test]
     fn test_order_amounts_buy_gtd() {
         // Test GTD order: 77.03 shares @ 0.41 (from error case)
-        let cfg = RoundConfig { price: 2, size: 2, amount: 4 };
-        let (side, maker_amt, taker_amt) = get_order_amounts_buy(77.03, 0.41, &cfg, false).unwrap();
+        let (side, maker_amt, taker_amt) = get_order_amounts_buy(77.03, 0.41, false, 6).unwrap();
 
         // For a GTD buy order:
         // - taker amount = shares = 77.03
@@ -663,8 +1238,7 @@ mod tests {
     #[test]
     fn test_order_amounts_sell_fak() {
         // Test FAK selling 100 shares @ 0.50
-        let cfg = RoundConfig { price: 2, size: 2, amount: 4 };
-        let (side, maker_amt, taker_amt) = get_order_amounts_sell(100.0, 0.50, &cfg, true).unwrap();
+        let (side, maker_amt, taker_amt) = get_order_amounts_sell(100.0, 0.50, true, 6).unwrap();
 
         assert_eq!(side, 1);
         assert_eq!(maker_amt, 100_000_000);
@@ -674,8 +1248,7 @@ mod tests {
     #[test]
     fn test_order_amounts_sell_gtd() {
         // Test GTD selling 116.88 shares @ 0.45 (from error case)
-        let cfg = RoundConfig { price: 2, size: 2, amount: 4 };
-        let (side, maker_amt, taker_amt) = get_order_amounts_sell(116.88, 0.45, &cfg, false).unwrap();
+        let (side, maker_amt, taker_amt) = get_order_amounts_sell(116.88, 0.45, false, 6).unwrap();
 
         // 116.88 * 0.45 = 52.596 (GTD: 4 decimal precision)
         assert_eq!(side, 1);
@@ -683,46 +1256,3 @@ mod tests {
         assert_eq!(taker_amt, 52_596_000);  // GTD: 4 decimal USDC (52.596)
     }
 }
-
-fn order_typed_data(chain_id: u64, exchange: &str, data: &OrderData) -> Result<TypedData> {
-    let json_str = format!(
-        concat!(
-            r#"{{"types":{{"EIP712Domain":["#,
-            r#"{{"name":"name","type":"string"}},"#,
-            r#"{{"name":"version","type":"string"}},"#,
-            r#"{{"name":"chainId","type":"uint256"}},"#,
-            r#"{{"name":"verifyingContract","type":"address"}}"#,
-            r#"],"Order":["#,
-            r#"{{"name":"salt","type":"uint256"}},"#,
-            r#"{{"name":"maker","type":"address"}},"#,
-            r#"{{"name":"signer","type":"address"}},"#,
-            r#"{{"name":"taker","type":"address"}},"#,
-            r#"{{"name":"tokenId","type":"uint256"}},"#,
-            r#"{{"name":"makerAmount","type":"uint256"}},"#,
-            r#"{{"name":"takerAmount","type":"uint256"}},"#,
-            r#"{{"name":"expiration","type":"uint256"}},"#,
-            r#"{{"name":"nonce","type":"uint256"}},"#,
-            r#"{{"name":"feeRateBps","type":"uint256"}},"#,
-            r#"{{"name":"side","type":"uint8"}},"#,
-            r#"{{"name":"signatureType","type":"uint8"}}"#,
-            r#"]}},"primaryType":"Order","#,
-            r#""domain":{{"name":"Polymarket CTF Exchange","version":"1","chainId":{},"verifyingContract":"{}"}},"#,
-            r#""message":{{"salt":"{}","maker":"{}","signer":"{}","taker":"{}","tokenId":"{}","makerAmount":"{}","takerAmount":"{}","expiration":"{}","nonce":"{}","feeRateBps":"0","side":{},"signatureType":{}}}}}"#
-        ),
-        chain_id,
-        exchange,
-        data.salt,
-        data.maker,
-        data.signer,
-        data.taker,
-        data.token_id_u256,
-        data.maker_amount_u256,
-        data.taker_amount_u256,
-        data.expiration_u256,
-        data.nonce_u256,
-        data.side,
-        data.signature_type,
-    );
-    Ok(serde_json::from_str(&json_str)?)
-}
-
@@ -0,0 +1,141 @@
+//! Markets that go live while we hold a copy (LIVE_FLIP_ACTION)
+//! A pre-match position can turn in-play, where prices move much faster. The watcher polls
+//! live status for tokens we hold or rest orders on and reacts to each non-live -> live flip:
+//! hold (log only), tighten (re-post resting buys with the live GTD expiry) or flatten
+//! (cancel resting buys and sell the position)
+
+use crate::resting_orders::RestingOrder;
+use rustc_hash::FxHashMap;
+
+/// What to do when a held market goes live
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LiveFlipAction {
+    /// Log the flip, change nothing
+    #[default]
+    Hold,
+    /// Re-post resting buys that outlive the live GTD expiry with that expiry
+    Tighten,
+    /// Cancel resting buys and sell the whole position
+    Flatten,
+}
+
+impl LiveFlipAction {
+    /// Parse "hold" / "tighten" / "flatten" (empty = Hold)
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "hold" => Ok(LiveFlipAction::Hold),
+            "tighten" => Ok(LiveFlipAction::Tighten),
+            "flatten" => Ok(LiveFlipAction::Flatten),
+            other => anyhow::bail!("LIVE_FLIP_ACTION must be 'hold', 'tighten' or 'flatten' (found '{}')", other),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LiveFlipAction::Hold => "hold",
+            LiveFlipAction::Tighten => "tighten",
+            LiveFlipAction::Flatten => "flatten",
+        }
+    }
+}
+
+/// One step of the reaction to a flip
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlipStep {
+    /// Cancel and re-post at the same price with the live expiry
+    Repost(RestingOrder),
+    Cancel(RestingOrder),
+    /// Sell this many shares
+    Sell(f64),
+}
+
+/// Steps for `action` on a token that just went live. `live_expires_at` is when a GTD placed now
+/// with the live expiry would expire; resting orders ending sooner are already short enough
+pub fn flip_steps(action: LiveFlipAction, held_shares: f64, resting: &[RestingOrder], live_expires_at: u64) -> Vec<FlipStep> {
+    match action {
+        LiveFlipAction::Hold => Vec::new(),
+        LiveFlipAction::Tighten => resting.iter()
+            .filter(|o| o.expires_at.is_none_or(|t| t > live_expires_at))
+            .cloned()
+            .map(FlipStep::Repost)
+            .collect(),
+        LiveFlipAction::Flatten => {
+            let mut steps: Vec<_> = resting.iter().cloned().map(FlipStep::Cancel).collect();
+            if held_shares > 0.0 {
+                steps.push(FlipStep::Sell(held_shares));
+            }
+            steps
+        }
+    }
+}
+
+/// Last live status seen per watched token
+#[derive(Debug, Default)]
+pub struct LiveWatch {
+    last: FxHashMap<String, bool>,
+}
+
+impl LiveWatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a token's status; true when it was seen non-live before and is live now.
+    /// A token first seen already live isn't a flip (we may have bought it live)
+    pub fn observe(&mut self, token_id: &str, is_live: bool) -> bool {
+        let was = self.last.insert(token_id.to_string(), is_live);
+        is_live && was == Some(false)
+    }
+
+    /// Stop watching tokens no longer held
+    pub fn retain(&mut self, keep: impl Fn(&str) -> bool) {
+        self.last.retain(|token, _| keep(token));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resting(id: &str, expires_at: Option<u64>) -> RestingOrder {
+        RestingOrder {
+            order_id: id.into(),
+            whale: "0xwhale".into(),
+            price: 0.40,
+            size: 10.0,
+            filled_at_post: 0.0,
+            counted_usd: 4.0,
+            expires_at,
+            placed_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_live_flip_detected_and_dispatched() {
+        let mut watch = LiveWatch::new();
+        assert!(!watch.observe("pre", false));
+        assert!(!watch.observe("pre", false));
+        assert!(watch.observe("pre", true));
+        // Only the transition counts
+        assert!(!watch.observe("pre", true));
+        // First seen live: no flip
+        assert!(!watch.observe("inplay", true));
+        watch.retain(|t| t == "inplay");
+        assert!(!watch.observe("pre", true));
+
+        // Live GTDs placed now would expire at 1_061
+        let orders = vec![resting("0xlong", Some(2_800)), resting("0xshort", Some(1_030))];
+        assert!(flip_steps(LiveFlipAction::Hold, 25.0, &orders, 1_061).is_empty());
+        assert_eq!(flip_steps(LiveFlipAction::Tighten, 25.0, &orders, 1_061), vec![FlipStep::Repost(orders[0].clone())]);
+        assert_eq!(
+            flip_steps(LiveFlipAction::Flatten, 25.0, &orders, 1_061),
+            vec![FlipStep::Cancel(orders[0].clone()), FlipStep::Cancel(orders[1].clone()), FlipStep::Sell(25.0)]
+        );
+        // Nothing held: only the cancels
+        assert_eq!(flip_steps(LiveFlipAction::Flatten, 0.0, &orders[1..], 1_061), vec![FlipStep::Cancel(orders[1].clone())]);
+
+        assert_eq!(LiveFlipAction::parse(" Flatten ").unwrap(), LiveFlipAction::Flatten);
+        assert_eq!(LiveFlipAction::parse("").unwrap(), LiveFlipAction::Hold);
+        assert!(LiveFlipAction::parse("sell").is_err());
+    }
+}
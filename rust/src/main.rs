@@ -1,18 +1,16 @@
-/// PM Whale Follower - Main entry point
-/// Monitors blockchain for whale trades and executes copy trades
+//! PM Whale Follower - Main entry point
+//! Monitors blockchain for whale trades and executes copy trades
 
-use anyhow::{Result, anyhow};
-use chrono::{DateTime, Utc};
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Timelike, Utc};
 use dotenvy::dotenv;
-use alloy::primitives::U256;
 use futures::{SinkExt, StreamExt};
-use rand::Rng;
-use pm_whale_follower::{ApiCreds, OrderArgs, RustClobClient, PreparedCreds, OrderResponse};
+use pm_whale_follower::{cancel_confirmed, copy_price_delta, parse_size_matched, round_to_units, ApiCreds, HttpTimeouts, OrderArgs, RustClobClient, PreparedCreds, OrderResponse, ClockSkewCheck, classify_clock_skew};
+use pm_whale_follower::{classify_creds_check, validate_or_rederive};
+use pm_whale_follower::signer::{LocalSigner, RemoteSigner, Signer};
 use pm_whale_follower::settings::Config;
 use serde_json::Value;
 use std::cell::RefCell;
-use std::collections::HashMap;
-use std::fmt::Write as _;
 use std::fs::{File, OpenOptions};
 use std::io::Write;
 use std::path::Path;
@@ -20,55 +18,105 @@ use std::time::Duration;
 use tokio::sync::{mpsc, oneshot};
 use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
 
-mod models;
-
-use pm_whale_follower::risk_guard::{RiskGuard, RiskGuardConfig, SafetyDecision, TradeSide, calc_liquidity_depth};
+use pm_whale_follower::risk_guard::{
+    BookFreshness, RiskGuard, RiskGuardConfig, TradeSide, book_depth_excluding_own, book_depth_from_json, book_freshness,
+    best_ask_from_json, fillable_shares_from_json, top_two_from_json, top_two_levels,
+};
 use pm_whale_follower::settings::*;
-use pm_whale_follower::market_cache;
+use pm_whale_follower::market_cache::{self, WarmupCheck, WarmupGate};
+use pm_whale_follower::status;
+use pm_whale_follower::block_resolver;
+use pm_whale_follower::csv_log;
+use pm_whale_follower::self_test::SelfTestReport;
+use pm_whale_follower::status_code::{Status, StatusCode};
+use pm_whale_follower::order_queue::{priority_channel, Enqueued, PriorityReceiver, PrioritySender};
+use pm_whale_follower::decode::{decode_report_configured, parse_ws_frame};
+use pm_whale_follower::resting_orders::{self, settle_expired, ExpiryOutcome, ExpirySettlement, RestingAdmission, RestingOrder};
+use pm_whale_follower::handler_limit::HandlerLimiter;
+use pm_whale_follower::reconnect::{ReconnectBudget, ReconnectDecision};
+use pm_whale_follower::positions;
+use pm_whale_follower::retry;
+use pm_whale_follower::gamma_health::{self, Transition};
+use pm_whale_follower::block_summary::{self, SUMMARY_FLUSH_DELAY};
+use pm_whale_follower::session_summary;
+use pm_whale_follower::shutdown::{self, DrainOutcome};
+use pm_whale_follower::replay;
+use pm_whale_follower::mempool;
+use pm_whale_follower::kill_switch;
+use pm_whale_follower::confirm;
+use pm_whale_follower::decision_socket;
+use pm_whale_follower::submit_jitter::{self, SubmitJitter};
+use pm_whale_follower::whale_portfolio;
+use pm_whale_follower::live_flip::{flip_steps, FlipStep, LiveFlipAction, LiveWatch};
+use pm_whale_follower::notify;
+use pm_whale_follower::watchdog;
+use pm_whale_follower::run_limits::{EventBudget, RunLimits};
+use pm_whale_follower::worker_pool::SharedReceiver;
+use pm_whale_follower::engine::{check_min_depth, cross_spread_floor, maker_reprice, maker_spread_check, maker_touch, plan_order_with_shadow, precheck_liquidity, recheck_daily_notional, second_level_limit, submit_price, tick_align_whale_price, DecisionTrace, OrderOutcome, MAKER_ORDER_ACTION, OpposingSignalTracker, OrderPlan, PlanParams, SameTokenTracker, ThinBookAction, WhalePriceTracker};
 use pm_whale_follower::tennis_markets;
 use pm_whale_follower::soccer_markets;
-use models::*;
-use std::sync::Arc;
-
-const GAMMA_API_BASE: &str = "https://gamma-api.polymarket.com";
+use pm_whale_follower::models::*;
+use std::sync::{Arc, OnceLock};
 
 // ============================================================================
 // Thread-local buffers 
 // ============================================================================
 
+/// Initial sizes of the per-thread CSV buffers (CSV_BUFFER_STATS shows how often rows outgrow them)
+const CSV_BUF_INITIAL_CAPACITY: usize = 512;
+const SANITIZE_BUF_INITIAL_CAPACITY: usize = 128;
+
 thread_local! {
-    static CSV_BUF: RefCell<String> = RefCell::new(String::with_capacity(512));
-    static SANITIZE_BUF: RefCell<String> = RefCell::new(String::with_capacity(128));
-    static TOKEN_ID_CACHE: RefCell<HashMap<[u8; 32], Arc<str>>> = RefCell::new(HashMap::with_capacity(256));
+    static CSV_BUF: RefCell<String> = RefCell::new(String::with_capacity(CSV_BUF_INITIAL_CAPACITY));
+    static SANITIZE_BUF: RefCell<String> = RefCell::new(String::with_capacity(SANITIZE_BUF_INITIAL_CAPACITY));
 }
 
 // ============================================================================
 // Order Engine 
 // ============================================================================
 
+/// Main runtime, for timers started from the order worker thread
+static RUNTIME: OnceLock<tokio::runtime::Handle> = OnceLock::new();
+
 #[derive(Clone)]
 struct OrderEngine {
-    tx: mpsc::Sender<WorkItem>,
+    tx: PrioritySender<WorkItem>,
     #[allow(dead_code)]
     resubmit_tx: mpsc::UnboundedSender<ResubmitRequest>,
-    enable_trading: bool,
+    /// SUBMIT_JITTER_MS delays, drawn per event as it's queued (None in dry runs or when off)
+    jitter: Option<Arc<std::sync::Mutex<SubmitJitter>>>,
 }
 
-impl OrderEngine {
-    async fn submit(&self, evt: ParsedEvent, is_live: Option<bool>) -> String {
-        if !self.enable_trading {
-            return "SKIPPED_DISABLED".into();
-        }
+/// API base URLs for the selected network (shared by event handler tasks)
+struct ApiEndpoints {
+    clob: String,
+    gamma: String,
+    data: String,
+    timeouts: HttpTimeouts,
+}
 
+impl OrderEngine {
+    /// Hand the event to the order worker. With trading disabled it still runs the guards,
+    /// so a skip reports its reason rather than SKIPPED_DISABLED
+    async fn submit(&self, evt: ParsedEvent, is_live: Option<bool>) -> OrderReply {
+        // SUBMIT_JITTER_MS: the worker waits it out only if the event turns into an order
+        let not_before = self.jitter.as_ref().map(|jitter| {
+            std::time::Instant::now() + jitter.lock().map(|mut j| j.next_delay()).unwrap_or_default()
+        });
         let (resp_tx, resp_rx) = oneshot::channel();
-        if let Err(e) = self.tx.try_send(WorkItem { event: evt, respond_to: resp_tx, is_live }) {
-            return format!("QUEUE_ERR: {e}");
+        match self.tx.try_send(WorkItem { event: evt, respond_to: resp_tx, is_live, not_before }) {
+            Ok(Enqueued::Queued) => {}
+            Ok(Enqueued::Displaced(shed)) => {
+                let _ = shed.respond_to.send(StatusCode::QueueShed.into());
+                status::publish("order_queue", serde_json::json!({ "shed": self.tx.shed_count() }));
+            }
+            Err(e) => return Status::with_message(StatusCode::QueueErr, e.to_string()).into(),
         }
 
         match tokio::time::timeout(ORDER_REPLY_TIMEOUT, resp_rx).await {
-            Ok(Ok(msg)) => msg,
-            Ok(Err(_)) => "WORKER_DROPPED".into(),
-            Err(_) => "WORKER_TIMEOUT".into(),
+            Ok(Ok(reply)) => reply,
+            Ok(Err(_)) => StatusCode::WorkerDropped.into(),
+            Err(_) => StatusCode::WorkerTimeout.into(),
         }
     }
 }
@@ -80,7 +128,18 @@ impl OrderEngine {
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenv().ok();
+    // Offline: decode WS messages from stdin and exit
+    if std::env::args().any(|a| a == "--decode") {
+        let mut input = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut input)?;
+        for line in decode_report_configured(&input) {
+            println!("{}", line);
+        }
+        return Ok(());
+    }
+    let limits = RunLimits::from_args(std::env::args().skip(1)).map_err(|e| anyhow!(e))?;
     ensure_csv()?;
+    let _ = RUNTIME.set(tokio::runtime::Handle::current());
 
     // Initialize market data caches
     market_cache::init_caches();
@@ -89,31 +148,125 @@ async fn main() -> Result<()> {
     let _cache_refresh_handle = market_cache::spawn_cache_refresh_task();
 
     let cfg = Config::from_env().await?;
+    // Before anything draws, so replays and seeded runs repeat their sizing rolls
+    if let Some(seed) = *RNG_SEED {
+        pm_whale_follower::rng::set_seed(seed);
+    }
+    if std::env::args().any(|a| a == "--print-config") || *PRINT_CONFIG {
+        print!("{}", cfg.describe());
+    }
+    if std::env::args().any(|a| a == "--self-test") {
+        return run_self_test(&cfg).await;
+    }
+    // Offline: replay a capture from stdin and diff its decisions against a CSV
+    if let Some(csv_path) = std::env::args().skip_while(|a| a != "--verify-against").nth(1) {
+        return run_verify_against(&cfg, &csv_path);
+    }
+
+    let run = csv_log::init_run_info(&cfg.instance_label);
+    if *CSV_BUFFER_STATS {
+        csv_log::enable_buffer_stats();
+    }
+    status::publish("run", serde_json::json!({ "instance": run.instance_label, "run_id": run.run_id }));
+    println!("🏷️ Instance: {} | run_id: {}", if run.instance_label.is_empty() { "-" } else { &run.instance_label }, run.run_id);
+
+    if SUMMARY_MODE.enabled() {
+        spawn_block_summary_flusher();
+    }
+
+    // Admin status endpoint + SIGUSR1 dump
+    if !cfg.status_addr.is_empty() {
+        status::spawn_status_server(cfg.status_addr.clone());
+    }
+    #[cfg(unix)]
+    status::spawn_sigusr1_dump();
+    if !cfg.kill_switch_file.is_empty() {
+        kill_switch::spawn_watcher(cfg.kill_switch_file.clone().into());
+    }
+    #[cfg(unix)]
+    if !cfg.decision_socket.is_empty() {
+        match decision_socket::DecisionSocket::bind(Path::new(&cfg.decision_socket)) {
+            Ok(socket) => {
+                decision_socket::install(socket);
+                println!("📡 Decisions streamed on unix socket {}", cfg.decision_socket);
+            }
+            Err(e) => eprintln!("⚠️ Decision socket failed to bind {}: {}", cfg.decision_socket, e),
+        }
+    }
+    // Allocation sizing needs each whale's portfolio value
+    if LIVE_SIZING.allocation_bankroll_usd.is_some() {
+        let whales = TARGET_TOPICS.iter().map(|t| format!("0x{}", &t[t.len() - 40..])).collect();
+        let http = reqwest::Client::builder().no_proxy().build()?;
+        whale_portfolio::spawn_refresh(http, cfg.data_api_base.clone(), whales, Duration::from_secs(*WHALE_PORTFOLIO_REFRESH_SECS));
+    }
+
+    // Pending txs have no block yet; a receipt lookup would only delay the order
+    if cfg.block_number_fallback && !cfg.use_mempool {
+        block_resolver::init(&cfg.block_rpc_url);
+    }
+    if cfg.use_mempool {
+        println!("⚠️ USE_MEMPOOL: copying unconfirmed whale txs (they can still be dropped, replaced, revert or be reorged out)");
+    }
     
-    let (client, creds) = build_worker_state(
-        cfg.private_key.clone(),
-        cfg.funder_address.clone(),
-        ".clob_market_cache.json",
-        ".clob_creds.json",
-    ).await?;
+    println!("🌐 Network: {} (chain {}) | CLOB {} | Gamma {}", cfg.network.as_str(), cfg.chain_id, cfg.clob_api_base, cfg.gamma_api_base);
+    let signer = build_signer(&cfg)?;
+    let (client, creds, clock_skew) = build_worker_state(signer, &cfg, ".clob_market_cache.json").await?;
+    check_clock_skew(&cfg, clock_skew)?;
     
     let prepared_creds = PreparedCreds::from_api_creds(&creds)?;
     let risk_config = cfg.risk_guard_config();
 
-    let (order_tx, order_rx) = mpsc::channel(1024);
+    let (order_tx, order_rx) = priority_channel(ORDER_QUEUE_CAPACITY, cfg.order_queue_priority);
     let (resubmit_tx, resubmit_rx) = mpsc::unbounded_channel::<ResubmitRequest>();
 
     let client_arc = Arc::new(client);
     let creds_arc = Arc::new(prepared_creds.clone());
 
-    start_order_worker(order_rx, client_arc.clone(), prepared_creds, cfg.enable_trading, cfg.mock_trading, risk_config, resubmit_tx.clone());
+    let warmup = WarmupGate::new(cfg.warmup_gate, Duration::from_secs(cfg.warmup_timeout_secs), std::time::Instant::now());
+    start_order_worker(order_rx, client_arc.clone(), prepared_creds, cfg.enable_trading, cfg.mock_trading, risk_config, cfg.plan_params(), resubmit_tx.clone(), warmup);
+
+    if cfg.reconcile_interval_secs > 0 && cfg.enable_trading && !cfg.mock_trading {
+        spawn_position_reconciler(
+            Arc::clone(&client_arc),
+            Arc::clone(&creds_arc),
+            cfg.reconcile_interval_secs,
+            cfg.reconcile_tolerance_shares,
+            cfg.reconcile_correct,
+        );
+    }
+    if cfg.gtd_expiry_check && cfg.enable_trading && !cfg.mock_trading {
+        spawn_expiry_follow_up(Arc::clone(&client_arc), Arc::clone(&creds_arc), cfg.gtd_expiry_grace_secs);
+    }
+    if cfg.live_flip_poll_secs > 0 && cfg.enable_trading && !cfg.mock_trading {
+        spawn_live_flip_watcher(
+            Arc::clone(&client_arc),
+            Arc::clone(&creds_arc),
+            cfg.gamma_api_base.clone(),
+            cfg.http_timeouts().request,
+            cfg.live_flip_poll_secs,
+            cfg.live_flip_action,
+        )?;
+    }
+
+    let resubmit_rx = SharedReceiver::new(resubmit_rx);
+    for worker in 0..*RESUBMIT_WORKERS {
+        tokio::spawn(resubmit_worker(worker, resubmit_rx.clone(), Arc::clone(&client_arc), Arc::clone(&creds_arc)));
+    }
 
-    tokio::spawn(resubmit_worker(resubmit_rx, client_arc, creds_arc));
+    let endpoints = Arc::new(ApiEndpoints {
+        clob: cfg.clob_api_base.clone(),
+        gamma: cfg.gamma_api_base.clone(),
+        data: cfg.data_api_base.clone(),
+        timeouts: cfg.http_timeouts(),
+    });
 
+    let run = csv_log::run_info();
+    let jitter_seed = submit_jitter::instance_seed(SUBMIT_JITTER_SEED.or(*RNG_SEED), &run.instance_label, &run.run_id);
+    let jitter = SubmitJitter::new(*SUBMIT_JITTER_MS, jitter_seed);
     let order_engine = OrderEngine {
         tx: order_tx,
         resubmit_tx,
-        enable_trading: cfg.enable_trading,
+        jitter: (jitter.enabled() && cfg.enable_trading && !cfg.mock_trading).then(|| Arc::new(std::sync::Mutex::new(jitter))),
     };
 
     println!(
@@ -121,70 +274,881 @@ async fn main() -> Result<()> {
         cfg.enable_trading, cfg.mock_trading
     );
 
-    loop {
-        if let Err(e) = run_ws_loop(&cfg.wss_url, &order_engine).await {
-            eprintln!("⚠️ WS error: {e}. Reconnecting...");
-            tokio::time::sleep(WS_RECONNECT_DELAY).await;
+    // Shared across reconnects so in-flight handlers from a dropped socket still count
+    let handlers = HandlerLimiter::new(cfg.max_concurrent_handlers, cfg.handler_saturation);
+
+    let mut reconnects = ReconnectBudget::new(cfg.max_reconnect_attempts);
+    // Built once; reconnects only clone the string
+    let subscription = WsSubscription::from_config(&cfg);
+
+    let budget = EventBudget::new(limits.max_events);
+    if limits.bounded() {
+        println!(
+            "⏱️ Bounded run: max events {} | max runtime {}",
+            limits.max_events.map_or("-".into(), |n| n.to_string()),
+            limits.max_runtime.map_or("-".into(), |d| format!("{}s", d.as_secs()))
+        );
+    }
+
+    let started = std::time::Instant::now();
+    let ws = async {
+        loop {
+            // Ok only once --max-events is reached; otherwise the loop runs until the socket fails
+            match run_ws_loop(&subscription, &order_engine, &endpoints, &handlers, &mut reconnects, &budget).await {
+                Ok(()) => {
+                    println!("🛑 Shutting down (--max-events {} reached)", budget.seen());
+                    return Ok(());
+                }
+                Err(e) => {
+                    if reconnects.on_failure() == ReconnectDecision::GiveUp {
+                        eprintln!(
+                            "❌ FATAL: WS failed {} times in a row without a healthy connection (MAX_RECONNECT_ATTEMPTS). Last error: {e}",
+                            reconnects.consecutive_failures()
+                        );
+                        return Err(e.context("reconnect attempts exhausted"));
+                    }
+                    eprintln!("⚠️ WS error: {e}. Reconnecting...");
+                    tokio::time::sleep(WS_RECONNECT_DELAY).await;
+                }
+            }
         }
+    };
+
+    let runtime_limit = async {
+        match limits.max_runtime {
+            Some(limit) => tokio::time::sleep(limit).await,
+            None => std::future::pending().await,
+        }
+    };
+
+    let result = tokio::select! {
+        res = ws => res,
+        _ = runtime_limit => {
+            println!("🛑 Shutting down (--max-runtime {}s reached)", started.elapsed().as_secs());
+            Ok(())
+        }
+        _ = tokio::signal::ctrl_c() => {
+            println!("🛑 Shutting down (Ctrl-C)");
+            Ok(())
+        }
+    };
+    // A bounded run lets events already read finish their orders and CSV rows so the summary covers them
+    if limits.bounded() && result.is_ok() && tokio::time::timeout(RUN_LIMIT_DRAIN_TIMEOUT, handlers.wait_idle()).await.is_err() {
+        eprintln!("⚠️ {} event handlers still running after {}s; exiting anyway", handlers.in_flight(), RUN_LIMIT_DRAIN_TIMEOUT.as_secs());
     }
+    match shutdown::resubmit_chains().shutdown(cfg.shutdown_mode()).await {
+        DrainOutcome::Idle => {}
+        DrainOutcome::Drained => println!("🔄 Resubmit chains drained"),
+        DrainOutcome::Abandoned(n) if cfg.drain_resubmits_on_shutdown => {
+            eprintln!("⚠️ {} resubmit chains still running after {}s; exiting anyway", n, cfg.resubmit_drain_timeout_secs)
+        }
+        DrainOutcome::Abandoned(n) => eprintln!("⚠️ Dropping {} in-flight resubmit chains (DRAIN_RESUBMITS_ON_SHUTDOWN=false)", n),
+    }
+    if let Ok(stats) = session_summary::global().lock() {
+        print!("{}", stats.render(started.elapsed()));
+    }
+    if *CSV_BUFFER_STATS {
+        let g = csv_log::buffer_growth();
+        println!(
+            "   csv buffers: row grew {}× (max {} bytes, initial {}) | sanitize grew {}× (max {} bytes, initial {})",
+            g.row_growths, g.row_capacity, CSV_BUF_INITIAL_CAPACITY, g.scratch_growths, g.scratch_capacity, SANITIZE_BUF_INITIAL_CAPACITY
+        );
+    }
+    result
 }
 
 // ============================================================================
 // Worker Setup
 // ============================================================================
 
+/// CLOB client (endpoint and timeouts from `cfg`), API creds and the measured clock skew
 async fn build_worker_state(
-    private_key: String,
-    funder: String,
+    signer: Arc<dyn Signer>,
+    cfg: &Config,
     cache_path: &str,
-    creds_path: &str,
-) -> Result<(RustClobClient, ApiCreds)> {
- 
+) -> Result<(RustClobClient, ApiCreds, Option<i64>)> {
+    let cache_path = cache_path.to_string();
+    let creds_path = cfg.network.creds_path().to_string();
+    let host = cfg.clob_api_base.clone();
+    let (funder, chain_id, timeouts, stale_creds) = (cfg.funder_address.clone(), cfg.chain_id, cfg.http_timeouts(), cfg.stale_creds);
+
+    tokio::task::spawn_blocking(move || -> Result<(RustClobClient, ApiCreds, Option<i64>)> {
+        let mut client = RustClobClient::with_signer_and_timeouts(&host, chain_id, signer, &funder, timeouts)?
+            .with_cache_path(&cache_path);
+        let _ = client.load_cache();
+        let clock_skew = client.measure_clock_skew().ok(); // Also pre-warms TLS
+
+        let creds: ApiCreds = if Path::new(&creds_path).exists() {
+            let data = std::fs::read_to_string(&creds_path)?;
+            let cached: ApiCreds = serde_json::from_str(&data)?;
+            // Revoked/rotated creds would fail every order with an auth error
+            let validate = |creds: &ApiCreds| {
+                let status = PreparedCreds::from_api_creds(creds)
+                    .and_then(|prepared| client.get_api_keys(&prepared))
+                    .ok()
+                    .map(|resp| resp.status().as_u16());
+                classify_creds_check(status)
+            };
+            let (creds, rederived) = validate_or_rederive(cached, stale_creds, validate, || client.derive_api_key(0))?;
+            if rederived {
+                println!("🔑 Cached API creds were rejected; derived fresh creds and updated {}", creds_path);
+                std::fs::write(&creds_path, serde_json::to_string_pretty(&creds)?)?;
+            }
+            creds
+        } else {
+            let derived = client.derive_api_key(0)?;
+            std::fs::write(&creds_path, serde_json::to_string_pretty(&derived)?)?;
+            derived
+        };
+
+        Ok((client, creds, clock_skew))
+    }).await?
+}
+
+fn build_signer(cfg: &Config) -> Result<Arc<dyn Signer>> {
+    Ok(match &cfg.remote_signer {
+        Some(remote) => {
+            println!("🔑 Signing via remote signer for {}", remote.address);
+            Arc::new(RemoteSigner::new(remote.clone())?)
+        }
+        None => Arc::new(LocalSigner::from_hex(&cfg.private_key)?),
+    })
+}
+
+/// --verify-against trades.csv: replay captured WS messages from stdin through decode and the
+/// decision engine, print every decision that differs from the CSV, and fail if any did
+fn run_verify_against(cfg: &Config, csv_path: &str) -> Result<()> {
+    let csv = std::fs::read_to_string(csv_path).with_context(|| format!("reading {}", csv_path))?;
+    let mut capture = String::new();
+    std::io::Read::read_to_string(&mut std::io::stdin(), &mut capture)?;
+    let report = replay::verify_replay_configured(&capture, &csv, cfg).map_err(|e| anyhow!(e))?;
+    print!("{}", report.render());
+    if report.passed() { Ok(()) } else { Err(anyhow!("replay does not match {}", csv_path)) }
+}
+
+/// --self-test: exercise signer, creds, clock, order signing and an authenticated call, then exit
+/// Nothing is posted - the throwaway order is only signed and verified locally
+async fn run_self_test(cfg: &Config) -> Result<()> {
+    let mut report = SelfTestReport::new();
+
+    let signer = match build_signer(cfg) {
+        Ok(signer) => {
+            report.record("signer", Ok(format!("{:?}", signer.address())));
+            signer
+        }
+        Err(e) => {
+            report.record("signer", Err(format!("{e:#}")));
+            for name in ["credentials", "clock", "sign_order", "auth"] {
+                report.skip(name, "needs a signer");
+            }
+            print!("{}", report.render());
+            return Err(anyhow!("self-test failed"));
+        }
+    };
+
+    let state = build_worker_state(signer, cfg, ".clob_market_cache.json").await;
+    let (client, creds, clock_skew) = match state {
+        Ok(state) => {
+            report.record("credentials", Ok(format!("api key {}", state.1.api_key)));
+            state
+        }
+        Err(e) => {
+            report.record("credentials", Err(format!("{e:#}")));
+            for name in ["clock", "sign_order", "auth"] {
+                report.skip(name, "needs credentials");
+            }
+            print!("{}", report.render());
+            return Err(anyhow!("self-test failed"));
+        }
+    };
+
+    report.record("clock", match clock_skew {
+        None => Err("could not measure skew against CLOB server time".into()),
+        Some(skew) => match classify_clock_skew(skew, cfg.clock_skew_warn_secs, cfg.clock_skew_max_secs) {
+            ClockSkewCheck::Ok => Ok(format!("{:+}s", skew)),
+            ClockSkewCheck::Warn => Ok(format!("{:+}s (above CLOCK_SKEW_WARN_SECS)", skew)),
+            ClockSkewCheck::Refuse => Err(format!("{:+}s exceeds CLOCK_SKEW_MAX_SECS={}", skew, cfg.clock_skew_max_secs)),
+        },
+    });
+
+    let (sign_result, auth_result) = tokio::task::spawn_blocking(move || {
+        let mut client = client;
+        let sign_result = self_test_sign_order(&mut client);
+        let auth_result = PreparedCreds::from_api_creds(&creds)
+            .and_then(|prepared| client.get_api_keys(&prepared))
+            .map_err(|e| format!("{e:#}"))
+            .and_then(|resp| {
+                let status = resp.status();
+                if status.is_success() { Ok(format!("GET /auth/api-keys {}", status)) } else { Err(format!("GET /auth/api-keys {}", status)) }
+            });
+        (sign_result, auth_result)
+    }).await?;
+    report.record("sign_order", sign_result);
+    report.record("auth", auth_result);
+
+    print!("{}", report.render());
+    if report.passed() { Ok(()) } else { Err(anyhow!("self-test failed: {}", report.failures().join(", "))) }
+}
+
+/// Sign a throwaway order for a dummy token and check the signature recovers to our signer
+fn self_test_sign_order(client: &mut RustClobClient) -> Result<String, String> {
+    const SELF_TEST_TOKEN: &str = "1";
+    // Pre-seed so create_order doesn't query /neg-risk for a token that doesn't exist
+    client.set_neg_risk(SELF_TEST_TOKEN, false);
+    let args = OrderArgs {
+        token_id: SELF_TEST_TOKEN.into(),
+        price: 0.50,
+        size: 5.0,
+        side: "BUY".into(),
+        fee_rate_bps: None,
+        nonce: Some(0),
+        expiration: None,
+        taker: None,
+        order_type: Some("FAK".into()),
+    };
+    let order = client.create_order(args).map_err(|e| format!("create_order: {e:#}"))?;
+    let recovered = client.recover_order_signer(&order, false).map_err(|e| format!("recover: {e:#}"))?;
+    let expected = client.signer_address();
+    if recovered == expected {
+        Ok(format!("signature recovers to {:?}", recovered))
+    } else {
+        Err(format!("signature recovers to {:?}, expected {:?}", recovered, expected))
+    }
+}
+
+/// GTD expiries are computed from the local clock - warn (or refuse) if it disagrees with the CLOB
+fn check_clock_skew(cfg: &Config, clock_skew: Option<i64>) -> Result<()> {
+    status::publish("clock", serde_json::json!({ "skew_secs": clock_skew }));
+    let Some(skew) = clock_skew else {
+        eprintln!("⚠️ Could not measure clock skew against CLOB server time");
+        return Ok(());
+    };
+    match classify_clock_skew(skew, cfg.clock_skew_warn_secs, cfg.clock_skew_max_secs) {
+        ClockSkewCheck::Ok => Ok(()),
+        ClockSkewCheck::Warn => {
+            eprintln!("⚠️ Local clock is {:+}s off CLOB server time - GTD expiries will be shifted", skew);
+            Ok(())
+        }
+        ClockSkewCheck::Refuse => Err(anyhow!(
+            "Local clock is {:+}s off CLOB server time (CLOCK_SKEW_MAX_SECS={}). Sync the system clock and restart.",
+            skew, cfg.clock_skew_max_secs
+        )),
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn start_order_worker(
-    rx: mpsc::Receiver<WorkItem>,
+    rx: PriorityReceiver<WorkItem>,
     client: Arc<RustClobClient>,
     creds: PreparedCreds,
     enable_trading: bool,
     mock_trading: bool,
     risk_config: RiskGuardConfig,
+    plan_params: PlanParams,
     resubmit_tx: mpsc::UnboundedSender<ResubmitRequest>,
+    warmup: WarmupGate,
 ) {
-    
+    std::thread::spawn(move || {
+        let mut guard = RiskGuard::new(risk_config);
+        order_worker(rx, client, creds, enable_trading, mock_trading, &mut guard, &plan_params, resubmit_tx, warmup);
+    });
 }
 
+#[allow(clippy::too_many_arguments)]
 fn order_worker(
-    mut rx: mpsc::Receiver<WorkItem>,
+    mut rx: PriorityReceiver<WorkItem>,
     client: Arc<RustClobClient>,
     creds: PreparedCreds,
     enable_trading: bool,
     mock_trading: bool,
     guard: &mut RiskGuard,
+    plan_params: &PlanParams,
     resubmit_tx: mpsc::UnboundedSender<ResubmitRequest>,
+    warmup: WarmupGate,
 ) {
-    
+    // Clone Arc for mutable access pattern
+    let mut client_mut = (*client).clone();
+    publish_risk_snapshot(guard);
+    let mut whale_prices = WhalePriceTracker::new(*WHALE_PRICE_MODE);
+    let mut same_token = SameTokenTracker::new(*SAME_TOKEN_PER_BLOCK);
+    let mut opposing = OpposingSignalTracker::new(*OPPOSING_SIGNALS, Duration::from_millis(*OPPOSING_SIGNAL_WINDOW_MS));
+    while let Some(work) = rx.blocking_recv() {
+        // Notional of resting copies that expired unfilled (GTD_EXPIRY_CHECK)
+        let now = unix_now_secs();
+        for (placed_at, usd) in resting_orders::global().take_released() {
+            guard.release_notional_at(usd, placed_at, now);
+        }
+        if !shutdown::resubmit_chains().accepting_orders() {
+            let _ = work.respond_to.send(Status::with_message(StatusCode::QueueErr, "shutting down").into());
+            continue;
+        }
+        if kill_switch::global().is_paused() {
+            let _ = work.respond_to.send(StatusCode::SkippedPaused.into());
+            continue;
+        }
+        match warmup.check(market_cache::caches_warm(), std::time::Instant::now()) {
+            WarmupCheck::Open => {}
+            WarmupCheck::Hold => {
+                let _ = work.respond_to.send(StatusCode::SkippedWarmup.into());
+                continue;
+            }
+            WarmupCheck::TimedOut => eprintln!("⚠️ Warmup gate timed out: market caches still empty, trading anyway"),
+        }
+        let Some(event) = same_token.admit(work.event) else {
+            let _ = work.respond_to.send(StatusCode::SkippedSameTokenBlock.into());
+            continue;
+        };
+        let admitted_at = std::time::Instant::now();
+        let Some(event) = opposing.admit(event, admitted_at) else {
+            let _ = work.respond_to.send(StatusCode::SkippedOpposingSignal.into());
+            continue;
+        };
+        let event = match tick_align_whale_price(whale_prices.apply(event), *WHALE_PRICE_ROUNDING) {
+            Ok(event) => event,
+            Err(status) => {
+                let _ = work.respond_to.send(status.into());
+                continue;
+            }
+        };
+        let reply = process_order(&event, &mut client_mut, &creds, enable_trading, mock_trading, guard, plan_params, &resubmit_tx, work.is_live, work.not_before);
+        // Only copies claim a block group or open an opposing signal, so an event a later guard
+        // skipped doesn't dedupe or offset the next whale
+        if reply.status.code.is_copy() {
+            same_token.record(&event);
+            opposing.record(&event, admitted_at);
+        }
+        let _ = work.respond_to.send(reply);
+        if enable_trading && !mock_trading {
+            cancel_exited_resting_orders(&event, &client_mut, &creds);
+        }
+        publish_risk_snapshot(guard);
+    }
+}
+
+/// Guard state plus per-token exposure for the status board
+fn publish_risk_snapshot(guard: &RiskGuard) {
+    let snapshot = guard.snapshot().with_exposure(&positions::global().snapshot());
+    status::publish("risk_guard", serde_json::to_value(snapshot).unwrap_or_default());
 }
 
 // ============================================================================
 // Order Processing
 // ============================================================================
 
+#[allow(clippy::too_many_arguments)]
 fn process_order(
-    info: &OrderInfo,
+    evt: &ParsedEvent,
     client: &mut RustClobClient,
     creds: &PreparedCreds,
     enable_trading: bool,
     mock_trading: bool,
     guard: &mut RiskGuard,
+    plan_params: &PlanParams,
     resubmit_tx: &mpsc::UnboundedSender<ResubmitRequest>,
     is_live: Option<bool>,
-) -> String {
-   
+    not_before: Option<std::time::Instant>,
+) -> OrderReply {
+    // Dry runs go through every guard so the audit row says why a trade would be skipped;
+    // SKIPPED_DISABLED / MOCK_ONLY then only mean "would have been placed"
+    let dry_run = if !enable_trading {
+        Some(StatusCode::SkippedDisabled)
+    } else if mock_trading {
+        Some(StatusCode::MockOnly)
+    } else {
+        None
+    };
+    // UNKNOWN_LIVE_DEFAULT=skip applies to dry runs too, so their audit rows match a live session
+    let Some(is_live) = UNKNOWN_LIVE_DEFAULT.resolve(is_live) else {
+        return StatusCode::SkippedUnknownLive.into();
+    };
+
+    let info = &evt.order;
+    let mut trace = if *TRACE_DECISIONS { DecisionTrace::new(evt) } else { DecisionTrace::disabled() };
+    // Same decision path as engine::simulate_event, with the live book fetcher and the worker's guard
+    let (outcome, shadow) = plan_order_with_shadow(
+        evt,
+        guard,
+        plan_params,
+        |side, limit_price| fetch_book_depth_blocking(client, &info.clob_token_id, side, limit_price),
+        &mut trace,
+        SHADOW_SIZING.as_ref(),
+    );
+    let (mut reply, plan) = match OrderOutcome::from(outcome) {
+        OrderOutcome::Submit(mut plan) => {
+            // SUBMIT_JITTER_MS: waiting in the worker delays only orders and keeps events in queue order
+            if let Some(wait) = not_before.map(|t| t.saturating_duration_since(std::time::Instant::now())) {
+                std::thread::sleep(wait);
+            }
+            let checked = presubmit_book_checks(client, evt, &mut plan, plan_params, &mut trace).and_then(|()| {
+                // The book checks can raise the limit past what the cap was checked against
+                recheck_daily_notional(&plan, guard, &mut trace)
+            });
+            let reply = match checked {
+                Ok(()) => match dry_run {
+                    Some(status) => OrderReply::from(status),
+                    None => submit_plan(evt, &plan, client, creds, guard, resubmit_tx, is_live),
+                },
+                Err(status) => OrderReply::from(status),
+            };
+            (reply, Some(plan))
+        }
+        OrderOutcome::Skip(status) => (OrderReply::from(status), None),
+    };
+    if let Some(line) = trace.finish(&reply.status) {
+        println!("TRACE {}", line);
+    }
+    decision_socket::emit(|| decision_socket::decision_message(evt, plan.as_ref(), &reply.status));
+    reply.shadow = shadow;
+    reply
 }
 
-fn calculate_safe_size(whale_shares: f64, price: f64, size_multiplier: f64) -> (f64, SizeType) {
-    
+/// One book fetch before the first order for MIN_CROSS_BUFFER (raise the buy limit to the ask),
+/// SECOND_LEVEL_BUFFER (price through a thin top level) and LIQUIDITY_PRECHECK (compare the
+/// plan against the shares fillable at its limit). In maker mode the book first decides whether
+/// to rest (MAKER_MIN_SPREAD_TICKS) and keeps a resting order from crossing; a copy that falls
+/// back to taking gets the taker checks. A failed book fetch lets the order through (the FAK
+/// result is the fallback check)
+fn presubmit_book_checks(
+    client: &RustClobClient,
+    evt: &ParsedEvent,
+    plan: &mut OrderPlan,
+    params: &PlanParams,
+    trace: &mut DecisionTrace,
+) -> Result<(), Status> {
+    let token_id = &evt.order.clob_token_id;
+    let min_cross = MIN_CROSS_BUFFER.filter(|_| plan.side_is_buy && tier_crosses_spread(evt.order.shares));
+    let walk = SECOND_LEVEL_BUFFER.filter(|_| tier_walks_second_level(evt.order.shares, plan.side_is_buy));
+    let min_depth = if *TIER_MIN_DEPTH { tier_min_depth_shares(evt.order.shares, plan.side_is_buy) } else { 0.0 };
+    if !plan.maker && *LIQUIDITY_PRECHECK == ThinBookAction::Off && min_cross.is_none() && walk.is_none() && min_depth <= 0.0 {
+        return Ok(());
+    }
+    let book = match fetch_book_blocking(client, token_id) {
+        Ok(book) => book,
+        Err(e) => {
+            eprintln!("⚠️ Pre-submit book checks skipped for {}: {}", token_id, e);
+            return Ok(());
+        }
+    };
+    let tick = market_cache::tick_size(token_id);
+    if plan.maker && maker_book_check(&book, evt, plan, params, tick, trace) {
+        return Ok(());
+    }
+    let cap = whale_price_cap(evt.order.price_per_share);
+    if let Some(min_cross) = min_cross {
+        cross_spread_floor(plan, best_ask_from_json(&book), min_cross, tick, cap, trace);
+    }
+    let side = if plan.side_is_buy { TradeSide::Buy } else { TradeSide::Sell };
+    if let Some(buffer) = walk {
+        let (best, second) = top_two_from_json(&book, side);
+        second_level_limit(plan, best, second, buffer, tick, cap.filter(|_| plan.side_is_buy), trace);
+    }
+    if *LIQUIDITY_PRECHECK == ThinBookAction::Off && min_depth <= 0.0 {
+        return Ok(());
+    }
+    let available = fillable_shares_from_json(&book, side, plan.limit_price);
+    check_min_depth(plan, available, min_depth, trace)?;
+    if *LIQUIDITY_PRECHECK == ThinBookAction::Off {
+        return Ok(());
+    }
+    let market_min = market_cache::get_market_override(token_id).and_then(|o| o.min_shares);
+    let min_shares = market_min.unwrap_or(MIN_SHARE_COUNT).max(MIN_CASH_VALUE / plan.limit_price);
+    precheck_liquidity(plan, available, *LIQUIDITY_PRECHECK, *LIQUIDITY_PRECHECK_MIN_FRACTION, min_shares, trace)
+}
+
+/// COPY_MODE=maker: rest only in a spread of at least MAKER_MIN_SPREAD_TICKS, pulling a limit
+/// that would cross back to one tick inside the touch. Returns false when the plan fell back to taking
+fn maker_book_check(book: &Value, evt: &ParsedEvent, plan: &mut OrderPlan, params: &PlanParams, tick: f64, trace: &mut DecisionTrace) -> bool {
+    let (best_ask, _) = top_two_from_json(book, TradeSide::Buy);
+    let (best_bid, _) = top_two_from_json(book, TradeSide::Sell);
+    let (best_bid, best_ask) = (best_bid.map(|l| l.0), best_ask.map(|l| l.0));
+    if !maker_spread_check(plan, evt, params, best_bid, best_ask, tick, *MAKER_MIN_SPREAD_TICKS, trace) {
+        return false;
+    }
+    maker_touch(plan, best_bid, best_ask, tick, trace);
+    true
+}
+
+/// Sign and post a planned order, queueing resubmits for FAK misses/underfills
+fn submit_plan(
+    evt: &ParsedEvent,
+    plan: &OrderPlan,
+    client: &mut RustClobClient,
+    creds: &PreparedCreds,
+    guard: &mut RiskGuard,
+    resubmit_tx: &mpsc::UnboundedSender<ResubmitRequest>,
+    is_live: bool,
+) -> OrderReply {
+    let info = &evt.order;
+    let whale_shares = info.shares;
+    let whale_price = info.price_per_share;
+    let OrderPlan { side_is_buy, limit_price, shares: my_shares, ref size_type, order_action, notional, maker } = *plan;
+    let tick = market_cache::tick_size(&info.clob_token_id);
+    // Same clamp as resubmits (submit_resubmit_order_sync)
+    let limit_price = submit_price(limit_price, tick, &SUBMIT_PRICE_BOUNDS);
+
+    if side_is_buy && is_resting_order(order_action) {
+        if let Err(status) = reserve_resting_slot(&info.clob_token_id, client, creds) {
+            return OrderReply::from(status);
+        }
+    }
+
+    let args = OrderArgs {
+        token_id: info.clob_token_id.to_string(),  // Arc<str> -> String
+        price: limit_price,
+        size: (my_shares * 100.0).floor() / 100.0,
+        side: if side_is_buy { "BUY".into() } else { "SELL".into() },
+        fee_rate_bps: None,
+        nonce: Some(0),
+        expiration: submit_expiration(order_action, is_live, unix_now_secs(), *FAK_EXPIRATION_SECS),
+        taker: TAKER_ADDRESS.clone(),
+        order_type: Some(order_action.to_string()),
+    };
+
+    // Every submission counts against the market's rate bucket, filled or not
+    guard.record_market_order(&info.clob_token_id);
+    // BUY notional is reserved before posting so a resubmit chain's fills stay under the daily cap;
+    // what doesn't fill is given back below, or by the chain / expiry follow-up that takes it over
+    let side = if side_is_buy { TradeSide::Buy } else { TradeSide::Sell };
+    let counted_at = unix_now_secs();
+    guard.record_notional_at(side, notional, counted_at);
+    let mut release_usd = 0.0;
+    let reply = match client.create_order(args).and_then(|signed| {
+        let body = signed.post_body(&creds.api_key, order_action);
+        // NEVER use retry for order submission - could create duplicates!
+        client.post_order_fast(body, creds)
+    }) {
+        Ok(resp) => {
+            let status = resp.status();
+            let body_text = resp.text().unwrap_or_default();
+            let parsed = OrderResponse::parse(&body_text);
+            let (order_id, order_tx) = parsed.as_ref()
+                .map(|r| (r.order_id.clone(), r.tx_hashes()))
+                .unwrap_or_default();
+            let mut overfill_msg: Option<String> = None;
+            let mut fill_pct: Option<f64> = None;
+            let mut fill_price: Option<f64> = None;
+            if status.is_success() {
+                // Exposure and positions follow the actual fill when the CLOB overfills us
+                let requested_shares = (my_shares * 100.0).floor() / 100.0;
+                let filled = record_position_fill(&info.clob_token_id, side_is_buy, requested_shares, &body_text);
+                fill_pct = filled.as_ref().filter(|_| requested_shares > 0.0).map(|(f, _)| f / requested_shares * 100.0);
+                fill_price = parsed.as_ref().and_then(|r| r.avg_fill_price(side_is_buy));
+                let recorded_notional = match &filled {
+                    Some((filled_shares, Some(warning))) => {
+                        eprintln!("⚠️ {}", warning);
+                        overfill_msg = Some(format!(" | \x1b[33mOVERFILL: {:.2}/{:.2} filled\x1b[0m", filled_shares, requested_shares));
+                        filled_shares * limit_price
+                    }
+                    _ => notional,
+                };
+                if recorded_notional > notional {
+                    guard.record_notional(side, recorded_notional - notional);
+                }
+                guard.record_whale_copy(&evt.whale_address);
+                session_summary::record(|s| {
+                    s.record_notional(recorded_notional);
+                    if let Some((filled_shares, _)) = filled {
+                        s.record_fill(&info.clob_token_id, filled_shares, limit_price, side_is_buy);
+                    }
+                    if let Some(fill) = fill_price {
+                        s.record_price_delta(copy_price_delta(whale_price, fill, side_is_buy));
+                    }
+                });
+                if side_is_buy && is_resting_order(order_action) {
+                    register_resting_order(
+                        &info.clob_token_id,
+                        &evt.whale_address,
+                        limit_price,
+                        (my_shares * 100.0).floor() / 100.0,
+                        &body_text,
+                        order_action,
+                        is_live,
+                        recorded_notional,
+                    );
+                } else if let Some((filled_shares, _)) = &filled {
+                    // Taker orders that missed part of their size give that part back (an
+                    // underfill resubmit takes it over instead, below)
+                    if requested_shares > 0.0 && *filled_shares < requested_shares {
+                        release_usd = notional * (requested_shares - filled_shares) / requested_shares;
+                    }
+                }
+                if maker && side_is_buy {
+                    let resting = requested_shares - filled.as_ref().map_or(0.0, |(f, _)| *f);
+                    if resting > 0.0 {
+                        spawn_maker_reprice(client, creds, MakerOrder {
+                            token_id: info.clob_token_id.to_string(),
+                            whale_address: evt.whale_address.clone(),
+                            order_id: order_id.clone(),
+                            price: limit_price,
+                            size: resting,
+                            max_price: get_resubmit_max_price(whale_shares, whale_price, limit_price, tick),
+                            is_live,
+                        });
+                    }
+                }
+            }
+
+            // Check for underfill on successful FAK orders (buys only)
+            // FAK orders return 200 OK even for partial fills - need to check takingAmount
+            // NOTE: API returns human-readable values (e.g., "5" = 5 shares), NOT micro-units
+            let mut underfill_msg: Option<String> = None;
+            if status.is_success() && side_is_buy && order_action == "FAK" {
+                if let Ok(order_resp) = serde_json::from_str::<OrderResponse>(&body_text) {
+                    let filled_shares: f64 = order_resp.taking_amount.parse().unwrap_or(0.0);
+                    let requested_shares = (my_shares * 100.0).floor() / 100.0;
+
+                    if filled_shares < requested_shares && filled_shares > 0.0 {
+                        let remaining_shares = requested_shares - filled_shares;
+
+                        // Only resubmit if remaining is above minimum threshold
+                        let min_threshold = MIN_SHARE_COUNT.max(MIN_CASH_VALUE / limit_price);
+                        if remaining_shares >= min_threshold {
+                            let max_price = get_resubmit_max_price(whale_shares, whale_price, limit_price, tick);
+                            let req = ResubmitRequest {
+                                token_id: info.clob_token_id.to_string(),  // Arc<str> -> String
+                                whale_address: evt.whale_address.clone(),
+                                chain_started: std::time::Instant::now(),
+                                whale_price,
+                                failed_price: limit_price,  // Start at same price (already filled some)
+                                size: (remaining_shares * 100.0).floor() / 100.0,
+                                whale_shares,
+                                side_is_buy: true,
+                                attempt: 1,
+                                max_price,
+                                cumulative_filled: filled_shares,
+                                original_size: requested_shares,
+                                counted_usd: release_usd,
+                                counted_at,
+                                is_live,
+                            };
+                            release_usd = 0.0;
+                            enqueue_resubmit(resubmit_tx, req);
+                            underfill_msg = Some(format!(
+                                " | \x1b[33mUNDERFILL: {:.2}/{:.2} filled, resubmit {:.2}\x1b[0m",
+                                filled_shares, my_shares, remaining_shares
+                            ));
+                        }
+                    }
+                }
+            }
+
+            if !status.is_success() {
+                release_usd = notional;
+            }
+
+            // Check for FAK failure and queue resubmit (buys only) - zero fill case
+            if status.as_u16() == 400 && body_text.contains("FAK") && side_is_buy {
+                // Use tier-based max buffer (flat, or percentage of whale price capped at the tier buffer)
+                let max_price = get_resubmit_max_price(whale_shares, whale_price, limit_price, tick);
+                let rounded_size = (my_shares * 100.0).floor() / 100.0;
+                let req = ResubmitRequest {
+                    token_id: info.clob_token_id.to_string(),  // Arc<str> -> String
+                    whale_address: evt.whale_address.clone(),
+                    chain_started: std::time::Instant::now(),
+                    whale_price,
+                    failed_price: limit_price,
+                    size: rounded_size,
+                    whale_shares,  // Pass whale size for tier-based max attempts
+                    side_is_buy: true,
+                    attempt: 1,
+                    max_price,
+                    cumulative_filled: 0.0,
+                    original_size: rounded_size,
+                    counted_usd: notional,
+                    counted_at,
+                    is_live,
+                };
+                release_usd = 0.0;
+                enqueue_resubmit(resubmit_tx, req);
+            }
+
+            // Format with fixed precision to avoid floating point artifacts
+            // Red ANSI color for my price (limit_price)
+            let red = "\x1b[31m";
+            let reset = "\x1b[0m";
+            let mut base = format!(
+                "{} [{}] | my {:.2} @ {}{:.2}{} | whale {:.1} @ {:.2}",
+                status, size_type, my_shares, red, limit_price, reset, whale_shares, whale_price
+            );
+            let price_delta = fill_price.map(|fill| copy_price_delta(whale_price, fill, side_is_buy));
+            if let (Some(fill), Some(delta)) = (fill_price, price_delta) {
+                base.push_str(&format!(" | fill {:.4} ({:+.4} vs whale)", fill, delta));
+            }
+            if let Some(msg) = underfill_msg.or(overfill_msg) {
+                base.push_str(&msg);
+            }
+            let status = if status.is_success() {
+                Status::with_message(StatusCode::OrderPosted, base)
+            } else {
+                Status::with_message(StatusCode::OrderRejected, format!("{} | {}", base, body_text))
+            };
+            OrderReply { status, order_id, order_tx, shadow: None, fill_pct, price_delta }
+        }
+        Err(e) => {
+            release_usd = notional;
+            let chain: Vec<_> = e.chain().map(|c| c.to_string()).collect();
+            Status::with_message(StatusCode::ExecFail, format!("{} | chain: {}", e, chain.join(" -> "))).into()
+        }
+    };
+    if side_is_buy && release_usd > 0.0 {
+        guard.release_notional_at(release_usd, counted_at, unix_now_secs());
+    }
+    reply
+}
+
+/// Queue a resubmit, holding the first attempt back by the tier's initial delay.
+/// Runs on the order worker thread, so the wait is a task on the main runtime
+fn enqueue_resubmit(resubmit_tx: &mpsc::UnboundedSender<ResubmitRequest>, req: ResubmitRequest) {
+    let delay = resubmit_enqueue_delay(req.whale_shares, req.attempt, *RESUBMIT_INITIAL_DELAY_MS, *RESUBMIT_INITIAL_DELAY_SMALL_MS);
+    // Counted from here so a shutdown drain also waits for chains still in their initial delay
+    let chains = shutdown::resubmit_chains();
+    chains.begin();
+    match RUNTIME.get() {
+        Some(rt) if !delay.is_zero() => {
+            let tx = resubmit_tx.clone();
+            rt.spawn(async move {
+                tokio::time::sleep(delay).await;
+                if tx.send(req).is_err() {
+                    chains.end();
+                }
+            });
+        }
+        _ => {
+            if resubmit_tx.send(req).is_err() {
+                chains.end();
+            }
+        }
+    }
+}
+
+/// Give back the daily notional a resubmit chain reserved for shares it ended without filling.
+/// Queued for the order worker, which owns the risk guard
+fn release_chain_notional(req: &ResubmitRequest, unfilled: f64) {
+    resting_orders::global().release_notional(req.counted_at, req.unfilled_usd(unfilled));
+}
+
+/// Start repricing a maker buy that didn't fill on arrival. Counted as a resubmit chain so a
+/// shutdown drain waits for it
+fn spawn_maker_reprice(client: &RustClobClient, creds: &PreparedCreds, order: MakerOrder) {
+    if order.order_id.is_empty() || *MAKER_REPRICE_SECS == 0 || *MAKER_REPRICE_STEPS == 0 {
+        return;
+    }
+    let Some(rt) = RUNTIME.get() else { return };
+    shutdown::resubmit_chains().begin();
+    rt.spawn(maker_reprice_chain(Arc::new(client.clone()), Arc::new(creds.clone()), order));
+}
+
+/// COPY_MODE=maker: every MAKER_REPRICE_SECS, cancel the resting buy and re-post it one tick
+/// closer to the touch, until it fills (the cancel finds nothing to cancel), reaches the touch
+/// or its ceiling, or uses up MAKER_REPRICE_STEPS. What matched before the cancel is recorded
+/// as a position and only the rest is re-posted, with its share of the counted notional
+async fn maker_reprice_chain(client: Arc<RustClobClient>, creds: Arc<PreparedCreds>, mut order: MakerOrder) {
+    let _chain_done = shutdown::resubmit_chains().end_on_drop();
+    for step in 1..=*MAKER_REPRICE_STEPS {
+        tokio::time::sleep(Duration::from_secs(*MAKER_REPRICE_SECS)).await;
+        let (c, k, o) = (Arc::clone(&client), Arc::clone(&creds), order.clone());
+        match tokio::task::spawn_blocking(move || reprice_maker_order_sync(&c, &k, &o)).await {
+            Ok(Some(next)) => {
+                println!(
+                    "🔄 Maker reprice {}/{}: {:.2} -> {:.2} | size {:.2} | max {:.2}",
+                    step, *MAKER_REPRICE_STEPS, order.price, next.price, next.size, order.max_price
+                );
+                order = next;
+            }
+            _ => return,
+        }
+    }
+}
+
+/// One maker reprice. None when the order stays put (at the touch or its ceiling, no book)
+/// or is gone (filled, expired, or the re-post failed)
+fn reprice_maker_order_sync(client: &RustClobClient, creds: &PreparedCreds, order: &MakerOrder) -> Option<MakerOrder> {
+    let book = fetch_book_blocking(client, &order.token_id).ok()?;
+    let (best_ask, _) = top_two_from_json(&book, TradeSide::Buy);
+    let (best_bid, _) = top_two_from_json(&book, TradeSide::Sell);
+    let tick = market_cache::tick_size(&order.token_id);
+    let next = maker_reprice(order.price, best_bid.map(|l| l.0), best_ask.map(|l| l.0), true, tick, order.max_price)?;
+
+    let cancelled = match client.cancel_order(&order.order_id, creds) {
+        Ok(resp) => cancel_confirmed(&resp.text().unwrap_or_default(), &order.order_id),
+        Err(e) => {
+            eprintln!("⚠️ Maker reprice: cancel {} failed: {}", order.order_id, e);
+            return None;
+        }
+    };
+    if !cancelled {
+        println!("🔄 Maker reprice: {} no longer resting (filled or expired)", order.order_id);
+        return None;
+    }
+    let Some(tracked) = resting_orders::global().remove(&order.token_id, &order.order_id) else {
+        println!("🔄 Maker reprice: {} no longer tracked", order.order_id);
+        return None;
+    };
+    let (size, carried_usd) = unmatched_after_cancel(&order.token_id, &tracked, next, client, creds)?;
+
+    match submit_resubmit_order_sync(client, creds, &order.token_id, next, size, order.is_live, MAKER_ORDER_ACTION) {
+        Ok((true, body, filled)) => {
+            register_resting_order(&order.token_id, &order.whale_address, next, size, &body, MAKER_ORDER_ACTION, order.is_live, carried_usd);
+            let order_id = OrderResponse::parse(&body).map(|r| r.order_id).unwrap_or_default();
+            (!order_id.is_empty()).then(|| MakerOrder { order_id, price: next, size: size - filled, ..order.clone() })
+        }
+        Ok((false, body, _)) => {
+            let error_msg = if DEBUG_FULL_ERRORS { body } else { body.chars().take(80).collect::<String>() };
+            println!("🔄 Maker reprice FAILED @ {:.2} | size {:.2} | {}", next, size, error_msg);
+            resting_orders::global().release_notional(tracked.placed_at, carried_usd);
+            None
+        }
+        Err(e) => {
+            println!("🔄 Maker reprice ERROR @ {:.2} | {}", next, e);
+            resting_orders::global().release_notional(tracked.placed_at, carried_usd);
+            None
+        }
+    }
+}
+
+/// For a tracked resting order we just cancelled to re-post: record what matched while it
+/// rested and return the shares left with their share of its counted notional. None when
+/// nothing worth re-posting is left (the notional goes back to the cap) or the fill couldn't
+/// be fetched (re-posting blind could overbuy; reconciliation picks up any fill)
+fn unmatched_after_cancel(token_id: &str, order: &RestingOrder, price: f64, client: &RustClobClient, creds: &PreparedCreds) -> Option<(f64, f64)> {
+    let Some(matched) = fetch_size_matched(&order.order_id, client, creds) else {
+        eprintln!("⚠️ Cancelled {} on {}; could not fetch its fill, not re-posting", order.order_id, token_id);
+        return None;
+    };
+    let settled = settle_expired(order, matched);
+    record_late_fill(token_id, order, settled.late_fill);
+    let size = ((order.size - matched.clamp(0.0, order.size)) * 100.0).floor() / 100.0;
+    if settled.outcome == ExpiryOutcome::Filled || size < MIN_SHARE_COUNT.max(MIN_CASH_VALUE / price) {
+        println!("🔄 {} matched {:.2}/{:.2}, nothing left to re-post", order.order_id, matched.min(order.size), order.size);
+        resting_orders::global().release_notional(order.placed_at, settled.released_usd);
+        return None;
+    }
+    Some((size, settled.released_usd))
+}
+
+/// Abort check at the top of each resubmit attempt: logs RESUBMIT_DEADLINE once the chain
+/// has outlived its (live-dependent) wall-clock limit, whatever attempts remain
+fn resubmit_deadline_hit(req: &ResubmitRequest) -> bool {
+    let deadline = resubmit_deadline(req.is_live, *RESUBMIT_DEADLINE_MS, *RESUBMIT_DEADLINE_LIVE_MS);
+    if !req.past_deadline(std::time::Instant::now(), deadline) {
+        return false;
+    }
+    session_summary::record(|s| s.resubmit.aborted += 1);
+    let fill_pct = if req.original_size > 0.0 { (req.cumulative_filled / req.original_size) * 100.0 } else { 0.0 };
+    println!(
+        "🔄 Resubmit RESUBMIT_DEADLINE: attempt {} after {}ms | filled {:.2}/{:.2} ({:.0}%)",
+        req.attempt, req.chain_started.elapsed().as_millis(), req.cumulative_filled, req.original_size, fill_pct
+    );
+    true
+}
+
+/// Session counters for one posted resubmit order (resubmits are buys only)
+fn record_resubmit_attempt(token_id: &str, filled: f64, price: f64) {
+    session_summary::record(|s| {
+        s.resubmit.attempts += 1;
+        s.resubmit.filled_shares += filled;
+        s.record_fill(token_id, filled, price, true);
+    });
 }
 
 /// Get ANSI color code based on fill percentage
@@ -197,115 +1161,167 @@ fn get_fill_color(filled: f64, requested: f64) -> &'static str {
     else { "\x1b[32m" }                          // Green
 }
 
-/// Get ANSI color code based on whale share count (gradient from small to large)
-fn get_whale_size_color(shares: f64) -> &'static str {
-    if shares < 500.0 { "\x1b[90m" }              // Gray (very small)
-    else if shares < 1000.0 { "\x1b[36m" }        // Cyan (small)
-    else if shares < 2000.0 { "\x1b[34m" }        // Blue (medium-small)
-    else if shares < 5000.0 { "\x1b[32m" }        // Green (medium)
-    else if shares < 8000.0 { "\x1b[33m" }        // Yellow (medium-large)
-    else if shares < 15000.0 { "\x1b[38;5;208m" } // Orange (large)
-    else { "\x1b[35m" }                           // Magenta (huge)
-}
-
 fn fetch_book_depth_blocking(
     client: &RustClobClient,
     token_id: &str,
     side: TradeSide,
     threshold: f64,
 ) -> Result<f64, &'static str> {
-    let url = format!("{}/book?token_id={}", CLOB_API_BASE, token_id);
-    let resp = client.http_client()
-        .get(&url)
-        .timeout(Duration::from_millis(500))
-        .send()
-        .map_err(|_| "NETWORK")?;
-    
-    if !resp.status().is_success() { return Err("HTTP_ERROR"); }
-    
-    let book: Value = resp.json().map_err(|_| "PARSE")?;
-    let key = if side == TradeSide::Buy { "asks" } else { "bids" };
+    let book = fetch_book_blocking(client, token_id)?;
+    if *EXCLUDE_OWN_RESTING_DEPTH {
+        // Our resting orders are buys, so they only sit on the bid side
+        let own = if side == TradeSide::Sell {
+            resting_orders::global().own_levels(token_id, unix_now_secs())
+        } else {
+            Vec::new()
+        };
+        return Ok(book_depth_excluding_own(&book, side, threshold, *BOOK_DEPTH_LEVELS, &own));
+    }
+    Ok(book_depth_from_json(&book, side, threshold, *BOOK_DEPTH_LEVELS))
+}
 
-    // Stack array instead of Vec - avoids heap allocation for max 10 items
-    let mut levels: [(f64, f64); 10] = [(0.0, 0.0); 10];
-    let mut count = 0;
-    if let Some(arr) = book[key].as_array() {
-        for lvl in arr.iter().take(10) {
-            if let (Some(p), Some(s)) = (
-                lvl["price"].as_str().and_then(|s| s.parse().ok()),
-                lvl["size"].as_str().and_then(|s| s.parse().ok()),
-            ) {
-                levels[count] = (p, s);
-                count += 1;
-            }
+/// GET the CLOB book for a token
+fn fetch_book_blocking(client: &RustClobClient, token_id: &str) -> Result<Value, &'static str> {
+    let url = format!("{}/book?token_id={}", client.host(), token_id);
+    let fetch = || -> Result<Value, &'static str> {
+        let resp = client.http_client()
+            .get(&url)
+            .timeout(client.timeouts().book)
+            .send()
+            .map_err(|_| "NETWORK")?;
+        if !resp.status().is_success() { return Err("HTTP_ERROR"); }
+        resp.json().map_err(|_| "PARSE")
+    };
+
+    // A snapshot older than BOOK_MAX_AGE_MS may already be swept: refetch once, then give up
+    let mut book = fetch()?;
+    if let BookFreshness::Stale(age) = book_freshness(&book, unix_now_ms(), *BOOK_MAX_AGE_MS) {
+        book = fetch()?;
+        if let BookFreshness::Stale(age2) = book_freshness(&book, unix_now_ms(), *BOOK_MAX_AGE_MS) {
+            eprintln!("⚠️ Stale book for {} ({}ms, then {}ms old)", token_id, age, age2);
+            return Err("STALE_BOOK");
         }
     }
-
-    Ok(calc_liquidity_depth(side, &levels[..count], threshold))
+    Ok(book)
 }
 
 // ============================================================================
 // WebSocket Loop
 // ============================================================================
 
-async fn run_ws_loop(wss_url: &str, order_engine: &OrderEngine) -> Result<()> {
-    let (mut ws, _) = connect_async(wss_url).await?;
-
-    let sub = serde_json::json!({
-        "jsonrpc": "2.0", "id": 1, "method": "eth_subscribe",
-        "params": ["logs", {
-            "address": MONITORED_ADDRESSES,
-            "topics": [[ORDERS_FILLED_EVENT_SIGNATURE], Value::Null, TARGET_TOPIC_HEX.as_str()]
-        }]
-    }).to_string();
+async fn run_ws_loop(
+    subscription: &WsSubscription,
+    order_engine: &OrderEngine,
+    endpoints: &Arc<ApiEndpoints>,
+    handlers: &HandlerLimiter,
+    reconnects: &mut ReconnectBudget,
+    budget: &EventBudget,
+) -> Result<()> {
+    let (mut ws, _) = connect_async(subscription.url.as_str()).await?;
 
     println!("🔌 Connected. Subscribing...");
-    ws.send(Message::Text(sub)).await?;
+    ws.send(Message::Text(subscription.payload.clone())).await?;
 
-    let http_client = reqwest::Client::builder().no_proxy().build()?;
+    let http_client = reqwest::Client::builder().no_proxy().connect_timeout(endpoints.timeouts.connect).build()?;
+    let events = watchdog::global();
+    events.reset(unix_now_ms());
+    let mut watchdog_tick = tokio::time::interval(WATCHDOG_CHECK_INTERVAL);
+    // Fixed deadline so watchdog ticks don't restart the frame timeout
+    let mut frame_deadline = tokio::time::Instant::now() + WS_PING_TIMEOUT;
 
     loop {
-        let msg = tokio::time::timeout(WS_PING_TIMEOUT, ws.next()).await
+        let next = tokio::select! {
+            next = tokio::time::timeout_at(frame_deadline, ws.next()) => next,
+            _ = watchdog_tick.tick() => {
+                check_event_watchdog(events)?;
+                continue;
+            }
+        };
+        let msg = next
             .map_err(|_| anyhow!("WS timeout"))?
             .ok_or_else(|| anyhow!("WS closed"))??;
-
-        match msg {
-            Message::Text(text) => {
-                if let Some(evt) = parse_event(text) {
-                    let engine = order_engine.clone();
-                    let client = http_client.clone();
-                    tokio::spawn(async move { handle_event(evt, &engine, &client).await });
-                }
-            }
-            Message::Binary(bin) => {
-                if let Ok(text) = String::from_utf8(bin) {
-                    if let Some(evt) = parse_event(text) {
-                        let engine = order_engine.clone();
-                        let client = http_client.clone();
-                        tokio::spawn(async move { handle_event(evt, &engine, &client).await });
-                    }
-                }
-            }
-            Message::Ping(d) => { ws.send(Message::Pong(d)).await?; }
+        frame_deadline = tokio::time::Instant::now() + WS_PING_TIMEOUT;
+        // First frame after subscribing (the subscription ack) marks the connection healthy
+        reconnects.on_healthy();
+
+        // Both frame types carry the same JSON; the transport is counted and tagged on the event
+        let (transport, payload) = match msg {
+            Message::Text(text) => (FrameTransport::Text, text.into_bytes()),
+            Message::Binary(bin) => (FrameTransport::Binary, bin),
+            Message::Ping(d) => { ws.send(Message::Pong(d)).await?; continue; }
             Message::Close(f) => return Err(anyhow!("WS closed: {:?}", f)),
-            _ => {}
+            _ => continue,
+        };
+        let parsed = match subscription.mempool_exchanges {
+            Some(exchanges) => mempool::parse_pending_frame(transport, &payload, exchanges),
+            None => parse_ws_frame(transport, &payload),
+        };
+        if let Some(evt) = parsed {
+            events.record_event(unix_now_ms());
+            let engine = order_engine.clone();
+            let client = http_client.clone();
+            let endpoints = Arc::clone(endpoints);
+            handlers.spawn(async move { handle_event(evt, &engine, &client, &endpoints).await });
+            if budget.record() {
+                return Ok(());
+            }
         }
     }
 }
 
-async fn handle_event(evt: ParsedEvent, order_engine: &OrderEngine, http_client: &reqwest::Client) {
+/// Publish the event age and act on a dead feed (Err drops the socket so the caller reconnects)
+fn check_event_watchdog(events: &watchdog::EventWatchdog) -> Result<()> {
+    let cfg = &*EVENT_WATCHDOG;
+    let now_ms = unix_now_ms();
+    status::publish("event_watchdog", serde_json::json!({
+        "last_event_age_ms": events.age_ms(now_ms),
+        "threshold_ms": cfg.threshold_ms,
+    }));
+    let hour_utc = Utc::now().hour() as u8;
+    let Some(age) = events.check(cfg, now_ms, hour_utc) else { return Ok(()) };
+    eprintln!("⚠️ No events for {}s (EVENT_WATCHDOG_SECS={}); feed may be dead", age / 1000, cfg.threshold_ms / 1000);
+    if cfg.reconnect {
+        return Err(anyhow!("event watchdog: no events for {}s", age / 1000));
+    }
+    Ok(())
+}
+
+/// CONFIRM_VIA_API: only copy fills the data API shows (dry runs too, for the audit row). Runs
+/// before the event is queued so the order worker never waits on the API; trades too small to
+/// copy aren't looked up
+async fn confirm_fill(evt: &ParsedEvent, http_client: &reqwest::Client, data_api_base: &str) -> Result<(), Status> {
+    if !*CONFIRM_VIA_API || should_skip_trade(evt.order.shares) {
+        return Ok(());
+    }
+    let result = confirm::fetch_confirmation(http_client, data_api_base, evt, Duration::from_millis(*CONFIRM_TIMEOUT_MS)).await;
+    confirm::decide(result, *CONFIRM_ON_TIMEOUT)
+}
+
+async fn handle_event(mut evt: ParsedEvent, order_engine: &OrderEngine, http_client: &reqwest::Client, endpoints: &ApiEndpoints) {
+    // Some providers omit blockNumber on subscribed logs
+    if let Some(resolver) = block_resolver::global() {
+        resolver.fill_block_number(&mut evt, http_client).await;
+    }
+
     // Check live status from cache, fallback to API lookup
     let is_live = match market_cache::get_is_live(&evt.order.clob_token_id) {
         Some(v) => Some(v),
-        None => fetch_is_live(&evt.order.clob_token_id, http_client).await,
+        None => resolve_is_live(&evt.order.clob_token_id, http_client, &endpoints.gamma, endpoints.timeouts.gamma).await,
     };
 
-    let status = order_engine.submit(evt.clone(), is_live).await;
+    let reply = match confirm_fill(&evt, http_client, &endpoints.data).await {
+        Ok(()) => order_engine.submit(evt.clone(), is_live).await,
+        Err(status) => {
+            decision_socket::emit(|| decision_socket::decision_message(&evt, None, &status));
+            OrderReply::from(status)
+        }
+    };
+    let OrderReply { status, order_id, order_tx, shadow, fill_pct, price_delta } = reply;
 
     tokio::time::sleep(Duration::from_secs_f32(2.8)).await;
 
     // Fetch order book for post-trade logging
-    let bests = fetch_best_book(&evt.order.clob_token_id, &evt.order.order_type, http_client).await;
+    let bests = fetch_best_book(&evt.order.clob_token_id, &evt.order.order_type, http_client, &endpoints.clob).await;
     let ((bp, bs), (sp, ss)) = bests.unwrap_or_else(|| (("N/A".into(), "N/A".into()), ("N/A".into(), "N/A".into())));
     let is_live = is_live.unwrap_or(false);
 
@@ -315,44 +1331,78 @@ async fn handle_event(evt: ParsedEvent, order_engine: &OrderEngine, http_client:
     let colored_bp = format!("{}{}{}", pink, bp, reset);
 
     let live_display = if is_live {
-        format!("\x1b[34mlive: true\x1b[0m")
+        "\x1b[34mlive: true\x1b[0m".to_string()
     } else {
         "live: false".to_string()
     };
 
     // Tennis market indicator (green)
-    let tennis_display = if tennis_markets::get_tennis_token_buffer(&evt.order.clob_token_id) > 0.0 {
+    let tennis_display = if tennis_markets::is_tennis_token(&evt.order.clob_token_id) {
         "\x1b[32m(TENNIS)\x1b[0m "
     } else {
         ""
     };
 
     // Soccer market indicator (cyan)
-    let soccer_display = if soccer_markets::get_soccer_token_buffer(&evt.order.clob_token_id) > 0.0 {
+    let soccer_display = if soccer_markets::is_soccer_token(&evt.order.clob_token_id) {
         "\x1b[36m(SOCCER)\x1b[0m "
     } else {
         ""
     };
 
-    println!(
-        "⚡ [B:{}] {}{}{} | ${:.0} | {} | best: {} @ {} | 2nd: {} @ {} | {}",
-        evt.block_number, tennis_display, soccer_display, evt.order.order_type, evt.order.usd_value, status, colored_bp, bs, sp, ss, live_display
-    );
+    let id_display = if order_id.is_empty() { String::new() } else { format!(" | id: {}", order_id) };
+    let market = market_cache::market_label(&evt.order.clob_token_id);
+
+    if SUMMARY_MODE.per_event_lines() {
+        println!(
+            "⚡ [B:{}] {}{}{} | {} | {} | {} | best: {} @ {} | 2nd: {} @ {} | {}{}",
+            evt.block_number, tennis_display, soccer_display, evt.order.order_type, market, USD_DISPLAY_DECIMALS.format(evt.order.usd_value), status, colored_bp, bs, sp, ss, live_display, id_display
+        );
+    }
+    session_summary::record(|s| s.record_event(status.code));
+    if !WEBHOOK_URL.is_empty() && WEBHOOK_OUTCOMES.allows(status.code) {
+        let fields = notify::OutcomeFields {
+            token: evt.order.clob_token_id.to_string(),
+            market: market.clone(),
+            side: evt.order.order_type.clone(),
+            usd: evt.order.usd_value,
+            status: status.to_string(),
+            fill_pct,
+            block: evt.block_number,
+            tx: evt.tx_hash.clone(),
+        };
+        send_webhook(http_client.clone(), WEBHOOK_TEMPLATE.render(&fields));
+    }
+    if SUMMARY_MODE.enabled() {
+        if let Ok(mut agg) = block_summary::global().lock() {
+            agg.record(evt.block_number, &evt.order.order_type, evt.order.usd_value, status.code, std::time::Instant::now());
+        }
+    }
 
     let ts: DateTime<Utc> = Utc::now();
+    // Known markets only; an unknown label would just repeat clob_asset_id
+    let label = market_cache::global_caches().get_label(&evt.order.clob_token_id).map(|l| l.short());
+    let tier = CSV_TIER_COLUMNS.then(|| {
+        let side_is_buy = evt.order.order_type.starts_with("BUY");
+        tier_report(evt.order.shares, evt.order.price_per_share, side_is_buy, &evt.order.clob_token_id)
+    });
+    let audit = csv_log::AuditRow {
+        evt: &evt,
+        status: &status,
+        book: [&bp, &bs, &sp, &ss],
+        is_live,
+        shadow: shadow.as_ref(),
+        order_id: &order_id,
+        order_tx: &order_tx,
+        market: label.as_deref(),
+        price_delta,
+        tier: tier.as_ref(),
+    };
     let row = CSV_BUF.with(|buf| {
         SANITIZE_BUF.with(|sbuf| {
             let mut b = buf.borrow_mut();
-            let mut sb = sbuf.borrow_mut();
-            sanitize_csv(&status, &mut sb);
-            b.clear();
-            let _ = write!(b,
-                "{},{},{},{:.2},{:.6},{:.4},{},{},{},{},{},{},{},{}",
-                ts.format("%Y-%m-%d %H:%M:%S%.3f"),
-                evt.block_number, evt.order.clob_token_id, evt.order.usd_value,
-                evt.order.shares, evt.order.price_per_share, evt.order.order_type,
-                sb, bp, bs, sp, ss, evt.tx_hash, is_live
-            );
+            let timestamp = ts.format("%Y-%m-%d %H:%M:%S%.3f").to_string();
+            csv_log::write_audit_row(&mut b, &mut sbuf.borrow_mut(), &timestamp, &audit, *CSV_QUOTING, *CSV_DIRECTION);
             b.clone()
         })
     });
@@ -364,35 +1414,41 @@ async fn handle_event(evt: ParsedEvent, order_engine: &OrderEngine, http_client:
 // ============================================================================
 
 async fn resubmit_worker(
-    mut rx: mpsc::UnboundedReceiver<ResubmitRequest>,
+    worker: usize,
+    rx: SharedReceiver<ResubmitRequest>,
     client: Arc<RustClobClient>,
     creds: Arc<PreparedCreds>,
 ) {
-    println!("🔄 Resubmitter worker started");
+    println!("🔄 Resubmitter worker {} started", worker);
 
     while let Some(req) = rx.recv().await {
+        let _chain_done = shutdown::resubmit_chains().end_on_drop();
+        session_summary::record(|s| s.resubmit.chains += 1);
         let max_attempts = get_max_resubmit_attempts(req.whale_shares);
         let is_last_attempt = req.attempt >= max_attempts;
+        // Wait and price step for this attempt from the tier's resubmit schedule
+        let step = resubmit_step(req.whale_shares, req.attempt);
+        if !step.delay().is_zero() {
+            tokio::time::sleep(step.delay()).await;
+        }
+        if resubmit_deadline_hit(&req) {
+            release_chain_notional(&req, req.size);
+            continue;
+        }
 
-        // Calculate increment: chase only if should_increment_price returns true
-        let increment = if should_increment_price(req.whale_shares, req.attempt) {
-            RESUBMIT_PRICE_INCREMENT
-        } else {
-            0.0  // Flat retry
-        };
-        let new_price = if req.side_is_buy {
-            (req.failed_price + increment).min(0.99)
-        } else {
-            (req.failed_price - increment).max(0.01)
-        };
+        let tick = market_cache::tick_size(&req.token_id);
+        let new_price = step.next_price(req.failed_price, req.side_is_buy, tick);
 
         // Check if we've exceeded max buffer (skip check for GTD - last attempt always goes through)
-        if !is_last_attempt && req.side_is_buy && new_price > req.max_price {
+        // or the whale premium cap (applies to every attempt)
+        if resubmit_exceeds_ceiling(req.side_is_buy, new_price, req.max_price, whale_price_cap(req.whale_price), is_last_attempt) {
+            session_summary::record(|s| s.resubmit.aborted += 1);
             let fill_pct = if req.original_size > 0.0 { (req.cumulative_filled / req.original_size) * 100.0 } else { 0.0 };
             println!(
                 "🔄 Resubmit ABORT: attempt {} price {:.2} > max {:.2} | filled {:.2}/{:.2} ({:.0}%)",
                 req.attempt, new_price, req.max_price, req.cumulative_filled, req.original_size, fill_pct
             );
+            release_chain_notional(&req, req.size);
             continue;
         }
 
@@ -405,18 +1461,35 @@ async fn resubmit_worker(
         let max_price = req.max_price;
         let is_live = req.is_live;
 
-        // Submit order: FAK for early attempts, GTD with expiry for last attempt
+        // Submit order: tier action for early attempts, tier final action (GTD by default) for last attempt
+        let order_type = get_resubmit_order_action(req.whale_shares, is_last_attempt);
+        if is_resting_order(order_type) {
+            let (c, k, t) = (Arc::clone(&client), Arc::clone(&creds), req.token_id.clone());
+            if let Ok(Err(status)) = tokio::task::spawn_blocking(move || reserve_resting_slot(&t, &c, &k)).await {
+                session_summary::record(|s| s.resubmit.aborted += 1);
+                println!(
+                    "🔄 Resubmit {}: attempt {} {} not placed | filled {:.2}/{:.2}",
+                    status, req.attempt, order_type, req.cumulative_filled, req.original_size
+                );
+                release_chain_notional(&req, req.size);
+                continue;
+            }
+        }
         let result = tokio::task::spawn_blocking(move || {
-            submit_resubmit_order_sync(&client_clone, &creds_clone, &token_id, new_price, size, is_live, is_last_attempt)
+            submit_resubmit_order_sync(&client_clone, &creds_clone, &token_id, new_price, size, is_live, order_type)
         }).await;
+        if let Ok(Ok((_, _, filled))) = &result {
+            record_resubmit_attempt(&req.token_id, *filled, new_price);
+        }
 
         match result {
-            Ok(Ok((true, _, filled_this_attempt))) => {
-                if is_last_attempt {
-                    // GTD order placed on book - we don't know fill amount yet
+            Ok(Ok((true, body, filled_this_attempt))) => {
+                if is_resting_order(order_type) {
+                    // Resting order placed on book - fill is settled after expiry (GTD_EXPIRY_CHECK)
+                    register_resting_order(&req.token_id, &req.whale_address, new_price, size, &body, order_type, is_live, req.counted_usd);
                     println!(
-                        "\x1b[32m🔄 Resubmit GTD SUBMITTED: attempt {} @ {:.2} | size {:.2} | prior filled {:.2}/{:.2}\x1b[0m",
-                        attempt, new_price, size, req.cumulative_filled, req.original_size
+                        "\x1b[32m🔄 Resubmit {} SUBMITTED: attempt {} @ {:.2} | size {:.2} | prior filled {:.2}/{:.2}\x1b[0m",
+                        order_type, attempt, new_price, size, req.cumulative_filled, req.original_size
                     );
                 } else {
                     // FAK order - check if partial fill
@@ -424,14 +1497,17 @@ async fn resubmit_worker(
                     let fill_pct = if req.original_size > 0.0 { (total_filled / req.original_size) * 100.0 } else { 0.0 };
                     let remaining = size - filled_this_attempt;
 
-                    // If partial fill, continue with remaining size
-                    if remaining > 1.0 && filled_this_attempt > 0.0 {
+                    // If partial fill, continue with remaining size until RESUBMIT_DONE_RULE is met
+                    if filled_this_attempt > 0.0 && !RESUBMIT_DONE_RULE.is_done(total_filled, req.original_size, remaining, new_price) {
                         println!(
                             "\x1b[33m🔄 Resubmit PARTIAL: attempt {} @ {:.2} | filled {:.2}/{:.2} ({:.0}%) | remaining {:.2}\x1b[0m",
                             attempt, new_price, total_filled, req.original_size, fill_pct, remaining
                         );
+                        let counted_usd = req.unfilled_usd(remaining);
                         let next_req = ResubmitRequest {
                             token_id: req.token_id,
+                            whale_address: req.whale_address,
+                            chain_started: req.chain_started,
                             whale_price,
                             failed_price: new_price,
                             size: remaining,
@@ -441,6 +1517,8 @@ async fn resubmit_worker(
                             max_price,
                             cumulative_filled: total_filled,
                             original_size: req.original_size,
+                            counted_usd,
+                            counted_at: req.counted_at,
                             is_live: req.is_live,
                         };
                         let _ = process_resubmit_chain(&client, &creds, next_req).await;
@@ -449,14 +1527,18 @@ async fn resubmit_worker(
                             "\x1b[32m🔄 Resubmit SUCCESS: attempt {} @ {:.2} | filled {:.2}/{:.2} ({:.0}%)\x1b[0m",
                             attempt, new_price, total_filled, req.original_size, fill_pct
                         );
+                        release_chain_notional(&req, remaining);
                     }
                 }
             }
             Ok(Ok((false, body, filled_this_attempt))) => {
-                if attempt < max_attempts {
+                // First failure always gets another attempt unless the error is known to be terminal
+                if attempt < max_attempts && !RESUBMIT_ERROR_POLICY.is_terminal(&body) {
                     // Re-queue with updated price
                     let next_req = ResubmitRequest {
                         token_id: req.token_id,
+                        whale_address: req.whale_address,
+                        chain_started: req.chain_started,
                         whale_price,
                         failed_price: new_price,
                         size: req.size,
@@ -466,20 +1548,15 @@ async fn resubmit_worker(
                         max_price,
                         cumulative_filled: req.cumulative_filled + filled_this_attempt,
                         original_size: req.original_size,
+                        counted_usd: req.counted_usd,
+                        counted_at: req.counted_at,
                         is_live: req.is_live,
                     };
-                    let next_increment = if should_increment_price(req.whale_shares, attempt + 1) {
-                        RESUBMIT_PRICE_INCREMENT
-                    } else {
-                        0.0
-                    };
+                    let next_step = resubmit_step(req.whale_shares, attempt + 1);
                     println!(
-                        "🔄 Resubmit attempt {} failed (FAK), retrying @ {:.2} (max: {})",
-                        attempt, new_price + next_increment, max_attempts
+                        "🔄 Resubmit attempt {} failed (FAK), retrying @ {} (max: {})",
+                        attempt, next_step.next_price(new_price, req.side_is_buy, tick), max_attempts
                     );
-                    if req.whale_shares < 1000.0 {
-                        tokio::time::sleep(Duration::from_millis(50)).await;
-                    }
                     let _ = process_resubmit_chain(
                         &client,
                         &creds,
@@ -493,6 +1570,7 @@ async fn resubmit_worker(
                         "🔄 Resubmit FAILED: attempt {} @ {:.2} | filled {:.2}/{:.2} ({:.0}%) | {}",
                         attempt, new_price, total_filled, req.original_size, fill_pct, error_msg
                     );
+                    release_chain_notional(&req, req.size - filled_this_attempt);
                 }
             }
             Ok(Err(e)) => {
@@ -501,6 +1579,7 @@ async fn resubmit_worker(
                     "🔄 Resubmit ERROR: attempt {} | filled {:.2}/{:.2} ({:.0}%) | {}",
                     attempt, req.cumulative_filled, req.original_size, fill_pct, e
                 );
+                release_chain_notional(&req, req.size);
             }
             Err(e) => {
                 let fill_pct = if req.original_size > 0.0 { (req.cumulative_filled / req.original_size) * 100.0 } else { 0.0 };
@@ -508,6 +1587,7 @@ async fn resubmit_worker(
                     "🔄 Resubmit TASK ERROR: filled {:.2}/{:.2} ({:.0}%) | {}",
                     req.cumulative_filled, req.original_size, fill_pct, e
                 );
+                release_chain_notional(&req, req.size);
             }
         }
     }
@@ -521,49 +1601,69 @@ async fn process_resubmit_chain(
     let max_attempts = get_max_resubmit_attempts(req.whale_shares);
 
     while req.attempt <= max_attempts {
+        // Wait and price step for this attempt from the tier's resubmit schedule
+        let step = resubmit_step(req.whale_shares, req.attempt);
+        if !step.delay().is_zero() {
+            tokio::time::sleep(step.delay()).await;
+        }
+        if resubmit_deadline_hit(&req) {
+            release_chain_notional(&req, req.size);
+            return;
+        }
         let is_last_attempt = req.attempt >= max_attempts;
 
-        // Calculate increment: chase only if should_increment_price returns true
-        let increment = if should_increment_price(req.whale_shares, req.attempt) {
-            RESUBMIT_PRICE_INCREMENT
-        } else {
-            0.0  // Flat retry
-        };
-        let new_price = if req.side_is_buy {
-            (req.failed_price + increment).min(0.99)
-        } else {
-            (req.failed_price - increment).max(0.01)
-        };
+        let tick = market_cache::tick_size(&req.token_id);
+        let new_price = step.next_price(req.failed_price, req.side_is_buy, tick);
 
         // Check if we've exceeded max buffer (skip check for GTD - last attempt always goes through)
-        if !is_last_attempt && req.side_is_buy && new_price > req.max_price {
+        // or the whale premium cap (applies to every attempt)
+        if resubmit_exceeds_ceiling(req.side_is_buy, new_price, req.max_price, whale_price_cap(req.whale_price), is_last_attempt) {
+            session_summary::record(|s| s.resubmit.aborted += 1);
             let fill_pct = if req.original_size > 0.0 { (req.cumulative_filled / req.original_size) * 100.0 } else { 0.0 };
             println!(
                 "🔄 Resubmit chain ABORT: attempt {} price {:.2} > max {:.2} | filled {:.2}/{:.2} ({:.0}%)",
                 req.attempt, new_price, req.max_price, req.cumulative_filled, req.original_size, fill_pct
             );
+            release_chain_notional(&req, req.size);
             return;
         }
 
-        let client_clone = Arc::clone(&client);
-        let creds_clone = Arc::clone(&creds);
+        let client_clone = Arc::clone(client);
+        let creds_clone = Arc::clone(creds);
         let token_id = req.token_id.clone();
         let size = req.size;
         let attempt = req.attempt;
         let is_live = req.is_live;
 
-        // Submit order: FAK for early attempts, GTD with expiry for last attempt
+        // Submit order: tier action for early attempts, tier final action (GTD by default) for last attempt
+        let order_type = get_resubmit_order_action(req.whale_shares, is_last_attempt);
+        if is_resting_order(order_type) {
+            let (c, k, t) = (Arc::clone(client), Arc::clone(creds), req.token_id.clone());
+            if let Ok(Err(status)) = tokio::task::spawn_blocking(move || reserve_resting_slot(&t, &c, &k)).await {
+                session_summary::record(|s| s.resubmit.aborted += 1);
+                println!(
+                    "🔄 Resubmit chain {}: attempt {} {} not placed | filled {:.2}/{:.2}",
+                    status, req.attempt, order_type, req.cumulative_filled, req.original_size
+                );
+                release_chain_notional(&req, req.size);
+                return;
+            }
+        }
         let result = tokio::task::spawn_blocking(move || {
-            submit_resubmit_order_sync(&client_clone, &creds_clone, &token_id, new_price, size, is_live, is_last_attempt)
+            submit_resubmit_order_sync(&client_clone, &creds_clone, &token_id, new_price, size, is_live, order_type)
         }).await;
+        if let Ok(Ok((_, _, filled))) = &result {
+            record_resubmit_attempt(&req.token_id, *filled, new_price);
+        }
 
         match result {
-            Ok(Ok((true, _, filled_this_attempt))) => {
-                if is_last_attempt {
-                    // GTD order placed on book - we don't know fill amount yet
+            Ok(Ok((true, body, filled_this_attempt))) => {
+                if is_resting_order(order_type) {
+                    // Resting order placed on book - fill is settled after expiry (GTD_EXPIRY_CHECK)
+                    register_resting_order(&req.token_id, &req.whale_address, new_price, size, &body, order_type, is_live, req.counted_usd);
                     println!(
-                        "\x1b[32m🔄 Resubmit chain GTD SUBMITTED: attempt {} @ {:.2} | size {:.2} | prior filled {:.2}/{:.2}\x1b[0m",
-                        attempt, new_price, req.size, req.cumulative_filled, req.original_size
+                        "\x1b[32m🔄 Resubmit chain {} SUBMITTED: attempt {} @ {:.2} | size {:.2} | prior filled {:.2}/{:.2}\x1b[0m",
+                        order_type, attempt, new_price, req.size, req.cumulative_filled, req.original_size
                     );
                     return;
                 } else {
@@ -572,13 +1672,14 @@ async fn process_resubmit_chain(
                     let fill_pct = if req.original_size > 0.0 { (total_filled / req.original_size) * 100.0 } else { 0.0 };
                     let remaining = req.size - filled_this_attempt;
 
-                    // If partial fill, continue with remaining size
-                    if remaining > 1.0 && filled_this_attempt > 0.0 {
+                    // If partial fill, continue with remaining size until RESUBMIT_DONE_RULE is met
+                    if filled_this_attempt > 0.0 && !RESUBMIT_DONE_RULE.is_done(total_filled, req.original_size, remaining, new_price) {
                         println!(
                             "\x1b[33m🔄 Resubmit chain PARTIAL: attempt {} @ {:.2} | filled {:.2}/{:.2} ({:.0}%) | remaining {:.2}\x1b[0m",
                             attempt, new_price, total_filled, req.original_size, fill_pct, remaining
                         );
                         req.cumulative_filled = total_filled;
+                        req.counted_usd = req.unfilled_usd(remaining);
                         req.size = remaining;
                         req.failed_price = new_price;
                         req.attempt += 1;
@@ -588,18 +1689,15 @@ async fn process_resubmit_chain(
                             "\x1b[32m🔄 Resubmit chain SUCCESS: attempt {} @ {:.2} | filled {:.2}/{:.2} ({:.0}%)\x1b[0m",
                             attempt, new_price, total_filled, req.original_size, fill_pct
                         );
+                        release_chain_notional(&req, remaining);
                         return;
                     }
                 }
             }
-            Ok(Ok((false, body, filled_this_attempt))) if body.contains("FAK") && attempt < max_attempts => {
+            Ok(Ok((false, body, filled_this_attempt))) if RESUBMIT_ERROR_POLICY.is_retryable(&body) && attempt < max_attempts => {
                 req.cumulative_filled += filled_this_attempt;
                 req.failed_price = new_price;
                 req.attempt += 1;
-                // Small trades get 50ms delay to let orderbook refresh
-                if req.whale_shares < 1000.0 {
-                    tokio::time::sleep(Duration::from_millis(50)).await;
-                }
                 continue;
             }
             Ok(Ok((false, body, filled_this_attempt))) => {
@@ -612,6 +1710,7 @@ async fn process_resubmit_chain(
                     "🔄 Resubmit chain FAILED: attempt {}/{} @ {:.2} | {}filled {:.2}/{:.2} ({:.0}%){} | {}",
                     attempt, max_attempts, new_price, fill_color, total_filled, req.original_size, fill_pct, reset, error_msg
                 );
+                release_chain_notional(&req, req.size - filled_this_attempt);
                 return;
             }
             Ok(Err(e)) => {
@@ -622,6 +1721,7 @@ async fn process_resubmit_chain(
                     "🔄 Resubmit chain ERROR: attempt {} | {}filled {:.2}/{:.2} ({:.0}%){} | {}",
                     attempt, fill_color, req.cumulative_filled, req.original_size, fill_pct, reset, e
                 );
+                release_chain_notional(&req, req.size);
                 return;
             }
             Err(e) => {
@@ -632,12 +1732,93 @@ async fn process_resubmit_chain(
                     "🔄 Resubmit chain TASK ERROR: {}filled {:.2}/{:.2} ({:.0}%){} | {}",
                     fill_color, req.cumulative_filled, req.original_size, fill_pct, reset, e
                 );
+                release_chain_notional(&req, req.size);
                 return;
             }
         }
     }
 }
 
+/// Track a resting buy so a later whale exit on the token can cancel it, and so its fill can be
+/// checked once it expires. `counted_usd` is what it added to the daily notional total
+#[allow(clippy::too_many_arguments)]
+fn register_resting_order(token_id: &str, whale: &str, price: f64, size: f64, body: &str, order_type: &str, is_live: bool, counted_usd: f64) {
+    let Ok(resp) = serde_json::from_str::<OrderResponse>(body) else { return };
+    resting_orders::global().register(token_id, RestingOrder {
+        order_id: resp.order_id,
+        whale: whale.to_ascii_lowercase(),
+        price,
+        size,
+        filled_at_post: resp.taking_amount.parse().unwrap_or(0.0),
+        counted_usd,
+        expires_at: order_expiration(order_type, is_live, unix_now_secs()).and_then(|t| t.parse().ok()),
+        placed_at: unix_now_secs(),
+    });
+}
+
+/// Make room for a new resting buy under RESTING_CAP and the registry's per-token limit:
+/// Err(SKIPPED_MAX_RESTING) at the cap, or cancel the oldest resting order when
+/// RESTING_CANCEL_OLDEST is set
+fn reserve_resting_slot(token_id: &str, client: &RustClobClient, creds: &PreparedCreds) -> Result<(), StatusCode> {
+    match resting_orders::global().admit(token_id, &RESTING_CAP, unix_now_secs()) {
+        RestingAdmission::Room => Ok(()),
+        RestingAdmission::Evict(order) => {
+            cancel_resting_order(&order, token_id, "resting cap", client, creds);
+            Ok(())
+        }
+        RestingAdmission::Full => Err(StatusCode::SkippedMaxResting),
+    }
+}
+
+/// Cancel a resting order already taken out of the registry and settle it like an expired one:
+/// what matched while it rested is recorded and the unfilled notional goes back to the daily cap
+fn cancel_resting_order(order: &RestingOrder, token_id: &str, reason: &str, client: &RustClobClient, creds: &PreparedCreds) {
+    match client.cancel_order(&order.order_id, creds) {
+        Ok(resp) if resp.status().is_success() => {
+            println!("🧹 Cancelled resting order {} on {} ({})", order.order_id, token_id, reason);
+        }
+        Ok(resp) => {
+            let status = resp.status();
+            eprintln!("⚠️ Cancel {} failed: {} | {}", order.order_id, status, resp.text().unwrap_or_default());
+        }
+        Err(e) => eprintln!("⚠️ Cancel {} failed: {}", order.order_id, e),
+    }
+    // The order is untracked either way; anything matching after this is left to reconciliation
+    let Some(matched) = fetch_size_matched(&order.order_id, client, creds) else {
+        eprintln!("⚠️ Cancelled {} on {}; could not fetch its fill", order.order_id, token_id);
+        return;
+    };
+    let settled = settle_resting_order(token_id, order, matched);
+    if settled.late_fill > 0.0 || settled.released_usd > 0.0 {
+        println!(
+            "🧹 {} matched {:.2}/{:.2} before the cancel | released ${:.2}",
+            order.order_id, matched.min(order.size), order.size, settled.released_usd
+        );
+    }
+}
+
+/// Track what a successful order filled immediately (BUY: takingAmount shares, SELL: makingAmount shares).
+/// Resting fills that happen later are picked up by reconciliation.
+/// Returns the filled shares, with an OVERFILL warning if they exceed `requested`.
+fn record_position_fill(token_id: &str, side_is_buy: bool, requested: f64, body: &str) -> Option<(f64, Option<String>)> {
+    let resp = serde_json::from_str::<OrderResponse>(body).ok()?;
+    let amount = if side_is_buy { &resp.taking_amount } else { &resp.making_amount };
+    let filled = amount.parse().unwrap_or(0.0);
+    let warning = positions::global().record_order_fill(token_id, requested, filled, side_is_buy, *OVERFILL_TOLERANCE_SHARES);
+    Some((filled, warning))
+}
+
+/// Cancel our resting buys on a token the whale just sold (runs after the sell copy is submitted)
+fn cancel_exited_resting_orders(evt: &ParsedEvent, client: &RustClobClient, creds: &PreparedCreds) {
+    if evt.order.order_type.starts_with("BUY") {
+        return;
+    }
+    let orders = resting_orders::global().take_for_exit(&evt.order.clob_token_id, &evt.whale_address, unix_now_secs());
+    for order in orders {
+        cancel_resting_order(&order, &evt.order.clob_token_id, "whale exited", client, creds);
+    }
+}
+
 /// Returns (success, body_text, filled_shares)
 fn submit_resubmit_order_sync(
     client: &RustClobClient,
@@ -646,30 +1827,18 @@ fn submit_resubmit_order_sync(
     price: f64,
     size: f64,
     is_live: bool,
-    is_last_attempt: bool,
+    order_type: &str,
 ) -> anyhow::Result<(bool, String, f64)> {
-    use std::time::{SystemTime, UNIX_EPOCH};
-
     let mut client = client.clone();
 
-    // Only use GTD with expiry on the LAST attempt; earlier attempts use FAK
-    let (expiration, order_type) = if is_last_attempt {
-        let expiry_secs = get_gtd_expiry_secs(is_live);
-        let expiry_timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs() + expiry_secs;
-        (Some(expiry_timestamp.to_string()), "GTD")
-    } else {
-        (None, "FAK")
-    };
+    // GTD carries its expiry; FAK only if FAK_EXPIRATION_SECS is set
+    let expiration = submit_expiration(order_type, is_live, unix_now_secs(), *FAK_EXPIRATION_SECS);
 
-    // Round to micro-units (6 decimals) then back to avoid floating-point truncation issues
-    // e.g., 40.80 stored as 40.7999999... would truncate to 40799999 instead of 40800000
-    let price_micro = (price * 1_000_000.0).round() as i64;
-    let size_micro = (size * 1_000_000.0).round() as i64;
-    let rounded_price = price_micro as f64 / 1_000_000.0;
-    let rounded_size = size_micro as f64 / 1_000_000.0;
+    // Price onto the market's tick grid (0.01, or 0.001 on three-decimal markets), within SUBMIT_PRICE_BOUNDS
+    let rounded_price = submit_price(price, market_cache::tick_size(token_id), &SUBMIT_PRICE_BOUNDS);
+    // Round size to the market's smallest unit (micro-units on 6-decimal USDC) to avoid
+    // floating-point truncation, e.g. 40.80 stored as 40.7999999... would truncate to 40799999
+    let rounded_size = round_to_units(size, market_cache::collateral_decimals(token_id, *COLLATERAL_DECIMALS));
 
     let args = OrderArgs {
         token_id: token_id.to_string(),
@@ -679,7 +1848,7 @@ fn submit_resubmit_order_sync(
         fee_rate_bps: None,
         nonce: Some(0),
         expiration,
-        taker: None,
+        taker: TAKER_ADDRESS.clone(),
         order_type: Some(order_type.to_string()),
     };
 
@@ -690,10 +1859,9 @@ fn submit_resubmit_order_sync(
     let status = resp.status();
     let body_text = resp.text().unwrap_or_default();
 
-    // Parse filled amount from successful responses
-    // GTD orders return taking_amount=0 since they're placed on book, not immediately filled
-    // For GTD, return 0 - caller handles GTD success messaging separately
-    let filled_shares = if status.is_success() && order_type == "FAK" {
+    // Parse filled amount from successful responses. A resting order (GTD/GTC) reports what
+    // matched on posting; the rest is settled once it expires or is cancelled
+    let filled_shares = if status.is_success() {
         serde_json::from_str::<OrderResponse>(&body_text)
             .ok()
             .and_then(|r| r.taking_amount.parse::<f64>().ok())
@@ -701,204 +1869,425 @@ fn submit_resubmit_order_sync(
     } else {
         0.0
     };
+    if let Some(warning) = positions::global().record_order_fill(token_id, rounded_size, filled_shares, true, *OVERFILL_TOLERANCE_SHARES) {
+        eprintln!("⚠️ {}", warning);
+    }
 
     Ok((status.is_success(), body_text, filled_shares))
 }
 
-async fn fetch_is_live(token_id: &str, client: &reqwest::Client) -> Option<bool> {
-    // Fetch market info to get slug
-    let market_url = format!("{}/markets?clob_token_ids={}", GAMMA_API_BASE, token_id);
-    let resp = client.get(&market_url).timeout(Duration::from_secs(2)).send().await.ok()?;
-    let val: Value = resp.json().await.ok()?;
-    let slug = val.get(0)?.get("slug")?.as_str()?.to_string();
+// ============================================================================
+// Notifications
+// ============================================================================
 
-    // Fetch live status from events API
-    let event_url = format!("{}/events/slug/{}", GAMMA_API_BASE, slug);
-    let resp = client.get(&event_url).timeout(Duration::from_secs(2)).send().await.ok()?;
-    let val: Value = resp.json().await.ok()?;
+/// POST an outcome message to WEBHOOK_URL in the background (failures are logged, never retried)
+fn send_webhook(client: reqwest::Client, message: String) {
+    tokio::spawn(async move {
+        let sent = client
+            .post(WEBHOOK_URL.as_str())
+            .json(&notify::webhook_body(&message))
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status());
+        if let Err(e) = sent {
+            eprintln!("⚠️ Webhook failed: {}", e);
+        }
+    });
+}
 
-    Some(val["live"].as_bool().unwrap_or(false))
+// ============================================================================
+// Block Summary
+// ============================================================================
+
+/// Print each block's summary line once its events have gone quiet
+fn spawn_block_summary_flusher() {
+    tokio::spawn(async {
+        let mut tick = tokio::time::interval(Duration::from_millis(250));
+        loop {
+            tick.tick().await;
+            let ready = block_summary::global()
+                .lock()
+                .map(|mut agg| agg.flush(std::time::Instant::now(), SUMMARY_FLUSH_DELAY))
+                .unwrap_or_default();
+            for summary in ready {
+                println!("{}", summary.render(*USD_DISPLAY_DECIMALS));
+            }
+        }
+    });
 }
 
-async fn fetch_best_book(token_id: &str, order_type: &str, client: &reqwest::Client) -> Option<((String, String), (String, String))> {
-    let url = format!("{}/book?token_id={}", CLOB_API_BASE, token_id);
-    let resp = client.get(&url).timeout(BOOK_REQ_TIMEOUT).send().await.ok()?;
-    if !resp.status().is_success() { return None; }
-    
-    let val: Value = resp.json().await.ok()?;
-    let key = if order_type.starts_with("BUY") { "asks" } else { "bids" };
-    let entries = val.get(key)?.as_array()?;
+// ============================================================================
+// Position Reconciliation
+// ============================================================================
 
-    let is_buy = order_type.starts_with("BUY");
-    
-    let (best, second): (Option<(&Value, f64)>, Option<(&Value, f64)>) = 
-        entries.iter().fold((None, None), |(best, second), entry| {
-            let price: f64 = match entry.get("price").and_then(|v| v.as_str()).and_then(|s| s.parse().ok()) {
-                Some(p) => p,
-                None => return (best, second),
-            };
-            
-            let better = |candidate: f64, current: f64| {
-                if is_buy { candidate < current } else { candidate > current }
-            };
-            
-            match best {
-                Some((_, bp)) if better(price, bp) => (Some((entry, price)), best),
-                Some((_, _bp)) => {
-                    let new_second = match second {
-                        Some((_, sp)) if better(price, sp) => Some((entry, price)),
-                        None => Some((entry, price)),
-                        _ => second,
-                    };
-                    (best, new_second)
-                }
-                None => (Some((entry, price)), second),
+/// Periodically compare tracked positions with exchange balances
+fn spawn_position_reconciler(
+    client: Arc<RustClobClient>,
+    creds: Arc<PreparedCreds>,
+    interval_secs: u64,
+    tolerance: f64,
+    correct: bool,
+) {
+    tokio::spawn(async move {
+        println!(
+            "🧮 Position reconciliation every {}s (tolerance {} shares, correct: {})",
+            interval_secs, tolerance, correct
+        );
+        loop {
+            tokio::time::sleep(Duration::from_secs(interval_secs)).await;
+            let client = Arc::clone(&client);
+            let creds = Arc::clone(&creds);
+            let result = tokio::task::spawn_blocking(move || reconcile_positions(&client, &creds, tolerance, correct)).await;
+            if let Err(e) = result {
+                eprintln!("⚠️ Reconcile task error: {}", e);
             }
-        });
+        }
+    });
+}
 
-    let b = best?.0;
-    let best_price = b.get("price")?.to_string();
-    let best_size = b.get("size")?.to_string();
-    
-    let (second_price, second_size) = second
-        .and_then(|(e, _)| {
-            let p = e.get("price")?.to_string();
-            let s = e.get("size")?.to_string();
-            Some((p, s))
-        })
-        .unwrap_or_else(|| ("N/A".into(), "N/A".into()));
-    
-    Some(((best_price, best_size), (second_price, second_size)))
+/// One reconciliation pass over the tokens we've traded. Tokens whose balance
+/// couldn't be fetched are left out rather than treated as zero.
+fn reconcile_positions(client: &RustClobClient, creds: &PreparedCreds, tolerance: f64, correct: bool) {
+    let tracker = positions::global();
+    let mut internal = tracker.snapshot();
+    let mut exchange = rustc_hash::FxHashMap::default();
+    internal.retain(|token, _| {
+        let balance = client.get_token_balance(token, creds).ok()
+            .filter(|resp| resp.status().is_success())
+            .and_then(|resp| positions::parse_balance(&resp.text().unwrap_or_default(), market_cache::collateral_decimals(token, *COLLATERAL_DECIMALS)));
+        match balance {
+            Some(b) => {
+                exchange.insert(token.clone(), b);
+                true
+            }
+            None => {
+                eprintln!("⚠️ Reconcile: could not fetch balance for {}", token);
+                false
+            }
+        }
+    });
+
+    let discrepancies = positions::diff_positions(&internal, &exchange, tolerance);
+    for d in &discrepancies {
+        eprintln!(
+            "⚠️ Position drift on {}: tracked {:.2} | exchange {:.2} ({:+.2}){}",
+            d.token_id, d.internal, d.exchange, d.drift(), if correct { " - corrected" } else { "" }
+        );
+    }
+    if correct {
+        tracker.apply_corrections(&discrepancies);
+    }
+    status::publish("positions", serde_json::json!({
+        "tracked": tracker.len(),
+        "checked": internal.len(),
+        "discrepancies": discrepancies.iter()
+            .map(|d| serde_json::json!({ "token_id": d.token_id, "internal": d.internal, "exchange": d.exchange }))
+            .collect::<Vec<_>>(),
+        "corrected": correct,
+        "checked_at": unix_now_secs(),
+    }));
 }
 
 // ============================================================================
-// Event Parsing
+// Live Flips
 // ============================================================================
 
-fn parse_event(message: String) -> Option<ParsedEvent> {
-    let msg: WsMessage = serde_json::from_str(&message).ok()?;
-    let result = msg.params?.result?;
-    
-    // just to double check! 
-    if result.topics.len() < 3 { return None; }
-    
-    let has_target = result.topics.get(2)
-        .map(|t| t.eq_ignore_ascii_case(TARGET_TOPIC_HEX.as_str()))
-        .unwrap_or(false);
-    if !has_target { return None; }
-
-    let hex_data = &result.data;
-    if hex_data.len() < 2 + 64 * 4 { return None; }
-
-    let (maker_id, maker_bytes) = parse_u256_hex_slice_with_bytes(hex_data, 2, 66)?;
-    let (taker_id, taker_bytes) = parse_u256_hex_slice_with_bytes(hex_data, 66, 130)?;
-
-    let (clob_id, token_bytes, maker_amt, taker_amt, base_type) =
-        if maker_id.is_zero() && !taker_id.is_zero() {
-            let m = parse_u256_hex_slice(hex_data, 130, 194)?;
-            let t = parse_u256_hex_slice(hex_data, 194, 258)?;
-            (taker_id, taker_bytes, m, t, "BUY")
-        } else if taker_id.is_zero() && !maker_id.is_zero() {
-            let m = parse_u256_hex_slice(hex_data, 130, 194)?;
-            let t = parse_u256_hex_slice(hex_data, 194, 258)?;
-            (maker_id, maker_bytes, m, t, "SELL")
-        } else {
-            return None;
-        };
+/// Poll Gamma for the live status of tokens we hold or rest buys on, and react to each
+/// pre-match -> live flip with LIVE_FLIP_ACTION
+fn spawn_live_flip_watcher(
+    client: Arc<RustClobClient>,
+    creds: Arc<PreparedCreds>,
+    gamma_api_base: String,
+    timeout: Duration,
+    interval_secs: u64,
+    action: LiveFlipAction,
+) -> Result<()> {
+    let http = reqwest::Client::builder().no_proxy().build()?;
+    tokio::spawn(async move {
+        println!("🟢 Live flip watch every {}s (action: {})", interval_secs, action.as_str());
+        let mut watch = LiveWatch::new();
+        let mut tick = tokio::time::interval(Duration::from_secs(interval_secs));
+        loop {
+            tick.tick().await;
+            let mut tokens: Vec<String> = positions::global().snapshot().into_iter()
+                .filter(|(_, shares)| *shares > 0.0)
+                .map(|(token, _)| token)
+                .collect();
+            for token in resting_orders::global().tokens() {
+                if !tokens.contains(&token) {
+                    tokens.push(token);
+                }
+            }
+            watch.retain(|t| tokens.iter().any(|k| k == t));
 
-    let shares = if base_type == "BUY" { u256_to_f64(&taker_amt)? } else { u256_to_f64(&maker_amt)? } / 1e6;
-    if shares <= 0.0 { return None; }
-    
-    let usd = if base_type == "BUY" { u256_to_f64(&maker_amt)? } else { u256_to_f64(&taker_amt)? } / 1e6;
-    let price = usd / shares;
-    
-    let mut order_type = base_type.to_string();
-    if result.topics[0].eq_ignore_ascii_case(ORDERS_FILLED_EVENT_SIGNATURE) {
-        order_type.push_str("_FILL");
-    }
-
-    Some(ParsedEvent {
-        block_number: result.block_number.as_deref()
-            .and_then(|s| u64::from_str_radix(s.trim_start_matches("0x"), 16).ok())
-            .unwrap_or_default(),
-        tx_hash: result.transaction_hash.unwrap_or_default(),
-        order: OrderInfo {
-            order_type,
-            clob_token_id: u256_to_dec_cached(&token_bytes, &clob_id),
-            usd_value: usd,
-            shares,
-            price_per_share: price,
-        },
-    })
+            for token in tokens {
+                // A failed lookup keeps the last status; the flip is caught on a later poll
+                let Ok(Some(is_live)) = fetch_is_live(&token, &http, &gamma_api_base, timeout).await else { continue };
+                if !watch.observe(&token, is_live) {
+                    continue;
+                }
+                println!("🟢 {} went live while held ({})", market_cache::market_label(&token), action.as_str());
+                let (client, creds) = (Arc::clone(&client), Arc::clone(&creds));
+                if let Err(e) = tokio::task::spawn_blocking(move || react_to_live_flip(&token, action, &client, &creds)).await {
+                    eprintln!("⚠️ Live flip task error: {}", e);
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+fn react_to_live_flip(token_id: &str, action: LiveFlipAction, client: &RustClobClient, creds: &PreparedCreds) {
+    let now = unix_now_secs();
+    let resting = resting_orders::global().live_on(token_id, now);
+    let held = positions::global().get(token_id);
+    for step in flip_steps(action, held, &resting, now + get_gtd_expiry_secs(true)) {
+        match step {
+            FlipStep::Repost(order) => repost_with_live_expiry(token_id, &order, client, creds),
+            FlipStep::Cancel(order) => {
+                resting_orders::global().remove(token_id, &order.order_id);
+                cancel_resting_order(&order, token_id, "market went live", client, creds);
+            }
+            FlipStep::Sell(shares) => flatten_position(token_id, shares, client, creds),
+        }
+    }
+}
+
+/// LIVE_FLIP_ACTION=tighten: cancel a resting buy and re-post what didn't match at the same
+/// price with the live GTD expiry. An order that's no longer resting stays tracked for the
+/// expiry follow-up
+fn repost_with_live_expiry(token_id: &str, order: &RestingOrder, client: &RustClobClient, creds: &PreparedCreds) {
+    let cancelled = match client.cancel_order(&order.order_id, creds) {
+        Ok(resp) => cancel_confirmed(&resp.text().unwrap_or_default(), &order.order_id),
+        Err(e) => {
+            eprintln!("⚠️ Live flip: cancel {} failed: {}", order.order_id, e);
+            return;
+        }
+    };
+    if !cancelled {
+        println!("🟢 Live flip: {} no longer resting (filled or expired)", order.order_id);
+        return;
+    }
+    resting_orders::global().remove(token_id, &order.order_id);
+    let Some((size, carried_usd)) = unmatched_after_cancel(token_id, order, order.price, client, creds) else { return };
+    match submit_resubmit_order_sync(client, creds, token_id, order.price, size, true, "GTD") {
+        Ok((true, body, _)) => {
+            register_resting_order(token_id, &order.whale, order.price, size, &body, "GTD", true, carried_usd);
+            println!("🟢 Live flip: re-posted {:.2}/{:.2} @ {:.2} with the live expiry", size, order.size, order.price);
+        }
+        Ok((false, body, _)) => {
+            eprintln!("⚠️ Live flip: re-post @ {:.2} rejected | {}", order.price, body.chars().take(80).collect::<String>());
+            resting_orders::global().release_notional(order.placed_at, carried_usd);
+        }
+        Err(e) => {
+            eprintln!("⚠️ Live flip: re-post @ {:.2} failed: {}", order.price, e);
+            resting_orders::global().release_notional(order.placed_at, carried_usd);
+        }
+    }
+}
+
+/// LIVE_FLIP_ACTION=flatten: sell the tracked position into the best bid (FAK)
+fn flatten_position(token_id: &str, shares: f64, client: &RustClobClient, creds: &PreparedCreds) {
+    let best_bid = fetch_book_blocking(client, token_id).ok()
+        .and_then(|book| top_two_from_json(&book, TradeSide::Sell).0)
+        .map(|(price, _)| price);
+    let Some(price) = best_bid else {
+        eprintln!("⚠️ Live flip: no bid to flatten {:.2} shares of {}", shares, token_id);
+        return;
+    };
+    let size = (shares * 100.0).floor() / 100.0;
+    let args = OrderArgs {
+        token_id: token_id.to_string(),
+        price,
+        size,
+        side: "SELL".into(),
+        fee_rate_bps: None,
+        nonce: Some(0),
+        expiration: submit_expiration("FAK", true, unix_now_secs(), *FAK_EXPIRATION_SECS),
+        taker: TAKER_ADDRESS.clone(),
+        order_type: Some("FAK".into()),
+    };
+    let mut client = client.clone();
+    let result = client.create_order(args).and_then(|signed| {
+        let body = signed.post_body(&creds.api_key, "FAK");
+        client.post_order_fast(body, creds)
+    });
+    match result {
+        Ok(resp) if resp.status().is_success() => {
+            let body = resp.text().unwrap_or_default();
+            let filled = record_position_fill(token_id, false, size, &body).map_or(0.0, |(f, _)| f);
+            println!("🟢 Live flip: flattened {:.2}/{:.2} shares @ {:.2}", filled, size, price);
+        }
+        Ok(resp) => eprintln!("⚠️ Live flip: flatten rejected ({}) | {}", resp.status(), resp.text().unwrap_or_default().chars().take(80).collect::<String>()),
+        Err(e) => eprintln!("⚠️ Live flip: flatten failed: {}", e),
+    }
 }
 
 // ============================================================================
-// Hex Parsing Helpers
+// GTD Expiry Follow-up
 // ============================================================================
 
-#[inline]
-fn parse_u256_hex_slice_with_bytes(full: &str, start: usize, end: usize) -> Option<(U256, [u8; 32])> {
-    let slice = full.get(start..end)?;
-    let clean = slice.strip_prefix("0x").unwrap_or(slice);
-    if clean.len() > 64 { return None; }
+/// How often expired resting orders are looked up (GTD_EXPIRY_CHECK)
+const EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Look up resting orders once they've expired: record what filled while they rested and
+/// give the unfilled notional back to the daily cap
+fn spawn_expiry_follow_up(client: Arc<RustClobClient>, creds: Arc<PreparedCreds>, grace_secs: u64) {
+    tokio::spawn(async move {
+        println!("⏱️ GTD expiry check: resting orders looked up {}s after they expire", grace_secs);
+        let mut tick = tokio::time::interval(EXPIRY_CHECK_INTERVAL);
+        loop {
+            tick.tick().await;
+            let due = resting_orders::global().take_expired(unix_now_secs(), grace_secs);
+            if due.is_empty() {
+                continue;
+            }
+            let client = Arc::clone(&client);
+            let creds = Arc::clone(&creds);
+            let result = tokio::task::spawn_blocking(move || {
+                for (token_id, order) in &due {
+                    settle_expired_order(token_id, order, &client, &creds);
+                }
+            }).await;
+            if let Err(e) = result {
+                eprintln!("⚠️ GTD expiry check task error: {}", e);
+            }
+        }
+    });
+}
 
-    let mut hex_buf = [b'0'; 64];
-    hex_buf[64 - clean.len()..].copy_from_slice(clean.as_bytes());
+/// CLOB size_matched for one of our orders (None when the lookup fails)
+fn fetch_size_matched(order_id: &str, client: &RustClobClient, creds: &PreparedCreds) -> Option<f64> {
+    client.get_order(order_id, creds).ok()
+        .filter(|resp| resp.status().is_success())
+        .and_then(|resp| parse_size_matched(&resp.text().unwrap_or_default()))
+}
 
-    let mut out = [0u8; 32];
-    for i in 0..32 {
-        let hi = hex_nibble(hex_buf[i * 2])?;
-        let lo = hex_nibble(hex_buf[i * 2 + 1])?;
-        out[i] = (hi << 4) | lo;
+/// Record shares a resting order matched after it was posted
+fn record_late_fill(token_id: &str, order: &RestingOrder, late_fill: f64) {
+    if late_fill > 0.0 {
+        positions::global().record_fill(token_id, late_fill, true);
+        session_summary::record(|s| s.record_fill(token_id, late_fill, order.price, true));
+    }
+}
+
+/// Settle a resting order that is off the book (expired or cancelled): record its late fill
+/// and give the unfilled notional back to the daily cap
+fn settle_resting_order(token_id: &str, order: &RestingOrder, matched: f64) -> ExpirySettlement {
+    let settled = settle_expired(order, matched);
+    record_late_fill(token_id, order, settled.late_fill);
+    resting_orders::global().release_notional(order.placed_at, settled.released_usd);
+    settled
+}
+
+fn settle_expired_order(token_id: &str, order: &RestingOrder, client: &RustClobClient, creds: &PreparedCreds) {
+    let Some(matched) = fetch_size_matched(&order.order_id, client, creds) else {
+        // Reconciliation picks up any fill we can't see here
+        eprintln!("⚠️ GTD {} on {} expired; could not fetch its fill", order.order_id, token_id);
+        return;
+    };
+
+    let settled = settle_resting_order(token_id, order, matched);
+
+    let line = format!(
+        "⏱️ GTD {} on {} {}: matched {:.2}/{:.2} @ {:.2}{}",
+        order.order_id, token_id, settled.outcome.as_str(), matched.min(order.size), order.size, order.price,
+        if settled.released_usd > 0.0 { format!(" | released ${:.2}", settled.released_usd) } else { String::new() }
+    );
+    match settled.outcome {
+        ExpiryOutcome::Filled => println!("{}", line),
+        ExpiryOutcome::Partial | ExpiryOutcome::Unfilled => println!("\x1b[33m{}\x1b[0m", line),
     }
-    Some((U256::from_be_slice(&out), out))
 }
 
 #[inline]
-fn parse_u256_hex_slice(full: &str, start: usize, end: usize) -> Option<U256> {
-    parse_u256_hex_slice_with_bytes(full, start, end).map(|(v, _)| v)
+fn unix_now_ms() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis() as u64
 }
 
-fn u256_to_dec_cached(bytes: &[u8; 32], val: &U256) -> Arc<str> {
-    TOKEN_ID_CACHE.with(|cache| {
-        let mut cache = cache.borrow_mut();
-        if let Some(s) = cache.get(bytes) { return Arc::clone(s); }  // Cheap Arc clone
-        let s: Arc<str> = val.to_string().into();
-        cache.insert(*bytes, Arc::clone(&s));
-        s
-    })
+fn unix_now_secs() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs()
 }
 
-fn u256_to_f64(v: &U256) -> Option<f64> {
-    if v.bit_len() <= 64 { Some(v.as_limbs()[0] as f64) }
-    else { v.to_string().parse().ok() }
+/// Live status from Gamma, falling back to the last value seen for the token while Gamma is failing
+async fn resolve_is_live(token_id: &str, client: &reqwest::Client, gamma_api_base: &str, timeout: Duration) -> Option<bool> {
+    let health = gamma_health::global();
+    let now = unix_now_secs();
+    let result = match fetch_is_live(token_id, client, gamma_api_base, timeout).await {
+        Ok(Some(is_live)) => {
+            if health.record_success(token_id, is_live, now) == Transition::Recovered {
+                println!("✅ Gamma API recovered");
+            }
+            Some(is_live)
+        }
+        // Market unknown to Gamma: not an outage
+        Ok(None) => None,
+        Err(e) => {
+            if health.record_failure(now) == Transition::StartedFailing {
+                eprintln!("⚠️ Gamma API failing ({e}); using last-known live status until it recovers");
+            }
+            health.last_known_live(token_id)
+        }
+    };
+    publish_gamma_status();
+    result
 }
 
-// Hex nibble lookup table - 2-3x faster than branching
-const HEX_NIBBLE_LUT: [u8; 256] = {
-    let mut lut = [255u8; 256];
-    let mut i = b'0';
-    while i <= b'9' {
-        lut[i as usize] = i - b'0';
-        i += 1;
-    }
-    let mut i = b'a';
-    while i <= b'f' {
-        lut[i as usize] = i - b'a' + 10;
-        i += 1;
-    }
-    let mut i = b'A';
-    while i <= b'F' {
-        lut[i as usize] = i - b'A' + 10;
-        i += 1;
+fn publish_gamma_status() {
+    let mut value = serde_json::to_value(gamma_health::global().snapshot()).unwrap_or_default();
+    let stale_since = market_cache::global_caches().stale_since.load(std::sync::atomic::Ordering::Relaxed);
+    value["cache_stale_since"] = if stale_since == 0 { Value::Null } else { stale_since.into() };
+    status::publish("gamma", value);
+}
+
+/// Ok(None) if Gamma doesn't know the token; Err on network/HTTP/parse failures
+async fn fetch_is_live(token_id: &str, client: &reqwest::Client, gamma_api_base: &str, timeout: Duration) -> Result<Option<bool>> {
+    // Fetch market info to get slug
+    let market_url = format!("{}/markets?clob_token_ids={}", gamma_api_base, token_id);
+    let resp = client.get(&market_url).timeout(timeout).send().await?.error_for_status()?;
+    let val: Value = resp.json().await?;
+    let Some(slug) = val.get(0).and_then(|m| m.get("slug")).and_then(|s| s.as_str()) else { return Ok(None) };
+    if let Some(label) = market_cache::MarketLabel::from_gamma_market(&val[0], token_id) {
+        market_cache::global_caches().set_label(token_id.to_string(), label);
     }
-    lut
-};
 
-#[inline(always)]
-fn hex_nibble(b: u8) -> Option<u8> {
-    let val = HEX_NIBBLE_LUT[b as usize];
-    if val == 255 { None } else { Some(val) }
+    // Fetch live status from events API
+    let event_url = format!("{}/events/slug/{}", gamma_api_base, slug);
+    let resp = client.get(&event_url).timeout(timeout).send().await?.error_for_status()?;
+    let val: Value = resp.json().await?;
+
+    Ok(Some(val["live"].as_bool().unwrap_or(false)))
+}
+
+async fn fetch_book_json(url: &str, client: &reqwest::Client) -> Option<Value> {
+    let resp = client.get(url).timeout(BOOK_REQ_TIMEOUT).send().await.ok()?;
+    if !resp.status().is_success() { return None; }
+    resp.json().await.ok()
+}
+
+async fn fetch_best_book(token_id: &str, order_type: &str, client: &reqwest::Client, clob_api_base: &str) -> Option<((String, String), (String, String))> {
+    let url = format!("{}/book?token_id={}", clob_api_base, token_id);
+    // Post-trade logging only: a couple of quick retries beats an N/A row in the CSV
+    let val = retry::retry_with_backoff(*BOOK_LOG_RETRIES, BOOK_LOG_RETRY_BACKOFF, || fetch_book_json(&url, client)).await?;
+    let key = if order_type.starts_with("BUY") { "asks" } else { "bids" };
+    let entries = val.get(key)?.as_array()?;
+
+    let (best, second) = top_two_levels(entries, order_type.starts_with("BUY"));
+
+    let b = best?;
+    let best_price = b.get("price")?.to_string();
+    let best_size = b.get("size")?.to_string();
+    
+    let (second_price, second_size) = second
+        .and_then(|e| {
+            let p = e.get("price")?.to_string();
+            let s = e.get("size")?.to_string();
+            Some((p, s))
+        })
+        .unwrap_or_else(|| ("N/A".into(), "N/A".into()));
+    
+    Some(((best_price, best_size), (second_price, second_size)))
 }
 
 // ============================================================================
@@ -906,9 +2295,19 @@ fn hex_nibble(b: u8) -> Option<u8> {
 // ============================================================================
 
 fn ensure_csv() -> Result<()> {
+    let mut header = csv_log::csv_header(*CSV_DIRECTION).to_string();
+    if *CSV_TIER_COLUMNS {
+        header.push_str(csv_log::CSV_TIER_HEADER);
+    }
     if !Path::new(CSV_FILE).exists() {
         let mut f = File::create(CSV_FILE)?;
-        writeln!(f, "timestamp,block,clob_asset_id,usd_value,shares,price_per_share,direction,order_status,best_price,best_size,second_price,second_size,tx_hash,is_live")?;
+        writeln!(f, "{}", header)?;
+    } else {
+        let mut existing = String::new();
+        std::io::BufRead::read_line(&mut std::io::BufReader::new(File::open(CSV_FILE)?), &mut existing)?;
+        if existing.trim_end() != header {
+            eprintln!("⚠️ {} has a different header than CSV_DIRECTION={:?} / CSV_TIER_COLUMNS={} writes; start a new file to switch layouts", CSV_FILE, *CSV_DIRECTION, *CSV_TIER_COLUMNS);
+        }
     }
     Ok(())
 }
@@ -918,16 +2317,3 @@ fn append_csv_row(row: String) {
         let _ = writeln!(f, "{}", row);
     }
 }
-
-#[inline]
-fn sanitize_csv(value: &str, out: &mut String) {
-    out.clear();
-    if !value.bytes().any(|b| b == b',' || b == b'\n' || b == b'\r') {
-        out.push_str(value);
-        return;
-    }
-    out.reserve(value.len());
-    for &b in value.as_bytes() {
-        out.push(match b { b',' => ';', b'\n' | b'\r' => ' ', _ => b as char });
-    }
-}
\ No newline at end of file
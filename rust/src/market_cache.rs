@@ -1,9 +1,10 @@
-/// Market cache management with automatic refresh
-/// Handles caching of market data, tokens, and live status
+//! Market cache management with automatic refresh
+//! Handles caching of market data, tokens, and live status
 
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Deserialize;
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::RwLock;
 use std::time::{Duration, Instant};
 
@@ -14,16 +15,98 @@ use std::time::{Duration, Instant};
 /// How often to refresh caches (in seconds)
 pub const CACHE_REFRESH_INTERVAL_SECS: u64 = 30 * 60; // 30 minutes
 
+/// Refresh interval until the caches are warm (live status loaded)
+pub const WARMUP_RETRY_SECS: u64 = 10;
+
 /// Cache file paths
 const NEG_RISK_CACHE_PATH: &str = ".clob_market_cache.json";
 const SLUG_CACHE_PATH: &str = ".clob_slug_cache.json";
 const ATP_TOKENS_CACHE_PATH: &str = ".atp_token_categories.json";
 const LIGUE1_TOKENS_CACHE_PATH: &str = ".ligue1_tokens.json";
 const LIVE_CACHE_PATH: &str = ".live_cache.json";
+const TICK_CACHE_PATH: &str = ".clob_tick_cache.json";
+const DECIMALS_CACHE_PATH: &str = ".clob_decimals_cache.json";
+const LABEL_CACHE_PATH: &str = ".clob_label_cache.json";
+
+/// Longest market question shown in log labels before it's cut with "…"
+pub const LABEL_QUESTION_MAX_CHARS: usize = 40;
+
+/// Tick size assumed for tokens missing from the tick cache (what create_order signs with)
+pub const DEFAULT_TICK_SIZE: f64 = 0.01;
 
-/// Price buffer adjustments for specialized markets
-const TENNIS_BUFFER: f64 = 0.01;
-const SOCCER_BUFFER: f64 = 0.01;
+/// Largest collateral decimals accepted from the decimals cache (amounts are encoded as u128)
+pub const MAX_COLLATERAL_DECIMALS: u8 = 18;
+
+/// Default price buffer adjustments for specialized markets
+/// (override with TENNIS_BUFFER / SOCCER_BUFFER)
+pub const DEFAULT_TENNIS_BUFFER: f64 = 0.01;
+pub const DEFAULT_SOCCER_BUFFER: f64 = 0.01;
+
+/// Human-readable market for a token, from Gamma market metadata
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MarketLabel {
+    pub question: String,
+    pub outcome: String,
+}
+
+impl MarketLabel {
+    /// Label from a Gamma /markets entry: its question, and the outcome at this token's
+    /// index in clobTokenIds (both lists arrive as JSON-encoded strings)
+    pub fn from_gamma_market(market: &serde_json::Value, token_id: &str) -> Option<Self> {
+        let question = market.get("question")?.as_str()?.trim();
+        if question.is_empty() {
+            return None;
+        }
+        let list = |key: &str| -> Vec<String> {
+            match market.get(key) {
+                Some(serde_json::Value::String(s)) => serde_json::from_str(s).unwrap_or_default(),
+                Some(v) => serde_json::from_value(v.clone()).unwrap_or_default(),
+                None => Vec::new(),
+            }
+        };
+        let outcome = list("clobTokenIds")
+            .iter()
+            .position(|id| id == token_id)
+            .and_then(|i| list("outcomes").into_iter().nth(i))
+            .unwrap_or_default();
+        Some(Self { question: question.to_string(), outcome })
+    }
+
+    /// "Will X win the…? [Yes]", question cut at LABEL_QUESTION_MAX_CHARS
+    pub fn short(&self) -> String {
+        let mut out: String = self.question.chars().take(LABEL_QUESTION_MAX_CHARS).collect();
+        if self.question.chars().count() > LABEL_QUESTION_MAX_CHARS {
+            out.push('…');
+        }
+        if !self.outcome.is_empty() {
+            out.push_str(&format!(" [{}]", self.outcome));
+        }
+        out
+    }
+}
+
+/// Per-market behavior from the MARKET_OVERRIDES file, keyed by clob_token_id.
+/// Set fields take precedence over the tier and sport buffers/multipliers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MarketOverride {
+    /// Limit price buffer (replaces tier + sport buffer, both sides)
+    pub buffer: Option<f64>,
+    /// Size multiplier (replaces the tier's)
+    pub size_multiplier: Option<f64>,
+    /// Never copy trades in this market
+    #[serde(default)]
+    pub skip: bool,
+    /// Smallest copy worth placing here, in shares (replaces MIN_SHARE_COUNT; smaller sizes are skipped)
+    pub min_shares: Option<f64>,
+}
+
+impl MarketOverride {
+    fn is_valid(&self) -> bool {
+        let ok = |v: Option<f64>| v.is_none_or(|x| x.is_finite() && x >= 0.0);
+        ok(self.buffer) && ok(self.size_multiplier) && ok(self.min_shares)
+    }
+}
 
 // ============================================================================
 // Cache Data Structures
@@ -41,10 +124,32 @@ pub struct MarketCaches {
     pub soccer_tokens: RwLock<FxHashMap<String, ()>>,
     /// Token ID -> live status (for GTD expiry calculation)
     pub live_status: RwLock<FxHashMap<String, bool>>,
+    /// Token ID -> minimum price tick (for aligning the whale price)
+    pub tick_sizes: RwLock<FxHashMap<String, f64>>,
+    /// Token ID -> decimals of the market's collateral (and outcome shares) on chain
+    pub collateral_decimals: RwLock<FxHashMap<String, u8>>,
+    /// Token ID -> market question and outcome (for log lines)
+    pub labels: RwLock<FxHashMap<String, MarketLabel>>,
     /// Last refresh timestamp (Unix seconds)
     pub last_refresh: AtomicU64,
+    /// When sport/live data first failed to refresh and last-known values were kept (Unix seconds, 0 = fresh)
+    pub stale_since: AtomicU64,
+    /// Set by the first load that finds live-status data (what WARMUP_GATE waits for)
+    warm: AtomicBool,
     /// Cache statistics
     pub stats: CacheStats,
+    /// Price buffer for tennis tokens
+    pub tennis_buffer: f64,
+    /// Price buffer for soccer tokens
+    pub soccer_buffer: f64,
+    /// Token ID -> per-market override (MARKET_OVERRIDES file, reloaded with the other caches)
+    pub overrides: RwLock<FxHashMap<String, MarketOverride>>,
+    /// MARKET_OVERRIDES file path (None = no overrides)
+    overrides_path: Option<String>,
+    /// Lowercase 0x addresses never copied as whale or counterparty (IGNORED_MAKERS file)
+    pub ignored_makers: RwLock<FxHashSet<String>>,
+    /// IGNORED_MAKERS file path (None = ignore nobody)
+    ignored_makers_path: Option<String>,
 }
 
 #[derive(Default)]
@@ -59,15 +164,38 @@ pub struct CacheStats {
 }
 
 impl MarketCaches {
+    /// Empty caches with buffers from TENNIS_BUFFER / SOCCER_BUFFER (0.01 each if unset)
     pub fn new() -> Self {
+        let mut caches = Self::with_buffers(
+            buffer_from_env("TENNIS_BUFFER", DEFAULT_TENNIS_BUFFER),
+            buffer_from_env("SOCCER_BUFFER", DEFAULT_SOCCER_BUFFER),
+        );
+        caches.overrides_path = std::env::var("MARKET_OVERRIDES").ok().filter(|p| !p.trim().is_empty());
+        caches.ignored_makers_path = std::env::var("IGNORED_MAKERS").ok().filter(|p| !p.trim().is_empty());
+        caches
+    }
+
+    /// Empty caches with explicit per-sport buffers
+    pub fn with_buffers(tennis_buffer: f64, soccer_buffer: f64) -> Self {
         Self {
             neg_risk: RwLock::new(FxHashMap::default()),
             slugs: RwLock::new(FxHashMap::default()),
             tennis_tokens: RwLock::new(FxHashMap::default()),
             soccer_tokens: RwLock::new(FxHashMap::default()),
             live_status: RwLock::new(FxHashMap::default()),
+            tick_sizes: RwLock::new(FxHashMap::default()),
+            collateral_decimals: RwLock::new(FxHashMap::default()),
+            labels: RwLock::new(FxHashMap::default()),
             last_refresh: AtomicU64::new(0),
+            stale_since: AtomicU64::new(0),
+            warm: AtomicBool::new(false),
             stats: CacheStats::default(),
+            tennis_buffer,
+            soccer_buffer,
+            overrides: RwLock::new(FxHashMap::default()),
+            overrides_path: None,
+            ignored_makers: RwLock::new(FxHashSet::default()),
+            ignored_makers_path: None,
         }
     }
 
@@ -82,7 +210,7 @@ impl MarketCaches {
                 let count = map.len();
                 if let Ok(mut cache) = self.neg_risk.write() {
                     cache.clear();
-                    cache.extend(map.into_iter());
+                    cache.extend(map);
                     result.neg_risk_loaded = count;
                     self.stats.neg_risk_count.store(count as u64, Ordering::Relaxed);
                 }
@@ -95,49 +223,97 @@ impl MarketCaches {
                 let count = map.len();
                 if let Ok(mut cache) = self.slugs.write() {
                     cache.clear();
-                    cache.extend(map.into_iter());
+                    cache.extend(map);
                     result.slugs_loaded = count;
                     self.stats.slug_count.store(count as u64, Ordering::Relaxed);
                 }
             }
         }
 
+        // Load tick size cache (token_id -> tick, e.g. 0.01 or 0.001)
+        if let Some(map) = read_json_file::<HashMap<String, f64>>(TICK_CACHE_PATH) {
+            if let Ok(mut cache) = self.tick_sizes.write() {
+                *cache = map.into_iter().filter(|(_, t)| t.is_finite() && *t > 0.0 && *t < 1.0).collect();
+                result.ticks_loaded = cache.len();
+            }
+        }
+
+        // Collateral decimals for markets not on 6-decimal USDC (token_id -> decimals)
+        if let Some(map) = read_json_file::<HashMap<String, u8>>(DECIMALS_CACHE_PATH) {
+            if let Ok(mut cache) = self.collateral_decimals.write() {
+                *cache = map.into_iter().filter(|(_, d)| *d <= MAX_COLLATERAL_DECIMALS).collect();
+                result.decimals_loaded = cache.len();
+            }
+        }
+
+        // Labels file is merged in: labels fetched at runtime are kept across refreshes
+        if let Some(map) = read_json_file::<HashMap<String, MarketLabel>>(LABEL_CACHE_PATH) {
+            if let Ok(mut cache) = self.labels.write() {
+                result.labels_loaded = map.len();
+                cache.extend(map);
+            }
+        }
+
+        // Sport classification and live status come from Gamma-backed files: a missing,
+        // unreadable or empty file (e.g. written during a Gamma outage) keeps last-known values
+        let mut stale = false;
+
         // Load ATP tokens cache
-        if let Ok(data) = std::fs::read_to_string(ATP_TOKENS_CACHE_PATH) {
-            if let Ok(map) = serde_json::from_str::<HashMap<String, String>>(&data) {
-                let count = map.len();
-                if let Ok(mut cache) = self.tennis_tokens.write() {
-                    cache.clear();
-                    cache.extend(map.into_iter());
-                    result.atp_loaded = count;
-                    self.stats.tennis_count.store(count as u64, Ordering::Relaxed);
-                }
+        let atp = read_json_file::<HashMap<String, String>>(ATP_TOKENS_CACHE_PATH).map(|m| m.into_iter().collect());
+        match swap_or_keep(&self.tennis_tokens, atp) {
+            Refresh::Loaded(count) => {
+                result.atp_loaded = count;
+                self.stats.tennis_count.store(count as u64, Ordering::Relaxed);
             }
+            Refresh::KeptStale => stale = true,
+            Refresh::Missing => {}
         }
 
         // Load Ligue 1 tokens cache
-        if let Ok(data) = std::fs::read_to_string(LIGUE1_TOKENS_CACHE_PATH) {
-            if let Ok(tokens) = serde_json::from_str::<Vec<String>>(&data) {
-                let count = tokens.len();
-                if let Ok(mut cache) = self.soccer_tokens.write() {
-                    cache.clear();
-                    cache.extend(tokens.into_iter().map(|t| (t, ())));
-                    result.ligue1_loaded = count;
-                    self.stats.soccer_count.store(count as u64, Ordering::Relaxed);
-                }
+        let ligue1 = read_json_file::<Vec<String>>(LIGUE1_TOKENS_CACHE_PATH).map(|v| v.into_iter().map(|t| (t, ())).collect());
+        match swap_or_keep(&self.soccer_tokens, ligue1) {
+            Refresh::Loaded(count) => {
+                result.ligue1_loaded = count;
+                self.stats.soccer_count.store(count as u64, Ordering::Relaxed);
             }
+            Refresh::KeptStale => stale = true,
+            Refresh::Missing => {}
         }
 
         // Load live status cache
-        if let Ok(data) = std::fs::read_to_string(LIVE_CACHE_PATH) {
-            if let Ok(map) = serde_json::from_str::<HashMap<String, bool>>(&data) {
-                let count = map.len();
-                if let Ok(mut cache) = self.live_status.write() {
-                    cache.clear();
-                    cache.extend(map.into_iter());
-                    result.live_loaded = count;
-                    self.stats.live_count.store(count as u64, Ordering::Relaxed);
+        let live = read_json_file::<HashMap<String, bool>>(LIVE_CACHE_PATH).map(|m| m.into_iter().collect());
+        match swap_or_keep(&self.live_status, live) {
+            Refresh::Loaded(count) => {
+                result.live_loaded = count;
+                self.stats.live_count.store(count as u64, Ordering::Relaxed);
+            }
+            Refresh::KeptStale => stale = true,
+            Refresh::Missing => {}
+        }
+
+        // Load per-market overrides (a bad edit keeps the previous overrides)
+        if let Some(path) = &self.overrides_path {
+            match load_overrides(path) {
+                Ok(map) => {
+                    result.overrides_loaded = Some(map.len());
+                    if let Ok(mut cache) = self.overrides.write() {
+                        *cache = map;
+                    }
+                }
+                Err(e) => eprintln!("⚠️ MARKET_OVERRIDES {}: {} (keeping previous overrides)", path, e),
+            }
+        }
+
+        // Load the wash-trade ignore list (a bad edit keeps the previous list)
+        if let Some(path) = &self.ignored_makers_path {
+            match load_ignored_makers(path) {
+                Ok(set) => {
+                    result.ignored_makers_loaded = Some(set.len());
+                    if let Ok(mut cache) = self.ignored_makers.write() {
+                        *cache = set;
+                    }
                 }
+                Err(e) => eprintln!("⚠️ IGNORED_MAKERS {}: {} (keeping previous list)", path, e),
             }
         }
 
@@ -150,12 +326,30 @@ impl MarketCaches {
             .unwrap()
             .as_secs();
         self.last_refresh.store(now, Ordering::Relaxed);
+        if stale {
+            // Keep the time the data first went stale; warn once per outage
+            if self.stale_since.compare_exchange(0, now, Ordering::Relaxed, Ordering::Relaxed).is_ok() {
+                eprintln!("⚠️ Sport/live cache refresh failed; keeping last-known values");
+            }
+        } else {
+            self.stale_since.store(0, Ordering::Relaxed);
+        }
+        result.stale = stale;
+        if self.live_status.read().is_ok_and(|c| !c.is_empty()) {
+            self.warm.store(true, Ordering::Relaxed);
+        }
         self.stats.refresh_count.fetch_add(1, Ordering::Relaxed);
         self.stats.last_refresh_duration_ms.store(result.load_time_ms, Ordering::Relaxed);
 
         result
     }
 
+    /// A load has found live-status data; stays true once set
+    #[inline]
+    pub fn is_warm(&self) -> bool {
+        self.warm.load(Ordering::Relaxed)
+    }
+
     /// Check if token is neg_risk
     #[inline]
     pub fn is_neg_risk(&self, token_id: &str) -> Option<bool> {
@@ -180,16 +374,16 @@ impl MarketCaches {
         self.soccer_tokens.read().map(|c| c.contains_key(token_id)).unwrap_or(false)
     }
 
-    /// Get ATP buffer for token (0.01 if ATP, 0.0 otherwise)
+    /// Get ATP buffer for token (tennis buffer if ATP, 0.0 otherwise)
     #[inline]
     pub fn get_atp_buffer(&self, token_id: &str) -> f64 {
-        if self.is_atp_token(token_id) { TENNIS_BUFFER } else { 0.0 }
+        if self.is_atp_token(token_id) { self.tennis_buffer } else { 0.0 }
     }
 
-    /// Get Ligue 1 buffer for token (0.01 if Ligue1, 0.0 otherwise)
+    /// Get Ligue 1 buffer for token (soccer buffer if Ligue1, 0.0 otherwise)
     #[inline]
     pub fn get_ligue1_buffer(&self, token_id: &str) -> f64 {
-        if self.is_ligue1_token(token_id) { SOCCER_BUFFER } else { 0.0 }
+        if self.is_ligue1_token(token_id) { self.soccer_buffer } else { 0.0 }
     }
 
     /// Get live status for token (for GTD expiry calculation)
@@ -198,6 +392,78 @@ impl MarketCaches {
         self.live_status.read().ok()?.get(token_id).copied()
     }
 
+    /// Minimum price tick for token (DEFAULT_TICK_SIZE if not cached)
+    #[inline]
+    pub fn tick_size(&self, token_id: &str) -> f64 {
+        self.tick_sizes.read().ok().and_then(|c| c.get(token_id).copied()).unwrap_or(DEFAULT_TICK_SIZE)
+    }
+
+    /// Insert tick size for a token (for dynamic updates)
+    pub fn set_tick_size(&self, token_id: String, tick: f64) {
+        if let Ok(mut cache) = self.tick_sizes.write() {
+            cache.insert(token_id, tick);
+        }
+    }
+
+    /// Collateral decimals for token, `default` if not cached
+    #[inline]
+    pub fn collateral_decimals(&self, token_id: &str, default: u8) -> u8 {
+        self.collateral_decimals.read().ok().and_then(|c| c.get(token_id).copied()).unwrap_or(default)
+    }
+
+    /// Insert collateral decimals for a token (for dynamic updates)
+    pub fn set_collateral_decimals(&self, token_id: String, decimals: u8) {
+        if let Ok(mut cache) = self.collateral_decimals.write() {
+            cache.insert(token_id, decimals);
+        }
+    }
+
+    /// Question and outcome for token, if known
+    pub fn get_label(&self, token_id: &str) -> Option<MarketLabel> {
+        self.labels.read().ok()?.get(token_id).cloned()
+    }
+
+    /// Insert the label for a token (for dynamic updates)
+    pub fn set_label(&self, token_id: String, label: MarketLabel) {
+        if let Ok(mut cache) = self.labels.write() {
+            cache.insert(token_id, label);
+        }
+    }
+
+    /// Short label for log lines, or the raw token id if the market is unknown
+    pub fn market_label(&self, token_id: &str) -> String {
+        self.get_label(token_id).map(|l| l.short()).unwrap_or_else(|| token_id.to_string())
+    }
+
+    /// Override for this market, if any
+    #[inline]
+    pub fn get_override(&self, token_id: &str) -> Option<MarketOverride> {
+        self.overrides.read().ok()?.get(token_id).copied()
+    }
+
+    /// Insert or replace a market override (until the next file reload)
+    pub fn set_override(&self, token_id: String, over: MarketOverride) {
+        if let Ok(mut cache) = self.overrides.write() {
+            cache.insert(token_id, over);
+        }
+    }
+
+    /// True if `address` is on the ignore list (any case, with or without 0x)
+    #[inline]
+    pub fn is_ignored_maker(&self, address: &str) -> bool {
+        if address.is_empty() {
+            return false;
+        }
+        self.ignored_makers.read().is_ok_and(|set| !set.is_empty() && set.contains(&normalize_address(address)))
+    }
+
+    /// Add an address to the ignore list (until the next file reload)
+    pub fn ignore_maker(&self, address: &str) {
+        if let Ok(mut set) = self.ignored_makers.write() {
+            set.insert(normalize_address(address));
+        }
+    }
+
     /// Insert neg_risk value for a token (for dynamic updates)
     pub fn set_neg_risk(&self, token_id: String, neg_risk: bool) {
         if let Ok(mut cache) = self.neg_risk.write() {
@@ -235,6 +501,81 @@ impl MarketCaches {
     }
 }
 
+/// Non-negative, finite buffer from env, or `default` if unset/invalid
+fn buffer_from_env(key: &str, default: f64) -> f64 {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|b| b.is_finite() && *b >= 0.0)
+        .unwrap_or(default)
+}
+
+/// Outcome of refreshing one cache from disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Refresh {
+    Loaded(usize),
+    /// Load failed or came back empty; the populated cache was left as-is
+    KeptStale,
+    /// Load failed and there was nothing cached to fall back on
+    Missing,
+}
+
+/// Parse a MARKET_OVERRIDES file: `{"<token_id>": {"buffer": 0.0, "size_multiplier": 2.0, "skip": false}}`.
+/// Entries with a negative or non-finite value are dropped with a warning
+pub fn parse_overrides(json: &str) -> Result<FxHashMap<String, MarketOverride>, serde_json::Error> {
+    let raw: HashMap<String, MarketOverride> = serde_json::from_str(json)?;
+    Ok(raw
+        .into_iter()
+        .filter(|(token, over)| {
+            let valid = over.is_valid();
+            if !valid {
+                eprintln!("⚠️ MARKET_OVERRIDES: ignoring {} (negative or invalid value)", token);
+            }
+            valid
+        })
+        .collect())
+}
+
+fn load_overrides(path: &str) -> Result<FxHashMap<String, MarketOverride>, String> {
+    let data = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    parse_overrides(&data).map_err(|e| e.to_string())
+}
+
+/// Lowercase, 0x-prefixed form addresses are compared in
+fn normalize_address(address: &str) -> String {
+    let address = address.trim().to_ascii_lowercase();
+    if address.starts_with("0x") { address } else { format!("0x{}", address) }
+}
+
+/// Parse an IGNORED_MAKERS file: a JSON array of addresses, e.g. `["0xabc...", "def..."]`
+pub fn parse_ignored_makers(json: &str) -> Result<FxHashSet<String>, serde_json::Error> {
+    let raw: Vec<String> = serde_json::from_str(json)?;
+    Ok(raw.iter().filter(|a| !a.trim().is_empty()).map(|a| normalize_address(a)).collect())
+}
+
+fn load_ignored_makers(path: &str) -> Result<FxHashSet<String>, String> {
+    let data = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    parse_ignored_makers(&data).map_err(|e| e.to_string())
+}
+
+fn read_json_file<T: serde::de::DeserializeOwned>(path: &str) -> Option<T> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+/// Replace `cache` with `loaded`, unless the load failed or is empty and the cache still has entries
+fn swap_or_keep<V>(cache: &RwLock<FxHashMap<String, V>>, loaded: Option<FxHashMap<String, V>>) -> Refresh {
+    let Ok(mut cache) = cache.write() else { return Refresh::Missing };
+    match loaded {
+        Some(map) if !map.is_empty() || cache.is_empty() => {
+            let count = map.len();
+            *cache = map;
+            Refresh::Loaded(count)
+        }
+        _ if !cache.is_empty() => Refresh::KeptStale,
+        _ => Refresh::Missing,
+    }
+}
+
 impl Default for MarketCaches {
     fn default() -> Self {
         Self::new()
@@ -248,7 +589,16 @@ pub struct CacheLoadResult {
     pub atp_loaded: usize,
     pub ligue1_loaded: usize,
     pub live_loaded: usize,
+    pub ticks_loaded: usize,
+    pub decimals_loaded: usize,
+    pub labels_loaded: usize,
     pub load_time_ms: u64,
+    /// Some sport/live data couldn't be refreshed and last-known values were kept
+    pub stale: bool,
+    /// Market overrides loaded (None = MARKET_OVERRIDES unset or unreadable)
+    pub overrides_loaded: Option<usize>,
+    /// Ignored maker addresses loaded (None = IGNORED_MAKERS unset or unreadable)
+    pub ignored_makers_loaded: Option<usize>,
 }
 
 impl std::fmt::Display for CacheLoadResult {
@@ -262,7 +612,26 @@ impl std::fmt::Display for CacheLoadResult {
             self.atp_loaded,
             self.ligue1_loaded,
             self.live_loaded
-        )
+        )?;
+        if self.ticks_loaded > 0 {
+            write!(f, ", ticks={}", self.ticks_loaded)?;
+        }
+        if self.decimals_loaded > 0 {
+            write!(f, ", decimals={}", self.decimals_loaded)?;
+        }
+        if self.labels_loaded > 0 {
+            write!(f, ", labels={}", self.labels_loaded)?;
+        }
+        if let Some(n) = self.overrides_loaded {
+            write!(f, ", overrides={}", n)?;
+        }
+        if let Some(n) = self.ignored_makers_loaded {
+            write!(f, ", ignored_makers={}", n)?;
+        }
+        if self.stale {
+            write!(f, " (STALE: serving last-known sport/live data)")?;
+        }
+        Ok(())
     }
 }
 
@@ -307,7 +676,9 @@ pub fn spawn_cache_refresh_task() -> tokio::task::JoinHandle<()> {
         println!("🔄 Cache refresh task started (interval: {}s)", CACHE_REFRESH_INTERVAL_SECS);
 
         loop {
-            tokio::time::sleep(interval).await;
+            // Retry soon while the startup load came up empty
+            let wait = if global_caches().is_warm() { interval } else { Duration::from_secs(WARMUP_RETRY_SECS) };
+            tokio::time::sleep(wait).await;
 
             // Run cache refresh in blocking task to avoid blocking async runtime
             let result = tokio::task::spawn_blocking(refresh_caches).await;
@@ -319,6 +690,43 @@ pub fn spawn_cache_refresh_task() -> tokio::task::JoinHandle<()> {
     })
 }
 
+// ============================================================================
+// Warmup Gate
+// ============================================================================
+
+/// WARMUP_GATE: hold new orders (SKIPPED_WARMUP) until the caches are warm, so the first
+/// trades don't go out without sport buffers, live status, ticks or overrides
+#[derive(Debug)]
+pub struct WarmupGate {
+    /// Past this, trade on whatever is cached (None = gate off)
+    deadline: Option<Instant>,
+    timed_out: AtomicBool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarmupCheck {
+    Open,
+    Hold,
+    /// Gave up waiting; reported once, open from then on
+    TimedOut,
+}
+
+impl WarmupGate {
+    pub fn new(enabled: bool, timeout: Duration, started: Instant) -> Self {
+        Self { deadline: enabled.then(|| started + timeout), timed_out: AtomicBool::new(false) }
+    }
+
+    pub fn check(&self, warm: bool, now: Instant) -> WarmupCheck {
+        match self.deadline {
+            None => WarmupCheck::Open,
+            Some(_) if warm => WarmupCheck::Open,
+            Some(deadline) if now < deadline => WarmupCheck::Hold,
+            Some(_) if self.timed_out.swap(true, Ordering::Relaxed) => WarmupCheck::Open,
+            Some(_) => WarmupCheck::TimedOut,
+        }
+    }
+}
+
 // ============================================================================
 // Convenience Functions (for backwards compatibility)
 // ============================================================================
@@ -371,6 +779,41 @@ pub fn is_neg_risk(token_id: &str) -> Option<bool> {
     global_caches().is_neg_risk(token_id)
 }
 
+/// Get the MARKET_OVERRIDES entry for a token (convenience function)
+#[inline]
+pub fn get_market_override(token_id: &str) -> Option<MarketOverride> {
+    global_caches().get_override(token_id)
+}
+
+/// Check if an address is on the IGNORED_MAKERS list (convenience function)
+#[inline]
+pub fn is_ignored_maker(address: &str) -> bool {
+    global_caches().is_ignored_maker(address)
+}
+
+/// Whether the caches are warm (convenience function)
+#[inline]
+pub fn caches_warm() -> bool {
+    global_caches().is_warm()
+}
+
+/// Get the price tick for a token (convenience function)
+#[inline]
+pub fn tick_size(token_id: &str) -> f64 {
+    global_caches().tick_size(token_id)
+}
+
+/// Get the collateral decimals for a token, `default` when unknown (convenience function)
+#[inline]
+pub fn collateral_decimals(token_id: &str, default: u8) -> u8 {
+    global_caches().collateral_decimals(token_id, default)
+}
+
+/// Short market label for a token, falling back to the raw id (convenience function)
+pub fn market_label(token_id: &str) -> String {
+    global_caches().market_label(token_id)
+}
+
 /// Get is_live for a token (convenience function)
 #[inline]
 pub fn get_is_live(token_id: &str) -> Option<bool> {
@@ -406,10 +849,147 @@ mod tests {
         assert_eq!(caches.get_slug("unknown"), None);
     }
 
+    #[test]
+    fn test_market_label_resolves_or_falls_back() {
+        let caches = MarketCaches::new();
+        let market = serde_json::json!({
+            "question": "Will the Lakers beat the Celtics on Friday night in Boston?",
+            "outcomes": "[\"Yes\", \"No\"]",
+            "clobTokenIds": "[\"111\", \"222\"]",
+        });
+        caches.set_label("222".into(), MarketLabel::from_gamma_market(&market, "222").unwrap());
+        assert_eq!(caches.market_label("222"), "Will the Lakers beat the Celtics on Frid… [No]");
+        // Unknown token: raw id
+        assert_eq!(caches.market_label("999"), "999");
+
+        // Short questions aren't cut; a token missing from clobTokenIds gets no outcome
+        let short = serde_json::json!({"question": "BTC above 100k?", "outcomes": ["Yes", "No"], "clobTokenIds": ["1", "2"]});
+        assert_eq!(MarketLabel::from_gamma_market(&short, "1").unwrap().short(), "BTC above 100k? [Yes]");
+        assert_eq!(MarketLabel::from_gamma_market(&short, "3").unwrap().short(), "BTC above 100k?");
+        assert_eq!(MarketLabel::from_gamma_market(&serde_json::json!({}), "1"), None);
+    }
+
     #[test]
     fn test_buffer_values() {
-        assert_eq!(ATP_BUFFER, 0.01);
-        assert_eq!(LIGUE1_BUFFER, 0.01);
+        assert_eq!(DEFAULT_TENNIS_BUFFER, 0.01);
+        assert_eq!(DEFAULT_SOCCER_BUFFER, 0.01);
+    }
+
+    #[test]
+    fn test_configured_buffers() {
+        let caches = MarketCaches::with_buffers(0.02, 0.005);
+        caches.tennis_tokens.write().unwrap().insert("atp_tok".to_string(), "atp".to_string());
+        caches.soccer_tokens.write().unwrap().insert("l1_tok".to_string(), ());
+
+        assert_eq!(caches.get_atp_buffer("atp_tok"), 0.02);
+        assert_eq!(caches.get_ligue1_buffer("l1_tok"), 0.005);
+        assert_eq!(caches.get_atp_buffer("l1_tok"), 0.0);
+        assert_eq!(caches.get_ligue1_buffer("atp_tok"), 0.0);
+        assert_eq!(caches.get_atp_buffer("other"), 0.0);
+    }
+
+    #[test]
+    fn test_stale_values_served_when_refresh_fails() {
+        let caches = MarketCaches::with_buffers(0.01, 0.01);
+        let fresh: FxHashMap<String, bool> = [("tok".to_string(), true)].into_iter().collect();
+        assert_eq!(swap_or_keep(&caches.live_status, Some(fresh)), Refresh::Loaded(1));
+
+        // Outage: missing file or an empty write keeps the last-known value
+        assert_eq!(swap_or_keep(&caches.live_status, None), Refresh::KeptStale);
+        assert_eq!(swap_or_keep(&caches.live_status, Some(FxHashMap::default())), Refresh::KeptStale);
+        assert_eq!(caches.get_is_live("tok"), Some(true));
+
+        // Nothing cached yet: nothing to fall back on
+        assert_eq!(swap_or_keep(&caches.tennis_tokens, None), Refresh::Missing);
+    }
+
+    #[test]
+    fn test_orders_held_until_warm() {
+        let caches = MarketCaches::with_buffers(0.01, 0.01);
+        let start = Instant::now();
+        let gate = WarmupGate::new(true, Duration::from_secs(60), start);
+        assert!(!caches.is_warm());
+        assert_eq!(gate.check(caches.is_warm(), start), WarmupCheck::Hold);
+        assert_eq!(gate.check(caches.is_warm(), start + Duration::from_secs(30)), WarmupCheck::Hold);
+
+        // A load that finds live status flips the flag and opens the gate
+        caches.live_status.write().unwrap().insert("tok".into(), true);
+        caches.load_all();
+        assert!(caches.is_warm());
+        assert_eq!(gate.check(caches.is_warm(), start + Duration::from_secs(31)), WarmupCheck::Open);
+
+        // Never warm: held until the timeout, reported once, then open
+        let gate = WarmupGate::new(true, Duration::from_secs(60), start);
+        assert_eq!(gate.check(false, start + Duration::from_secs(59)), WarmupCheck::Hold);
+        assert_eq!(gate.check(false, start + Duration::from_secs(60)), WarmupCheck::TimedOut);
+        assert_eq!(gate.check(false, start + Duration::from_secs(61)), WarmupCheck::Open);
+
+        // Gate off
+        assert_eq!(WarmupGate::new(false, Duration::ZERO, start).check(false, start), WarmupCheck::Open);
+    }
+
+    #[test]
+    fn test_parse_overrides() {
+        let json = r#"{
+            "tok_a": {"buffer": 0.0, "size_multiplier": 2.5},
+            "tok_b": {"skip": true},
+            "tok_c": {"min_shares": 50},
+            "tok_bad": {"buffer": -0.01},
+            "tok_bad_min": {"min_shares": -5}
+        }"#;
+        let map = parse_overrides(json).unwrap();
+        assert_eq!(map.len(), 3);
+        assert_eq!(map["tok_a"], MarketOverride { buffer: Some(0.0), size_multiplier: Some(2.5), ..Default::default() });
+        assert_eq!(map["tok_b"], MarketOverride { skip: true, ..Default::default() });
+        assert_eq!(map["tok_c"].min_shares, Some(50.0));
+
+        // Typos are errors rather than silently ignored
+        assert!(parse_overrides(r#"{"tok": {"bufer": 0.02}}"#).is_err());
+    }
+
+    #[test]
+    fn test_overrides_reload_keeps_previous_on_bad_edit() {
+        let path = std::env::temp_dir().join(format!("market_overrides_{}.json", std::process::id()));
+        let path_str = path.to_string_lossy().to_string();
+        let mut caches = MarketCaches::with_buffers(0.01, 0.01);
+        caches.overrides_path = Some(path_str);
+
+        std::fs::write(&path, r#"{"tok": {"buffer": 0.03}}"#).unwrap();
+        assert_eq!(caches.load_all().overrides_loaded, Some(1));
+        assert_eq!(caches.get_override("tok").unwrap().buffer, Some(0.03));
+
+        // Edited file is picked up on the next refresh; a broken one is ignored
+        std::fs::write(&path, r#"{"tok": {"skip": true}}"#).unwrap();
+        caches.load_all();
+        assert!(caches.get_override("tok").unwrap().skip);
+        std::fs::write(&path, "{not json").unwrap();
+        assert_eq!(caches.load_all().overrides_loaded, None);
+        assert!(caches.get_override("tok").unwrap().skip);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_ignored_makers_reloaded() {
+        let path = std::env::temp_dir().join(format!("ignored_makers_{}.json", std::process::id()));
+        let mut caches = MarketCaches::with_buffers(0.01, 0.01);
+        caches.ignored_makers_path = Some(path.to_string_lossy().to_string());
+
+        std::fs::write(&path, r#"["0xABC", "def", " "]"#).unwrap();
+        assert_eq!(caches.load_all().ignored_makers_loaded, Some(2));
+        assert!(caches.is_ignored_maker("0xabc"));
+        assert!(caches.is_ignored_maker("0xDEF"));
+        assert!(!caches.is_ignored_maker(""));
+
+        // Edits are picked up on the next refresh; a broken file keeps the old list
+        std::fs::write(&path, r#"["0xdef"]"#).unwrap();
+        caches.load_all();
+        assert!(!caches.is_ignored_maker("0xabc"));
+        std::fs::write(&path, "[not json").unwrap();
+        assert_eq!(caches.load_all().ignored_makers_loaded, None);
+        assert!(caches.is_ignored_maker("0xdef"));
+
+        let _ = std::fs::remove_file(&path);
     }
 
     #[test]
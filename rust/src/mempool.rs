@@ -0,0 +1,362 @@
+//! Pending-transaction feed (USE_MEMPOOL)
+//! Subscribes to pending transactions sent to the exchange contracts and decodes their calldata
+//! (fillOrder / fillOrders / matchOrders) to find the followed whale's signed order and the amount
+//! the tx fills before it is mined. Nothing here is confirmed: a pending tx can be dropped, replaced, revert, or be
+//! reorged out after inclusion, so a copy placed from it may have no whale fill behind it
+
+use alloy::primitives::U256;
+use serde::Deserialize;
+
+use crate::decode::{parse_u256_hex_slice_with_bytes, record_decode_failure, topic_address, u256_to_dec_cached, DecodeError, MAX_FILL_PRICE, MIN_FILL_PRICE};
+use crate::models::{FrameTransport, OrderInfo, ParsedEvent};
+use crate::settings::{is_target_topic, COLLATERAL_DECIMALS, TARGET_TOPICS};
+use crate::u256_to_scaled_f64;
+
+// ============================================================================
+// Subscription
+// ============================================================================
+
+/// eth_subscribe request for pending transactions with full tx objects. Alchemy filters by
+/// recipient server-side (alchemy_pendingTransactions); other providers send every pending tx
+/// (newPendingTransactions, true) and the exchange filter runs here
+pub fn build_pending_subscribe_payload(wss_url: &str, exchanges: &[&str]) -> String {
+    let params = if wss_url.contains(".alchemy.com") {
+        serde_json::json!(["alchemy_pendingTransactions", { "toAddress": exchanges, "hashesOnly": false }])
+    } else {
+        serde_json::json!(["newPendingTransactions", true])
+    };
+    serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "eth_subscribe", "params": params }).to_string()
+}
+
+// ============================================================================
+// Calldata Decoding
+// ============================================================================
+
+/// Selectors of the exchange's fill entry points
+const FILL_ORDER: &str = "fe729aaf"; // fillOrder(Order, uint256)
+const FILL_ORDERS: &str = "d798eff6"; // fillOrders(Order[], uint256[])
+const MATCH_ORDERS: &str = "e60f0c05"; // matchOrders(Order, Order[], uint256, uint256[])
+
+/// Word offsets in an encoded CTF exchange Order, from its salt: salt, maker, signer, taker,
+/// tokenId, makerAmount, takerAmount, expiration, nonce, feeRateBps, side, signatureType, signature
+const ORDER_MAKER: usize = 1;
+const ORDER_TOKEN_ID: usize = 4;
+const ORDER_MAKER_AMOUNT: usize = 5;
+const ORDER_TAKER_AMOUNT: usize = 6;
+const ORDER_SIDE: usize = 10;
+
+/// Hex chars of the 12 zero bytes in front of an address word
+const ADDRESS_PADDING: &str = "000000000000000000000000";
+
+#[derive(Deserialize)]
+struct PendingMessage {
+    params: Option<PendingParams>,
+}
+
+#[derive(Deserialize)]
+struct PendingParams {
+    result: Option<PendingTx>,
+}
+
+/// Full pending tx object (hash-only notifications don't deserialize and are ignored)
+#[derive(Deserialize)]
+struct PendingTx {
+    hash: String,
+    to: Option<String>,
+    input: String,
+}
+
+/// Calldata arguments after the selector, read as 32-byte hex words
+struct Args<'a>(&'a str);
+
+impl<'a> Args<'a> {
+    fn word(&self, i: usize) -> Option<&'a str> {
+        self.0.get(i * 64..(i + 1) * 64)
+    }
+
+    fn uint(&self, i: usize) -> Option<U256> {
+        parse_u256_hex_slice_with_bytes(self.word(i)?, 0, 64).map(|(v, _)| v)
+    }
+
+    /// Word index of the ABI offset stored at word `i`, relative to word `base`
+    fn offset(&self, i: usize, base: usize) -> Option<usize> {
+        let bytes = usize::try_from(u64::try_from(self.uint(i)?).ok()?).ok()?;
+        if bytes % 32 != 0 { return None; }
+        base.checked_add(bytes / 32)
+    }
+
+    /// (order word, fill amount) pairs of the dynamic `orders` / `fills` arrays at words `orders` and `fills`
+    fn paired_arrays(&self, orders: usize, fills: usize, out: &mut Vec<(usize, U256)>) -> Option<()> {
+        let len = usize::try_from(u64::try_from(self.uint(orders)?).ok()?).ok()?;
+        if len > self.0.len() / 64 || self.uint(fills)? != U256::from(len) { return None; }
+        for k in 0..len {
+            out.push((self.offset(orders + 1 + k, orders + 1)?, self.uint(fills + 1 + k)?));
+        }
+        Some(())
+    }
+
+    /// Every order in a fill call with the amount the call fills of it (in the order's maker asset)
+    fn fills(&self, selector: &str) -> Option<Vec<(usize, U256)>> {
+        let mut out = Vec::new();
+        match selector {
+            FILL_ORDER => out.push((self.offset(0, 0)?, self.uint(1)?)),
+            FILL_ORDERS => self.paired_arrays(self.offset(0, 0)?, self.offset(1, 0)?, &mut out)?,
+            MATCH_ORDERS => {
+                out.push((self.offset(0, 0)?, self.uint(2)?));
+                self.paired_arrays(self.offset(1, 0)?, self.offset(3, 0)?, &mut out)?;
+            }
+            _ => return None,
+        }
+        Some(out)
+    }
+}
+
+/// Decode a pending-tx notification into the whale's fill. `exchanges` are the accepted
+/// recipients; `is_target` is matched against each order's maker, padded like a log topic.
+/// The event carries the amount this tx fills of the whale's order at the order's limit
+/// price, block 0 and no counterparty
+pub fn decode_pending_tx<F>(message: &str, exchanges: &[&str], is_target: F, decimals: u8) -> Result<ParsedEvent, DecodeError>
+where
+    F: Fn(&str) -> bool,
+{
+    let msg: PendingMessage = serde_json::from_str(message).map_err(|_| DecodeError::Ignored)?;
+    let tx = msg.params.and_then(|p| p.result).ok_or(DecodeError::Ignored)?;
+    let to = tx.to.as_deref().ok_or(DecodeError::Ignored)?;
+    if !exchanges.iter().any(|a| a.eq_ignore_ascii_case(to)) {
+        return Err(DecodeError::Ignored);
+    }
+
+    let input = tx.input.strip_prefix("0x").unwrap_or(&tx.input);
+    let selector = input.get(..8).ok_or(DecodeError::Ignored)?.to_ascii_lowercase();
+    let args = Args(input.get(8..).ok_or(DecodeError::Ignored)?);
+    let fills = args.fills(&selector).ok_or(DecodeError::Ignored)?;
+
+    for (order, fill) in fills {
+        let Some(maker) = args.word(order + ORDER_MAKER) else { continue };
+        if !maker.starts_with(ADDRESS_PADDING) {
+            continue;
+        }
+        let topic = format!("0x{}", maker);
+        if !is_target(&topic) {
+            continue;
+        }
+        let side_is_buy = match args.uint(order + ORDER_SIDE) {
+            Some(v) if v.is_zero() => true,
+            Some(v) if v == U256::from(1u8) => false,
+            _ => continue,
+        };
+        return order_event(&tx.hash, &topic, side_is_buy, &args, order, fill, decimals);
+    }
+    Err(DecodeError::Ignored)
+}
+
+fn order_event(tx_hash: &str, whale_topic: &str, side_is_buy: bool, args: &Args, order: usize, fill: U256, decimals: u8) -> Result<ParsedEvent, DecodeError> {
+    use crate::decode::DecodeField;
+    let parse = |i: usize, field: DecodeField| {
+        args.word(order + i).and_then(|w| parse_u256_hex_slice_with_bytes(w, 0, 64)).ok_or(DecodeError::Field(field))
+    };
+    let (token_id, token_bytes) = parse(ORDER_TOKEN_ID, DecodeField::MakerAssetId)?;
+    let (maker_amt, _) = parse(ORDER_MAKER_AMOUNT, DecodeField::MakerAmount)?;
+    let (taker_amt, _) = parse(ORDER_TAKER_AMOUNT, DecodeField::TakerAmount)?;
+    if token_id.is_zero() {
+        return Err(DecodeError::AmbiguousSide);
+    }
+    if maker_amt.is_zero() {
+        return Err(DecodeError::Field(DecodeField::MakerAmount));
+    }
+
+    // The fill is in the order's maker asset; the taker side scales by the order's own ratio
+    let making = fill.min(maker_amt);
+    let taking = making.checked_mul(taker_amt).ok_or(DecodeError::NonFinite)? / maker_amt;
+
+    // A buy order gives USDC for shares, a sell the reverse
+    let clob_token_id = u256_to_dec_cached(&token_bytes, &token_id);
+    let decimals = crate::market_cache::collateral_decimals(&clob_token_id, decimals);
+    let (share_amt, usd_amt) = if side_is_buy { (&taking, &making) } else { (&making, &taking) };
+    let shares = u256_to_scaled_f64(share_amt, decimals).ok_or(DecodeError::NonFinite)?;
+    let usd = u256_to_scaled_f64(usd_amt, decimals).ok_or(DecodeError::NonFinite)?;
+    if !shares.is_finite() || !usd.is_finite() { return Err(DecodeError::NonFinite); }
+    if shares <= 0.0 { return Err(DecodeError::ZeroShares); }
+    if usd <= 0.0 { return Err(DecodeError::ZeroUsd); }
+    let price = usd / shares;
+    if !(MIN_FILL_PRICE..=MAX_FILL_PRICE).contains(&price) { return Err(DecodeError::PriceOutOfRange); }
+
+    Ok(ParsedEvent {
+        block_number: 0,
+        tx_hash: tx_hash.to_string(),
+        whale_address: topic_address(whale_topic),
+        counterparty: String::new(),
+        order: OrderInfo {
+            // Tagged as a fill: the tx is an execution in flight, not a resting order placement
+            order_type: if side_is_buy { "BUY_FILL" } else { "SELL_FILL" }.to_string(),
+            clob_token_id,
+            usd_value: usd,
+            shares,
+            price_per_share: price,
+        },
+        transport: FrameTransport::Text,
+    })
+}
+
+/// Decode a pending-tx frame with the configured targets and decimals, counting failures in
+/// the decode-failure telemetry (non-exchange and non-whale txs are ignored, not failures)
+pub fn parse_pending_frame(transport: FrameTransport, payload: &[u8], exchanges: &[&str]) -> Option<ParsedEvent> {
+    let parsed = std::str::from_utf8(payload)
+        .map_err(|_| DecodeError::InvalidUtf8)
+        .and_then(|text| decode_pending_tx(text, exchanges, |topic| is_target_topic(topic, &TARGET_TOPICS), *COLLATERAL_DECIMALS));
+    match parsed {
+        Ok(mut evt) => {
+            evt.transport = transport;
+            Some(evt)
+        }
+        Err(err) => {
+            record_decode_failure(err);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::tests::WHALE_TOPIC;
+
+    const EXCHANGE: &str = "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E";
+
+    fn word(v: u128) -> String {
+        format!("{:064x}", v)
+    }
+
+    /// Encoded Order signed by `maker` (a padded topic), 17 words with its signature
+    fn order(maker: &str, side: u128, maker_amount: u128, taker_amount: u128) -> String {
+        let maker = maker.trim_start_matches("0x");
+        let order = [
+            word(7),                 // salt
+            maker.to_string(),       // maker
+            maker.to_string(),       // signer
+            word(0),                 // taker (anyone)
+            word(12345),             // tokenId
+            word(maker_amount),
+            word(taker_amount),
+            word(0),                 // expiration
+            word(0),                 // nonce
+            word(0),                 // feeRateBps
+            word(side),
+            word(0),                 // signatureType
+            word(13 * 32),           // signature offset
+        ];
+        let signature = [word(65), "ab".repeat(65) + &"00".repeat(31)];
+        order.concat() + &signature.concat()
+    }
+
+    /// fillOrder(Order, uint256) calldata filling `fill` of the order's maker amount
+    fn fill_order_input(maker: &str, side: u128, maker_amount: u128, taker_amount: u128, fill: u128) -> String {
+        format!("0xfe729aaf{}{}{}", word(0x40), word(fill), order(maker, side, maker_amount, taker_amount))
+    }
+
+    /// matchOrders(Order, Order[], uint256, uint256[]) with one maker order
+    fn match_orders_input(taker: &str, taker_fill: u128, maker: &str, maker_fill: u128) -> String {
+        let head = [word(4 * 32), word(21 * 32), word(taker_fill), word(40 * 32)];
+        let makers = [word(1), word(32), order(maker, 1, 30_000_000, 15_000_000)];
+        let maker_fills = [word(1), word(maker_fill)];
+        format!("0xe60f0c05{}{}{}{}", head.concat(), order(taker, 0, 10_000_000, 20_000_000), makers.concat(), maker_fills.concat())
+    }
+
+    fn pending(to: &str, input: &str) -> String {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "eth_subscription",
+            "params": { "subscription": "0x1", "result": { "hash": "0xpending", "to": to, "input": input } }
+        })
+        .to_string()
+    }
+
+    fn decode(message: &str) -> Result<ParsedEvent, DecodeError> {
+        decode_pending_tx(message, &[EXCHANGE], |t| t.eq_ignore_ascii_case(WHALE_TOPIC), 6)
+    }
+
+    #[test]
+    fn test_decode_pending_fill_order() {
+        // Whale buys 20 shares for 10 USDC
+        let evt = decode(&pending(&EXCHANGE.to_lowercase(), &fill_order_input(WHALE_TOPIC, 0, 10_000_000, 20_000_000, 10_000_000))).unwrap();
+        assert_eq!(evt.order.order_type, "BUY_FILL");
+        assert_eq!(&*evt.order.clob_token_id, "12345");
+        assert_eq!(evt.order.shares, 20.0);
+        assert_eq!(evt.order.usd_value, 10.0);
+        assert_eq!(evt.order.price_per_share, 0.5);
+        assert_eq!(evt.tx_hash, "0xpending");
+        assert_eq!(evt.block_number, 0);
+        assert_eq!(evt.whale_address, "0x204f72f35326db932158cba6adff0b9a1da95e14");
+
+        // Sell of 40 shares for 30 USDC
+        let evt = decode(&pending(EXCHANGE, &fill_order_input(WHALE_TOPIC, 1, 40_000_000, 30_000_000, 40_000_000))).unwrap();
+        assert_eq!(evt.order.order_type, "SELL_FILL");
+        assert_eq!(evt.order.shares, 40.0);
+        assert_eq!(evt.order.price_per_share, 0.75);
+    }
+
+    #[test]
+    fn test_decode_pending_uses_fill_amount() {
+        // A 10 USDC buy order of which this tx fills 4 USDC
+        let evt = decode(&pending(EXCHANGE, &fill_order_input(WHALE_TOPIC, 0, 10_000_000, 20_000_000, 4_000_000))).unwrap();
+        assert_eq!(evt.order.usd_value, 4.0);
+        assert_eq!(evt.order.shares, 8.0);
+        assert_eq!(evt.order.price_per_share, 0.5);
+
+        // A fill beyond the order's size is capped at it
+        let evt = decode(&pending(EXCHANGE, &fill_order_input(WHALE_TOPIC, 0, 10_000_000, 20_000_000, 50_000_000))).unwrap();
+        assert_eq!(evt.order.usd_value, 10.0);
+    }
+
+    #[test]
+    fn test_decode_pending_match_orders_maker_side() {
+        // The whale's sell (30 shares for 15 USDC) is a maker order filled for 12 shares
+        let other = "0x000000000000000000000000000000000000000000000000000000000000beef";
+        let evt = decode(&pending(EXCHANGE, &match_orders_input(other, 6_000_000, WHALE_TOPIC, 12_000_000))).unwrap();
+        assert_eq!(evt.order.order_type, "SELL_FILL");
+        assert_eq!(evt.order.shares, 12.0);
+        assert_eq!(evt.order.usd_value, 6.0);
+
+        // As the taker order it gets the taker fill amount
+        let evt = decode(&pending(EXCHANGE, &match_orders_input(WHALE_TOPIC, 6_000_000, other, 12_000_000))).unwrap();
+        assert_eq!(evt.order.order_type, "BUY_FILL");
+        assert_eq!(evt.order.usd_value, 6.0);
+        assert_eq!(evt.order.shares, 12.0);
+    }
+
+    #[test]
+    fn test_decode_pending_malformed_calldata() {
+        // Multi-byte chars across word boundaries and offsets past the end don't panic
+        let input = format!("0xfe729aaf0{}{}", "é".repeat(64), word(0));
+        assert_eq!(decode(&pending(EXCHANGE, &input)).unwrap_err(), DecodeError::Ignored);
+        let input = format!("0xfe729aaf{}{}", word(u64::MAX as u128 * 32), word(1));
+        assert_eq!(decode(&pending(EXCHANGE, &input)).unwrap_err(), DecodeError::Ignored);
+        let truncated = fill_order_input(WHALE_TOPIC, 0, 10_000_000, 20_000_000, 10_000_000);
+        assert_eq!(decode(&pending(EXCHANGE, &truncated[..10 + 64 * 4])).unwrap_err(), DecodeError::Ignored);
+    }
+
+    #[test]
+    fn test_pending_tx_ignored_unless_whale_order_to_exchange() {
+        let input = fill_order_input(WHALE_TOPIC, 0, 10_000_000, 20_000_000, 10_000_000);
+        assert_eq!(decode(&pending("0x0000000000000000000000000000000000000001", &input)).unwrap_err(), DecodeError::Ignored);
+
+        let other = "0x000000000000000000000000000000000000000000000000000000000000beef";
+        assert_eq!(decode(&pending(EXCHANGE, &fill_order_input(other, 0, 10_000_000, 20_000_000, 10_000_000))).unwrap_err(), DecodeError::Ignored);
+
+        // Not a full tx object (hash-only notification) or not an order at all
+        let hash_only = r#"{"params":{"subscription":"0x1","result":"0xabc"}}"#;
+        assert_eq!(decode(hash_only).unwrap_err(), DecodeError::Ignored);
+        assert_eq!(decode(&pending(EXCHANGE, "0xa9059cbb")).unwrap_err(), DecodeError::Ignored);
+    }
+
+    #[test]
+    fn test_pending_subscribe_payload() {
+        let alchemy: serde_json::Value =
+            serde_json::from_str(&build_pending_subscribe_payload("wss://polygon-mainnet.g.alchemy.com/v2/key", &[EXCHANGE])).unwrap();
+        assert_eq!(alchemy["params"][0], "alchemy_pendingTransactions");
+        assert_eq!(alchemy["params"][1]["toAddress"][0], EXCHANGE);
+
+        let other: serde_json::Value = serde_json::from_str(&build_pending_subscribe_payload("wss://node.example/ws", &[EXCHANGE])).unwrap();
+        assert_eq!(other["params"], serde_json::json!(["newPendingTransactions", true]));
+    }
+}
@@ -4,8 +4,11 @@
 use serde::Deserialize;
 use std::fmt;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::oneshot;
 
+use crate::status_code::{Status, StatusCode};
+
 /// Parsed order information from blockchain events
 #[derive(Debug, Clone)]
 pub struct OrderInfo {
@@ -21,15 +24,74 @@ pub struct OrderInfo {
 pub struct ParsedEvent {
     pub block_number: u64,
     pub tx_hash: String,
+    pub whale_address: String,  // Lowercase 0x-prefixed address from topics[2]
+    pub counterparty: String,   // Lowercase 0x-prefixed taker from topics[3] (empty if absent)
     pub order: OrderInfo,
+    /// WebSocket frame type the log arrived in
+    pub transport: FrameTransport,
+}
+
+/// WebSocket frame type carrying an event (providers may send either, or switch mid-session)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrameTransport {
+    #[default]
+    Text,
+    Binary,
+}
+
+impl FrameTransport {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FrameTransport::Text => "text",
+            FrameTransport::Binary => "binary",
+        }
+    }
 }
 
 /// Work item for the order processing queue
 #[derive(Debug)]
 pub struct WorkItem {
     pub event: ParsedEvent,
-    pub respond_to: oneshot::Sender<String>,
+    pub respond_to: oneshot::Sender<OrderReply>,
     pub is_live: Option<bool>,
+    /// SUBMIT_JITTER_MS: earliest time a planned order for this event may be posted
+    pub not_before: Option<Instant>,
+}
+
+/// Worker's answer for one event: the order status, the CLOB's ids for a posted order,
+/// plus the shadow sizing if enabled
+#[derive(Debug, Clone)]
+pub struct OrderReply {
+    pub status: Status,
+    /// Polymarket order id (empty if nothing was posted or the response had none)
+    pub order_id: String,
+    /// Settlement tx hashes, ';'-joined (empty for unmatched/resting orders)
+    pub order_tx: String,
+    pub shadow: Option<ShadowPlan>,
+    /// Filled / requested shares of the posted order, in percent (None if not known)
+    pub fill_pct: Option<f64>,
+    /// Our average fill price vs the whale's, per share (positive = better; None if nothing filled)
+    pub price_delta: Option<f64>,
+}
+
+impl From<Status> for OrderReply {
+    fn from(status: Status) -> Self {
+        Self { status, order_id: String::new(), order_tx: String::new(), shadow: None, fill_pct: None, price_delta: None }
+    }
+}
+
+impl From<StatusCode> for OrderReply {
+    fn from(code: StatusCode) -> Self {
+        Status::new(code).into()
+    }
+}
+
+/// What the shadow sizing config would have submitted (logged, never sent)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadowPlan {
+    /// Unrounded size in shares (0 = shadow would have skipped)
+    pub shares: f64,
+    pub limit_price: f64,
 }
 
 /// Size calculation result 
@@ -40,11 +102,27 @@ pub enum SizeType {
     ProbSkip(u8),  // percentage
 }
 
+/// A maker-mode (COPY_MODE=maker) buy resting on the book, repriced toward the touch while unfilled
+#[derive(Debug, Clone)]
+pub struct MakerOrder {
+    pub token_id: String,
+    pub whale_address: String,
+    pub order_id: String,
+    pub price: f64,
+    /// Shares still resting (what wasn't filled when it was posted)
+    pub size: f64,
+    /// Highest price a reprice may reach (resubmit ceiling, whale premium cap)
+    pub max_price: f64,
+    pub is_live: bool,
+}
+
 /// Request to resubmit a failed FAK order 
 /// Fields ordered to minimize padding: f64s together, then bools/u8 at end
 #[derive(Debug, Clone)]
 pub struct ResubmitRequest {
     pub token_id: String,       // 24 bytes
+    pub whale_address: String,  // Whale being copied (resting orders are cancelled if it exits)
+    pub chain_started: Instant, // When the original order missed (chain deadline is measured from here)
     pub whale_price: f64,       // Original whale price
     pub failed_price: f64,      // Price that failed (our limit)
     pub size: f64,              // Order size in shares
@@ -52,11 +130,31 @@ pub struct ResubmitRequest {
     pub max_price: f64,         // Price ceiling (don't exceed this)
     pub cumulative_filled: f64, // Total filled before this attempt
     pub original_size: f64,     // Original order size (for final summary)
+    pub counted_usd: f64,       // Daily notional reserved for `size` (released as the chain ends unfilled)
+    pub counted_at: u64,        // Unix secs the notional was reserved (releases only count the same UTC day)
     pub side_is_buy: bool,      // Always true for now (only resubmit buys)
     pub is_live: bool,          // Market liveness (for GTD expiry calculation)
     pub attempt: u8,            // Current attempt number (1-indexed)
 }
 
+impl ResubmitRequest {
+    /// True once the chain has run longer than `deadline` (None = no deadline)
+    #[inline]
+    pub fn past_deadline(&self, now: Instant, deadline: Option<Duration>) -> bool {
+        deadline.is_some_and(|d| now.saturating_duration_since(self.chain_started) > d)
+    }
+
+    /// Share of counted_usd covering `unfilled` of this attempt's shares
+    #[inline]
+    pub fn unfilled_usd(&self, unfilled: f64) -> f64 {
+        if self.size > 0.0 {
+            self.counted_usd * (unfilled / self.size).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
 impl fmt::Display for SizeType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
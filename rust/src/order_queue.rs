@@ -0,0 +1,271 @@
+//! Bounded order queue between the event handlers and the order worker
+//! FIFO by default; with ORDER_QUEUE_PRIORITY=usd_value the biggest whale trades are served
+//! first and, when the queue is full, a bigger signal displaces the smallest one queued
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::{Arc, Condvar, Mutex};
+
+use crate::models::WorkItem;
+
+/// Order in which queued events reach the worker (ORDER_QUEUE_PRIORITY env var)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QueuePriority {
+    /// Arrival order; a full queue rejects new events
+    #[default]
+    Fifo,
+    /// Largest whale USD value first; a full queue sheds the smallest event
+    UsdValue,
+}
+
+impl QueuePriority {
+    /// Parse "fifo" / "usd_value" (empty = Fifo)
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "fifo" => Ok(QueuePriority::Fifo),
+            "usd_value" | "usd" | "value" => Ok(QueuePriority::UsdValue),
+            other => anyhow::bail!("ORDER_QUEUE_PRIORITY must be 'fifo' or 'usd_value' (found '{}')", other),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            QueuePriority::Fifo => "fifo",
+            QueuePriority::UsdValue => "usd_value",
+        }
+    }
+}
+
+/// Something the queue can rank
+pub trait Prioritized {
+    fn priority_value(&self) -> f64;
+}
+
+impl Prioritized for WorkItem {
+    fn priority_value(&self) -> f64 {
+        self.event.order.usd_value
+    }
+}
+
+/// Result of a successful send
+#[derive(Debug)]
+pub enum Enqueued<T> {
+    Queued,
+    /// Queued by displacing this lower-value item, which the caller must answer
+    Displaced(T),
+}
+
+#[derive(Debug)]
+pub enum SendError<T> {
+    /// Queue full and nothing queued ranks below the item
+    Full(T),
+    /// Receiver is gone
+    Closed(T),
+}
+
+impl<T> std::fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SendError::Full(_) => f.write_str("no available capacity"),
+            SendError::Closed(_) => f.write_str("channel closed"),
+        }
+    }
+}
+
+/// Highest value wins; among equals the earliest arrival (lower seq) ranks higher
+#[derive(Debug, Clone, Copy)]
+struct Rank {
+    value: f64,
+    seq: u64,
+}
+
+impl Ord for Rank {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.total_cmp(&other.value).then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl PartialOrd for Rank {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Rank {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Rank {}
+
+struct State<T> {
+    items: BTreeMap<Rank, T>,
+    senders: usize,
+    receiver_alive: bool,
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    ready: Condvar,
+    capacity: usize,
+    priority: QueuePriority,
+    seq: AtomicU64,
+    shed: AtomicUsize,
+}
+
+pub struct PrioritySender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+pub struct PriorityReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Bounded queue ranked by `priority` (capacity is at least 1)
+pub fn priority_channel<T: Prioritized>(capacity: usize, priority: QueuePriority) -> (PrioritySender<T>, PriorityReceiver<T>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State { items: BTreeMap::new(), senders: 1, receiver_alive: true }),
+        ready: Condvar::new(),
+        capacity: capacity.max(1),
+        priority,
+        seq: AtomicU64::new(0),
+        shed: AtomicUsize::new(0),
+    });
+    (PrioritySender { shared: Arc::clone(&shared) }, PriorityReceiver { shared })
+}
+
+impl<T: Prioritized> PrioritySender<T> {
+    /// Never waits: queues, displaces the lowest-ranked item, or hands the item back
+    pub fn try_send(&self, item: T) -> Result<Enqueued<T>, SendError<T>> {
+        let shared = &*self.shared;
+        let rank = Rank {
+            value: match shared.priority {
+                QueuePriority::Fifo => 0.0,
+                QueuePriority::UsdValue => item.priority_value(),
+            },
+            seq: shared.seq.fetch_add(1, AtomicOrdering::Relaxed),
+        };
+        let mut state = shared.state.lock().unwrap_or_else(|e| e.into_inner());
+        if !state.receiver_alive {
+            return Err(SendError::Closed(item));
+        }
+        let mut displaced = None;
+        if state.items.len() >= shared.capacity {
+            // Under FIFO every queued item outranks a newcomer, so this only sheds in value mode
+            match state.items.first_key_value() {
+                Some((lowest, _)) if *lowest < rank => {
+                    displaced = state.items.pop_first().map(|(_, item)| item);
+                    shared.shed.fetch_add(1, AtomicOrdering::Relaxed);
+                }
+                _ => return Err(SendError::Full(item)),
+            }
+        }
+        state.items.insert(rank, item);
+        drop(state);
+        shared.ready.notify_one();
+        Ok(displaced.map_or(Enqueued::Queued, Enqueued::Displaced))
+    }
+}
+
+impl<T> PrioritySender<T> {
+    /// Items displaced by higher-value ones since startup
+    pub fn shed_count(&self) -> usize {
+        self.shared.shed.load(AtomicOrdering::Relaxed)
+    }
+}
+
+impl<T> Clone for PrioritySender<T> {
+    fn clone(&self) -> Self {
+        self.shared.state.lock().unwrap_or_else(|e| e.into_inner()).senders += 1;
+        Self { shared: Arc::clone(&self.shared) }
+    }
+}
+
+impl<T> Drop for PrioritySender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.senders -= 1;
+        if state.senders == 0 {
+            drop(state);
+            self.shared.ready.notify_all();
+        }
+    }
+}
+
+impl<T> PriorityReceiver<T> {
+    /// Highest-ranked item, blocking the thread until one arrives; None once every sender is
+    /// gone and the queue is drained
+    pub fn blocking_recv(&mut self) -> Option<T> {
+        let mut state = self.shared.state.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            if let Some((_, item)) = state.items.pop_last() {
+                return Some(item);
+            }
+            if state.senders == 0 {
+                return None;
+            }
+            state = self.shared.ready.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Highest-ranked item if one is queued
+    pub fn try_recv(&mut self) -> Option<T> {
+        self.shared.state.lock().unwrap_or_else(|e| e.into_inner()).items.pop_last().map(|(_, item)| item)
+    }
+}
+
+impl<T> Drop for PriorityReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap_or_else(|e| e.into_inner()).receiver_alive = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Signal(f64);
+
+    impl Prioritized for Signal {
+        fn priority_value(&self) -> f64 {
+            self.0
+        }
+    }
+
+    fn drain(rx: &mut PriorityReceiver<Signal>) -> Vec<f64> {
+        std::iter::from_fn(|| rx.try_recv()).map(|s| s.0).collect()
+    }
+
+    #[test]
+    fn test_higher_value_dequeued_first_under_capacity() {
+        let (tx, mut rx) = priority_channel(2, QueuePriority::UsdValue);
+        assert!(matches!(tx.try_send(Signal(50.0)), Ok(Enqueued::Queued)));
+        assert!(matches!(tx.try_send(Signal(5000.0)), Ok(Enqueued::Queued)));
+        // Full: a bigger signal displaces the smallest queued one, a smaller one is refused
+        match tx.try_send(Signal(800.0)) {
+            Ok(Enqueued::Displaced(Signal(v))) => assert_eq!(v, 50.0),
+            other => panic!("expected displacement, got {other:?}"),
+        }
+        assert!(matches!(tx.try_send(Signal(10.0)), Err(SendError::Full(Signal(_)))));
+        assert_eq!(tx.shed_count(), 1);
+        assert_eq!(drain(&mut rx), vec![5000.0, 800.0]);
+    }
+
+    #[test]
+    fn test_fifo_default_keeps_arrival_order() {
+        assert_eq!(QueuePriority::parse("").unwrap(), QueuePriority::Fifo);
+        assert_eq!(QueuePriority::parse("USD_VALUE").unwrap(), QueuePriority::UsdValue);
+        assert!(QueuePriority::parse("lifo").is_err());
+        let (tx, mut rx) = priority_channel(2, QueuePriority::default());
+        tx.try_send(Signal(50.0)).unwrap();
+        tx.try_send(Signal(5000.0)).unwrap();
+        // FIFO never sheds: the newcomer is refused whatever its size
+        assert!(matches!(tx.try_send(Signal(9000.0)), Err(SendError::Full(_))));
+        assert_eq!(drain(&mut rx), vec![50.0, 5000.0]);
+        drop(tx);
+        assert_eq!(rx.blocking_recv(), None);
+    }
+}
@@ -0,0 +1,75 @@
+//! WebSocket reconnect budget
+//! Gives up after MAX_RECONNECT_ATTEMPTS consecutive failures without a healthy connection
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReconnectDecision {
+    Retry,
+    /// Budget exhausted - exit non-zero so an orchestrator surfaces it
+    GiveUp,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReconnectBudget {
+    /// 0 = unlimited
+    max_attempts: u32,
+    consecutive_failures: u32,
+}
+
+impl ReconnectBudget {
+    pub fn new(max_attempts: u32) -> Self {
+        Self { max_attempts, consecutive_failures: 0 }
+    }
+
+    /// A connection was healthy (subscribed and received data): reset the count
+    #[inline]
+    pub fn on_healthy(&mut self) {
+        self.consecutive_failures = 0;
+    }
+
+    /// A connection attempt or session ended in an error
+    pub fn on_failure(&mut self) -> ReconnectDecision {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        if self.max_attempts > 0 && self.consecutive_failures >= self.max_attempts {
+            ReconnectDecision::GiveUp
+        } else {
+            ReconnectDecision::Retry
+        }
+    }
+
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gives_up_after_consecutive_failures() {
+        let mut budget = ReconnectBudget::new(3);
+        assert_eq!(budget.on_failure(), ReconnectDecision::Retry);
+        assert_eq!(budget.on_failure(), ReconnectDecision::Retry);
+        assert_eq!(budget.on_failure(), ReconnectDecision::GiveUp);
+    }
+
+    #[test]
+    fn test_healthy_connection_resets() {
+        let mut budget = ReconnectBudget::new(3);
+        budget.on_failure();
+        budget.on_failure();
+        budget.on_healthy();
+        assert_eq!(budget.consecutive_failures(), 0);
+        assert_eq!(budget.on_failure(), ReconnectDecision::Retry);
+        assert_eq!(budget.on_failure(), ReconnectDecision::Retry);
+        assert_eq!(budget.on_failure(), ReconnectDecision::GiveUp);
+    }
+
+    #[test]
+    fn test_unlimited_by_default() {
+        let mut budget = ReconnectBudget::new(0);
+        for _ in 0..1000 {
+            assert_eq!(budget.on_failure(), ReconnectDecision::Retry);
+        }
+    }
+}
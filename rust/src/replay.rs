@@ -0,0 +1,351 @@
+//! Dry replay of a captured WS log against a session's CSV (--verify-against trades.csv)
+//! Captured messages are decoded and planned offline, in order, with one risk guard; each
+//! decision is compared with the audit row the session wrote for the same event
+
+use rustc_hash::FxHashMap;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+
+use crate::decode::decode_ws_frame;
+use crate::engine::{plan_order, tick_align_whale_price, DecisionTrace, PlanParams, TickRounding, WhalePriceMode, WhalePriceTracker};
+use crate::models::{FrameTransport, ParsedEvent};
+use crate::risk_guard::RiskGuard;
+use crate::settings::{is_target_topic, target_topics_from, Config, COLLATERAL_DECIMALS, WHALE_PRICE_MODE, WHALE_PRICE_ROUNDING};
+use crate::status_code::StatusCode;
+
+/// What the decision engine did with an event, as far as a replay can tell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// Planned an order; whatever happened at submit is outside the engine
+    Submit,
+    Skip(StatusCode),
+}
+
+impl Decision {
+    /// The engine decision behind a recorded status. None for codes decided before the
+    /// engine ran (same-token block, unknown is_live, kill switch, warmup), which a replay can't reproduce
+    pub fn from_recorded(code: StatusCode) -> Option<Self> {
+        match code {
+            StatusCode::SkippedSameTokenBlock
+            | StatusCode::SkippedOpposingSignal
+            | StatusCode::SkippedUnknownLive
+            | StatusCode::SkippedPaused
+            | StatusCode::SkippedWarmup => None,
+            StatusCode::SkippedOffTick
+            | StatusCode::SkippedNotFill
+            | StatusCode::SkippedSideFilter
+            | StatusCode::SkippedIgnoredMaker
+            | StatusCode::SkippedWhaleAdd
+            | StatusCode::SkippedMarketOverride
+            | StatusCode::SkippedSmall
+            | StatusCode::SkippedWhaleCooldown
+            | StatusCode::SkippedRateLimit
+            | StatusCode::CbBlocked
+            | StatusCode::CbBookFail
+            | StatusCode::SkippedProbability
+            | StatusCode::SkippedDailyNotionalCap
+            | StatusCode::SkippedBelowMarketMin => Some(Decision::Skip(code)),
+            // Posted, rejected, dry run, queue / worker failures and pre-submit checks all had a plan
+            _ => Some(Decision::Submit),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Decision::Submit => "SUBMIT",
+            Decision::Skip(code) => code.as_str(),
+        }
+    }
+
+    /// Depends on something a replay doesn't have: the live book (circuit breaker) or the
+    /// sizing roll (probabilistic sizing)
+    fn unverifiable(self) -> bool {
+        matches!(self, Decision::Skip(StatusCode::CbBlocked | StatusCode::CbBookFail | StatusCode::SkippedProbability))
+    }
+}
+
+/// One audit row, reduced to what identifies its event and the recorded decision
+#[derive(Debug, Clone)]
+struct RecordedRow {
+    line: usize,
+    shares: f64,
+    code: StatusCode,
+}
+
+/// Rows keyed by (tx_hash, clob_asset_id, direction), in file order
+type RowsByEvent = FxHashMap<(String, String, String), VecDeque<RecordedRow>>;
+
+#[derive(Debug, Clone, Default)]
+pub struct ReplayReport {
+    /// Decoded events replayed
+    pub events: usize,
+    pub matched: usize,
+    /// Compared but not judged (see Decision::unverifiable / from_recorded)
+    pub unverifiable: usize,
+    pub mismatches: Vec<String>,
+}
+
+impl ReplayReport {
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for line in &self.mismatches {
+            let _ = writeln!(out, "MISMATCH {}", line);
+        }
+        let _ = writeln!(
+            out,
+            "Replay: {} events, {} matched, {} unverifiable, {} mismatched",
+            self.events,
+            self.matched,
+            self.unverifiable,
+            self.mismatches.len()
+        );
+        out
+    }
+}
+
+/// Replay every JSON message in `capture` (back to back, as for --decode) and compare each
+/// decoded event's decision with its row in `csv` (either CSV_DIRECTION layout). Rows for
+/// transactions the capture doesn't contain are ignored, so the CSV may span other sessions.
+/// The book is taken as deep enough for the circuit breaker, and time-based guards
+/// (cooldowns, rate buckets, windows) see replay time, not the original timing
+pub fn verify_replay<F>(
+    capture: &str,
+    csv: &str,
+    cfg: &Config,
+    is_target: F,
+    decimals: u8,
+    price_mode: WhalePriceMode,
+    rounding: TickRounding,
+) -> Result<ReplayReport, String>
+where
+    F: Fn(&str) -> bool,
+{
+    let mut rows = parse_rows(csv)?;
+    let mut report = ReplayReport::default();
+    let mut guard = RiskGuard::new(cfg.risk_guard_config());
+    let params = cfg.plan_params();
+    let mut whale_prices = WhalePriceTracker::new(price_mode);
+
+    for value in serde_json::Deserializer::from_str(capture).into_iter::<serde_json::Value>() {
+        let msg = value.map_err(|e| format!("capture: invalid JSON ({})", e))?;
+        let Ok(evt) = decode_ws_frame(FrameTransport::Text, msg.to_string().as_bytes(), &is_target, decimals) else {
+            continue;
+        };
+        report.events += 1;
+        let label = format!("tx={} token={} {}", evt.tx_hash, evt.order.clob_token_id, evt.order.order_type);
+        let key = (evt.tx_hash.clone(), evt.order.clob_token_id.to_string(), evt.order.order_type.clone());
+        let Some(row) = rows.get_mut(&key).and_then(VecDeque::pop_front) else {
+            report.mismatches.push(format!("{}: no CSV row", label));
+            continue;
+        };
+        if (row.shares - evt.order.shares).abs() > 1e-6 {
+            report.mismatches.push(format!("{}: shares {:.6} decoded, {:.6} in CSV line {}", label, evt.order.shares, row.shares, row.line));
+        }
+
+        let replayed = replay_decision(whale_prices.apply(evt), &mut guard, &params, rounding);
+        let Some(recorded) = Decision::from_recorded(row.code) else {
+            report.unverifiable += 1;
+            continue;
+        };
+        if replayed == recorded {
+            report.matched += 1;
+        } else if replayed.unverifiable() || recorded.unverifiable() {
+            report.unverifiable += 1;
+        } else {
+            report.mismatches.push(format!("{}: replay {}, CSV line {} {}", label, replayed.as_str(), row.line, recorded.as_str()));
+        }
+    }
+
+    // Leftover rows for a replayed tx have no event behind them
+    let mut leftover: Vec<(usize, String)> = rows
+        .into_iter()
+        .filter(|((tx, _, _), queue)| !queue.is_empty() && capture.contains(tx.as_str()))
+        .flat_map(|((tx, token, direction), queue)| {
+            queue.into_iter().map(move |row| (row.line, format!("tx={} token={} {}: CSV line {} has no captured event", tx, token, direction, row.line)))
+        })
+        .collect();
+    leftover.sort();
+    report.mismatches.extend(leftover.into_iter().map(|(_, line)| line));
+    Ok(report)
+}
+
+/// verify_replay with TARGET_WHALE_ADDRESS, COLLATERAL_DECIMALS, WHALE_PRICE_MODE and
+/// WHALE_PRICE_ROUNDING from the environment
+pub fn verify_replay_configured(capture: &str, csv: &str, cfg: &Config) -> Result<ReplayReport, String> {
+    let topics = std::env::var("TARGET_WHALE_ADDRESS").map(|a| target_topics_from(&a)).unwrap_or_default();
+    verify_replay(
+        capture,
+        csv,
+        cfg,
+        |topic| topics.is_empty() || is_target_topic(topic, &topics),
+        *COLLATERAL_DECIMALS,
+        *WHALE_PRICE_MODE,
+        *WHALE_PRICE_ROUNDING,
+    )
+}
+
+/// The worker's decision path: tick alignment, then the engine with an ample book
+fn replay_decision(evt: ParsedEvent, guard: &mut RiskGuard, params: &PlanParams, rounding: TickRounding) -> Decision {
+    let evt = match tick_align_whale_price(evt, rounding) {
+        Ok(evt) => evt,
+        Err(status) => return Decision::Skip(status.code),
+    };
+    match plan_order(&evt, guard, params, |_, _| Ok(f64::INFINITY), &mut DecisionTrace::disabled()) {
+        Ok(_) => Decision::Submit,
+        Err(status) => Decision::Skip(status.code),
+    }
+}
+
+fn parse_rows(csv: &str) -> Result<RowsByEvent, String> {
+    let mut lines = csv.lines().enumerate();
+    let header = lines.next().map(|(_, h)| split_csv_line(h)).ok_or("CSV is empty")?;
+    let column = |name: &str| header.iter().position(|h| h == name);
+    let required = |name: &str| column(name).ok_or_else(|| format!("CSV header has no {} column", name));
+    let (tx_col, token_col, shares_col, status_col) =
+        (required("tx_hash")?, required("clob_asset_id")?, required("shares")?, required("order_status")?);
+    let direction_col = column("direction");
+    let split_cols = column("side").zip(column("is_fill"));
+    if direction_col.is_none() && split_cols.is_none() {
+        return Err("CSV header has neither direction nor side,is_fill".into());
+    }
+
+    let mut rows = RowsByEvent::default();
+    for (idx, line) in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line_no = idx + 1;
+        let fields = split_csv_line(line);
+        let field = |col: usize| fields.get(col).map(String::as_str).unwrap_or("");
+        let direction = match (direction_col, split_cols) {
+            (Some(col), _) => field(col).to_string(),
+            (None, Some((side, is_fill))) if field(is_fill) == "true" => format!("{}_FILL", field(side)),
+            (None, Some((side, _))) => field(side).to_string(),
+            (None, None) => unreachable!(),
+        };
+        // Rows from before status codes (free text) can't be compared
+        let Some(code) = StatusCode::parse(field(status_col)) else {
+            continue;
+        };
+        let row = RecordedRow { line: line_no, shares: field(shares_col).parse().unwrap_or(f64::NAN), code };
+        rows.entry((field(tx_col).to_string(), field(token_col).to_string(), direction)).or_default().push_back(row);
+    }
+    Ok(rows)
+}
+
+/// Split one CSV line, honouring CSV_QUOTING=strict quoting
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv_log::{write_audit_row, AuditRow, CsvDirection, CsvQuoting, CSV_HEADER};
+    use crate::decode::tests::{log_message, WHALE_TOPIC};
+    use crate::status_code::Status;
+
+    /// A whale BUY of `shares` at 0.50 on token 777 in tx `tx`
+    fn buy(tx: &str, shares: u128) -> String {
+        let data = format!("0x{:064x}{:064x}{:064x}{:064x}{:064x}", 0, 777, shares * 500_000, shares * 1_000_000, 0);
+        log_message(WHALE_TOPIC, &data).replace("0xtx", tx)
+    }
+
+    fn csv_for(capture: &[(String, StatusCode)]) -> String {
+        let mut csv = format!("{}\n", CSV_HEADER);
+        let (mut row, mut scratch) = (String::new(), String::new());
+        for (msg, code) in capture {
+            let evt = decode_ws_frame(FrameTransport::Text, msg.as_bytes(), |_| true, 6).unwrap();
+            let status = Status::with_message(*code, "detail, with comma");
+            let rec = AuditRow { evt: &evt, status: &status, book: ["N/A"; 4], is_live: false, shadow: None, order_id: "", order_tx: "", market: None, price_delta: None, tier: None };
+            write_audit_row(&mut row, &mut scratch, "t", &rec, CsvQuoting::Strict, CsvDirection::Combined);
+            csv.push_str(&row);
+            csv.push('\n');
+        }
+        csv
+    }
+
+    fn verify(capture: &str, csv: &str) -> ReplayReport {
+        let is_whale = |t: &str| t.eq_ignore_ascii_case(WHALE_TOPIC);
+        verify_replay(capture, csv, &Config::default(), is_whale, 6, WhalePriceMode::Fill, TickRounding::Nearest).unwrap()
+    }
+
+    #[test]
+    fn test_matching_capture_and_csv() {
+        let small = buy("0xaa", 2);
+        let big = buy("0xbb", 5000);
+        let capture = format!("{}\n{}\n", small, big);
+        let expected = |msg: &str| {
+            let evt = decode_ws_frame(FrameTransport::Text, msg.as_bytes(), |_| true, 6).unwrap();
+            let cfg = Config::default();
+            replay_decision(evt, &mut RiskGuard::new(cfg.risk_guard_config()), &cfg.plan_params(), TickRounding::Nearest)
+        };
+        assert_eq!(expected(&small), Decision::Skip(StatusCode::SkippedSmall));
+        assert_eq!(expected(&big), Decision::Submit);
+
+        // The dry-run session logged the skip and SKIPPED_DISABLED for the planned order;
+        // rows for a tx outside the capture are ignored
+        let csv = csv_for(&[
+            (small, StatusCode::SkippedSmall),
+            (big, StatusCode::SkippedDisabled),
+            (buy("0xcc", 3000), StatusCode::OrderPosted),
+        ]);
+        let report = verify(&capture, &csv);
+        assert!(report.passed(), "{}", report.render());
+        assert_eq!((report.events, report.matched, report.unverifiable), (2, 2, 0));
+    }
+
+    #[test]
+    fn test_injected_mismatch_reported() {
+        let small = buy("0xaa", 2);
+        let big = buy("0xbb", 5000);
+        let capture = format!("{}\n{}\n", small, big);
+        // The big trade was recorded as too small, and an extra row shares the small trade's tx
+        let csv = csv_for(&[
+            (small.clone(), StatusCode::SkippedSmall),
+            (big, StatusCode::SkippedSmall),
+            (small, StatusCode::OrderPosted),
+        ]);
+        let report = verify(&capture, &csv);
+        assert!(!report.passed());
+        assert_eq!(report.matched, 1);
+        assert_eq!(
+            report.mismatches,
+            vec![
+                "tx=0xbb token=777 BUY_FILL: replay SUBMIT, CSV line 3 SKIPPED_SMALL".to_string(),
+                "tx=0xaa token=777 BUY_FILL: CSV line 4 has no captured event".to_string(),
+            ]
+        );
+        assert!(report.render().contains("1 matched, 0 unverifiable, 2 mismatched"));
+
+        // An event with no row at all
+        let report = verify(&capture, &format!("{}\n", CSV_HEADER));
+        assert_eq!(report.mismatches.len(), 2);
+        assert!(report.mismatches[0].ends_with("no CSV row"));
+    }
+
+    #[test]
+    fn test_split_csv_line_quoting() {
+        assert_eq!(split_csv_line(r#"a,"b, ""c""",,d"#), vec!["a", "b, \"c\"", "", "d"]);
+    }
+}
@@ -0,0 +1,457 @@
+//! Registry of our resting (GTD/GTC) buy orders
+//! Lets a whale SELL on a token cancel the buys we left on the book copying that whale,
+//! keeps our own size out of book depth estimates, and caps how many can rest at once.
+//! Orders that reach their expiry are queued for a follow-up (GTD_EXPIRY_CHECK) that asks the
+//! CLOB how much matched while they rested
+
+use rustc_hash::FxHashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// Hard limit on resting orders per token, on top of MAX_RESTING_PER_TOKEN. admit enforces it
+/// like the configured cap, so a placed order is never left on the book untracked
+const MAX_ORDERS_PER_TOKEN: usize = 16;
+
+/// Max expired orders awaiting a follow-up (oldest dropped first, left to reconciliation)
+const MAX_EXPIRED: usize = 256;
+
+/// Shares below which an order counts as fully filled / not filled at all
+const FILL_EPSILON: f64 = 0.01;
+
+/// One resting buy we placed while copying `whale`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestingOrder {
+    pub order_id: String,
+    /// Lowercase 0x whale address the order copied
+    pub whale: String,
+    pub price: f64,
+    /// Size in shares as submitted (fills aren't tracked, so this is an upper bound)
+    pub size: f64,
+    /// Shares matched immediately on posting (already recorded as a position)
+    pub filled_at_post: f64,
+    /// Notional counted against the daily cap for this order's size
+    pub counted_usd: f64,
+    /// Unix seconds after which the order has expired on its own (None = no expiry)
+    pub expires_at: Option<u64>,
+    /// Unix seconds when we placed it (oldest is cancelled first to make room)
+    pub placed_at: u64,
+}
+
+impl RestingOrder {
+    fn live_at(&self, now_unix_secs: u64) -> bool {
+        self.expires_at.is_none_or(|t| t > now_unix_secs)
+    }
+}
+
+/// What became of a resting order by the time it expired
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpiryOutcome {
+    Filled,
+    Partial,
+    Unfilled,
+}
+
+impl ExpiryOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExpiryOutcome::Filled => "FILLED",
+            ExpiryOutcome::Partial => "PARTIAL",
+            ExpiryOutcome::Unfilled => "UNFILLED",
+        }
+    }
+}
+
+/// Position and exposure adjustments for an expired order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExpirySettlement {
+    pub outcome: ExpiryOutcome,
+    /// Shares matched while the order rested, not yet recorded as a position
+    pub late_fill: f64,
+    /// Share of counted_usd that never filled, to give back to the daily cap
+    pub released_usd: f64,
+}
+
+/// Settle an expired (or cancelled) order given the CLOB's size_matched for it
+pub fn settle_expired(order: &RestingOrder, size_matched: f64) -> ExpirySettlement {
+    let matched = size_matched.clamp(0.0, order.size);
+    let unfilled = order.size - matched;
+    let outcome = if unfilled < FILL_EPSILON {
+        ExpiryOutcome::Filled
+    } else if matched < FILL_EPSILON {
+        ExpiryOutcome::Unfilled
+    } else {
+        ExpiryOutcome::Partial
+    };
+    let released_usd = if order.size > 0.0 && outcome != ExpiryOutcome::Filled {
+        order.counted_usd * unfilled / order.size
+    } else {
+        0.0
+    };
+    ExpirySettlement { outcome, late_fill: (matched - order.filled_at_post).max(0.0), released_usd }
+}
+
+/// Caps on concurrent resting orders, which tie up collateral until they fill or expire
+/// (MAX_RESTING_ORDERS / MAX_RESTING_PER_TOKEN, 0 = no cap)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RestingCap {
+    pub max_total: usize,
+    pub max_per_token: usize,
+    /// At the cap, cancel the oldest resting order instead of skipping the new one
+    pub cancel_oldest: bool,
+}
+
+impl RestingCap {
+    pub fn enabled(&self) -> bool {
+        self.max_total > 0 || self.max_per_token > 0
+    }
+}
+
+/// Whether a new resting order on a token fits under the cap
+#[derive(Debug, Clone, PartialEq)]
+pub enum RestingAdmission {
+    Room,
+    /// Room was made by dropping this order from the registry; the caller cancels it
+    Evict(RestingOrder),
+    /// At the cap: skip the new order (SKIPPED_MAX_RESTING)
+    Full,
+}
+
+// ============================================================================
+// Registry
+// ============================================================================
+
+#[derive(Default)]
+pub struct RestingOrders {
+    by_token: Mutex<FxHashMap<String, Vec<RestingOrder>>>,
+    /// (token_id, order) that expired while tracked, oldest first
+    expired: Mutex<Vec<(String, RestingOrder)>>,
+    /// (placed_at, usd) of unfilled notional waiting to go back to the daily cap
+    released: Mutex<Vec<(u64, f64)>>,
+}
+
+/// Move orders on `token_id` that have expired by `now_unix_secs` to `expired`
+fn prune_expired(token_id: &str, orders: &mut Vec<RestingOrder>, expired: &mut Vec<(String, RestingOrder)>, now_unix_secs: u64) {
+    let (live, dead): (Vec<_>, Vec<_>) = orders.drain(..).partition(|o| o.live_at(now_unix_secs));
+    *orders = live;
+    expired.extend(dead.into_iter().map(|o| (token_id.to_string(), o)));
+    if expired.len() > MAX_EXPIRED {
+        let excess = expired.len() - MAX_EXPIRED;
+        expired.drain(..excess);
+    }
+}
+
+impl RestingOrders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track a resting buy order on `token_id` (admit it first to stay under the per-token limit)
+    pub fn register(&self, token_id: &str, order: RestingOrder) {
+        if order.order_id.is_empty() {
+            return;
+        }
+        let Ok(mut map) = self.by_token.lock() else { return };
+        map.entry(token_id.to_string()).or_default().push(order);
+    }
+
+    /// Remove and return the unexpired orders on `token_id` that copied `whale`
+    /// (call when that whale sells the token)
+    pub fn take_for_exit(&self, token_id: &str, whale: &str, now_unix_secs: u64) -> Vec<RestingOrder> {
+        let Ok(mut map) = self.by_token.lock() else { return Vec::new() };
+        let Some(orders) = map.get_mut(token_id) else { return Vec::new() };
+
+        if let Ok(mut expired) = self.expired.lock() {
+            prune_expired(token_id, orders, &mut expired, now_unix_secs);
+        }
+        let (taken, kept): (Vec<_>, Vec<_>) = orders.drain(..).partition(|o| o.whale.eq_ignore_ascii_case(whale));
+        if kept.is_empty() {
+            map.remove(token_id);
+        } else {
+            *orders = kept;
+        }
+        taken
+    }
+
+    /// Stop tracking one order (cancelled to be re-posted at another price), returning it
+    pub fn remove(&self, token_id: &str, order_id: &str) -> Option<RestingOrder> {
+        let mut map = self.by_token.lock().ok()?;
+        let orders = map.get_mut(token_id)?;
+        let idx = orders.iter().position(|o| o.order_id == order_id)?;
+        let removed = orders.remove(idx);
+        if orders.is_empty() {
+            map.remove(token_id);
+        }
+        Some(removed)
+    }
+
+    /// Our unexpired resting orders on `token_id`
+    pub fn live_on(&self, token_id: &str, now_unix_secs: u64) -> Vec<RestingOrder> {
+        let Ok(map) = self.by_token.lock() else { return Vec::new() };
+        map.get(token_id)
+            .map(|orders| orders.iter().filter(|o| o.live_at(now_unix_secs)).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Tokens with at least one tracked order
+    pub fn tokens(&self) -> Vec<String> {
+        self.by_token.lock().map(|m| m.keys().cloned().collect()).unwrap_or_default()
+    }
+
+    /// (price, size) of our unexpired resting orders on `token_id`, for excluding them from book depth
+    pub fn own_levels(&self, token_id: &str, now_unix_secs: u64) -> Vec<(f64, f64)> {
+        let Ok(map) = self.by_token.lock() else { return Vec::new() };
+        map.get(token_id)
+            .map(|orders| {
+                orders.iter()
+                    .filter(|o| o.live_at(now_unix_secs))
+                    .map(|o| (o.price, o.size))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Check `cap` (and MAX_ORDERS_PER_TOKEN) before placing a resting order on `token_id`.
+    /// Expired orders are pruned first (they no longer hold collateral). With cancel_oldest, the
+    /// oldest order on the token (if the per-token cap is the one hit) or across all tokens is
+    /// removed and returned for cancelling
+    pub fn admit(&self, token_id: &str, cap: &RestingCap, now_unix_secs: u64) -> RestingAdmission {
+        let Ok(mut map) = self.by_token.lock() else { return RestingAdmission::Room };
+        if let Ok(mut expired) = self.expired.lock() {
+            map.iter_mut().for_each(|(token, orders)| prune_expired(token, orders, &mut expired, now_unix_secs));
+        }
+        map.retain(|_, orders| !orders.is_empty());
+
+        let on_token = map.get(token_id).map_or(0, Vec::len);
+        let total: usize = map.values().map(Vec::len).sum();
+        let per_token = match cap.max_per_token {
+            0 => MAX_ORDERS_PER_TOKEN,
+            n => n.min(MAX_ORDERS_PER_TOKEN),
+        };
+        let token_full = on_token >= per_token;
+        let total_full = cap.max_total > 0 && total >= cap.max_total;
+        if !token_full && !total_full {
+            return RestingAdmission::Room;
+        }
+        if !cap.cancel_oldest {
+            return RestingAdmission::Full;
+        }
+
+        // Each token's orders are in placement order, so the oldest is always first
+        let victim = if token_full {
+            Some(token_id.to_string())
+        } else {
+            map.iter().min_by_key(|(_, orders)| orders[0].placed_at).map(|(token, _)| token.clone())
+        };
+        let Some(victim) = victim else { return RestingAdmission::Full };
+        let Some(orders) = map.get_mut(&victim) else { return RestingAdmission::Full };
+        let oldest = orders.remove(0);
+        if orders.is_empty() {
+            map.remove(&victim);
+        }
+        RestingAdmission::Evict(oldest)
+    }
+
+    /// Remove and return (token_id, order) for orders that expired at least `grace_secs` ago,
+    /// for the expiry follow-up
+    pub fn take_expired(&self, now_unix_secs: u64, grace_secs: u64) -> Vec<(String, RestingOrder)> {
+        let (Ok(mut map), Ok(mut expired)) = (self.by_token.lock(), self.expired.lock()) else { return Vec::new() };
+        map.iter_mut().for_each(|(token, orders)| prune_expired(token, orders, &mut expired, now_unix_secs));
+        map.retain(|_, orders| !orders.is_empty());
+
+        let cutoff = now_unix_secs.saturating_sub(grace_secs);
+        let (due, waiting): (Vec<_>, Vec<_>) = expired.drain(..).partition(|(_, o)| !o.live_at(cutoff));
+        *expired = waiting;
+        due
+    }
+
+    /// Queue unfilled notional (an expired order, an ended resubmit chain) for the order worker's risk guard
+    pub fn release_notional(&self, placed_at: u64, usd: f64) {
+        if usd > 0.0 {
+            if let Ok(mut released) = self.released.lock() {
+                released.push((placed_at, usd));
+            }
+        }
+    }
+
+    /// Drain notional queued by release_notional
+    pub fn take_released(&self) -> Vec<(u64, f64)> {
+        self.released.lock().map(|mut r| std::mem::take(&mut *r)).unwrap_or_default()
+    }
+
+    /// Number of tracked orders across all tokens
+    pub fn len(&self) -> usize {
+        self.by_token.lock().map(|m| m.values().map(Vec::len).sum()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// ============================================================================
+// Global Instance
+// ============================================================================
+
+static GLOBAL_RESTING: OnceLock<RestingOrders> = OnceLock::new();
+
+/// Get the global registry
+pub fn global() -> &'static RestingOrders {
+    GLOBAL_RESTING.get_or_init(RestingOrders::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(id: &str, whale: &str, expires_at: Option<u64>) -> RestingOrder {
+        RestingOrder {
+            order_id: id.into(),
+            whale: whale.into(),
+            price: 0.50,
+            size: 10.0,
+            filled_at_post: 0.0,
+            counted_usd: 5.0,
+            expires_at,
+            placed_at: 0,
+        }
+    }
+
+    fn placed(id: &str, placed_at: u64) -> RestingOrder {
+        RestingOrder { placed_at, ..order(id, "0xwhale", Some(1_000)) }
+    }
+
+    #[test]
+    fn test_resting_cap_enforced() {
+        let reg = RestingOrders::new();
+        let cap = RestingCap { max_total: 3, max_per_token: 2, cancel_oldest: false };
+        assert_eq!(reg.admit("a", &cap, 500), RestingAdmission::Room);
+        reg.register("a", placed("a1", 10));
+        reg.register("a", placed("a2", 20));
+
+        // Per-token cap hit on "a", other tokens still fit
+        assert_eq!(reg.admit("a", &cap, 500), RestingAdmission::Full);
+        assert_eq!(reg.admit("b", &cap, 500), RestingAdmission::Room);
+        reg.register("b", placed("b1", 30));
+        // Global cap hit
+        assert_eq!(reg.admit("c", &cap, 500), RestingAdmission::Full);
+        assert_eq!(reg.len(), 3);
+
+        // Expired orders free their slot
+        assert_eq!(reg.admit("c", &cap, 1_000), RestingAdmission::Room);
+        assert!(reg.is_empty());
+        // No cap configured
+        assert_eq!(reg.admit("a", &RestingCap::default(), 0), RestingAdmission::Room);
+    }
+
+    #[test]
+    fn test_per_token_limit_without_cap() {
+        let reg = RestingOrders::new();
+        for i in 0..MAX_ORDERS_PER_TOKEN as u64 {
+            reg.register("a", placed(&format!("a{}", i), i));
+        }
+        // Nothing is dropped on register; admit refuses the next one instead
+        assert_eq!(reg.len(), MAX_ORDERS_PER_TOKEN);
+        assert_eq!(reg.admit("a", &RestingCap::default(), 500), RestingAdmission::Full);
+        assert_eq!(reg.admit("b", &RestingCap::default(), 500), RestingAdmission::Room);
+        // or hands the oldest back for cancelling
+        let cancel_oldest = RestingCap { cancel_oldest: true, ..Default::default() };
+        assert_eq!(reg.admit("a", &cancel_oldest, 500), RestingAdmission::Evict(placed("a0", 0)));
+        // A larger configured cap is still held to the limit
+        let wide = RestingCap { max_per_token: 100, ..Default::default() };
+        reg.register("a", placed("a16", 16));
+        assert_eq!(reg.admit("a", &wide, 500), RestingAdmission::Full);
+    }
+
+    #[test]
+    fn test_resting_cap_cancels_oldest() {
+        let reg = RestingOrders::new();
+        let cap = RestingCap { max_total: 3, max_per_token: 2, cancel_oldest: true };
+        reg.register("a", placed("a1", 20));
+        reg.register("b", placed("b1", 10));
+        reg.register("a", placed("a2", 30));
+
+        // Per-token cap on "a": its oldest goes, even though "b1" is older overall
+        assert_eq!(reg.admit("a", &cap, 500), RestingAdmission::Evict(placed("a1", 20)));
+        assert_eq!(reg.len(), 2);
+        reg.register("a", placed("a3", 40));
+
+        // Global cap from a new token: the oldest anywhere goes
+        assert_eq!(reg.admit("c", &cap, 500), RestingAdmission::Evict(placed("b1", 10)));
+        assert_eq!(reg.own_levels("b", 500), vec![]);
+        assert_eq!(reg.len(), 2);
+    }
+
+    #[test]
+    fn test_sell_takes_matching_resting_order() {
+        let reg = RestingOrders::new();
+        reg.register("tok", order("0xaaa", "0xwhale", Some(1_000)));
+        reg.register("tok", order("0xbbb", "0xother", Some(1_000)));
+        reg.register("tok2", order("0xccc", "0xwhale", None));
+        assert_eq!(reg.len(), 3);
+
+        // Sell from a different whale or token: nothing to cancel
+        assert!(reg.take_for_exit("tok", "0xnobody", 500).is_empty());
+        assert!(reg.take_for_exit("tok3", "0xwhale", 500).is_empty());
+
+        // Sell from the copied whale returns its order for cancellation (case-insensitive)
+        let taken = reg.take_for_exit("tok", "0xWHALE", 500);
+        assert_eq!(taken, vec![order("0xaaa", "0xwhale", Some(1_000))]);
+        assert_eq!(reg.len(), 2);
+
+        // Already taken
+        assert!(reg.take_for_exit("tok", "0xwhale", 500).is_empty());
+    }
+
+    #[test]
+    fn test_own_levels() {
+        let reg = RestingOrders::new();
+        reg.register("tok", RestingOrder { price: 0.48, size: 25.0, ..order("0xaaa", "0xwhale", Some(1_000)) });
+        reg.register("tok", order("0xold", "0xwhale", Some(100)));
+        assert_eq!(reg.own_levels("tok", 500), vec![(0.48, 25.0)]);
+        assert!(reg.own_levels("other", 500).is_empty());
+
+        // A repriced order drops out; the rest stay
+        assert_eq!(reg.remove("tok", "0xaaa").map(|o| o.size), Some(25.0));
+        assert_eq!(reg.remove("tok", "0xaaa"), None);
+        assert!(reg.own_levels("tok", 500).is_empty());
+        assert_eq!(reg.len(), 1);
+    }
+
+    #[test]
+    fn test_expired_orders_not_cancelled() {
+        let reg = RestingOrders::new();
+        reg.register("tok", order("0xold", "0xwhale", Some(100)));
+        reg.register("tok", order("", "0xwhale", None));
+        assert!(reg.take_for_exit("tok", "0xwhale", 200).is_empty());
+        assert!(reg.is_empty());
+    }
+
+    #[test]
+    fn test_expired_orders_reconciled() {
+        let reg = RestingOrders::new();
+        reg.register("tok", order("0xfilled", "0xwhale", Some(100)));
+        reg.register("tok", order("0xlive", "0xwhale", Some(1_000)));
+        reg.register("tok2", RestingOrder { filled_at_post: 2.0, ..order("0xpartial", "0xwhale", Some(100)) });
+
+        // Not due until the grace period after expiry has passed
+        assert!(reg.take_expired(105, 10).is_empty());
+        assert_eq!(reg.len(), 1);
+        let due = reg.take_expired(110, 10);
+        assert_eq!(due.iter().map(|(t, o)| (t.as_str(), o.order_id.as_str())).collect::<Vec<_>>(),
+            vec![("tok", "0xfilled"), ("tok2", "0xpartial")]);
+        assert!(reg.take_expired(110, 10).is_empty());
+
+        // Filled while resting: the whole size is a late fill, nothing released
+        let filled = settle_expired(&due[0].1, 10.0);
+        assert_eq!(filled, ExpirySettlement { outcome: ExpiryOutcome::Filled, late_fill: 10.0, released_usd: 0.0 });
+
+        // Never filled: no position change, all counted notional released
+        let unfilled = settle_expired(&due[0].1, 0.0);
+        assert_eq!(unfilled, ExpirySettlement { outcome: ExpiryOutcome::Unfilled, late_fill: 0.0, released_usd: 5.0 });
+
+        // Partial: shares matched at post time aren't recorded twice
+        let partial = settle_expired(&due[1].1, 6.0);
+        assert_eq!(partial.outcome, ExpiryOutcome::Partial);
+        assert!((partial.late_fill - 4.0).abs() < 1e-9);
+        assert!((partial.released_usd - 2.0).abs() < 1e-9);
+    }
+}
@@ -8,8 +8,9 @@
 // 4. Max attempts and price ceiling enforcement
 // 5. Minimum threshold check for resubmits
 
-use crate::config::*;
-use crate::types::ResubmitRequest;
+use crate::settings::*;
+use crate::models::ResubmitRequest;
+use std::time::{Duration, Instant};
 
 // =========================================================================
 // Helper: Simulate underfill detection logic from main.rs
@@ -44,6 +45,8 @@ fn should_resubmit_underfill(
     let rounded_size = (remaining_shares * 100.0).round() / 100.0;
     let req = ResubmitRequest {
         token_id: token_id.to_string(),
+        whale_address: String::new(),
+        chain_started: Instant::now(),
         whale_price,
         failed_price: limit_price, // Start at same price for underfills
         size: rounded_size,
@@ -53,6 +56,8 @@ fn should_resubmit_underfill(
         max_price,
         cumulative_filled: filled_shares,
         original_size: requested_shares,
+        counted_usd: 0.0,
+        counted_at: 0,
         is_live: false,
     };
 
@@ -73,6 +78,8 @@ fn should_resubmit_fak_failure(
 
     ResubmitRequest {
         token_id: token_id.to_string(),
+        whale_address: String::new(),
+        chain_started: Instant::now(),
         whale_price,
         failed_price: limit_price,
         size: rounded_size,
@@ -82,23 +89,16 @@ fn should_resubmit_fak_failure(
         max_price,
         cumulative_filled: 0.0,
         original_size: rounded_size,
+        counted_usd: 0.0,
+        counted_at: 0,
         is_live: false,
     }
 }
 
-/// Simulates the resubmit worker's price calculation for a given attempt
+/// The resubmit worker's price calculation for a given attempt (cent-tick market)
 fn calculate_next_price(req: &ResubmitRequest) -> f64 {
-    let increment = if should_increment_price(req.whale_shares, req.attempt) {
-        RESUBMIT_PRICE_INCREMENT
-    } else {
-        0.0 // Flat retry
-    };
-
-    if req.side_is_buy {
-        (req.failed_price + increment).min(0.99)
-    } else {
-        (req.failed_price - increment).max(0.01)
-    }
+    let chase = should_increment_price(req.whale_shares, req.attempt);
+    crate::settings::calculate_next_price(req.failed_price, req.side_is_buy, chase, crate::market_cache::DEFAULT_TICK_SIZE)
 }
 
 /// Simulates whether the resubmitter would abort due to price ceiling
@@ -129,6 +129,30 @@ fn test_fak_failure_resubmits_full_size() {
     assert_eq!(req.failed_price, limit_price, "Failed price should be original limit");
 }
 
+#[test]
+fn test_fak_failure_resubmit_fill_counts_against_daily_cap() {
+    use crate::risk_guard::{RiskGuard, RiskGuardConfig, TradeSide};
+
+    let mut guard = RiskGuard::new(RiskGuardConfig { daily_notional_cap_usd: 80.0, ..Default::default() });
+    let t = 1_700_000_000;
+
+    // 100 shares @ 0.50 reserved before the post; the FAK misses and the chain takes it over
+    guard.record_notional_at(TradeSide::Buy, 50.0, t);
+    let req = ResubmitRequest {
+        counted_usd: 50.0,
+        counted_at: t,
+        ..should_resubmit_fak_failure(100.0, 0.50, 1000.0, 0.48, "token")
+    };
+
+    // The chain fills 80 shares and gives back the 20 it never filled
+    guard.release_notional_at(req.unfilled_usd(req.size - 80.0), req.counted_at, t + 5);
+    assert!((guard.daily_notional() - 40.0).abs() < 1e-9);
+
+    // The resubmit fill is what trips the cap for the next $50 buy
+    assert!(!guard.daily_notional_allows_at(TradeSide::Buy, 50.0, t + 10));
+    assert!(guard.daily_notional_allows_at(TradeSide::Buy, 40.0, t + 10));
+}
+
 #[test]
 fn test_fak_failure_different_sizes() {
     // Test with various whale sizes to verify full size is always used
@@ -262,6 +286,8 @@ fn test_tier_8000_plus_chases_once_then_flat() {
     // 4000+ tier: buffer 0.01
     let mut req = ResubmitRequest {
         token_id: "token".into(),
+        whale_address: String::new(),
+        chain_started: Instant::now(),
         whale_price: 0.50,
         failed_price: 0.51, // Initial limit (0.50 + 0.01 buffer)
         size: 100.0,
@@ -271,6 +297,8 @@ fn test_tier_8000_plus_chases_once_then_flat() {
         max_price: 0.52, // 0.51 + 0.01 resubmit buffer
         cumulative_filled: 0.0,
         original_size: 100.0,
+        counted_usd: 0.0,
+        counted_at: 0,
         is_live: false,
     };
 
@@ -306,6 +334,8 @@ fn test_tier_4000_to_8000_chases_once_then_flat() {
     // 4000+ tier gets 0.01 tier buffer and 0.01 resubmit buffer
     let mut req = ResubmitRequest {
         token_id: "token".into(),
+        whale_address: String::new(),
+        chain_started: Instant::now(),
         whale_price: 0.50,
         failed_price: 0.51, // Initial limit (0.50 + 0.01 tier buffer for 4000+)
         size: 100.0,
@@ -315,6 +345,8 @@ fn test_tier_4000_to_8000_chases_once_then_flat() {
         max_price: 0.52, // 0.51 + 0.01 resubmit buffer for 4000+
         cumulative_filled: 0.0,
         original_size: 100.0,
+        counted_usd: 0.0,
+        counted_at: 0,
         is_live: false,
     };
 
@@ -353,6 +385,8 @@ fn test_tier_small_never_chases() {
     // Verify price progression - all flat at initial price
     let mut req = ResubmitRequest {
         token_id: "token".into(),
+        whale_address: String::new(),
+        chain_started: Instant::now(),
         whale_price: 0.50,
         failed_price: 0.50, // Initial limit (no tier buffer for 1000+)
         size: 10.0,
@@ -362,6 +396,8 @@ fn test_tier_small_never_chases() {
         max_price: 0.50, // No resubmit buffer for <4000
         cumulative_filled: 0.0,
         original_size: 10.0,
+        counted_usd: 0.0,
+        counted_at: 0,
         is_live: false,
     };
 
@@ -432,6 +468,8 @@ fn test_price_ceiling_abort() {
     // Note: 8000+ with attempt 2 is flat (no increment), so stays at 0.52
     let req_at_ceiling = ResubmitRequest {
         token_id: "token".into(),
+        whale_address: String::new(),
+        chain_started: Instant::now(),
         whale_price: 0.50,
         failed_price: 0.52,
         size: 100.0,
@@ -441,6 +479,8 @@ fn test_price_ceiling_abort() {
         max_price: 0.52,
         cumulative_filled: 0.0,
         original_size: 100.0,
+        counted_usd: 0.0,
+        counted_at: 0,
         is_live: false,
     };
     // New price would be 0.52 (flat), which equals max_price
@@ -452,6 +492,8 @@ fn test_price_ceiling_abort() {
     // Use attempt 1 which does chase for >= 4000
     let req_over_ceiling = ResubmitRequest {
         token_id: "token".into(),
+        whale_address: String::new(),
+        chain_started: Instant::now(),
         whale_price: 0.50,
         failed_price: 0.53, // At this price, chase would go to 0.54
         size: 100.0,
@@ -461,11 +503,45 @@ fn test_price_ceiling_abort() {
         max_price: 0.53,
         cumulative_filled: 0.0,
         original_size: 100.0,
+        counted_usd: 0.0,
+        counted_at: 0,
         is_live: false,
     };
     assert!(would_abort_price_ceiling(&req_over_ceiling), "Over ceiling should abort");
 }
 
+#[test]
+fn test_chase_stops_at_whale_premium_cap() {
+    // Whale paid 0.50; never pay more than 0.50 + 0.03
+    let cap = whale_premium_cap(0.50, 0.03, 0.0);
+    assert_eq!(cap, Some(0.53));
+
+    // Chase from 0.50 in 0.01 steps with a generous tier ceiling: 0.51..0.53 go through, 0.54 aborts
+    let mut price = 0.50;
+    let mut submitted = Vec::new();
+    for _ in 0..10 {
+        let new_price = price + RESUBMIT_PRICE_INCREMENT;
+        if resubmit_exceeds_ceiling(true, new_price, 0.99, cap, false) {
+            break;
+        }
+        submitted.push(new_price);
+        price = new_price;
+    }
+    assert_eq!(submitted.len(), 3);
+    assert!((submitted[2] - 0.53).abs() < 1e-9);
+
+    // The last (GTD) attempt skips the chase ceiling but not the premium cap
+    assert!(!resubmit_exceeds_ceiling(true, 0.54, 0.52, None, true));
+    assert!(resubmit_exceeds_ceiling(true, 0.54, 0.99, cap, true));
+    // Sells aren't capped
+    assert!(!resubmit_exceeds_ceiling(false, 0.54, 0.52, cap, false));
+
+    // Percentage mode, and the tighter of the two when both are set
+    assert!((whale_premium_cap(0.40, 0.0, 5.0).unwrap() - 0.42).abs() < 1e-9);
+    assert!((whale_premium_cap(0.40, 0.01, 5.0).unwrap() - 0.41).abs() < 1e-9);
+    assert_eq!(whale_premium_cap(0.40, 0.0, 0.0), None);
+}
+
 #[test]
 fn test_price_ceiling_hard_cap_099() {
     // Even with high max_price, should never exceed 0.99
@@ -488,6 +564,8 @@ fn test_full_resubmit_sequence_8000_plus() {
 
     let mut req = ResubmitRequest {
         token_id: "token".into(),
+        whale_address: String::new(),
+        chain_started: Instant::now(),
         whale_price: 0.50,
         failed_price: initial_limit,
         size: 100.0,
@@ -497,6 +575,8 @@ fn test_full_resubmit_sequence_8000_plus() {
         max_price,
         cumulative_filled: 0.0,
         original_size: 100.0,
+        counted_usd: 0.0,
+        counted_at: 0,
         is_live: false,
     };
 
@@ -532,6 +612,8 @@ fn test_full_resubmit_sequence_3000() {
 
     let mut req = ResubmitRequest {
         token_id: "token".into(),
+        whale_address: String::new(),
+        chain_started: Instant::now(),
         whale_price: 0.50,
         failed_price: initial_limit,
         size: 50.0,
@@ -541,6 +623,8 @@ fn test_full_resubmit_sequence_3000() {
         max_price,
         cumulative_filled: 0.0,
         original_size: 50.0,
+        counted_usd: 0.0,
+        counted_at: 0,
         is_live: false,
     };
 
@@ -574,6 +658,8 @@ fn test_full_resubmit_sequence_small() {
 
     let mut req = ResubmitRequest {
         token_id: "token".into(),
+        whale_address: String::new(),
+        chain_started: Instant::now(),
         whale_price: 0.50,
         failed_price: initial_limit,
         size: 10.0,
@@ -583,6 +669,8 @@ fn test_full_resubmit_sequence_small() {
         max_price,
         cumulative_filled: 0.0,
         original_size: 10.0,
+        counted_usd: 0.0,
+        counted_at: 0,
         is_live: false,
     };
 
@@ -701,6 +789,8 @@ fn test_sell_orders_not_resubmitted() {
 
     let req = ResubmitRequest {
         token_id: "token".into(),
+        whale_address: String::new(),
+        chain_started: Instant::now(),
         whale_price: 0.50,
         failed_price: 0.49,
         size: 100.0,
@@ -710,6 +800,8 @@ fn test_sell_orders_not_resubmitted() {
         max_price: 0.48,
         cumulative_filled: 0.0,
         original_size: 100.0,
+        counted_usd: 0.0,
+        counted_at: 0,
         is_live: false,
     };
 
@@ -727,6 +819,8 @@ fn test_price_clamping_near_boundaries() {
     // Test near 0.99 boundary for buys
     let req_high = ResubmitRequest {
         token_id: "token".into(),
+        whale_address: String::new(),
+        chain_started: Instant::now(),
         whale_price: 0.98,
         failed_price: 0.985,
         size: 100.0,
@@ -736,6 +830,8 @@ fn test_price_clamping_near_boundaries() {
         max_price: 0.99,
         cumulative_filled: 0.0,
         original_size: 100.0,
+        counted_usd: 0.0,
+        counted_at: 0,
         is_live: false,
     };
     let price_high = calculate_next_price(&req_high);
@@ -744,6 +840,8 @@ fn test_price_clamping_near_boundaries() {
     // Test near 0.01 boundary for sells (if ever implemented)
     let req_low = ResubmitRequest {
         token_id: "token".into(),
+        whale_address: String::new(),
+        chain_started: Instant::now(),
         whale_price: 0.02,
         failed_price: 0.015,
         size: 100.0,
@@ -753,6 +851,8 @@ fn test_price_clamping_near_boundaries() {
         max_price: 0.01,
         cumulative_filled: 0.0,
         original_size: 100.0,
+        counted_usd: 0.0,
+        counted_at: 0,
         is_live: false,
     };
     let price_low = calculate_next_price(&req_low);
@@ -1112,3 +1212,212 @@ fn test_atp_near_price_ceiling() {
     // Now 0.99 + 0.01 = 1.00 (clamped to 0.99), but 0.99 > 0.98, so should abort
     assert!(would_abort_price_ceiling(&req), "Should abort when new_price > max_price");
 }
+
+// =========================================================================
+// Percentage chase ceiling
+// =========================================================================
+
+#[test]
+fn test_flat_vs_percentage_ceiling() {
+    let whale_shares = 5000.0; // 4000+ tier: flat buffer 0.01
+
+    // Flat mode: same 0.01 at any price
+    assert_eq!(resubmit_max_buffer(whale_shares, 0.10, 0.0), 0.01);
+    assert_eq!(resubmit_max_buffer(whale_shares, 0.90, 0.0), 0.01);
+
+    // 2% mode: 0.002 at 0.10 (no room for a 0.01 tick), capped at 0.01 at 0.90
+    assert!((resubmit_max_buffer(whale_shares, 0.10, 2.0) - 0.002).abs() < 1e-9);
+    assert_eq!(resubmit_max_buffer(whale_shares, 0.90, 2.0), 0.01);
+
+    // 1% mode at 0.90 stays under the cap
+    assert!((resubmit_max_buffer(whale_shares, 0.90, 1.0) - 0.009).abs() < 1e-9);
+
+    // A chase of one tick at 0.10 is blocked in 2% mode, allowed in flat mode
+    let limit_price = 0.11;
+    let flat_max = limit_price + resubmit_max_buffer(whale_shares, 0.10, 0.0);
+    let pct_max = limit_price + resubmit_max_buffer(whale_shares, 0.10, 2.0);
+    let next_price = limit_price + RESUBMIT_PRICE_INCREMENT;
+    assert!(next_price <= flat_max + 1e-9);
+    assert!(next_price > pct_max);
+
+    // Tiers that never chase stay at zero in either mode
+    assert_eq!(resubmit_max_buffer(1000.0, 0.90, 2.0), 0.0);
+
+    // Default (env unset) is flat mode
+    assert!((get_resubmit_max_price(whale_shares, 0.50, 0.51, 0.01) - 0.52).abs() < 1e-9);
+}
+
+// =========================================================================
+// Three-decimal (0.001 tick) markets
+// =========================================================================
+
+#[test]
+fn test_tenth_cent_tick_chase() {
+    let tick = 0.001;
+    let whale_shares = 5000.0;
+    // (calculate_next_price in this file is the ResubmitRequest helper)
+    let next_price = crate::settings::calculate_next_price;
+
+    // One tick per chase: a tenth of a cent, not a cent
+    assert!((resubmit_increment(tick) - 0.001).abs() < 1e-12);
+    assert_eq!(next_price(0.467, true, true, tick), 0.468);
+    assert_eq!(next_price(0.467, true, false, tick), 0.467);
+    assert_eq!(next_price(0.467, false, true, tick), 0.466);
+    // Cent markets unchanged
+    assert_eq!(next_price(0.51, true, true, 0.01), 0.52);
+
+    // Bounds follow the tick: 0.999 is a valid price here, 1.000 is not
+    assert_eq!(next_price(0.998, true, true, tick), 0.999);
+    assert_eq!(next_price(0.999, true, true, tick), 0.999);
+    assert_eq!(next_price(0.001, false, true, tick), 0.001);
+
+    // Ceiling is tenth-cent aligned: 0.467 + 0.01 flat buffer
+    assert_eq!(get_resubmit_max_price(whale_shares, 0.457, 0.467, tick), 0.477);
+    // A fractional buffer keeps its tenth cents instead of flooring to the cent below
+    assert_eq!(crate::engine::floor_to_tick(0.467 + 0.0075, tick), 0.474);
+    assert_eq!(crate::engine::floor_to_tick(0.51 + 0.0075, 0.01), 0.51);
+
+    // The chain walks 0.468 .. 0.477 and stops there
+    let mut price = 0.467;
+    let mut steps = 0;
+    loop {
+        let next = next_price(price, true, true, tick);
+        if next > 0.477 {
+            break;
+        }
+        price = next;
+        steps += 1;
+    }
+    assert_eq!((price, steps), (0.477, 10));
+}
+
+// =========================================================================
+// Initial resubmit delay
+// =========================================================================
+
+#[test]
+fn test_first_resubmit_delayed_by_tier() {
+    // Small (thin) markets configured to wait longer than the 4000+ tier
+    assert_eq!(resubmit_enqueue_delay(500.0, 1, 20, 120), Duration::from_millis(120));
+    assert_eq!(resubmit_enqueue_delay(5000.0, 1, 20, 120), Duration::from_millis(20));
+
+    // Only the first attempt waits; chained attempts keep their own timing
+    assert_eq!(resubmit_enqueue_delay(500.0, 2, 20, 120), Duration::ZERO);
+
+    // Unset (default): immediate, as before
+    assert_eq!(resubmit_enqueue_delay(500.0, 1, *RESUBMIT_INITIAL_DELAY_MS, *RESUBMIT_INITIAL_DELAY_SMALL_MS), Duration::ZERO);
+}
+
+// =========================================================================
+// Per-tier resubmit schedule
+// =========================================================================
+
+/// Walk a schedule from the original limit: (delay, price) for each attempt
+fn walk_schedule(schedule: &[ResubmitStep], limit: f64, tick: f64) -> Vec<(Duration, f64)> {
+    let mut price = limit;
+    (1..=schedule.len() as u8)
+        .map(|attempt| {
+            let step = schedule_step(schedule, attempt);
+            price = step.next_price_with(crate::engine::TickRounding::Nearest, price, true, tick);
+            (step.delay(), price)
+        })
+        .collect()
+}
+
+#[test]
+fn test_each_attempt_uses_scheduled_delay_and_increment() {
+    // Attempt 1 immediate no-chase, attempt 2 after 100ms chasing 0.01, attempt 3 after 300ms flat
+    let schedule = [
+        ResubmitStep { delay_ms: 0, increment: 0.0 },
+        ResubmitStep { delay_ms: 100, increment: 0.01 },
+        ResubmitStep { delay_ms: 300, increment: 0.0 },
+    ];
+    assert_eq!(
+        walk_schedule(&schedule, 0.50, 0.01),
+        vec![(Duration::ZERO, 0.50), (Duration::from_millis(100), 0.51), (Duration::from_millis(300), 0.51)]
+    );
+    // The increment is one tick on a 0.001 market, and 0.02 is two ticks
+    assert_eq!(walk_schedule(&schedule, 0.500, 0.001)[1].1, 0.501);
+    let double = [ResubmitStep { delay_ms: 0, increment: 0.02 }];
+    assert_eq!(walk_schedule(&double, 0.50, 0.01)[0].1, 0.52);
+    // Attempts past the end repeat the last step
+    assert_eq!(schedule_step(&schedule, 7), schedule[2]);
+
+    // Built-in tiers: 4000+ chases attempt 1 only, 2000 and 1000 retry flat right away,
+    // below all tiers retries flat 50ms apart
+    assert_eq!(
+        walk_schedule(resubmit_schedule(5000.0), 0.50, 0.01).iter().map(|s| s.1).collect::<Vec<_>>(),
+        vec![0.51; 5]
+    );
+    assert!(walk_schedule(resubmit_schedule(2500.0), 0.50, 0.01).iter().all(|s| *s == (Duration::ZERO, 0.50)));
+    assert_eq!(
+        walk_schedule(resubmit_schedule(500.0), 0.50, 0.01).iter().map(|s| s.0.as_millis()).collect::<Vec<_>>(),
+        vec![0, 50, 50, 50]
+    );
+    assert_eq!(resubmit_step(5000.0, 1), ResubmitStep { delay_ms: 0, increment: 0.01 });
+    assert_eq!(get_max_resubmit_attempts(5000.0), resubmit_schedule(5000.0).len() as u8);
+}
+
+// =========================================================================
+// Chain deadline
+// =========================================================================
+
+#[test]
+fn test_chain_aborts_after_deadline_with_attempts_left() {
+    let started = Instant::now();
+    let mut req = should_resubmit_fak_failure(100.0, 0.51, 5000.0, 0.50, "token");
+    req.chain_started = started;
+    req.attempt = 2;
+    let max_attempts = get_max_resubmit_attempts(req.whale_shares);
+    assert!(req.attempt < max_attempts);
+
+    // Live markets get the tighter limit
+    let deadline = resubmit_deadline(true, 5_000, 800);
+    assert_eq!(deadline, Some(Duration::from_millis(800)));
+    assert!(!req.past_deadline(started + Duration::from_millis(500), deadline));
+    assert!(req.past_deadline(started + Duration::from_millis(900), deadline));
+
+    // Non-live falls back to the general limit; unset means no deadline
+    assert_eq!(resubmit_deadline(false, 5_000, 800), Some(Duration::from_millis(5_000)));
+    assert_eq!(resubmit_deadline(true, 5_000, 0), Some(Duration::from_millis(5_000)));
+    assert_eq!(resubmit_deadline(true, 0, 0), None);
+    assert!(!req.past_deadline(started + Duration::from_secs(3600), None));
+}
+
+// =========================================================================
+// Partial-fill stop threshold
+// =========================================================================
+
+/// Run FAK partial fills through the chain's done check; returns attempts used and total filled
+fn run_partial_chain(rule: &ResubmitDoneRule, original: f64, price: f64, fills: &[f64]) -> (usize, f64) {
+    let (mut remaining, mut total) = (original, 0.0);
+    for (i, filled) in fills.iter().enumerate() {
+        total += filled;
+        remaining -= filled;
+        if rule.is_done(total, original, remaining, price) {
+            return (i + 1, total);
+        }
+    }
+    (fills.len(), total)
+}
+
+#[test]
+fn test_chain_stops_at_configured_fill_ratio() {
+    let fills = [60.0, 30.0, 6.0, 3.5];
+
+    // 95%: 60% and 90% keep going, 96% stops
+    assert_eq!(run_partial_chain(&ResubmitDoneRule::new(95.0, 0.0), 100.0, 0.50, &fills), (3, 96.0));
+    // 99% continues below it, through the last fill
+    assert_eq!(run_partial_chain(&ResubmitDoneRule::new(99.0, 0.0), 100.0, 0.50, &fills), (4, 99.5));
+
+    // Cash: 4 shares left at 0.50 is $2 (continue), 0.5 left is $0.25 (stop)
+    let cash = ResubmitDoneRule::new(0.0, MIN_CASH_VALUE);
+    assert!(!cash.is_done(96.0, 100.0, 4.0, 0.50));
+    assert!(cash.is_done(99.5, 100.0, 0.5, 0.50));
+
+    // Unset keeps the 1-share rule
+    let legacy = ResubmitDoneRule::default();
+    assert_eq!(legacy, ResubmitDoneRule::new(0.0, 0.0));
+    assert!(!legacy.is_done(98.5, 100.0, 1.5, 0.50));
+    assert!(legacy.is_done(99.0, 100.0, 1.0, 0.50));
+}
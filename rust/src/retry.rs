@@ -0,0 +1,56 @@
+//! Best-effort retry for non-critical fetches (post-trade book logging)
+//! Never used on the order path: order submission must not be retried
+
+use std::future::Future;
+use std::time::Duration;
+
+/// Run `attempt` up to `1 + retries` times, sleeping `backoff * n` before the n-th retry.
+/// Returns the first `Some`, or `None` once every attempt has failed.
+pub async fn retry_with_backoff<T, F, Fut>(retries: u32, backoff: Duration, mut attempt: F) -> Option<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Option<T>>,
+{
+    for n in 0..=retries {
+        if n > 0 {
+            tokio::time::sleep(backoff * n).await;
+        }
+        if let Some(v) = attempt().await {
+            return Some(v);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Fails the first `fail_first` calls, then succeeds
+    async fn flaky(calls: &AtomicU32, fail_first: u32) -> Option<&'static str> {
+        let n = calls.fetch_add(1, Ordering::SeqCst);
+        (n >= fail_first).then_some("book")
+    }
+
+    #[tokio::test]
+    async fn test_retry_then_succeed() {
+        let calls = AtomicU32::new(0);
+        let got = retry_with_backoff(2, Duration::from_millis(1), || flaky(&calls, 2)).await;
+        assert_eq!(got, Some("book"));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_then_give_up() {
+        let calls = AtomicU32::new(0);
+        let got = retry_with_backoff(2, Duration::from_millis(1), || flaky(&calls, u32::MAX)).await;
+        assert_eq!(got, None);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // No retries configured: a single attempt
+        let calls = AtomicU32::new(0);
+        assert_eq!(retry_with_backoff(0, Duration::from_millis(1), || flaky(&calls, 1)).await, None);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
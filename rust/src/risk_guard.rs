@@ -1,9 +1,9 @@
-/// Risk management and safety guard for trade execution
-/// Provides protection against dangerous market conditions
-#[allow(dead_code)]
+//! Risk management and safety guard for trade execution
+//! Provides protection against dangerous market conditions
 
 use rustc_hash::FxHashMap;
-use std::time::{Duration, Instant};
+use serde::Serialize;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 // =============================================================================
 // Type Definitions
@@ -54,6 +54,83 @@ pub struct SafetyEvaluation {
     pub consecutive_large: u8,
 }
 
+/// Point-in-time view of guard state for the status endpoint
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RiskSnapshot {
+    pub daily_notional_usd: f64,
+    pub daily_notional_cap_usd: f64,
+    /// BUYs are halted for the rest of the UTC day: the notional cap is used up
+    pub buys_halted: bool,
+    pub tracked_tokens: usize,
+    pub tripped_tokens: Vec<SnapshotEntry>,
+    /// Large whale trades per token inside the sequence window (the breaker checks the book
+    /// at consecutive_trigger)
+    pub consecutive_large: Vec<CountEntry>,
+    /// Shares held per token (see with_exposure; the guard itself doesn't track positions)
+    pub token_exposure: Vec<ExposureEntry>,
+    pub whale_cooldowns: Vec<SnapshotEntry>,
+    /// Orders left in each market's rate bucket
+    pub market_rate_buckets: Vec<RateEntry>,
+    /// Live (whale, token) conviction streaks
+    pub conviction_streaks: Vec<StreakEntry>,
+    /// Open (whale, token) entry windows: further BUYs inside them are adds
+    pub entry_windows: Vec<WindowEntry>,
+}
+
+/// A token or whale with the seconds left on its block
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SnapshotEntry {
+    pub key: String,
+    pub secs_left: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CountEntry {
+    pub key: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExposureEntry {
+    pub key: String,
+    pub shares: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RateEntry {
+    pub key: String,
+    pub orders_left: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StreakEntry {
+    pub whale: String,
+    pub token_id: String,
+    pub count: u32,
+    /// Seconds until the streak resets without another BUY
+    pub secs_left: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WindowEntry {
+    pub whale: String,
+    pub token_id: String,
+    pub secs_left: u64,
+}
+
+impl RiskSnapshot {
+    /// Fill in per-token exposure from the tracked positions (nonzero holdings, sorted by token)
+    pub fn with_exposure(mut self, shares: &FxHashMap<String, f64>) -> Self {
+        self.token_exposure = shares
+            .iter()
+            .filter(|(_, s)| **s != 0.0)
+            .map(|(token_id, s)| ExposureEntry { key: token_id.clone(), shares: *s })
+            .collect();
+        self.token_exposure.sort_by(|a, b| a.key.cmp(&b.key));
+        self
+    }
+}
+
 // =============================================================================
 // Config
 // =============================================================================
@@ -65,6 +142,23 @@ pub struct RiskGuardConfig {
     pub sequence_window: Duration,
     pub min_depth_beyond_usd: f64,
     pub trip_duration: Duration,
+    /// Max gross USD submitted per UTC day for BUYs (0 = disabled)
+    pub daily_notional_cap_usd: f64,
+    /// Min time between copies of the same whale, across all tokens (zero = disabled)
+    pub whale_cooldown: Duration,
+    /// Extra size multiplier per consecutive same-whale/same-token BUY fill (0 = disabled)
+    pub conviction_step: f64,
+    /// Ceiling on the conviction multiplier
+    pub conviction_max_multiplier: f64,
+    /// A streak resets after this long without another BUY from the whale on the token
+    pub conviction_window: Duration,
+    /// Per-token order bucket size: orders allowed back to back on one market (0 = disabled)
+    pub market_rate_burst: u32,
+    /// Per-token bucket refill, in orders per minute
+    pub market_rate_per_min: f64,
+    /// Only a whale's first BUY on a token within this window is an entry; later BUYs are adds
+    /// and skipped (zero = disabled)
+    pub whale_entry_window: Duration,
 }
 
 impl Default for RiskGuardConfig {
@@ -75,6 +169,14 @@ impl Default for RiskGuardConfig {
             sequence_window: Duration::from_secs(40),
             min_depth_beyond_usd: 200.0,
             trip_duration: Duration::from_secs(60 * 60 * 5), // 5 hours
+            daily_notional_cap_usd: 0.0,
+            whale_cooldown: Duration::ZERO,
+            conviction_step: 0.0,
+            conviction_max_multiplier: 2.0,
+            conviction_window: Duration::from_secs(300),
+            market_rate_burst: 0,
+            market_rate_per_min: 3.0,
+            whale_entry_window: Duration::ZERO,
         }
     }
 }
@@ -103,6 +205,58 @@ impl Default for TokenState {
     }
 }
 
+/// Gross notional submitted during the current UTC day
+#[derive(Default)]
+struct DailyNotional {
+    day: u64,
+    total_usd: f64,
+}
+
+impl DailyNotional {
+    /// Roll over to a fresh total when the UTC day changes
+    #[inline]
+    fn roll(&mut self, now_unix_secs: u64) {
+        let day = now_unix_secs / 86_400;
+        if day != self.day {
+            self.day = day;
+            self.total_usd = 0.0;
+        }
+    }
+}
+
+/// Max (whale, token) streaks kept before expired ones are pruned
+const MAX_CONVICTION_STREAKS: usize = 1024;
+
+/// Consecutive BUY fills by one whale on one token
+#[derive(Clone, Copy)]
+struct BuyStreak {
+    count: u32,
+    last: Instant,
+}
+
+/// Max (whale, token) first-entry times kept before expired ones are pruned
+const MAX_WHALE_ENTRIES: usize = 4096;
+
+/// Max per-token order buckets kept before full ones are pruned
+const MAX_RATE_BUCKETS: usize = 1024;
+
+/// Token bucket for one market's order rate
+#[derive(Clone, Copy)]
+struct RateBucket {
+    tokens: f64,
+    last: Instant,
+}
+
+impl RateBucket {
+    /// Add what accrued since the last refill, up to `capacity`
+    #[inline]
+    fn refill(&mut self, now: Instant, capacity: f64, per_min: f64) {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * per_min / 60.0).min(capacity);
+        self.last = now;
+    }
+}
+
 // =============================================================================
 // Circuit Breaker
 // =============================================================================
@@ -110,6 +264,12 @@ impl Default for TokenState {
 pub struct RiskGuard {
     config: RiskGuardConfig,
     tokens: FxHashMap<String, TokenState>,
+    daily: DailyNotional,
+    whale_last_copy: FxHashMap<String, Instant>,
+    buy_streaks: FxHashMap<(String, String), BuyStreak>,
+    rate_buckets: FxHashMap<String, RateBucket>,
+    /// First BUY seen per (whale, token) in the current entry window
+    whale_entries: FxHashMap<(String, String), Instant>,
 }
 
 impl RiskGuard {
@@ -117,6 +277,11 @@ impl RiskGuard {
         Self {
             config,
             tokens: FxHashMap::default(),
+            daily: DailyNotional::default(),
+            whale_last_copy: FxHashMap::default(),
+            buy_streaks: FxHashMap::default(),
+            rate_buckets: FxHashMap::default(),
+            whale_entries: FxHashMap::default(),
         }
     }
     
@@ -126,7 +291,7 @@ impl RiskGuard {
         let now = Instant::now();
         
         // Use entry API - single lookup instead of get_mut + insert + get_mut
-        let state = self.tokens.entry(token_id.to_string()).or_insert_with(TokenState::new);
+        let state = self.tokens.entry(token_id.to_string()).or_default();
         
         // Check trip
         if let Some(until) = state.tripped_until {
@@ -219,11 +384,296 @@ impl RiskGuard {
         }
     }
     
+    /// Returns false if this BUY would push today's gross notional past the cap.
+    /// SELLs are always allowed (exiting reduces exposure).
+    #[inline]
+    pub fn daily_notional_allows(&mut self, side: TradeSide, notional_usd: f64) -> bool {
+        self.daily_notional_allows_at(side, notional_usd, unix_now_secs())
+    }
+
+    pub fn daily_notional_allows_at(&mut self, side: TradeSide, notional_usd: f64, now_unix_secs: u64) -> bool {
+        if side == TradeSide::Sell || self.config.daily_notional_cap_usd <= 0.0 {
+            return true;
+        }
+        self.daily.roll(now_unix_secs);
+        self.daily.total_usd + notional_usd <= self.config.daily_notional_cap_usd
+    }
+
+    /// Record notional for a submitted order against today's total. Only BUYs count,
+    /// matching what the cap gates
+    #[inline]
+    pub fn record_notional(&mut self, side: TradeSide, notional_usd: f64) {
+        self.record_notional_at(side, notional_usd, unix_now_secs());
+    }
+
+    pub fn record_notional_at(&mut self, side: TradeSide, notional_usd: f64, now_unix_secs: u64) {
+        if side == TradeSide::Sell {
+            return;
+        }
+        self.daily.roll(now_unix_secs);
+        self.daily.total_usd += notional_usd;
+    }
+
+    /// Give back notional counted for a resting order that expired unfilled. Only orders
+    /// placed today count; earlier days' totals are already gone
+    pub fn release_notional_at(&mut self, notional_usd: f64, placed_unix_secs: u64, now_unix_secs: u64) {
+        self.daily.roll(now_unix_secs);
+        if placed_unix_secs / 86_400 == self.daily.day {
+            self.daily.total_usd = (self.daily.total_usd - notional_usd).max(0.0);
+        }
+    }
+
+    /// Gross BUY notional submitted so far today
+    #[inline]
+    pub fn daily_notional(&self) -> f64 {
+        self.daily.total_usd
+    }
+
+    /// Returns true if this whale was copied within the cooldown window
+    #[inline]
+    pub fn whale_on_cooldown(&self, whale: &str) -> bool {
+        self.whale_on_cooldown_at(whale, Instant::now())
+    }
+
+    pub fn whale_on_cooldown_at(&self, whale: &str, now: Instant) -> bool {
+        if self.config.whale_cooldown.is_zero() {
+            return false;
+        }
+        self.whale_last_copy
+            .get(whale)
+            .is_some_and(|last| now.saturating_duration_since(*last) < self.config.whale_cooldown)
+    }
+
+    /// Start the whale's cooldown window (call once an order is actually submitted)
+    #[inline]
+    pub fn record_whale_copy(&mut self, whale: &str) {
+        self.record_whale_copy_at(whale, Instant::now());
+    }
+
+    pub fn record_whale_copy_at(&mut self, whale: &str, now: Instant) {
+        if self.config.whale_cooldown.is_zero() {
+            return;
+        }
+        match self.whale_last_copy.get_mut(whale) {
+            Some(last) => *last = now,
+            None => { self.whale_last_copy.insert(whale.to_string(), now); }
+        }
+    }
+
+    /// True if the token's order bucket has an order left (always true when disabled).
+    /// Distinct from the whale cooldown: this limits churn on one market, whoever trades it
+    #[inline]
+    pub fn market_rate_allows(&mut self, token_id: &str) -> bool {
+        self.market_rate_allows_at(token_id, Instant::now())
+    }
+
+    pub fn market_rate_allows_at(&mut self, token_id: &str, now: Instant) -> bool {
+        if self.config.market_rate_burst == 0 {
+            return true;
+        }
+        let (capacity, per_min) = (self.config.market_rate_burst as f64, self.config.market_rate_per_min);
+        match self.rate_buckets.get_mut(token_id) {
+            Some(bucket) => {
+                bucket.refill(now, capacity, per_min);
+                bucket.tokens >= 1.0
+            }
+            None => true,
+        }
+    }
+
+    /// Take one order from the token's bucket (call once an order is submitted)
+    #[inline]
+    pub fn record_market_order(&mut self, token_id: &str) {
+        self.record_market_order_at(token_id, Instant::now());
+    }
+
+    pub fn record_market_order_at(&mut self, token_id: &str, now: Instant) {
+        if self.config.market_rate_burst == 0 {
+            return;
+        }
+        let (capacity, per_min) = (self.config.market_rate_burst as f64, self.config.market_rate_per_min);
+        if self.rate_buckets.len() >= MAX_RATE_BUCKETS && !self.rate_buckets.contains_key(token_id) {
+            self.rate_buckets.retain(|_, b| {
+                b.refill(now, capacity, per_min);
+                b.tokens < capacity
+            });
+        }
+        let bucket = self
+            .rate_buckets
+            .entry(token_id.to_string())
+            .or_insert(RateBucket { tokens: capacity, last: now });
+        bucket.refill(now, capacity, per_min);
+        bucket.tokens = (bucket.tokens - 1.0).max(0.0);
+    }
+
+    /// Record a whale BUY and return true if it's an add: the whale already bought the token
+    /// within the entry window. The window runs from the first BUY, so adds don't extend it.
+    /// SELLs and a disabled window are never adds
+    #[inline]
+    pub fn observe_whale_entry(&mut self, whale: &str, token_id: &str, is_buy: bool) -> bool {
+        self.observe_whale_entry_at(whale, token_id, is_buy, Instant::now())
+    }
+
+    pub fn observe_whale_entry_at(&mut self, whale: &str, token_id: &str, is_buy: bool, now: Instant) -> bool {
+        let window = self.config.whale_entry_window;
+        if window.is_zero() || !is_buy {
+            return false;
+        }
+        let key = (whale.to_string(), token_id.to_string());
+        if let Some(first) = self.whale_entries.get_mut(&key) {
+            if now.saturating_duration_since(*first) < window {
+                return true;
+            }
+            *first = now;
+            return false;
+        }
+        if self.whale_entries.len() >= MAX_WHALE_ENTRIES {
+            self.whale_entries.retain(|_, first| now.saturating_duration_since(*first) < window);
+        }
+        self.whale_entries.insert(key, now);
+        false
+    }
+
+    /// Record a whale fill and return the conviction size multiplier for it.
+    /// BUYs extend the (whale, token) streak, a SELL ends it; 1.0 when disabled.
+    #[inline]
+    pub fn observe_conviction(&mut self, whale: &str, token_id: &str, is_buy: bool) -> f64 {
+        self.observe_conviction_at(whale, token_id, is_buy, Instant::now())
+    }
+
+    pub fn observe_conviction_at(&mut self, whale: &str, token_id: &str, is_buy: bool, now: Instant) -> f64 {
+        if self.config.conviction_step <= 0.0 {
+            return 1.0;
+        }
+        let key = (whale.to_string(), token_id.to_string());
+        if !is_buy {
+            self.buy_streaks.remove(&key);
+            return 1.0;
+        }
+
+        let window = self.config.conviction_window;
+        if self.buy_streaks.len() >= MAX_CONVICTION_STREAKS {
+            self.buy_streaks.retain(|_, s| now.saturating_duration_since(s.last) < window);
+        }
+        let streak = self.buy_streaks.entry(key).or_insert(BuyStreak { count: 0, last: now });
+        // Decay: a quiet period starts a fresh streak
+        if now.saturating_duration_since(streak.last) >= window {
+            streak.count = 0;
+        }
+        streak.count += 1;
+        streak.last = now;
+
+        conviction_multiplier(streak.count, self.config.conviction_step, self.config.conviction_max_multiplier)
+    }
+
     pub fn trip(&mut self, token_id: &str) {
         if let Some(state) = self.tokens.get_mut(token_id) {
             state.tripped_until = Some(Instant::now() + self.config.trip_duration);
         }
     }
+
+    /// Snapshot of current guard state (entries sorted by key)
+    pub fn snapshot(&self) -> RiskSnapshot {
+        self.snapshot_at(Instant::now())
+    }
+
+    pub fn snapshot_at(&self, now: Instant) -> RiskSnapshot {
+        let mut tripped_tokens: Vec<SnapshotEntry> = self
+            .tokens
+            .iter()
+            .filter_map(|(token_id, state)| {
+                let until = state.tripped_until?;
+                (now < until).then(|| SnapshotEntry {
+                    key: token_id.clone(),
+                    secs_left: (until - now).as_secs(),
+                })
+            })
+            .collect();
+        tripped_tokens.sort_by(|a, b| a.key.cmp(&b.key));
+
+        let cooldown = self.config.whale_cooldown;
+        let mut whale_cooldowns: Vec<SnapshotEntry> = self
+            .whale_last_copy
+            .iter()
+            .filter_map(|(whale, last)| {
+                let elapsed = now.saturating_duration_since(*last);
+                (elapsed < cooldown).then(|| SnapshotEntry {
+                    key: whale.clone(),
+                    secs_left: (cooldown - elapsed).as_secs(),
+                })
+            })
+            .collect();
+        whale_cooldowns.sort_by(|a, b| a.key.cmp(&b.key));
+
+        let mut consecutive_large: Vec<CountEntry> = self
+            .tokens
+            .iter()
+            .filter_map(|(token_id, state)| {
+                let count = Self::count_large_in_window(state, now, self.config.sequence_window, self.config.large_trade_shares);
+                (count > 0).then(|| CountEntry { key: token_id.clone(), count })
+            })
+            .collect();
+        consecutive_large.sort_by(|a, b| a.key.cmp(&b.key));
+
+        // Refilled to `now` on a copy, so reading doesn't move the buckets
+        let (capacity, per_min) = (self.config.market_rate_burst as f64, self.config.market_rate_per_min);
+        let mut market_rate_buckets: Vec<RateEntry> = self
+            .rate_buckets
+            .iter()
+            .map(|(token_id, bucket)| {
+                let mut bucket = *bucket;
+                bucket.refill(now, capacity, per_min);
+                RateEntry { key: token_id.clone(), orders_left: bucket.tokens }
+            })
+            .collect();
+        market_rate_buckets.sort_by(|a, b| a.key.cmp(&b.key));
+
+        let conviction_window = self.config.conviction_window;
+        let mut conviction_streaks: Vec<StreakEntry> = self
+            .buy_streaks
+            .iter()
+            .filter_map(|((whale, token_id), streak)| {
+                let elapsed = now.saturating_duration_since(streak.last);
+                (elapsed < conviction_window).then(|| StreakEntry {
+                    whale: whale.clone(),
+                    token_id: token_id.clone(),
+                    count: streak.count,
+                    secs_left: (conviction_window - elapsed).as_secs(),
+                })
+            })
+            .collect();
+        conviction_streaks.sort_by(|a, b| (&a.whale, &a.token_id).cmp(&(&b.whale, &b.token_id)));
+
+        let entry_window = self.config.whale_entry_window;
+        let mut entry_windows: Vec<WindowEntry> = self
+            .whale_entries
+            .iter()
+            .filter_map(|((whale, token_id), first)| {
+                let elapsed = now.saturating_duration_since(*first);
+                (elapsed < entry_window).then(|| WindowEntry {
+                    whale: whale.clone(),
+                    token_id: token_id.clone(),
+                    secs_left: (entry_window - elapsed).as_secs(),
+                })
+            })
+            .collect();
+        entry_windows.sort_by(|a, b| (&a.whale, &a.token_id).cmp(&(&b.whale, &b.token_id)));
+
+        let cap = self.config.daily_notional_cap_usd;
+        RiskSnapshot {
+            daily_notional_usd: self.daily.total_usd,
+            daily_notional_cap_usd: cap,
+            buys_halted: cap > 0.0 && self.daily.total_usd >= cap,
+            tracked_tokens: self.tokens.len(),
+            tripped_tokens,
+            consecutive_large,
+            token_exposure: Vec::new(),
+            whale_cooldowns,
+            market_rate_buckets,
+            conviction_streaks,
+            entry_windows,
+        }
+    }
     
     #[inline]
     fn count_large_in_window(
@@ -242,6 +692,17 @@ impl RiskGuard {
     }
 }
 
+/// 1.0 for the first fill of a streak, + `step` for each consecutive one, capped at `max`
+#[inline]
+pub fn conviction_multiplier(streak: u32, step: f64, max: f64) -> f64 {
+    (1.0 + step * streak.saturating_sub(1) as f64).min(max.max(1.0))
+}
+
+#[inline]
+fn unix_now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
 // =============================================================================
 // Book depth - separate from hot path
 // =============================================================================
@@ -268,10 +729,189 @@ pub fn calc_liquidity_depth(side: TradeSide, levels: &[(f64, f64)], threshold: f
     total
 }
 
+/// Shares a taker could fill at `limit` or better (buy: asks <= limit, sell: bids >= limit)
+#[inline]
+pub fn fillable_shares(side: TradeSide, levels: &[(f64, f64)], limit: f64) -> f64 {
+    levels
+        .iter()
+        .filter(|(price, _)| if side == TradeSide::Buy { *price <= limit + 1e-9 } else { *price >= limit - 1e-9 })
+        .map(|(_, size)| size)
+        .sum()
+}
+
+/// fillable_shares over a CLOB /book JSON response (every level: the API doesn't list best first on both sides)
+pub fn fillable_shares_from_json(book: &serde_json::Value, side: TradeSide, limit: f64) -> f64 {
+    let key = if side == TradeSide::Buy { "asks" } else { "bids" };
+    let Some(arr) = book[key].as_array() else { return 0.0 };
+    let levels: Vec<(f64, f64)> = arr
+        .iter()
+        .filter_map(|lvl| Some((lvl["price"].as_str()?.parse().ok()?, lvl["size"].as_str()?.parse().ok()?)))
+        .collect();
+    fillable_shares(side, &levels, limit)
+}
+
+/// Lowest ask in a CLOB /book JSON response (None if the ask side is empty)
+pub fn best_ask_from_json(book: &serde_json::Value) -> Option<f64> {
+    book["asks"]
+        .as_array()?
+        .iter()
+        .filter_map(|lvl| lvl["price"].as_str()?.parse::<f64>().ok())
+        .min_by(|a, b| a.total_cmp(b))
+}
+
+/// Best and second-best entries among `levels` (asks when `is_buy`, else bids), by price
+pub fn top_two_levels(levels: &[serde_json::Value], is_buy: bool) -> (Option<&serde_json::Value>, Option<&serde_json::Value>) {
+    type Level<'a> = Option<(&'a serde_json::Value, f64)>;
+    let better = |candidate: f64, current: f64| if is_buy { candidate < current } else { candidate > current };
+    let (best, second): (Level, Level) = levels.iter().fold((None, None), |(best, second), entry| {
+        let Some(price) = entry["price"].as_str().and_then(|s| s.parse::<f64>().ok()) else {
+            return (best, second);
+        };
+        match best {
+            Some((_, bp)) if better(price, bp) => (Some((entry, price)), best),
+            Some(_) => match second {
+                Some((_, sp)) if !better(price, sp) => (best, second),
+                _ => (best, Some((entry, price))),
+            },
+            None => (Some((entry, price)), second),
+        }
+    });
+    (best.map(|(e, _)| e), second.map(|(e, _)| e))
+}
+
+/// A book level as (price, size)
+pub type BookLevel = (f64, f64);
+
+/// Best and second-best levels a `side` order takes from, in a CLOB /book JSON response
+/// (asks for buys, bids for sells)
+pub fn top_two_from_json(book: &serde_json::Value, side: TradeSide) -> (Option<BookLevel>, Option<BookLevel>) {
+    let key = if side == TradeSide::Buy { "asks" } else { "bids" };
+    let Some(levels) = book[key].as_array() else { return (None, None) };
+    let level = |e: &serde_json::Value| Some((e["price"].as_str()?.parse::<f64>().ok()?, e["size"].as_str()?.parse::<f64>().ok()?));
+    let (best, second) = top_two_levels(levels, side == TradeSide::Buy);
+    (best.and_then(level), second.and_then(level))
+}
+
+/// Levels that fit the stack buffer; deeper configs fall back to a heap Vec
+pub const STACK_BOOK_LEVELS: usize = 10;
+/// Upper bound on configured book levels
+pub const MAX_BOOK_LEVELS: usize = 100;
+
+/// Depth beyond `threshold` from a CLOB /book JSON response, reading at most `max_levels` levels
+#[inline]
+pub fn book_depth_from_json(book: &serde_json::Value, side: TradeSide, threshold: f64, max_levels: usize) -> f64 {
+    book_depth_excluding_own(book, side, threshold, max_levels, &[])
+}
+
+/// Like book_depth_from_json, but first subtracts our own resting `(price, size)` orders
+/// from the level at the same price, so our liquidity isn't counted as someone else's
+pub fn book_depth_excluding_own(
+    book: &serde_json::Value,
+    side: TradeSide,
+    threshold: f64,
+    max_levels: usize,
+    own: &[(f64, f64)],
+) -> f64 {
+    let key = if side == TradeSide::Buy { "asks" } else { "bids" };
+    let Some(arr) = book[key].as_array() else { return 0.0 };
+    let max_levels = max_levels.min(MAX_BOOK_LEVELS);
+    let parsed = arr.iter().take(max_levels).filter_map(|lvl| {
+        Some((
+            lvl["price"].as_str()?.parse::<f64>().ok()?,
+            lvl["size"].as_str()?.parse::<f64>().ok()?,
+        ))
+    });
+
+    if max_levels <= STACK_BOOK_LEVELS {
+        // Stack array instead of Vec - avoids heap allocation in the common case
+        let mut levels: [(f64, f64); STACK_BOOK_LEVELS] = [(0.0, 0.0); STACK_BOOK_LEVELS];
+        let mut count = 0;
+        for lvl in parsed {
+            levels[count] = lvl;
+            count += 1;
+        }
+        subtract_own_levels(&mut levels[..count], own);
+        calc_liquidity_depth(side, &levels[..count], threshold)
+    } else {
+        let mut levels: Vec<(f64, f64)> = parsed.collect();
+        subtract_own_levels(&mut levels, own);
+        calc_liquidity_depth(side, &levels, threshold)
+    }
+}
+
+/// Whether a /book snapshot is recent enough to trust its depth
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookFreshness {
+    Fresh,
+    /// Older than the limit (age in ms)
+    Stale(u64),
+    /// No usable timestamp, or the check is disabled: depth is used as-is
+    Unknown,
+}
+
+/// The book's `timestamp` in unix ms (string or number; values below 1e12 are taken as seconds)
+pub fn book_timestamp_ms(book: &serde_json::Value) -> Option<u64> {
+    let ts = match &book["timestamp"] {
+        serde_json::Value::String(s) => s.trim().parse::<u64>().ok()?,
+        serde_json::Value::Number(n) => n.as_u64()?,
+        _ => return None,
+    };
+    match ts {
+        0 => None,
+        t if t < 1_000_000_000_000 => Some(t * 1000),
+        t => Some(t),
+    }
+}
+
+/// Compare the book's timestamp with `now_ms`. `max_age_ms` 0 disables the check.
+/// A timestamp ahead of our clock counts as fresh
+pub fn book_freshness(book: &serde_json::Value, now_ms: u64, max_age_ms: u64) -> BookFreshness {
+    if max_age_ms == 0 {
+        return BookFreshness::Unknown;
+    }
+    match book_timestamp_ms(book) {
+        Some(ts) => {
+            let age = now_ms.saturating_sub(ts);
+            if age > max_age_ms { BookFreshness::Stale(age) } else { BookFreshness::Fresh }
+        }
+        None => BookFreshness::Unknown,
+    }
+}
+
+/// Remove our own size from book levels at matching prices (never below zero)
+#[inline]
+pub fn subtract_own_levels(levels: &mut [(f64, f64)], own: &[(f64, f64)]) {
+    for &(own_price, own_size) in own {
+        // Book prices are tick-aligned strings; compare with a tolerance well under a tick
+        if let Some(level) = levels.iter_mut().find(|(p, _)| (*p - own_price).abs() < 1e-6) {
+            level.1 = (level.1 - own_size).max(0.0);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_book_freshness() {
+        let now = 1_700_000_010_000;
+        let book = serde_json::json!({ "timestamp": "1700000009900", "asks": [] });
+        assert_eq!(book_freshness(&book, now, 250), BookFreshness::Fresh);
+        assert_eq!(book_freshness(&book, now + 400, 250), BookFreshness::Stale(500));
+
+        // Seconds and numeric timestamps are normalized to ms
+        let secs = serde_json::json!({ "timestamp": 1_700_000_009u64 });
+        assert_eq!(book_freshness(&secs, now, 250), BookFreshness::Stale(1000));
+
+        // No timestamp, or the check disabled: no-op
+        assert_eq!(book_freshness(&serde_json::json!({ "asks": [] }), now, 250), BookFreshness::Unknown);
+        assert_eq!(book_freshness(&book, now + 400, 0), BookFreshness::Unknown);
+
+        // Ahead of our clock: trusted
+        assert_eq!(book_freshness(&book, now - 5_000, 250), BookFreshness::Fresh);
+    }
+
     #[test]
     fn test_small_trade_allows() {
         let mut guard = RiskGuard::new(RiskGuardConfig::default());
@@ -281,67 +921,399 @@ mod tests {
 
     #[test]
     fn test_single_large_allows() {
-        let mut cb = CircuitBreaker::new(CircuitBreakerConfig::default());
-        let eval = cb.check_fast("token1", 2000.0);
-        assert_eq!(eval.decision, Decision::Allow);
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+        let eval = guard.check_fast("token1", 2000.0);
+        assert_eq!(eval.decision, SafetyDecision::Allow);
         assert_eq!(eval.consecutive_large, 1);
     }
 
     #[test]
     fn test_two_large_triggers_fetch() {
-        let mut cb = CircuitBreaker::new(CircuitBreakerConfig::default());
-        cb.check_fast("token1", 2000.0);  // First
-        cb.check_fast("token1", 2000.0);
-        cb.check_fast("token1", 2000.0);
-        cb.check_fast("token1", 2000.0);
-        let eval = cb.check_fast("token1", 2000.0);  // Second
-        assert_eq!(eval.decision, Decision::FetchBook);
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+        guard.check_fast("token1", 2000.0);  // First
+        guard.check_fast("token1", 2000.0);
+        guard.check_fast("token1", 2000.0);
+        guard.check_fast("token1", 2000.0);
+        let eval = guard.check_fast("token1", 2000.0);  // Second
+        assert_eq!(eval.decision, SafetyDecision::FetchBook);
         assert_eq!(eval.consecutive_large, 5);
     }
 
     #[test]
     fn test_thin_book_blocks() {
-        let mut cb = CircuitBreaker::new(CircuitBreakerConfig::default());
-        let eval = cb.check_with_book("token1", 2, 50.0);  // $50 < $200 threshold
-        assert_eq!(eval.decision, Decision::Block);
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+        let eval = guard.check_with_book("token1", 2, 50.0);  // $50 < $200 threshold
+        assert_eq!(eval.decision, SafetyDecision::Block);
     }
 
     #[test]
     fn test_good_depth_allows() {
-        let mut cb = CircuitBreaker::new(CircuitBreakerConfig::default());
-        let eval = cb.check_with_book("token1", 2, 500.0);  // $500 > $200 threshold
-        assert_eq!(eval.decision, Decision::Allow);
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+        let eval = guard.check_with_book("token1", 2, 500.0);  // $500 > $200 threshold
+        assert_eq!(eval.decision, SafetyDecision::Allow);
     }
 
     #[test]
     fn test_tripped_token_stays_blocked() {
-        let mut cb = CircuitBreaker::new(CircuitBreakerConfig {
+        let mut guard = RiskGuard::new(RiskGuardConfig {
             trip_duration: Duration::from_secs(10),
             ..Default::default()
         });
         
         // Trip it
-        cb.check_with_book("token1", 2, 50.0);
+        guard.check_with_book("token1", 2, 50.0);
         
         // Should still be blocked
-        let eval = cb.check_fast("token1", 100.0);  // Even small trade
-        assert_eq!(eval.decision, Decision::Block);
+        let eval = guard.check_fast("token1", 100.0);  // Even small trade
+        assert_eq!(eval.decision, SafetyDecision::Block);
     }
 
     #[test]
     fn test_different_tokens_independent() {
-        let mut cb = CircuitBreaker::new(CircuitBreakerConfig::default());
-        cb.check_fast("token1", 2000.0);
-        cb.check_fast("token1", 2000.0);
-        cb.check_fast("token1", 2000.0);
-        cb.check_fast("token1", 2000.0);
-        cb.check_fast("token1", 2000.0); 
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+        guard.check_fast("token1", 2000.0);
+        guard.check_fast("token1", 2000.0);
+        guard.check_fast("token1", 2000.0);
+        guard.check_fast("token1", 2000.0);
+        guard.check_fast("token1", 2000.0); 
         
-        let eval = cb.check_fast("token2", 2000.0);  // token2 first large
-        assert_eq!(eval.decision, Decision::Allow);
+        let eval = guard.check_fast("token2", 2000.0);  // token2 first large
+        assert_eq!(eval.decision, SafetyDecision::Allow);
         assert_eq!(eval.consecutive_large, 1);
     }
 
+    #[test]
+    fn test_daily_notional_accumulates() {
+        let mut guard = RiskGuard::new(RiskGuardConfig {
+            daily_notional_cap_usd: 100.0,
+            ..Default::default()
+        });
+        let t = 1_700_000_000;
+        guard.record_notional_at(TradeSide::Buy, 30.0, t);
+        guard.record_notional_at(TradeSide::Buy, 20.0, t + 60);
+        assert!((guard.daily_notional() - 50.0).abs() < 1e-9);
+        // Exits don't use up the cap
+        guard.record_notional_at(TradeSide::Sell, 40.0, t + 90);
+        assert!((guard.daily_notional() - 50.0).abs() < 1e-9);
+        assert!(guard.daily_notional_allows_at(TradeSide::Buy, 50.0, t + 120));
+    }
+
+    #[test]
+    fn test_daily_notional_cap_trips_buys_only() {
+        let mut guard = RiskGuard::new(RiskGuardConfig {
+            daily_notional_cap_usd: 100.0,
+            ..Default::default()
+        });
+        let t = 1_700_000_000;
+        guard.record_notional_at(TradeSide::Buy, 90.0, t);
+        assert!(!guard.daily_notional_allows_at(TradeSide::Buy, 20.0, t + 1));
+        assert!(guard.daily_notional_allows_at(TradeSide::Sell, 20.0, t + 1));
+    }
+
+    #[test]
+    fn test_daily_notional_resets_at_utc_midnight() {
+        let mut guard = RiskGuard::new(RiskGuardConfig {
+            daily_notional_cap_usd: 100.0,
+            ..Default::default()
+        });
+        let midnight = 1_700_006_400; // 2023-11-15 00:00:00 UTC
+        guard.record_notional_at(TradeSide::Buy, 100.0, midnight - 1);
+        assert!(!guard.daily_notional_allows_at(TradeSide::Buy, 1.0, midnight - 1));
+        assert!(guard.daily_notional_allows_at(TradeSide::Buy, 1.0, midnight));
+        assert_eq!(guard.daily_notional(), 0.0);
+    }
+
+    #[test]
+    fn test_release_notional_same_day_only() {
+        let mut guard = RiskGuard::new(RiskGuardConfig {
+            daily_notional_cap_usd: 100.0,
+            ..Default::default()
+        });
+        let midnight = 1_700_006_400;
+        guard.record_notional_at(TradeSide::Buy, 80.0, midnight + 10);
+        guard.release_notional_at(30.0, midnight + 10, midnight + 100);
+        assert!((guard.daily_notional() - 50.0).abs() < 1e-9);
+        // Placed yesterday: today's total is untouched
+        guard.release_notional_at(30.0, midnight - 10, midnight + 100);
+        assert!((guard.daily_notional() - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_daily_notional_disabled_by_default() {
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+        guard.record_notional(TradeSide::Buy, 1_000_000.0);
+        assert!(guard.daily_notional_allows(TradeSide::Buy, 1_000_000.0));
+    }
+
+    #[test]
+    fn test_whale_cooldown_rate_limits_same_whale_only() {
+        let mut guard = RiskGuard::new(RiskGuardConfig {
+            whale_cooldown: Duration::from_secs(5),
+            ..Default::default()
+        });
+        let t0 = Instant::now();
+        assert!(!guard.whale_on_cooldown_at("0xwhale_a", t0));
+        guard.record_whale_copy_at("0xwhale_a", t0);
+
+        // Same whale, different token, same burst -> limited
+        assert!(guard.whale_on_cooldown_at("0xwhale_a", t0 + Duration::from_secs(1)));
+        // Different whale -> not limited
+        assert!(!guard.whale_on_cooldown_at("0xwhale_b", t0 + Duration::from_secs(1)));
+        // Window elapsed -> allowed again
+        assert!(!guard.whale_on_cooldown_at("0xwhale_a", t0 + Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_whale_entry_copied_and_adds_skipped() {
+        let mut guard = RiskGuard::new(RiskGuardConfig {
+            whale_entry_window: Duration::from_secs(600),
+            ..Default::default()
+        });
+        let t0 = Instant::now();
+        // First BUY is the entry; a later BUY in the window is an add
+        assert!(!guard.observe_whale_entry_at("0xa", "tok", true, t0));
+        assert!(guard.observe_whale_entry_at("0xa", "tok", true, t0 + Duration::from_secs(60)));
+        // Adds don't extend the window
+        assert!(guard.observe_whale_entry_at("0xa", "tok", true, t0 + Duration::from_secs(599)));
+        // Other tokens / whales are their own entries; SELLs are never gated
+        assert!(!guard.observe_whale_entry_at("0xa", "other", true, t0 + Duration::from_secs(60)));
+        assert!(!guard.observe_whale_entry_at("0xb", "tok", true, t0 + Duration::from_secs(60)));
+        assert!(!guard.observe_whale_entry_at("0xa", "tok", false, t0 + Duration::from_secs(60)));
+        // After the window the next BUY is a new entry
+        assert!(!guard.observe_whale_entry_at("0xa", "tok", true, t0 + Duration::from_secs(600)));
+        assert!(guard.observe_whale_entry_at("0xa", "tok", true, t0 + Duration::from_secs(601)));
+
+        let mut off = RiskGuard::new(RiskGuardConfig::default());
+        assert!(!off.observe_whale_entry_at("0xa", "tok", true, t0));
+        assert!(!off.observe_whale_entry_at("0xa", "tok", true, t0));
+    }
+
+    #[test]
+    fn test_market_rate_bucket() {
+        // 3 orders back to back, then one every 20s (3/min)
+        let mut guard = RiskGuard::new(RiskGuardConfig {
+            market_rate_burst: 3,
+            market_rate_per_min: 3.0,
+            ..Default::default()
+        });
+        let t0 = Instant::now();
+        for _ in 0..3 {
+            assert!(guard.market_rate_allows_at("tok", t0));
+            guard.record_market_order_at("tok", t0);
+        }
+        // Empty: rejected; other markets have their own bucket
+        assert!(!guard.market_rate_allows_at("tok", t0 + Duration::from_secs(1)));
+        assert!(guard.market_rate_allows_at("other", t0 + Duration::from_secs(1)));
+
+        // Refill over time: one order back after 20s
+        assert!(!guard.market_rate_allows_at("tok", t0 + Duration::from_secs(19)));
+        assert!(guard.market_rate_allows_at("tok", t0 + Duration::from_secs(20)));
+        guard.record_market_order_at("tok", t0 + Duration::from_secs(20));
+        assert!(!guard.market_rate_allows_at("tok", t0 + Duration::from_secs(21)));
+
+        // Refill stops at the burst size
+        let later = t0 + Duration::from_secs(3600);
+        for _ in 0..3 {
+            assert!(guard.market_rate_allows_at("tok", later));
+            guard.record_market_order_at("tok", later);
+        }
+        assert!(!guard.market_rate_allows_at("tok", later));
+
+        // Disabled by default
+        let mut off = RiskGuard::new(RiskGuardConfig::default());
+        for _ in 0..10 {
+            off.record_market_order_at("tok", t0);
+        }
+        assert!(off.market_rate_allows_at("tok", t0));
+    }
+
+    #[test]
+    fn test_whale_cooldown_disabled_by_default() {
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+        let t0 = Instant::now();
+        guard.record_whale_copy_at("0xwhale_a", t0);
+        assert!(!guard.whale_on_cooldown_at("0xwhale_a", t0));
+    }
+
+    #[test]
+    fn test_snapshot_reflects_state() {
+        let mut guard = RiskGuard::new(RiskGuardConfig {
+            daily_notional_cap_usd: 500.0,
+            whale_cooldown: Duration::from_secs(30),
+            ..Default::default()
+        });
+        let empty = guard.snapshot();
+        assert_eq!(empty.tracked_tokens, 0);
+        assert!(empty.tripped_tokens.is_empty() && empty.whale_cooldowns.is_empty());
+
+        let t0 = Instant::now();
+        guard.record_notional(TradeSide::Buy, 120.0);
+        guard.check_with_book("tok_b", 5, 10.0); // trips
+        guard.check_fast("tok_a", 10.0);
+        guard.record_whale_copy_at("0xwhale_a", t0);
+
+        let snap = guard.snapshot_at(t0 + Duration::from_secs(10));
+        assert_eq!(snap.daily_notional_usd, 120.0);
+        assert_eq!(snap.daily_notional_cap_usd, 500.0);
+        assert_eq!(snap.tracked_tokens, 2);
+        assert_eq!(snap.tripped_tokens.len(), 1);
+        assert_eq!(snap.tripped_tokens[0].key, "tok_b");
+        assert_eq!(snap.whale_cooldowns, vec![SnapshotEntry { key: "0xwhale_a".into(), secs_left: 20 }]);
+
+        let json = serde_json::to_value(&snap).unwrap();
+        assert_eq!(json["tripped_tokens"][0]["key"], "tok_b");
+
+        // Cooldown expires out of the snapshot
+        assert!(guard.snapshot_at(t0 + Duration::from_secs(31)).whale_cooldowns.is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_reflects_windows_and_halt() {
+        let mut guard = RiskGuard::new(RiskGuardConfig {
+            daily_notional_cap_usd: 100.0,
+            large_trade_shares: 1000.0,
+            conviction_step: 0.5,
+            conviction_window: Duration::from_secs(60),
+            market_rate_burst: 3,
+            market_rate_per_min: 6.0,
+            whale_entry_window: Duration::from_secs(120),
+            ..Default::default()
+        });
+        let t0 = Instant::now();
+        guard.check_fast("tok", 5000.0);
+        guard.check_fast("tok", 5000.0);
+        guard.observe_conviction_at("0xwhale", "tok", true, t0);
+        guard.observe_conviction_at("0xwhale", "tok", true, t0);
+        guard.observe_whale_entry_at("0xwhale", "tok", true, t0);
+        guard.record_market_order_at("tok", t0);
+        guard.record_market_order_at("tok", t0);
+
+        let snap = guard.snapshot_at(t0 + Duration::from_secs(10));
+        assert!(!snap.buys_halted);
+        assert_eq!(snap.consecutive_large, vec![CountEntry { key: "tok".into(), count: 2 }]);
+        assert_eq!(snap.conviction_streaks, vec![StreakEntry { whale: "0xwhale".into(), token_id: "tok".into(), count: 2, secs_left: 50 }]);
+        assert_eq!(snap.entry_windows, vec![WindowEntry { whale: "0xwhale".into(), token_id: "tok".into(), secs_left: 110 }]);
+        // One order left after two, plus one refilled over 10s at 6/min
+        assert_eq!(snap.market_rate_buckets.len(), 1);
+        assert!((snap.market_rate_buckets[0].orders_left - 2.0).abs() < 1e-6);
+
+        // Windows lapse out of the snapshot; a spent cap halts BUYs
+        guard.record_notional(TradeSide::Buy, 100.0);
+        let later = guard.snapshot_at(t0 + Duration::from_secs(121));
+        assert!(later.buys_halted);
+        assert!(later.conviction_streaks.is_empty() && later.entry_windows.is_empty());
+
+        let mut held = FxHashMap::default();
+        held.insert("tok_b".to_string(), 4.0);
+        held.insert("tok_a".to_string(), 0.0);
+        let snap = later.with_exposure(&held);
+        assert_eq!(snap.token_exposure, vec![ExposureEntry { key: "tok_b".into(), shares: 4.0 }]);
+    }
+
+    #[test]
+    fn test_book_depth_levels() {
+        // 15 ask levels of 100 shares from 0.51 to 0.65
+        let asks: Vec<serde_json::Value> = (0..15)
+            .map(|i| serde_json::json!({"price": format!("{:.2}", 0.51 + i as f64 * 0.01), "size": "100"}))
+            .collect();
+        let book = serde_json::json!({ "asks": asks, "bids": [] });
+        let threshold = 0.50;
+
+        let all: f64 = (0..15).map(|i| (0.51 + i as f64 * 0.01) * 100.0).sum();
+        let first_10: f64 = (0..10).map(|i| (0.51 + i as f64 * 0.01) * 100.0).sum();
+
+        // Default truncates at 10 levels
+        let d10 = book_depth_from_json(&book, TradeSide::Buy, threshold, STACK_BOOK_LEVELS);
+        assert!((d10 - first_10).abs() < 1e-6);
+
+        // Deeper config accumulates the rest (heap path)
+        let d20 = book_depth_from_json(&book, TradeSide::Buy, threshold, 20);
+        assert!((d20 - all).abs() < 1e-6);
+
+        // Fewer levels than configured, and missing side
+        let d3 = book_depth_from_json(&book, TradeSide::Buy, threshold, 3);
+        assert!((d3 - (51.0 + 52.0 + 53.0)).abs() < 1e-6);
+        assert_eq!(book_depth_from_json(&book, TradeSide::Sell, threshold, 10), 0.0);
+    }
+
+    #[test]
+    fn test_own_resting_size_excluded_from_depth() {
+        let book = serde_json::json!({
+            "bids": [
+                {"price": "0.48", "size": "100"},
+                {"price": "0.47", "size": "200"},
+            ]
+        });
+        // Selling at 0.50: both bid levels are beyond the threshold
+        let full = book_depth_from_json(&book, TradeSide::Sell, 0.50, 10);
+        assert!((full - (48.0 + 94.0)).abs() < 1e-6);
+
+        // Our 60 share resting bid at 0.48 isn't someone else's liquidity
+        let own = [(0.48, 60.0)];
+        let net = book_depth_excluding_own(&book, TradeSide::Sell, 0.50, 10, &own);
+        assert!((net - (0.48 * 40.0 + 94.0)).abs() < 1e-6);
+
+        // Never negative, and prices with no level are ignored
+        let net = book_depth_excluding_own(&book, TradeSide::Sell, 0.50, 10, &[(0.48, 500.0), (0.30, 10.0)]);
+        assert!((net - 94.0).abs() < 1e-6);
+
+        // Heap path too
+        let net = book_depth_excluding_own(&book, TradeSide::Sell, 0.50, 20, &own);
+        assert!((net - (0.48 * 40.0 + 94.0)).abs() < 1e-6);
+    }
+
+    fn conviction_guard() -> RiskGuard {
+        RiskGuard::new(RiskGuardConfig {
+            conviction_step: 0.25,
+            conviction_max_multiplier: 1.6,
+            conviction_window: Duration::from_secs(60),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_conviction_streak_growth_and_cap() {
+        let mut guard = conviction_guard();
+        let t0 = Instant::now();
+        let s = Duration::from_secs(1);
+
+        assert_eq!(guard.observe_conviction_at("w", "tok", true, t0), 1.0);
+        assert_eq!(guard.observe_conviction_at("w", "tok", true, t0 + s), 1.25);
+        assert_eq!(guard.observe_conviction_at("w", "tok", true, t0 + s * 2), 1.5);
+        // Capped
+        assert_eq!(guard.observe_conviction_at("w", "tok", true, t0 + s * 3), 1.6);
+        assert_eq!(guard.observe_conviction_at("w", "tok", true, t0 + s * 4), 1.6);
+
+        // Streaks are per whale and per token
+        assert_eq!(guard.observe_conviction_at("w", "other", true, t0 + s * 5), 1.0);
+        assert_eq!(guard.observe_conviction_at("w2", "tok", true, t0 + s * 5), 1.0);
+    }
+
+    #[test]
+    fn test_conviction_decay_and_sell_reset() {
+        let mut guard = conviction_guard();
+        let t0 = Instant::now();
+        guard.observe_conviction_at("w", "tok", true, t0);
+        assert_eq!(guard.observe_conviction_at("w", "tok", true, t0 + Duration::from_secs(30)), 1.25);
+
+        // Quiet longer than the window: back to 1.0
+        assert_eq!(guard.observe_conviction_at("w", "tok", true, t0 + Duration::from_secs(100)), 1.0);
+        assert_eq!(guard.observe_conviction_at("w", "tok", true, t0 + Duration::from_secs(101)), 1.25);
+
+        // Opposite direction ends the streak
+        assert_eq!(guard.observe_conviction_at("w", "tok", false, t0 + Duration::from_secs(102)), 1.0);
+        assert_eq!(guard.observe_conviction_at("w", "tok", true, t0 + Duration::from_secs(103)), 1.0);
+    }
+
+    #[test]
+    fn test_conviction_disabled_by_default() {
+        let mut guard = RiskGuard::new(RiskGuardConfig::default());
+        let t0 = Instant::now();
+        for i in 0..5 {
+            assert_eq!(guard.observe_conviction_at("w", "tok", true, t0 + Duration::from_secs(i)), 1.0);
+        }
+    }
+
     #[test]
     fn test_depth_calculation() {
         let asks = vec![
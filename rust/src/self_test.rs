@@ -0,0 +1,108 @@
+//! Pre-flight check for a live session (--self-test)
+//! Signer, credentials, clock skew, a locally verified order signature and an authenticated
+//! CLOB call, reported together with a single pass/fail
+
+/// One check's outcome
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckOutcome {
+    Pass(String),
+    Fail(String),
+    /// Not run because an earlier check it depends on failed
+    Skipped(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfTestCheck {
+    pub name: &'static str,
+    pub outcome: CheckOutcome,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SelfTestReport {
+    pub checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a check's result (Ok = detail shown on pass, Err = why it failed)
+    pub fn record(&mut self, name: &'static str, result: Result<String, String>) {
+        let outcome = match result {
+            Ok(detail) => CheckOutcome::Pass(detail),
+            Err(reason) => CheckOutcome::Fail(reason),
+        };
+        self.checks.push(SelfTestCheck { name, outcome });
+    }
+
+    pub fn skip(&mut self, name: &'static str, reason: &str) {
+        self.checks.push(SelfTestCheck { name, outcome: CheckOutcome::Skipped(reason.to_string()) });
+    }
+
+    /// Passed only if something ran and nothing failed or was skipped
+    pub fn passed(&self) -> bool {
+        !self.checks.is_empty() && self.checks.iter().all(|c| matches!(c.outcome, CheckOutcome::Pass(_)))
+    }
+
+    /// Names of the checks that failed
+    pub fn failures(&self) -> Vec<&'static str> {
+        self.checks
+            .iter()
+            .filter(|c| matches!(c.outcome, CheckOutcome::Fail(_)))
+            .map(|c| c.name)
+            .collect()
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::from("🩺 Self-test\n");
+        for check in &self.checks {
+            let (mark, detail) = match &check.outcome {
+                CheckOutcome::Pass(d) => ("✅", d),
+                CheckOutcome::Fail(d) => ("❌", d),
+                CheckOutcome::Skipped(d) => ("⏭️", d),
+            };
+            out.push_str(&format!("   {} {:<12} {}\n", mark, check.name, detail));
+        }
+        out.push_str(if self.passed() { "   PASS\n" } else { "   FAIL\n" });
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_aggregation() {
+        let mut ok = SelfTestReport::new();
+        ok.record("signer", Ok("0xabc".into()));
+        ok.record("credentials", Ok("cached".into()));
+        ok.record("auth", Ok("200 OK".into()));
+        assert!(ok.passed());
+        assert!(ok.failures().is_empty());
+        assert!(ok.render().ends_with("PASS\n"));
+
+        // Revoked creds: the auth call fails, the rest still passes
+        let mut revoked = SelfTestReport::new();
+        revoked.record("signer", Ok("0xabc".into()));
+        revoked.record("auth", Err("401 Unauthorized".into()));
+        assert!(!revoked.passed());
+        assert_eq!(revoked.failures(), vec!["auth"]);
+        let text = revoked.render();
+        assert!(text.contains("❌ auth") && text.contains("401 Unauthorized") && text.ends_with("FAIL\n"), "{text}");
+
+        // Bad key: dependents are skipped, which is still a failure
+        let mut bad_key = SelfTestReport::new();
+        bad_key.record("signer", Err("Failed to parse private key".into()));
+        bad_key.skip("credentials", "needs a signer");
+        assert!(!bad_key.passed());
+        assert_eq!(bad_key.failures(), vec!["signer"]);
+
+        // A skip on its own never passes, nor does an empty report
+        let mut skipped = SelfTestReport::new();
+        skipped.skip("auth", "no creds");
+        assert!(!skipped.passed() && skipped.failures().is_empty());
+        assert!(!SelfTestReport::new().passed());
+    }
+}
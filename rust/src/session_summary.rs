@@ -0,0 +1,204 @@
+//! Session totals printed on shutdown
+//! Events, copies, skips by reason, our notional and fills, a realized PnL estimate and resubmit stats
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use rustc_hash::FxHashMap;
+
+use crate::block_summary::{classify_status, StatusClass};
+use crate::status_code::StatusCode;
+
+/// Resubmit chain counters
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResubmitStats {
+    /// Requests picked up by the resubmit worker
+    pub chains: u64,
+    /// Orders posted across all chains
+    pub attempts: u64,
+    /// Chains that stopped on the price ceiling, the deadline or the resting order cap
+    pub aborted: u64,
+    pub filled_shares: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SessionStats {
+    pub events: u64,
+    pub copied: u64,
+    pub failed: u64,
+    /// Reason code -> count
+    pub skipped: BTreeMap<String, u64>,
+    /// Our USD committed (first orders, as charged against the daily notional limit)
+    pub notional: f64,
+    /// Orders (first or resubmit) that filled immediately
+    pub fills: u64,
+    pub filled_shares: f64,
+    /// Sells matched against this session's buys at their average cost
+    pub realized_pnl: f64,
+    pub resubmit: ResubmitStats,
+    /// Sum of first-order fill price deltas vs the whale (positive = better) and how many
+    pub price_delta_sum: f64,
+    pub price_delta_fills: u64,
+    /// Token -> (shares, cost) bought this session, for the PnL estimate
+    basis: FxHashMap<String, (f64, f64)>,
+}
+
+impl SessionStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a handled event by its order status
+    pub fn record_event(&mut self, status: StatusCode) {
+        self.events += 1;
+        match classify_status(status) {
+            StatusClass::Copied => self.copied += 1,
+            StatusClass::Skipped(reason) => *self.skipped.entry(reason).or_insert(0) += 1,
+            StatusClass::Failed => self.failed += 1,
+        }
+    }
+
+    pub fn record_notional(&mut self, usd: f64) {
+        self.notional += usd;
+    }
+
+    /// Record an immediate fill at `price` (our limit; the CLOB may have filled better).
+    /// Sells of shares not bought this session carry no basis and add no PnL
+    pub fn record_fill(&mut self, token_id: &str, shares: f64, price: f64, is_buy: bool) {
+        if shares <= 0.0 {
+            return;
+        }
+        self.fills += 1;
+        self.filled_shares += shares;
+        if is_buy {
+            let entry = self.basis.entry(token_id.to_string()).or_insert((0.0, 0.0));
+            entry.0 += shares;
+            entry.1 += shares * price;
+        } else if let Some((held, cost)) = self.basis.get_mut(token_id) {
+            let sold = shares.min(*held);
+            if sold > 0.0 {
+                let avg = *cost / *held;
+                self.realized_pnl += sold * (price - avg);
+                *cost -= sold * avg;
+                *held -= sold;
+            }
+        }
+    }
+
+    /// Record how our fill price compared with the whale's (lib::copy_price_delta)
+    pub fn record_price_delta(&mut self, delta: f64) {
+        self.price_delta_sum += delta;
+        self.price_delta_fills += 1;
+    }
+
+    /// Average fill price delta vs the whale (None before the first fill)
+    pub fn avg_price_delta(&self) -> Option<f64> {
+        (self.price_delta_fills > 0).then(|| self.price_delta_sum / self.price_delta_fills as f64)
+    }
+
+    pub fn skipped_total(&self) -> u64 {
+        self.skipped.values().sum()
+    }
+
+    pub fn render(&self, uptime: Duration) -> String {
+        let secs = uptime.as_secs();
+        let mut out = format!(
+            "🏁 Session summary ({}h{:02}m{:02}s)\n",
+            secs / 3600,
+            (secs / 60) % 60,
+            secs % 60
+        );
+        out.push_str(&format!(
+            "   events {} | copied {} | failed {} | skipped {}\n",
+            self.events, self.copied, self.failed, self.skipped_total()
+        ));
+        for (reason, n) in &self.skipped {
+            out.push_str(&format!("     {} ×{}\n", reason, n));
+        }
+        out.push_str(&format!(
+            "   notional ${:.2} | fills {} ({:.2} shares) | realized PnL est ${:+.2}\n",
+            self.notional, self.fills, self.filled_shares, self.realized_pnl
+        ));
+        if let Some(avg) = self.avg_price_delta() {
+            out.push_str(&format!(
+                "   price vs whale: avg {:+.4}/share over {} fills (+ = better)\n",
+                avg, self.price_delta_fills
+            ));
+        }
+        let r = &self.resubmit;
+        out.push_str(&format!(
+            "   resubmits: {} chains | {} attempts | {} aborted | {:.2} shares filled\n",
+            r.chains, r.attempts, r.aborted, r.filled_shares
+        ));
+        out
+    }
+}
+
+// ============================================================================
+// Global Instance
+// ============================================================================
+
+static GLOBAL_SESSION: OnceLock<Mutex<SessionStats>> = OnceLock::new();
+
+/// Get the global session counters
+pub fn global() -> &'static Mutex<SessionStats> {
+    GLOBAL_SESSION.get_or_init(|| Mutex::new(SessionStats::new()))
+}
+
+/// Update the global counters (no-op if the lock is poisoned)
+pub fn record(f: impl FnOnce(&mut SessionStats)) {
+    if let Ok(mut stats) = global().lock() {
+        f(&mut stats);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_realized_pnl_from_session_basis() {
+        let mut s = SessionStats::new();
+        s.record_fill("tok", 100.0, 0.40, true);
+        s.record_fill("tok", 100.0, 0.60, true);
+        // Average cost 0.50: selling 50 @ 0.70 realizes +10
+        s.record_fill("tok", 50.0, 0.70, false);
+        assert!((s.realized_pnl - 10.0).abs() < 1e-9);
+        // Only 150 left with basis; the extra 50 carries no PnL
+        s.record_fill("tok", 200.0, 0.45, false);
+        assert!((s.realized_pnl - 2.5).abs() < 1e-9);
+        // Sells of tokens never bought this session are counted but not priced
+        s.record_fill("other", 10.0, 0.90, false);
+        assert!((s.realized_pnl - 2.5).abs() < 1e-9);
+        assert_eq!(s.fills, 5);
+    }
+
+    #[test]
+    fn test_summary_renders_fields() {
+        let mut s = SessionStats::new();
+        s.record_event(StatusCode::OrderPosted);
+        s.record_event(StatusCode::OrderPosted);
+        s.record_event(StatusCode::SkippedSmall);
+        s.record_event(StatusCode::SkippedSmall);
+        s.record_event(StatusCode::CbBlocked);
+        s.record_event(StatusCode::ExecFail);
+        s.record_notional(13.0);
+        s.record_fill("tok", 20.0, 0.50, true);
+        s.record_fill("tok", 10.0, 0.60, false);
+        s.resubmit = ResubmitStats { chains: 3, attempts: 7, aborted: 1, filled_shares: 12.5 };
+        s.record_price_delta(-0.01);
+        s.record_price_delta(0.004);
+
+        assert_eq!(
+            s.render(Duration::from_secs(3725)),
+            "🏁 Session summary (1h02m05s)\n\
+             \x20  events 6 | copied 2 | failed 1 | skipped 3\n\
+             \x20    CB_BLOCKED ×1\n\
+             \x20    SKIPPED_SMALL ×2\n\
+             \x20  notional $13.00 | fills 2 (30.00 shares) | realized PnL est $+1.00\n\
+             \x20  price vs whale: avg -0.0030/share over 2 fills (+ = better)\n\
+             \x20  resubmits: 3 chains | 7 attempts | 1 aborted | 12.50 shares filled\n"
+        );
+    }
+}
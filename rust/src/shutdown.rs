@@ -0,0 +1,174 @@
+//! Shutdown of in-flight resubmit chains
+//! By default exiting drops chains wherever they are, which can leave a copy half filled.
+//! DRAIN_RESUBMITS_ON_SHUTDOWN lets queued and running chains finish (up to a timeout) while
+//! new top-level orders are refused. Resting GTD orders are not touched either way
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// What shutdown does with resubmit chains still in flight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownMode {
+    /// Exit at once; in-flight chains are dropped
+    Abort,
+    /// Wait up to this long for in-flight chains to finish
+    Drain(Duration),
+}
+
+impl ShutdownMode {
+    pub fn new(drain: bool, timeout: Duration) -> Self {
+        if drain { ShutdownMode::Drain(timeout) } else { ShutdownMode::Abort }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrainOutcome {
+    /// No chain was in flight
+    Idle,
+    /// Every in-flight chain finished
+    Drained,
+    /// Chains left behind on exit (Abort, or the drain timed out)
+    Abandoned(usize),
+}
+
+/// Resubmit chains queued or running, and whether top-level orders are still taken
+#[derive(Debug, Default)]
+pub struct ResubmitChains {
+    in_flight: AtomicUsize,
+    closed: AtomicBool,
+    idle: Notify,
+}
+
+impl ResubmitChains {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A chain is about to be queued
+    pub fn begin(&self) {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// A chain ended (or was never delivered)
+    pub fn end(&self) {
+        if self.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.idle.notify_waiters();
+        }
+    }
+
+    /// Ends the chain when dropped, however the worker leaves it
+    pub fn end_on_drop(&self) -> ChainGuard<'_> {
+        ChainGuard(self)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// False once shutdown has started
+    pub fn accepting_orders(&self) -> bool {
+        !self.closed.load(Ordering::SeqCst)
+    }
+
+    /// Refuse new top-level orders, then abort or drain the chains in flight per `mode`
+    pub async fn shutdown(&self, mode: ShutdownMode) -> DrainOutcome {
+        self.closed.store(true, Ordering::SeqCst);
+        let running = self.in_flight();
+        if running == 0 {
+            return DrainOutcome::Idle;
+        }
+        match mode {
+            ShutdownMode::Abort => DrainOutcome::Abandoned(running),
+            ShutdownMode::Drain(timeout) => match tokio::time::timeout(timeout, self.wait_idle()).await {
+                Ok(()) => DrainOutcome::Drained,
+                Err(_) => DrainOutcome::Abandoned(self.in_flight()),
+            },
+        }
+    }
+
+    async fn wait_idle(&self) {
+        loop {
+            // Registered before the check so an end() in between isn't missed
+            let notified = self.idle.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.in_flight() == 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+pub struct ChainGuard<'a>(&'a ResubmitChains);
+
+impl Drop for ChainGuard<'_> {
+    fn drop(&mut self) {
+        self.0.end();
+    }
+}
+
+static RESUBMIT_CHAINS: OnceLock<ResubmitChains> = OnceLock::new();
+
+/// Process-wide chain tracker (order worker, resubmit workers and main)
+pub fn resubmit_chains() -> &'static ResubmitChains {
+    RESUBMIT_CHAINS.get_or_init(ResubmitChains::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn test_abort_drops_in_flight_chains() {
+        let chains = ResubmitChains::new();
+        assert_eq!(chains.shutdown(ShutdownMode::Abort).await, DrainOutcome::Idle);
+
+        let chains = ResubmitChains::new();
+        chains.begin();
+        let started = Instant::now();
+        assert_eq!(chains.shutdown(ShutdownMode::Abort).await, DrainOutcome::Abandoned(1));
+        assert!(started.elapsed() < Duration::from_millis(50));
+        assert!(!chains.accepting_orders());
+    }
+
+    #[tokio::test]
+    async fn test_drain_waits_for_chains_to_finish() {
+        let chains = Arc::new(ResubmitChains::new());
+        chains.begin();
+        chains.begin();
+        for delay in [10, 30] {
+            let chains = Arc::clone(&chains);
+            tokio::spawn(async move {
+                let _done = chains.end_on_drop();
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+            });
+        }
+        let outcome = chains.shutdown(ShutdownMode::new(true, Duration::from_secs(5))).await;
+        assert_eq!(outcome, DrainOutcome::Drained);
+        assert_eq!(chains.in_flight(), 0);
+        assert!(!chains.accepting_orders());
+    }
+
+    #[tokio::test]
+    async fn test_drain_gives_up_at_timeout() {
+        let chains = Arc::new(ResubmitChains::new());
+        chains.begin();
+        chains.begin();
+        let quick = Arc::clone(&chains);
+        tokio::spawn(async move {
+            let _done = quick.end_on_drop();
+        });
+
+        // The other chain never ends: shutdown returns once the timeout is up
+        let started = Instant::now();
+        let outcome = chains.shutdown(ShutdownMode::Drain(Duration::from_millis(40))).await;
+        assert_eq!(outcome, DrainOutcome::Abandoned(1));
+        let waited = started.elapsed();
+        assert!(waited >= Duration::from_millis(40) && waited < Duration::from_secs(2), "waited {:?}", waited);
+    }
+}
@@ -0,0 +1,150 @@
+//! Digest signing for orders and L1 auth headers
+//! The local key is the default; SIGNER=remote asks an HTTP endpoint instead, so the key
+//! never has to be on the trading host
+
+use std::time::Duration;
+
+use alloy::primitives::{Address, Signature, B256};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::SignerSync;
+use anyhow::{anyhow, Context, Result};
+
+/// Signs 32-byte EIP-712 digests for one address
+pub trait Signer: Send + Sync {
+    /// Address the signatures recover to (the CLOB order `signer` / POLY_ADDRESS)
+    fn address(&self) -> Address;
+    fn sign_digest(&self, digest: &[u8; 32]) -> Result<Signature>;
+}
+
+// ============================================================================
+// Local Key
+// ============================================================================
+
+/// Key held in process memory (PRIVATE_KEY / PRIVATE_KEY_FILE / KEYSTORE_PATH)
+pub struct LocalSigner {
+    wallet: PrivateKeySigner,
+}
+
+impl LocalSigner {
+    pub fn new(wallet: PrivateKeySigner) -> Self {
+        Self { wallet }
+    }
+
+    pub fn from_hex(private_key: &str) -> Result<Self> {
+        let wallet: PrivateKeySigner = private_key.parse()
+            .map_err(|e| anyhow!("Failed to parse private key: {}", e))?;
+        Ok(Self::new(wallet))
+    }
+}
+
+impl Signer for LocalSigner {
+    fn address(&self) -> Address {
+        self.wallet.address()
+    }
+
+    fn sign_digest(&self, digest: &[u8; 32]) -> Result<Signature> {
+        self.wallet.sign_hash_sync(&B256::from(*digest)).map_err(|e| anyhow!("Failed to sign: {}", e))
+    }
+}
+
+// ============================================================================
+// Remote Signer
+// ============================================================================
+
+/// REMOTE_SIGNER_* settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteSignerConfig {
+    pub url: String,
+    /// Expected signing address; every returned signature must recover to it
+    pub address: String,
+    /// Sent as `Authorization: Bearer <token>` (empty = no header)
+    pub token: String,
+    pub timeout_ms: u64,
+}
+
+/// POSTs `{"digest": "0x..", "address": "0x.."}` to the endpoint and expects
+/// `{"signature": "0x<65 bytes r|s|v>"}` back (a KMS proxy, HSM gateway, ...)
+pub struct RemoteSigner {
+    cfg: RemoteSignerConfig,
+    address: Address,
+    http: reqwest::blocking::Client,
+}
+
+impl RemoteSigner {
+    pub fn new(cfg: RemoteSignerConfig) -> Result<Self> {
+        let address: Address = cfg.address.parse().context("REMOTE_SIGNER_ADDRESS is not a valid address")?;
+        let http = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_millis(cfg.timeout_ms))
+            .build()?;
+        Ok(Self { cfg, address, http })
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn sign_digest(&self, digest: &[u8; 32]) -> Result<Signature> {
+        let body = remote_sign_request(digest, self.address);
+        let mut req = self.http.post(&self.cfg.url).json(&body);
+        if !self.cfg.token.is_empty() {
+            req = req.bearer_auth(&self.cfg.token);
+        }
+        let resp = req.send().context("remote signer request failed")?;
+        let status = resp.status();
+        let text = resp.text().unwrap_or_default();
+        if !status.is_success() {
+            return Err(anyhow!("remote signer returned {}: {}", status, text));
+        }
+        parse_remote_signature(&text, digest, self.address)
+    }
+}
+
+/// Request body for the remote signer
+pub fn remote_sign_request(digest: &[u8; 32], address: Address) -> serde_json::Value {
+    serde_json::json!({
+        "digest": format!("{}", B256::from(*digest)),
+        "address": format!("{}", address),
+    })
+}
+
+/// Parse a remote signer reply and check it recovers to `expected` (a signature from the wrong
+/// key would be rejected by the CLOB anyway, but this names the actual problem)
+pub fn parse_remote_signature(body: &str, digest: &[u8; 32], expected: Address) -> Result<Signature> {
+    let value: serde_json::Value = serde_json::from_str(body).context("remote signer reply is not JSON")?;
+    let hex = value["signature"].as_str().ok_or_else(|| anyhow!("remote signer reply has no \"signature\""))?;
+    let sig: Signature = hex.trim().parse().map_err(|e| anyhow!("bad remote signature {}: {}", hex, e))?;
+    let recovered = sig
+        .recover_address_from_prehash(&B256::from(*digest))
+        .map_err(|e| anyhow!("remote signature doesn't recover: {}", e))?;
+    if recovered != expected {
+        return Err(anyhow!("remote signature is from {}, expected {}", recovered, expected));
+    }
+    Ok(sig)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_signature_checked() {
+        let key = PrivateKeySigner::random();
+        let other = PrivateKeySigner::random();
+        let digest = [7u8; 32];
+        let sig = key.sign_hash_sync(&B256::from(digest)).unwrap();
+
+        let body = remote_sign_request(&digest, key.address());
+        assert_eq!(body["digest"], format!("0x{}", "07".repeat(32)));
+
+        let reply = serde_json::json!({ "signature": sig.to_string() }).to_string();
+        assert_eq!(parse_remote_signature(&reply, &digest, key.address()).unwrap(), sig);
+        // Wrong key, other digest, garbage
+        let err = parse_remote_signature(&reply, &digest, other.address()).unwrap_err();
+        assert!(err.to_string().contains("expected"), "{err}");
+        assert!(parse_remote_signature(&reply, &[8u8; 32], key.address()).is_err());
+        assert!(parse_remote_signature(r#"{"sig": "0x00"}"#, &digest, key.address()).is_err());
+        assert!(parse_remote_signature(r#"{"signature": "0x1234"}"#, &digest, key.address()).is_err());
+    }
+}
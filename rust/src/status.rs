@@ -0,0 +1,145 @@
+//! Runtime status board and admin endpoint
+//! Components publish JSON sections; served at GET /status and dumped on SIGUSR1
+
+use serde_json::{Map, Value};
+use std::sync::{OnceLock, RwLock};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+// ============================================================================
+// Status Board
+// ============================================================================
+
+/// Named JSON sections published by the running components
+pub struct StatusBoard {
+    sections: RwLock<Map<String, Value>>,
+}
+
+impl StatusBoard {
+    pub fn new() -> Self {
+        Self { sections: RwLock::new(Map::new()) }
+    }
+
+    /// Replace a section with the latest value
+    pub fn publish(&self, section: &str, value: Value) {
+        if let Ok(mut sections) = self.sections.write() {
+            sections.insert(section.to_string(), value);
+        }
+    }
+
+    /// Get a copy of a single section
+    pub fn get(&self, section: &str) -> Option<Value> {
+        self.sections.read().ok()?.get(section).cloned()
+    }
+
+    /// Render all sections as a single JSON object
+    pub fn render(&self) -> String {
+        match self.sections.read() {
+            Ok(sections) => Value::Object(sections.clone()).to_string(),
+            Err(_) => "{}".to_string(),
+        }
+    }
+}
+
+impl Default for StatusBoard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static GLOBAL_STATUS: OnceLock<StatusBoard> = OnceLock::new();
+
+/// Get the global status board
+pub fn global_status() -> &'static StatusBoard {
+    GLOBAL_STATUS.get_or_init(StatusBoard::new)
+}
+
+/// Publish a section to the global status board (convenience function)
+#[inline]
+pub fn publish(section: &str, value: Value) {
+    global_status().publish(section, value);
+}
+
+// ============================================================================
+// Admin Endpoint
+// ============================================================================
+
+/// Spawn a minimal HTTP server answering GET /status with the board as JSON
+pub fn spawn_status_server(addr: String) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let listener = match TcpListener::bind(&addr).await {
+            Ok(l) => l,
+            Err(e) => {
+                eprintln!("⚠️ Status endpoint failed to bind {}: {}", addr, e);
+                return;
+            }
+        };
+        println!("📊 Status endpoint listening on http://{}/status", addr);
+
+        loop {
+            let Ok((mut stream, _)) = listener.accept().await else { continue };
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1024];
+                let n = stream.read(&mut buf).await.unwrap_or(0);
+                let response = build_response(&buf[..n]);
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            });
+        }
+    })
+}
+
+fn build_response(request: &[u8]) -> String {
+    let is_status = request.starts_with(b"GET /status ") || request.starts_with(b"GET /status?");
+    let (code, body) = if is_status {
+        ("200 OK", global_status().render())
+    } else {
+        ("404 Not Found", r#"{"error":"not found"}"#.to_string())
+    };
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        code,
+        body.len(),
+        body
+    )
+}
+
+/// Print the status board whenever the process receives SIGUSR1
+#[cfg(unix)]
+pub fn spawn_sigusr1_dump() -> tokio::task::JoinHandle<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+    tokio::spawn(async {
+        let mut sig = match signal(SignalKind::user_defined1()) {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("⚠️ SIGUSR1 handler not installed: {}", e);
+                return;
+            }
+        };
+        while sig.recv().await.is_some() {
+            println!("📊 Status: {}", global_status().render());
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_publish_and_render() {
+        let board = StatusBoard::new();
+        board.publish("risk", json!({"daily_notional_usd": 12.5}));
+        board.publish("risk", json!({"daily_notional_usd": 20.0}));
+        let rendered: Value = serde_json::from_str(&board.render()).unwrap();
+        assert_eq!(rendered["risk"]["daily_notional_usd"], 20.0);
+        assert!(board.get("missing").is_none());
+    }
+
+    #[test]
+    fn test_only_status_path_is_served() {
+        assert!(build_response(b"GET /status HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 200"));
+        assert!(build_response(b"GET /other HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 404"));
+    }
+}
@@ -0,0 +1,226 @@
+//! Order status codes
+//! Every status an event can end with, as a stable code (the CSV order_status column) plus an
+//! optional human-readable message (status_detail)
+
+use std::fmt;
+
+/// Outcome code for one handled event. `as_str` values are stable - they're what the CSV,
+/// summaries and webhooks aggregate on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum StatusCode {
+    /// CLOB accepted the order (2xx)
+    OrderPosted,
+    /// CLOB answered with a non-2xx status
+    OrderRejected,
+    /// Signing or posting failed before a response
+    ExecFail,
+    /// Order queue full or closed
+    QueueErr,
+    /// Displaced from a full queue by a bigger trade (ORDER_QUEUE_PRIORITY=usd_value)
+    QueueShed,
+    /// Order worker dropped the reply
+    WorkerDropped,
+    /// No reply within ORDER_REPLY_TIMEOUT
+    WorkerTimeout,
+    /// Would have been placed; trading disabled
+    SkippedDisabled,
+    /// Would have been placed; MOCK_TRADING
+    MockOnly,
+    /// KILL_SWITCH_FILE present
+    SkippedPaused,
+    /// WARMUP_GATE holding orders until the market caches are loaded
+    SkippedWarmup,
+    SkippedUnknownLive,
+    SkippedSameTokenBlock,
+    /// OPPOSING_SIGNALS: another whale took the other side of the token just before
+    SkippedOpposingSignal,
+    SkippedMaxResting,
+    SkippedNotFill,
+    SkippedSideFilter,
+    /// Whale or its counterparty is on the IGNORED_MAKERS list (wash trades)
+    SkippedIgnoredMaker,
+    SkippedMarketOverride,
+    SkippedSmall,
+    SkippedWhaleCooldown,
+    /// Whale adding to a token it entered within WHALE_ENTRY_WINDOW_SECS
+    SkippedWhaleAdd,
+    SkippedRateLimit,
+    /// Risk guard tripped; the message is the guard reason
+    CbBlocked,
+    /// Risk guard needed the book and the fetch failed
+    CbBookFail,
+    SkippedProbability,
+    SkippedDailyNotionalCap,
+    /// Copy size under the market's MARKET_OVERRIDES min_shares
+    SkippedBelowMarketMin,
+    SkippedThinPrecheck,
+    /// Book shallower than the tier's min_depth_shares (TIER_MIN_DEPTH)
+    SkippedInsufficientDepth,
+    /// CONFIRM_VIA_API: the data API didn't confirm the whale's fill
+    SkippedUnconfirmed,
+    SkippedOffTick,
+}
+
+impl StatusCode {
+    pub const ALL: [StatusCode; 32] = [
+        StatusCode::OrderPosted,
+        StatusCode::OrderRejected,
+        StatusCode::ExecFail,
+        StatusCode::QueueErr,
+        StatusCode::QueueShed,
+        StatusCode::WorkerDropped,
+        StatusCode::WorkerTimeout,
+        StatusCode::SkippedDisabled,
+        StatusCode::MockOnly,
+        StatusCode::SkippedPaused,
+        StatusCode::SkippedWarmup,
+        StatusCode::SkippedUnknownLive,
+        StatusCode::SkippedSameTokenBlock,
+        StatusCode::SkippedOpposingSignal,
+        StatusCode::SkippedMaxResting,
+        StatusCode::SkippedNotFill,
+        StatusCode::SkippedSideFilter,
+        StatusCode::SkippedIgnoredMaker,
+        StatusCode::SkippedMarketOverride,
+        StatusCode::SkippedSmall,
+        StatusCode::SkippedWhaleCooldown,
+        StatusCode::SkippedWhaleAdd,
+        StatusCode::SkippedRateLimit,
+        StatusCode::CbBlocked,
+        StatusCode::CbBookFail,
+        StatusCode::SkippedProbability,
+        StatusCode::SkippedDailyNotionalCap,
+        StatusCode::SkippedBelowMarketMin,
+        StatusCode::SkippedThinPrecheck,
+        StatusCode::SkippedInsufficientDepth,
+        StatusCode::SkippedUnconfirmed,
+        StatusCode::SkippedOffTick,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StatusCode::OrderPosted => "ORDER_POSTED",
+            StatusCode::OrderRejected => "ORDER_REJECTED",
+            StatusCode::ExecFail => "EXEC_FAIL",
+            StatusCode::QueueErr => "QUEUE_ERR",
+            StatusCode::QueueShed => "QUEUE_SHED",
+            StatusCode::WorkerDropped => "WORKER_DROPPED",
+            StatusCode::WorkerTimeout => "WORKER_TIMEOUT",
+            StatusCode::SkippedDisabled => "SKIPPED_DISABLED",
+            StatusCode::MockOnly => "MOCK_ONLY",
+            StatusCode::SkippedPaused => "SKIPPED_PAUSED",
+            StatusCode::SkippedWarmup => "SKIPPED_WARMUP",
+            StatusCode::SkippedUnknownLive => "SKIPPED_UNKNOWN_LIVE",
+            StatusCode::SkippedSameTokenBlock => "SKIPPED_SAME_TOKEN_BLOCK",
+            StatusCode::SkippedOpposingSignal => "SKIPPED_OPPOSING_SIGNAL",
+            StatusCode::SkippedMaxResting => "SKIPPED_MAX_RESTING",
+            StatusCode::SkippedNotFill => "SKIPPED_NOT_FILL",
+            StatusCode::SkippedSideFilter => "SKIPPED_SIDE_FILTER",
+            StatusCode::SkippedIgnoredMaker => "SKIPPED_IGNORED_MAKER",
+            StatusCode::SkippedMarketOverride => "SKIPPED_MARKET_OVERRIDE",
+            StatusCode::SkippedSmall => "SKIPPED_SMALL",
+            StatusCode::SkippedWhaleCooldown => "SKIPPED_WHALE_COOLDOWN",
+            StatusCode::SkippedWhaleAdd => "SKIPPED_WHALE_ADD",
+            StatusCode::SkippedRateLimit => "SKIPPED_RATE_LIMIT",
+            StatusCode::CbBlocked => "CB_BLOCKED",
+            StatusCode::CbBookFail => "CB_BOOK_FAIL",
+            StatusCode::SkippedProbability => "SKIPPED_PROBABILITY",
+            StatusCode::SkippedDailyNotionalCap => "SKIPPED_DAILY_NOTIONAL_CAP",
+            StatusCode::SkippedBelowMarketMin => "SKIPPED_BELOW_MARKET_MIN",
+            StatusCode::SkippedThinPrecheck => "SKIPPED_THIN_PRECHECK",
+            StatusCode::SkippedInsufficientDepth => "SKIPPED_INSUFFICIENT_DEPTH",
+            StatusCode::SkippedUnconfirmed => "SKIPPED_UNCONFIRMED",
+            StatusCode::SkippedOffTick => "SKIPPED_OFF_TICK",
+        }
+    }
+
+    /// Inverse of as_str (None for anything that isn't a code)
+    pub fn parse(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str() == code)
+    }
+
+    /// Posted, or would have been in a dry run (what the per-block and opposing-signal
+    /// trackers count as a copy)
+    pub fn is_copy(self) -> bool {
+        matches!(self, StatusCode::OrderPosted | StatusCode::SkippedDisabled | StatusCode::MockOnly)
+    }
+
+    /// Deliberately not copied (as opposed to posted or failed)
+    pub fn is_skip(self) -> bool {
+        self == StatusCode::MockOnly || self.as_str().starts_with("SKIPPED_") || self.as_str().starts_with("CB_")
+    }
+}
+
+impl fmt::Display for StatusCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A status code with its optional detail (guard reason, HTTP response, error chain...)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Status {
+    pub code: StatusCode,
+    /// Empty when the code says it all
+    pub message: String,
+}
+
+impl Status {
+    pub fn new(code: StatusCode) -> Self {
+        Self { code, message: String::new() }
+    }
+
+    pub fn with_message(code: StatusCode, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+impl From<StatusCode> for Status {
+    fn from(code: StatusCode) -> Self {
+        Self::new(code)
+    }
+}
+
+/// "CODE" or "CODE: message" (console lines, traces and webhooks)
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.message.is_empty() {
+            f.write_str(self.code.as_str())
+        } else {
+            write!(f, "{}: {}", self.code.as_str(), self.message)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_code_strings() {
+        let expected = [
+            "ORDER_POSTED", "ORDER_REJECTED", "EXEC_FAIL", "QUEUE_ERR", "QUEUE_SHED", "WORKER_DROPPED",
+            "WORKER_TIMEOUT", "SKIPPED_DISABLED", "MOCK_ONLY", "SKIPPED_PAUSED", "SKIPPED_WARMUP",
+            "SKIPPED_UNKNOWN_LIVE", "SKIPPED_SAME_TOKEN_BLOCK", "SKIPPED_OPPOSING_SIGNAL",
+            "SKIPPED_MAX_RESTING", "SKIPPED_NOT_FILL", "SKIPPED_SIDE_FILTER", "SKIPPED_IGNORED_MAKER", "SKIPPED_MARKET_OVERRIDE",
+            "SKIPPED_SMALL", "SKIPPED_WHALE_COOLDOWN", "SKIPPED_WHALE_ADD", "SKIPPED_RATE_LIMIT", "CB_BLOCKED", "CB_BOOK_FAIL",
+            "SKIPPED_PROBABILITY", "SKIPPED_DAILY_NOTIONAL_CAP", "SKIPPED_BELOW_MARKET_MIN", "SKIPPED_THIN_PRECHECK",
+            "SKIPPED_INSUFFICIENT_DEPTH",
+            "SKIPPED_UNCONFIRMED", "SKIPPED_OFF_TICK",
+        ];
+        let strings: Vec<&str> = StatusCode::ALL.iter().map(|c| c.as_str()).collect();
+        assert_eq!(strings, expected);
+        for code in StatusCode::ALL {
+            assert_eq!(StatusCode::parse(code.as_str()), Some(code));
+        }
+        assert_eq!(StatusCode::parse("SKIPPED_SMALL (<10 shares)"), None);
+
+        let skips: Vec<&str> = StatusCode::ALL.iter().filter(|c| !c.is_skip()).map(|c| c.as_str()).collect();
+        assert_eq!(skips, ["ORDER_POSTED", "ORDER_REJECTED", "EXEC_FAIL", "QUEUE_ERR", "QUEUE_SHED", "WORKER_DROPPED", "WORKER_TIMEOUT"]);
+        let copies: Vec<&str> = StatusCode::ALL.iter().filter(|c| c.is_copy()).map(|c| c.as_str()).collect();
+        assert_eq!(copies, ["ORDER_POSTED", "SKIPPED_DISABLED", "MOCK_ONLY"]);
+
+        assert_eq!(Status::new(StatusCode::MockOnly).to_string(), "MOCK_ONLY");
+        assert_eq!(Status::with_message(StatusCode::CbBlocked, "TRAP").to_string(), "CB_BLOCKED: TRAP");
+    }
+}
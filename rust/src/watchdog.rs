@@ -0,0 +1,129 @@
+//! Time-since-last-event watchdog
+//! Catches a WS feed that stays connected (pings keep flowing) but stops delivering events
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+/// EVENT_WATCHDOG_* settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WatchdogConfig {
+    /// Silence (ms) before the feed counts as dead; 0 = off
+    pub threshold_ms: u64,
+    /// Drop and reconnect the WS when it fires (otherwise only warn)
+    pub reconnect: bool,
+    /// UTC hours [start, end) when silence is unexpected (wraps past midnight; None = always)
+    pub active_hours: Option<(u8, u8)>,
+}
+
+/// Parse "start-end" UTC hours, e.g. "13-23" or "22-4" (empty or malformed = None)
+pub fn parse_active_hours(value: &str) -> Option<(u8, u8)> {
+    let (start, end) = value.trim().split_once('-')?;
+    let (start, end) = (start.trim().parse::<u8>().ok()?, end.trim().parse::<u8>().ok()?);
+    (start < 24 && end <= 24 && start != end).then_some((start, end))
+}
+
+fn in_hours(hours: Option<(u8, u8)>, hour_utc: u8) -> bool {
+    match hours {
+        None => true,
+        Some((start, end)) if start < end => (start..end).contains(&hour_utc),
+        Some((start, end)) => hour_utc >= start || hour_utc < end,
+    }
+}
+
+pub struct EventWatchdog {
+    /// Last parsed event (or start), unix ms
+    last_event_ms: AtomicU64,
+    /// Fired for the current silence; re-armed by the next event
+    fired: AtomicBool,
+}
+
+impl EventWatchdog {
+    pub fn new(now_ms: u64) -> Self {
+        Self { last_event_ms: AtomicU64::new(now_ms), fired: AtomicBool::new(false) }
+    }
+
+    pub fn record_event(&self, now_ms: u64) {
+        self.last_event_ms.store(now_ms, Ordering::Relaxed);
+        self.fired.store(false, Ordering::Relaxed);
+    }
+
+    /// Ms since the last event (or since start if none yet)
+    pub fn age_ms(&self, now_ms: u64) -> u64 {
+        now_ms.saturating_sub(self.last_event_ms.load(Ordering::Relaxed))
+    }
+
+    /// Some(age) the first time the silence exceeds the threshold within active hours;
+    /// a quiet spell shorter than the threshold, or outside the hours, never fires
+    pub fn check(&self, cfg: &WatchdogConfig, now_ms: u64, hour_utc: u8) -> Option<u64> {
+        let age = self.age_ms(now_ms);
+        if cfg.threshold_ms == 0 || age < cfg.threshold_ms || !in_hours(cfg.active_hours, hour_utc) {
+            return None;
+        }
+        (!self.fired.swap(true, Ordering::Relaxed)).then_some(age)
+    }
+
+    /// Start counting from `now_ms` again (after a reconnect, so a fresh socket gets a full window)
+    pub fn reset(&self, now_ms: u64) {
+        self.record_event(now_ms);
+    }
+}
+
+// ============================================================================
+// Global Instance
+// ============================================================================
+
+static GLOBAL_WATCHDOG: OnceLock<EventWatchdog> = OnceLock::new();
+
+/// Get the global watchdog (the clock starts on first use)
+pub fn global() -> &'static EventWatchdog {
+    GLOBAL_WATCHDOG.get_or_init(|| {
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        EventWatchdog::new(now_ms)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fires_after_threshold_without_events() {
+        let cfg = WatchdogConfig { threshold_ms: 60_000, reconnect: false, active_hours: None };
+        let wd = EventWatchdog::new(1_000_000);
+
+        // Quiet market: under the threshold
+        assert_eq!(wd.check(&cfg, 1_059_999, 12), None);
+        // Dead feed: fires once, with the age
+        assert_eq!(wd.check(&cfg, 1_060_000, 12), Some(60_000));
+        assert_eq!(wd.check(&cfg, 1_090_000, 12), None);
+        assert_eq!(wd.age_ms(1_090_000), 90_000);
+
+        // An event re-arms it
+        wd.record_event(1_100_000);
+        assert_eq!(wd.check(&cfg, 1_150_000, 12), None);
+        assert_eq!(wd.check(&cfg, 1_160_000, 12), Some(60_000));
+
+        // Off
+        let off = WatchdogConfig { threshold_ms: 0, ..cfg };
+        assert_eq!(EventWatchdog::new(0).check(&off, u64::MAX / 2, 12), None);
+    }
+
+    #[test]
+    fn test_active_hours() {
+        assert_eq!(parse_active_hours("13-23"), Some((13, 23)));
+        assert_eq!(parse_active_hours(" 22 - 4 "), Some((22, 4)));
+        assert_eq!(parse_active_hours(""), None);
+        assert_eq!(parse_active_hours("25-3"), None);
+
+        // Overnight window: silence at 10:00 UTC is a quiet market, at 23:00 a dead feed
+        let cfg = WatchdogConfig { threshold_ms: 1_000, reconnect: true, active_hours: Some((22, 4)) };
+        let wd = EventWatchdog::new(0);
+        assert_eq!(wd.check(&cfg, 5_000, 10), None);
+        assert_eq!(wd.check(&cfg, 5_000, 23), Some(5_000));
+        let wd = EventWatchdog::new(0);
+        assert_eq!(wd.check(&cfg, 5_000, 3), Some(5_000));
+    }
+}
@@ -0,0 +1,74 @@
+//! Fan-out of one unbounded channel to several worker tasks
+//! Used by the resubmit worker so a slow chain doesn't hold up the requests queued behind it
+
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+
+/// A receiver several tasks can pull from; each message goes to exactly one of them
+pub struct SharedReceiver<T> {
+    inner: Arc<Mutex<mpsc::UnboundedReceiver<T>>>,
+}
+
+impl<T> Clone for SharedReceiver<T> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<T> SharedReceiver<T> {
+    pub fn new(rx: mpsc::UnboundedReceiver<T>) -> Self {
+        Self { inner: Arc::new(Mutex::new(rx)) }
+    }
+
+    /// Next message, or None once every sender is gone. The lock is only held while
+    /// waiting, so an idle worker picks up the next message as soon as it's sent
+    pub async fn recv(&self) -> Option<T> {
+        self.inner.lock().await.recv().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::sync::Barrier;
+
+    #[tokio::test]
+    async fn test_two_workers_process_concurrently() {
+        let (tx, rx) = mpsc::unbounded_channel::<u32>();
+        let shared = SharedReceiver::new(rx);
+        // Each request blocks until both are in progress: a single worker would never get past it
+        let barrier = Arc::new(Barrier::new(2));
+        let (done_tx, mut done_rx) = mpsc::unbounded_channel();
+
+        for _ in 0..2 {
+            let (rx, barrier, done_tx) = (shared.clone(), barrier.clone(), done_tx.clone());
+            tokio::spawn(async move {
+                while let Some(req) = rx.recv().await {
+                    barrier.wait().await;
+                    let _ = done_tx.send(req);
+                }
+            });
+        }
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+
+        let mut done = Vec::new();
+        for _ in 0..2 {
+            let req = tokio::time::timeout(Duration::from_secs(2), done_rx.recv()).await.expect("requests were serialized");
+            done.push(req.unwrap());
+        }
+        done.sort();
+        assert_eq!(done, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_recv_ends_when_senders_dropped() {
+        let (tx, rx) = mpsc::unbounded_channel::<u32>();
+        let shared = SharedReceiver::new(rx);
+        tx.send(7).unwrap();
+        drop(tx);
+        assert_eq!(shared.recv().await, Some(7));
+        assert_eq!(shared.clone().recv().await, None);
+    }
+}